@@ -1,31 +1,259 @@
-mod ery;
+pub mod capability;
+pub mod client;
+pub mod clipboard;
+pub mod diff;
+pub mod efu;
+pub mod ery;
+pub mod event;
+pub mod excludes;
+pub mod extensions;
+pub mod filters;
+pub mod git;
+pub mod glob;
+pub mod grep;
+pub mod link;
+pub mod location;
+pub mod merge;
+pub mod notes;
+pub mod opened;
+pub mod phrase;
+pub mod pins;
+pub mod project;
+pub mod recent;
+pub mod rename;
+pub mod report;
+pub mod scope;
+pub mod shortcut;
+pub mod snapshot;
+pub mod sort;
+pub mod suggest;
+pub mod task;
+pub mod transfer;
+pub mod undo;
+pub mod usage;
+pub mod volumes;
+pub mod watch;
 
 use std::{
-    sync::{mpsc, Arc, Mutex, RwLock},
+    collections::HashSet,
+    ffi::{OsStr, OsString},
+    fs, mem,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Arc, Mutex,
+    },
     thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
-use everything_sdk::{global, FileInfoType, SortType};
+use anyhow::Context;
+use arc_swap::ArcSwap;
+use everything_sdk::{global, FileInfoType, RequestFlags, SortType};
 
-use crate::tui::Event;
+use crate::archive;
+use crate::config::{Config, FlagPreset};
+use crate::export;
+use crate::format;
+use crate::script::Scripts;
+use crate::shell;
+use crate::tr;
 
-use self::ery::{item_to_entry, Query, QueryResults};
+use self::capability::Feature;
+use self::client::EverythingClient;
+use self::diff::ResultsDiff;
+use self::ery::{
+    update_shared, Query, QueryEntry, QueryError, QueryResults, Request, SharedResults,
+};
+use self::event::{AppEvent, AppSender};
+use self::excludes::Excludes;
+use self::filters::Filter;
+use self::git::GitStatus;
+use self::link::LinkKind;
+use self::location::Location;
+use self::merge::Merge;
+use self::notes::Notes;
+use self::opened::Opened;
+use self::pins::Pins;
+use self::project::Project;
+use self::recent::Recent;
+use self::rename::Renaming;
+use self::report::Report;
+use self::scope::Scope;
+use self::shortcut::Shortcuts;
+use self::snapshot::Snapshot;
+use self::sort::{default_sort, is_recently_changed, sort_for, sort_name, SortKey, SORT_CYCLE};
+use self::suggest::Suggestion;
+use self::task::{Task, TaskContext, Tasks, Unit};
+use self::transfer::TransferKind;
+use self::undo::{QueryState, QueryUndo};
+use self::usage::DiskUsage;
+use self::watch::Watch;
 
 #[derive(Debug)]
 pub struct App {
+    /// user config
+    pub config: Config,
     /// everything status
     pub status: Status,
-    /// event sender
-    pub tui_sender: mpsc::Sender<Event>,
+    /// sends the events to the frontend
+    pub sender: AppSender,
     /// query sender
-    pub query_sender: mpsc::Sender<Query>,
-    /// send back the results when query done
-    pub back_recevier: Arc<Mutex<mpsc::Receiver<QueryResults>>>,
+    pub query_sender: mpsc::Sender<Request>,
     /// query back results
-    pub query_results: Arc<RwLock<QueryResults>>,
+    pub query_results: SharedResults,
+    /// the most frequently or recently run items of Everything
+    pub run_history: SharedResults,
+    /// only the total number of the matches for the text being typed, without any entry
+    pub live_count: SharedResults,
+    /// the children or all the files of `usage_folder`, for the disk usage view
+    pub usage_results: SharedResults,
+    pub usage_folder: Option<PathBuf>,
+    /// all the matches of `report_search`, for the report over the full set
+    pub report_results: SharedResults,
+    pub report_search: Option<String>,
+    /// the folders for completing the target path of moving or copying
+    pub completion_results: SharedResults,
+    /// the long operations in the background, e.g. moving or copying files
+    pub tasks: Tasks,
+    /// results snapshot as the baseline for comparing
+    pub baseline: Option<QueryResults>,
+    /// the snapshot file or the file list (*.efu) being browsed offline, no Everything IPC will
+    /// be sent if it is some.
+    pub offline_snapshot: Option<PathBuf>,
+    /// the search text of the last query sent
+    pub last_search: String,
+    /// query the last search periodically if it is some
+    pub watch: Option<Watch>,
+    /// sort type of the queries
+    pub sort_type: SortType,
+    /// sort the results by it in the same primary sort, done by ery.
+    pub secondary_sort: Option<SortKey>,
+    /// the sort is switched by the user, kept for all the searches instead of the smart sorts.
+    pub is_sort_chosen: bool,
+    /// launcher mode, only search the programs and shortcuts
+    pub launcher: bool,
+    /// the destructive operations only tell what they would do, e.g. recycling and moving.
+    pub dry_run: bool,
+    /// the searches merged into the results, the text typed narrows all of them.
+    pub merge: Option<Merge>,
+    /// the entries kept at the top of the results across the queries.
+    pub pins: Pins,
+    /// the notes of the paths, and the `note:` filter of the last query.
+    pub notes: Notes,
+    /// the folders excluded by `x`, besides `query.excludes` in config.
+    pub excludes: Excludes,
+    /// the searches submitted lately, across the runs.
+    pub recent: Recent,
+    /// the files opened through ery lately, across the runs.
+    pub opened: Opened,
+    /// the files recycled by ery in this run with the times, the latest first, to restore them.
+    pub recycled: Vec<(PathBuf, u64)>,
+    /// the search of no results, when the results are of the relaxed one of it.
+    pub fallback: Option<String>,
+    /// the `:line[:col]` split off the search, e.g. `main.rs:120`, to open the file at.
+    pub location: Option<Location>,
+    /// the filter put before the query text, switch it by `Ctrl+L`.
+    pub filter: Option<Filter>,
+    /// what the words of the search match, switch it by `F4`.
+    pub scope: Scope,
+    /// the words of the search are globs, e.g. `src/**/*.rs`, switch it by `Alt+G`.
+    pub glob: bool,
+    /// the project of the folder ery is launched in, if any.
+    pub project: Option<Project>,
+    /// the searches in the project only, switch it by `Alt+P`.
+    pub in_project: bool,
+    /// the git statuses of the results in the repositories.
+    pub git: GitStatus,
+    /// the targets of the shortcuts in the results, read when asked.
+    pub shortcuts: Shortcuts,
+    /// the sort and the filter before the recently changed view, which is on when it is some.
+    pub recent_view: Option<(SortType, Option<SortKey>, Option<Filter>)>,
+    /// the states of the queries sent, for going back and forth.
+    pub query_undo: QueryUndo,
+    /// the request flags by the command line or the saved search, over `query.flags` in config.
+    pub flags_override: Option<FlagPreset>,
+    /// seconds of the reply timeout by the command line, over `query.timeout` in config.
+    pub timeout_override: Option<u64>,
+    /// the hooks of the user scripts, none if there is no scripts folder.
+    pub scripts: Option<Scripts>,
+    /// offset of the next query only, for loading the last results of the search.
+    window_offset: u32,
+    /// results of a query loaded at most, some pages of the terminal height.
+    max_results: u32,
+    /// id of the next query
+    next_query_id: u64,
+    /// ids of the latest queries for `query_results`, `run_history`, `live_count`,
+    /// `usage_results`, `report_results` and `completion_results`, the results of the older
+    /// queries are dropped when back, e.g. a slow query finished after a newer one.
+    latest_query_id: Arc<AtomicU64>,
+    latest_history_id: Arc<AtomicU64>,
+    latest_count_id: Arc<AtomicU64>,
+    latest_usage_id: Arc<AtomicU64>,
+    latest_report_id: Arc<AtomicU64>,
+    latest_completion_id: Arc<AtomicU64>,
+    /// ids of the queries in flight and waited for, the canceled ones are not.
+    querying: Arc<Mutex<HashSet<u64>>>,
+    /// id of the last query replied by Everything.
+    replied_id: Arc<AtomicU64>,
+    /// a query timed out, cleared when the results of it or a later one are back.
+    query_error: Arc<Mutex<Option<QueryError>>>,
+    /// bumped by every file operation, the refresh is sent only for the last one in a while.
+    files_changed_id: Arc<AtomicU64>,
 }
 
-#[derive(Debug)]
+/// Results of a query loaded at most, before the terminal height is known.
+const DEFAULT_MAX_RESULTS: u32 = 512;
+
+/// Pages of the results loaded by a query, fewer on the small terminals for the faster replies.
+const PAGES_LOADED: u32 = 5;
+
+/// Results of a query loaded at least, however small the terminal is.
+const MIN_RESULTS: u32 = 100;
+
+/// The results are refreshed once after the file operations stop for it, and Everything has
+/// some time to index the changes.
+const FILES_CHANGED_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Fields only shown in the preview of the selected entry, converted lazily.
+const PREVIEW_FLAGS: RequestFlags = RequestFlags::EVERYTHING_REQUEST_DATE_CREATED
+    .union(RequestFlags::EVERYTHING_REQUEST_DATE_ACCESSED)
+    .union(RequestFlags::EVERYTHING_REQUEST_ATTRIBUTES);
+
+/// More fields in the preview of the full metadata preset, converted lazily too.
+const METADATA_FLAGS: RequestFlags = RequestFlags::EVERYTHING_REQUEST_RUN_COUNT
+    .union(RequestFlags::EVERYTHING_REQUEST_DATE_RUN)
+    .union(RequestFlags::EVERYTHING_REQUEST_DATE_RECENTLY_CHANGED);
+
+/// The requested fields of the preset, and the lazy ones of them.
+fn preset_flags(preset: FlagPreset) -> (RequestFlags, RequestFlags) {
+    let names = RequestFlags::EVERYTHING_REQUEST_FILE_NAME | RequestFlags::EVERYTHING_REQUEST_PATH;
+    // size and date modified are needed to find out the changed entries when comparing.
+    let standard = names
+        | RequestFlags::EVERYTHING_REQUEST_SIZE
+        | RequestFlags::EVERYTHING_REQUEST_DATE_MODIFIED
+        // empty unless the entry is from a file list included in the database.
+        | RequestFlags::EVERYTHING_REQUEST_FILE_LIST_FILE_NAME;
+    match preset {
+        FlagPreset::Minimal => (names, RequestFlags::empty()),
+        FlagPreset::Standard => (standard | PREVIEW_FLAGS, PREVIEW_FLAGS),
+        FlagPreset::FullMetadata => (
+            standard | PREVIEW_FLAGS | METADATA_FLAGS,
+            PREVIEW_FLAGS | METADATA_FLAGS,
+        ),
+    }
+}
+
+/// Search of the recently changed view, the files Everything saw changed today.
+const RECENTLY_CHANGED_FILTER: &str = "rc:today";
+
+/// Search filter of the launcher mode, programs and shortcuts only.
+const LAUNCHER_FILTER: &str = "ext:exe;lnk";
+
+/// The run count is waited for at most this long, a query in flight may hold Everything.
+const RUN_COUNT_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Default)]
 pub struct Status {
     pub is_db_loaded: bool,
 
@@ -51,64 +279,186 @@ pub struct Status {
     pub is_extension_fast_sort: bool,
 }
 
+impl Status {
+    /// Whether the running Everything has the feature, none of them if the version is unknown.
+    pub fn supports(&self, feature: Feature) -> bool {
+        let (major, minor, _, _) = self.version;
+        (major, minor) >= feature.since()
+    }
+
+    /// Whether Everything keeps the sort fast, others may take a long time over many files. The
+    /// name sort is always fast, and the ones unknown here are taken as fast.
+    pub fn is_fast_sort(&self, sort_type: SortType) -> bool {
+        match sort_name(sort_type).0 {
+            "Path" => self.is_path_fast_sort,
+            "Size" => self.is_size_fast_sort,
+            "Extension" => self.is_extension_fast_sort,
+            "Date Created" => self.is_date_created_fast_sort,
+            "Date Modified" => self.is_date_modified_fast_sort,
+            "Date Accessed" => self.is_date_accessed_fast_sort,
+            "Attributes" => self.is_attributes_fast_sort,
+            _ => true,
+        }
+    }
+}
+
 impl App {
-    pub fn with_sender(tui_sender: mpsc::Sender<Event>) -> Self {
-        let status = App::load_status().unwrap();
-        let (tx_query, rx_query) = mpsc::channel::<Query>();
+    pub fn with_sender(sender: impl Into<AppSender>, config: Config) -> Self {
+        let sender = sender.into();
+        let status = App::load_status(&*global().try_lock().unwrap()).unwrap();
+        let (tx_query, rx_query) = mpsc::channel::<Request>();
         let query_sender = tx_query;
-        let (sync_tx_back, rx_back) = mpsc::sync_channel(0);
-        let back_recevier = Arc::new(Mutex::new(rx_back));
+        let refresh_sender = sender.clone();
         thread::spawn(move || {
             let mut everything = global().lock().unwrap();
-            let mut searcher = everything.searcher();
-            while let Ok(query) = rx_query.recv() {
-                if query.search.is_empty() {
-                    // do not send IPC search, return empty result
-                    let empty_result = QueryResults::default();
-                    sync_tx_back.send(empty_result).unwrap();
-                } else {
-                    searcher
-                        .set_search(query.search)
-                        .set_match_path(query.match_path)
-                        .set_match_case(query.match_case)
-                        .set_match_whole_word(query.match_whole_word)
-                        .set_regex(query.regex)
-                        .set_max(query.max)
-                        .set_offset(query.offset)
-                        .set_sort(query.sort_type)
-                        .set_request_flags(query.request_flags);
-                    let search_text = searcher.get_search();
-                    let results = searcher.query();
-                    let flags = results.request_flags();
-                    let entrys: Vec<_> = results.iter().map(|i| item_to_entry(i, flags)).collect();
-                    let query_results = QueryResults {
-                        search: search_text,
-                        offset: query.offset,
-                        number: results.num(),
-                        total: results.total(),
-                        request_flags: flags,
-                        sort_type: results.sort_type(),
-                        entrys: entrys,
-                    };
-                    sync_tx_back.send(query_results).unwrap();
-                }
-            }
+            client::serve(&mut *everything, rx_query, refresh_sender);
         });
 
+        Self::with_channels(sender, config, status, query_sender)
+    }
+
+    /// Served by the client instead of Everything, e.g. the [`client::mock::MockClient`] in
+    /// tests.
+    pub fn with_client(
+        sender: impl Into<AppSender>,
+        config: Config,
+        mut client: impl EverythingClient + Send + 'static,
+    ) -> anyhow::Result<Self> {
+        let sender = sender.into();
+        let status = App::load_status(&client)?;
+        let (query_sender, rx_query) = mpsc::channel::<Request>();
+        let refresh_sender = sender.clone();
+        thread::spawn(move || client::serve(&mut client, rx_query, refresh_sender));
+        Ok(Self::with_channels(sender, config, status, query_sender))
+    }
+
+    fn with_channels(
+        sender: AppSender,
+        config: Config,
+        status: Status,
+        query_sender: mpsc::Sender<Request>,
+    ) -> Self {
+        let (sort_type, secondary_sort) = default_sort(config.query.sort);
         Self {
-            status: status,
-            tui_sender,
+            config,
+            status,
+            sender,
             query_sender,
-            back_recevier,
             query_results: Default::default(),
+            run_history: Default::default(),
+            live_count: Default::default(),
+            usage_results: Default::default(),
+            usage_folder: None,
+            report_results: Default::default(),
+            report_search: None,
+            completion_results: Default::default(),
+            tasks: Tasks::default(),
+            baseline: None,
+            offline_snapshot: None,
+            last_search: String::new(),
+            watch: None,
+            sort_type,
+            secondary_sort,
+            is_sort_chosen: false,
+            launcher: false,
+            dry_run: false,
+            merge: None,
+            pins: Pins::default(),
+            notes: Notes::default(),
+            excludes: Excludes::default(),
+            recent: Recent::default(),
+            opened: Opened::default(),
+            recycled: vec![],
+            fallback: None,
+            location: None,
+            filter: None,
+            scope: Scope::default(),
+            glob: false,
+            project: None,
+            in_project: false,
+            git: GitStatus::default(),
+            shortcuts: Shortcuts::default(),
+            recent_view: None,
+            query_undo: QueryUndo::default(),
+            flags_override: None,
+            timeout_override: None,
+            scripts: Scripts::load(),
+            window_offset: 0,
+            max_results: DEFAULT_MAX_RESULTS,
+            next_query_id: 1,
+            latest_query_id: Default::default(),
+            latest_history_id: Default::default(),
+            latest_count_id: Default::default(),
+            latest_usage_id: Default::default(),
+            latest_report_id: Default::default(),
+            latest_completion_id: Default::default(),
+            querying: Default::default(),
+            replied_id: Default::default(),
+            query_error: Default::default(),
+            files_changed_id: Default::default(),
+        }
+    }
+
+    /// Browse the results of a snapshot file, without Everything running.
+    pub fn with_snapshot(
+        sender: impl Into<AppSender>,
+        config: Config,
+        path: PathBuf,
+    ) -> anyhow::Result<Self> {
+        let snapshot = Snapshot::load(&path)?;
+        let status = Status {
+            version: snapshot.everything_version,
+            ..Default::default()
+        };
+        Ok(Self::offline(
+            sender.into(),
+            config,
+            status,
+            snapshot.results,
+            path,
+        ))
+    }
+
+    /// Browse the files of an Everything file list (*.efu), without Everything running.
+    pub fn with_file_list(
+        sender: impl Into<AppSender>,
+        config: Config,
+        path: PathBuf,
+    ) -> anyhow::Result<Self> {
+        let results = efu::load(&path)?;
+        Ok(Self::offline(
+            sender.into(),
+            config,
+            Default::default(),
+            results,
+            path,
+        ))
+    }
+
+    fn offline(
+        sender: AppSender,
+        config: Config,
+        status: Status,
+        results: QueryResults,
+        path: PathBuf,
+    ) -> Self {
+        // nobody is listening on them, queries are ignored when offline.
+        let (query_sender, _) = mpsc::channel::<Request>();
+        Self {
+            query_results: Arc::new(ArcSwap::from_pointee(results)),
+            offline_snapshot: Some(path),
+            sort_type: Default::default(),
+            ..Self::with_channels(sender, config, status, query_sender)
         }
     }
 
-    fn load_status() -> anyhow::Result<Status> {
-        let everything = global().try_lock().unwrap();
+    pub fn is_offline(&self) -> bool {
+        self.offline_snapshot.is_some()
+    }
+
+    fn load_status(everything: &impl EverythingClient) -> anyhow::Result<Status> {
         let is_db_loaded = everything.is_db_loaded()?;
-        let (major, minor, revision, build, _target) = everything.version()?;
-        let version = (major, minor, revision, build);
+        let version = everything.version()?;
         let is_admin = everything.is_admin()?;
         let is_appdata = everything.is_appdata()?;
         let is_file_size_indexed =
@@ -158,31 +508,1148 @@ impl App {
         Ok(status)
     }
 
+    /// Launcher mode, search the programs and shortcuts, the most frequently run first.
+    pub fn set_launcher(&mut self) {
+        self.launcher = true;
+        self.sort_type = SortType::EVERYTHING_SORT_RUN_COUNT_DESCENDING;
+    }
+
+    /// The next sort in [`SORT_CYCLE`] after the current one.
+    pub fn next_sort(&self) -> (SortType, Option<SortKey>) {
+        let current = SORT_CYCLE
+            .iter()
+            .position(|&sort| sort == (self.sort_type, self.secondary_sort));
+        let next = current.map_or(0, |i| (i + 1) % SORT_CYCLE.len());
+        SORT_CYCLE[next]
+    }
+
+    /// Switch to the next filter of Everything or of config, and none after the last one. It
+    /// takes effect from the next query.
+    pub fn cycle_filter(&mut self) {
+        let filters = filters::load(&self.config.filters, self.status.is_appdata);
+        let next = match &self.filter {
+            None => 0,
+            Some(current) => filters
+                .iter()
+                .position(|filter| filter.name == current.name)
+                .map_or(0, |i| i + 1),
+        };
+        self.filter = filters.into_iter().nth(next);
+    }
+
+    /// Show the files changed today, the latest changed first, or back to the sort and the
+    /// filter before. True if the view is on now. It takes effect from the next query.
+    pub fn toggle_recently_changed(&mut self) -> bool {
+        match self.recent_view.take() {
+            Some((sort_type, secondary_sort, filter)) => {
+                (self.sort_type, self.secondary_sort) = (sort_type, secondary_sort);
+                self.filter = filter;
+                false
+            }
+            None => {
+                let filter = Filter {
+                    name: tr!(RecentlyChangedFilter).to_string(),
+                    search: RECENTLY_CHANGED_FILTER.to_string(),
+                };
+                self.recent_view = Some((
+                    self.sort_type,
+                    self.secondary_sort,
+                    self.filter.replace(filter),
+                ));
+                self.sort_type = SortType::EVERYTHING_SORT_DATE_RECENTLY_CHANGED_DESCENDING;
+                self.secondary_sort = None;
+                true
+            }
+        }
+    }
+
+    /// The search text sent to Everything for the text in the search bar.
+    pub fn search_for(&self, query_text: &str) -> String {
+        // opened at by the editor, not searched
+        let (query_text, _) = location::split(query_text);
+        // the notes are filtered by ery, out of the noted paths searched
+        let (query_text, note_terms) = notes::split_terms(query_text);
+        let query_text = query_text.as_str();
+        let transformed;
+        let query_text = match &self.scripts {
+            Some(scripts) => {
+                transformed = scripts.transform_query(query_text);
+                transformed.as_str()
+            }
+            None => query_text,
+        };
+        let globbed;
+        let query_text = if self.glob {
+            globbed = glob::translate(query_text);
+            globbed.as_str()
+        } else {
+            query_text
+        };
+        let rewritten = self.scope.rewrite(query_text);
+        let query_text = rewritten.as_str();
+        let noted;
+        let query_text = if note_terms.is_empty() {
+            query_text
+        } else {
+            // no note matches, so no results
+            let Some(paths) = self.notes.search(&note_terms) else {
+                return String::new();
+            };
+            noted = if query_text.is_empty() {
+                paths
+            } else {
+                format!("{paths} {query_text}")
+            };
+            noted.as_str()
+        };
+        let filtered;
+        let query_text = match &self.filter {
+            Some(filter) if query_text.is_empty() => filter.search.as_str(),
+            Some(filter) => {
+                filtered = format!("{} {query_text}", filter.search);
+                filtered.as_str()
+            }
+            None => query_text,
+        };
+        let scoped;
+        let query_text = match self.project.as_ref().filter(|_| self.in_project) {
+            Some(project) if query_text.is_empty() => {
+                scoped = project.search();
+                scoped.as_str()
+            }
+            Some(project) => {
+                scoped = format!("{} {query_text}", project.search());
+                scoped.as_str()
+            }
+            None => query_text,
+        };
+        let search = match (self.launcher, query_text.is_empty()) {
+            (true, true) => LAUNCHER_FILTER.to_string(),
+            (true, false) => format!("{LAUNCHER_FILTER} {query_text}"),
+            (false, _) => query_text.to_owned(),
+        };
+        excludes::append(&search, &self.excludes.clauses_with(&self.config))
+    }
+
+    /// Exclude the folder of the entry of the path from all the queries, or the entry itself if
+    /// it is a folder, and query again. The folder, or none if it is excluded already.
+    pub fn exclude(&mut self, path: &Path) -> anyhow::Result<Option<PathBuf>> {
+        let is_folder = {
+            let results = self.query_results.load();
+            results
+                .position(path)
+                .is_some_and(|i| results.entrys[i].is_folder)
+        };
+        let folder = if is_folder {
+            path
+        } else {
+            path.parent().unwrap_or(path)
+        };
+        if !self.excludes.add(folder)? {
+            return Ok(None);
+        }
+        let text = self.last_search.clone();
+        self.send_query(&text)?;
+        Ok(Some(folder.to_path_buf()))
+    }
+
+    /// Search the query text in the Everything desktop app, with the same options and sort as
+    /// the queries of ery.
+    pub fn open_in_everything(&self, query_text: &str) -> anyhow::Result<()> {
+        // the secondary sort is not supported by the command line
+        let (sort, descending) = sort_name(self.sort_type);
+        let args = [
+            "-search",
+            &self.search_for(query_text),
+            // override the options saved in Everything by the ones of ery
+            "-nocase",
+            "-nowholeword",
+            if self.scope.match_path() {
+                "-matchpath"
+            } else {
+                "-nomatchpath"
+            },
+            "-noregex",
+            "-sort",
+            sort,
+            if descending {
+                "-sort-descending"
+            } else {
+                "-sort-ascending"
+            },
+        ];
+        shell::everything_gui(&args.map(OsString::from))
+    }
+
+    /// Whether the user scripts allow opening the path.
+    pub fn allow_open(&self, path: &Path) -> bool {
+        self.scripts
+            .as_ref()
+            .is_none_or(|scripts| scripts.on_open(path))
+    }
+
+    /// Increase the run count of the file in Everything, like what Everything does when
+    /// opening a file.
+    pub fn inc_run_count(&mut self, path: PathBuf) -> anyhow::Result<()> {
+        self.send_run_count(path)?;
+        Ok(())
+    }
+
+    /// Increase the run count like [`App::inc_run_count`], and wait until it is done, e.g.
+    /// before quitting, which would drop the request not done yet.
+    pub fn inc_run_count_and_wait(&mut self, path: PathBuf) -> anyhow::Result<()> {
+        let done = self.send_run_count(path)?;
+        // disconnected at once if offline
+        let _ = done.recv_timeout(RUN_COUNT_TIMEOUT);
+        Ok(())
+    }
+
+    fn send_run_count(&mut self, path: PathBuf) -> anyhow::Result<mpsc::Receiver<()>> {
+        let (done, receiver) = mpsc::channel();
+        if !self.is_offline() {
+            self.query_sender.send(Request::IncRunCount(path, done))?;
+        }
+        Ok(receiver)
+    }
+
+    /// Remember the file opened through ery, for the panel of the opened files. Not for the
+    /// snapshots and the file lists, which do not load them, so they are not saved over.
+    pub fn add_opened(&mut self, path: &Path) -> anyhow::Result<()> {
+        if self.is_offline() {
+            return Ok(());
+        }
+        self.opened.add(path)
+    }
+
     /// trigger the SendQuery event (Everything Searching) in the terminal.
     pub fn send_query(&mut self, query_text: &str) -> anyhow::Result<()> {
+        if self.is_offline() {
+            return Ok(());
+        }
+        self.last_search = query_text.to_owned();
+        self.fallback = None;
+        self.location = location::split(query_text).1;
+        if !self.is_sort_chosen && !self.launcher && self.recent_view.is_none() {
+            // the slow sorts are not switched to without the confirmation
+            let sort = sort_for(query_text, &self.config.query);
+            if self.status.is_fast_sort(sort.0) {
+                (self.sort_type, self.secondary_sort) = sort;
+            }
+        }
+        self.query_undo.push(self.query_state());
+        self.notes.set_filter(query_text);
+        let (request_flags, lazy_flags) = self.query_flags();
+        if self.merge.is_some() {
+            return self.send_merge_queries(query_text, request_flags);
+        }
         let query = Query {
-            search: query_text.to_owned(),
-            match_path: false,
+            id: 0, // assigned when submitted
+            search: self.search_for(query_text),
+            match_path: self.scope.match_path(),
             match_case: false,
             match_whole_word: false,
             regex: false,
-            max: 512, // TODO: limit for now, maybe dynamic loading in the future.
-            offset: 0,
-            sort_type: Default::default(),
-            request_flags: Default::default(),
+            max: self.max_results,
+            offset: mem::take(&mut self.window_offset),
+            sort_type: self.sort_type,
+            secondary_sort: self.secondary_sort,
+            request_flags,
+            lazy_flags,
+        };
+        let (target, latest) = (
+            Arc::clone(&self.query_results),
+            Arc::clone(&self.latest_query_id),
+        );
+        self.submit(query, target, latest)
+    }
+
+    /// The fields requested by the queries, and the ones of them converted lazily.
+    fn query_flags(&self) -> (RequestFlags, RequestFlags) {
+        let (mut request_flags, mut lazy_flags) =
+            preset_flags(self.flags_override.unwrap_or(self.config.query.flags));
+        if is_recently_changed(self.sort_type) {
+            // shown in the list instead of the date modified
+            request_flags |= RequestFlags::EVERYTHING_REQUEST_DATE_RECENTLY_CHANGED;
+            lazy_flags -= RequestFlags::EVERYTHING_REQUEST_DATE_RECENTLY_CHANGED;
+        }
+        // sorted by ery as the results come, e.g. the run count for the relevance
+        if let Some(key) = self.secondary_sort {
+            request_flags |= key.request_flags();
+            lazy_flags -= key.request_flags();
+        }
+        (request_flags, lazy_flags)
+    }
+
+    /// Load the next results of the search after the loaded ones in the background, before
+    /// paging to them. False if all are loaded or any query is in flight.
+    ///
+    /// The loaded ones are queried again with them, so the details of all are of one query. It
+    /// is the latest query, so dropped for any newer one or by [`Self::cancel_queries`].
+    pub fn prefetch(&mut self) -> anyhow::Result<bool> {
+        let (offset, number, total) = {
+            let results = self.query_results.load();
+            (results.offset, results.number, results.total)
+        };
+        // the ones of watch mode would be told as new
+        if self.is_offline()
+            || self.merge.is_some()
+            || self.watch.is_some()
+            || self.is_querying()
+            || self.last_search.is_empty()
+            || offset + number >= total
+        {
+            return Ok(false);
+        }
+        let (request_flags, lazy_flags) = self.query_flags();
+        let query = Query {
+            search: self.search_for(&self.last_search),
+            match_path: self.scope.match_path(),
+            max: number + self.max_results,
+            offset,
+            sort_type: self.sort_type,
+            secondary_sort: self.secondary_sort,
+            request_flags,
+            lazy_flags,
+            ..Default::default()
+        };
+        let (target, latest) = (
+            Arc::clone(&self.query_results),
+            Arc::clone(&self.latest_query_id),
+        );
+        self.submit(query, target, latest)?;
+        Ok(true)
+    }
+
+    /// Query each search of the merge with the text. The lazy fields are converted with the
+    /// results, which are of several queries then.
+    fn send_merge_queries(
+        &mut self,
+        query_text: &str,
+        request_flags: RequestFlags,
+    ) -> anyhow::Result<()> {
+        let Some(merge) = &self.merge else {
+            return Ok(());
+        };
+        let queries: Vec<_> = merge
+            .sources
+            .iter()
+            .map(|source| {
+                let query = Query {
+                    search: self.search_for(&format!("{} {query_text}", source.query)),
+                    match_path: self.scope.match_path(),
+                    max: self.max_results,
+                    sort_type: self.sort_type,
+                    secondary_sort: self.secondary_sort,
+                    request_flags,
+                    ..Default::default()
+                };
+                (
+                    query,
+                    Arc::clone(&source.results),
+                    Arc::clone(&source.latest),
+                )
+            })
+            .collect();
+        for (query, target, latest) in queries {
+            self.submit(query, target, latest)?;
+        }
+        Ok(())
+    }
+
+    /// Put the merged results in place when the results of all the searches of the merge are
+    /// back.
+    pub fn update_merge(&mut self) {
+        let Some(merged) = self.merge.as_mut().and_then(|merge| merge.merge()) else {
+            return;
+        };
+        let search = self.search_for(&self.last_search);
+        self.query_results.store(Arc::new(QueryResults {
+            search: search.into(),
+            ..merged
+        }));
+    }
+
+    /// Query the git statuses of the folders of the results in the background, if they are
+    /// not known yet.
+    pub fn update_git(&self) {
+        if !self.config.display.git || self.is_offline() {
+            return;
+        }
+        let results = self.query_results.load();
+        let folders = results.paths.iter().map(Path::to_path_buf).collect();
+        self.git.update(folders, &self.sender);
+    }
+
+    /// Keep the pinned entries at the top of the results, e.g. the new results are back.
+    pub fn update_pins(&mut self) {
+        if !self.pins.is_empty() {
+            update_shared(&self.query_results, |results| self.pins.apply(results));
+        }
+    }
+
+    /// Narrow the results by the `note:` terms of the query, e.g. the new results are back.
+    pub fn update_notes(&mut self) {
+        update_shared(&self.query_results, |results| self.notes.apply(results));
+    }
+
+    /// Set the note of the path, an empty one removes it.
+    pub fn set_note(&mut self, path: PathBuf, note: &str) -> anyhow::Result<()> {
+        self.notes.set(path, note)?;
+        self.update_notes();
+        Ok(())
+    }
+
+    /// Pin the entry of the path in the results, or unpin it. True if it is pinned now.
+    pub fn toggle_pin(&mut self, path: &Path) -> anyhow::Result<bool> {
+        let results = self.query_results.load();
+        let Some(i) = results.position(path) else {
+            return Ok(false);
         };
-        self.query_sender.send(query)?;
+        let pinned = self
+            .pins
+            .toggle(path.to_path_buf(), results.entrys[i].clone())?;
+        update_shared(&self.query_results, |results| self.pins.apply(results));
+        Ok(pinned)
+    }
+
+    fn query_state(&self) -> QueryState {
+        QueryState {
+            text: self.last_search.clone(),
+            flags: self.flags_override,
+            sort_type: self.sort_type,
+            secondary_sort: self.secondary_sort,
+            scope: self.scope,
+        }
+    }
+
+    /// Go back to the state of the query before, and query it. The text of it, none if there
+    /// is nothing to undo.
+    pub fn undo_query(&mut self) -> anyhow::Result<Option<String>> {
+        match self.query_undo.undo() {
+            Some(state) => self.restore_query(state).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Go forth to the state of the query undone, and query it. The text of it, none if there
+    /// is nothing to redo.
+    pub fn redo_query(&mut self) -> anyhow::Result<Option<String>> {
+        match self.query_undo.redo() {
+            Some(state) => self.restore_query(state).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn restore_query(&mut self, state: QueryState) -> anyhow::Result<String> {
+        self.flags_override = state.flags;
+        self.sort_type = state.sort_type;
+        self.secondary_sort = state.secondary_sort;
+        self.scope = state.scope;
+        self.send_query(&state.text)?;
+        Ok(state.text)
+    }
+
+    /// Count the matches of the text being typed, before querying the results of it.
+    pub fn send_count(&mut self, query_text: &str) -> anyhow::Result<()> {
+        if self.is_offline() {
+            return Ok(());
+        }
+        let query = Query {
+            search: self.search_for(query_text),
+            match_path: self.scope.match_path(),
+            max: 0,
+            request_flags: RequestFlags::EVERYTHING_REQUEST_FILE_NAME,
+            ..Default::default()
+        };
+        let (target, latest) = (
+            Arc::clone(&self.live_count),
+            Arc::clone(&self.latest_count_id),
+        );
+        self.submit(query, target, latest)
+    }
+
+    /// Fetch the lazy fields of the entry at `index` of the results, it is updated in place
+    /// later. It works only if the results are of the last query sent.
+    pub fn request_details(&mut self, index: usize) -> anyhow::Result<()> {
+        if self.is_offline() {
+            return Ok(());
+        }
+        let (id, index) = {
+            let results = self.query_results.load();
+            let Some(entry) = results.entrys.get(index) else {
+                return Ok(());
+            };
+            // the index in Everything, which differs from the one in the list if sorted by ery
+            (results.id, entry.index)
+        };
+        self.query_sender.send(Request::Details {
+            id,
+            index,
+            target: Arc::clone(&self.query_results),
+        })?;
+        Ok(())
+    }
+
+    /// Query the items in the run history of Everything, most recently run first if
+    /// `by_recent`, otherwise most frequently run first.
+    pub fn query_run_history(&mut self, by_recent: bool) -> anyhow::Result<()> {
+        if self.is_offline() {
+            return Ok(());
+        }
+        let query = Query {
+            search: "runcount:>0".to_string(),
+            max: 256,
+            sort_type: if by_recent {
+                SortType::EVERYTHING_SORT_DATE_RUN_DESCENDING
+            } else {
+                SortType::EVERYTHING_SORT_RUN_COUNT_DESCENDING
+            },
+            request_flags: RequestFlags::EVERYTHING_REQUEST_FILE_NAME
+                | RequestFlags::EVERYTHING_REQUEST_PATH
+                | RequestFlags::EVERYTHING_REQUEST_RUN_COUNT
+                | RequestFlags::EVERYTHING_REQUEST_DATE_RUN,
+            ..Default::default()
+        };
+        let (target, latest) = (
+            Arc::clone(&self.run_history),
+            Arc::clone(&self.latest_history_id),
+        );
+        self.submit(query, target, latest)
+    }
+
+    /// Query the sizes in the folder for the disk usage view, the children with their sizes if
+    /// the folder sizes are indexed, or all the files in it to sum up otherwise.
+    pub fn query_disk_usage(&mut self, folder: PathBuf) -> anyhow::Result<()> {
+        if self.is_offline() {
+            return Ok(());
+        }
+        let text = folder
+            .to_str()
+            .with_context(|| format!("{} is not valid unicode", folder.display()))?
+            .trim_end_matches('\\');
+        let request_flags = RequestFlags::EVERYTHING_REQUEST_FILE_NAME
+            | RequestFlags::EVERYTHING_REQUEST_PATH
+            | RequestFlags::EVERYTHING_REQUEST_SIZE;
+        let query = if self.status.is_folder_size_indexed {
+            Query {
+                // the drives keep the backslash, `C:\`
+                search: if text.ends_with(':') {
+                    format!("parent:\"{text}\\\"")
+                } else {
+                    format!("parent:\"{text}\"")
+                },
+                sort_type: SortType::EVERYTHING_SORT_SIZE_DESCENDING,
+                request_flags,
+                ..Default::default()
+            }
+        } else {
+            // the path with the trailing backslash, so not the siblings with the same prefix
+            Query {
+                search: format!("file: \"{text}\\\""),
+                match_path: true,
+                request_flags,
+                ..Default::default()
+            }
+        };
+        self.usage_folder = Some(folder);
+        let (target, latest) = (
+            Arc::clone(&self.usage_results),
+            Arc::clone(&self.latest_usage_id),
+        );
+        self.submit(query, target, latest)
+    }
+
+    /// The disk usage of `usage_folder`, none until the results of it are back.
+    pub fn disk_usage(&self) -> Option<DiskUsage> {
+        let folder = self.usage_folder.as_deref()?;
+        let results = self.usage_results.load();
+        if results.id != self.latest_usage_id.load(Ordering::SeqCst) {
+            return None;
+        }
+        Some(if self.status.is_folder_size_indexed {
+            DiskUsage::from_children(folder, &results)
+        } else {
+            DiskUsage::from_files(folder, &results)
+        })
+    }
+
+    /// Query all the matches of the last search in the background, for the report over the
+    /// full set instead of the loaded results only.
+    pub fn query_full_report(&mut self) -> anyhow::Result<()> {
+        if self.is_offline() || self.last_search.is_empty() {
+            return Ok(());
+        }
+        let query = Query {
+            search: self.search_for(&self.last_search),
+            sort_type: self.sort_type,
+            request_flags: RequestFlags::EVERYTHING_REQUEST_FILE_NAME
+                | RequestFlags::EVERYTHING_REQUEST_PATH
+                | RequestFlags::EVERYTHING_REQUEST_EXTENSION
+                | RequestFlags::EVERYTHING_REQUEST_SIZE
+                | RequestFlags::EVERYTHING_REQUEST_DATE_MODIFIED,
+            ..Default::default()
+        };
+        self.report_search = Some(self.last_search.clone());
+        let (target, latest) = (
+            Arc::clone(&self.report_results),
+            Arc::clone(&self.latest_report_id),
+        );
+        self.submit(query, target, latest)
+    }
+
+    /// Whether the full set of the last search is being queried for the report.
+    pub fn is_full_report_pending(&self) -> bool {
+        self.report_search.as_ref() == Some(&self.last_search)
+            && self.report_results.load().id != self.latest_report_id.load(Ordering::SeqCst)
+    }
+
+    /// The report over the full set of the last search if queried and back, or over the
+    /// loaded results.
+    pub fn report(&self) -> Report {
+        if self.report_search.as_ref() == Some(&self.last_search) {
+            let results = self.report_results.load();
+            if results.id == self.latest_report_id.load(Ordering::SeqCst) {
+                return Report::new(&results);
+            }
+        }
+        Report::new(&self.query_results.load())
+    }
+
+    /// Query the folders starting with the text, for completing the path typed. The text is a
+    /// folder and the start of the name of a subfolder in it, e.g. `C:\Users\Pu`.
+    pub fn query_folder_completions(&mut self, text: &str) -> anyhow::Result<()> {
+        if self.is_offline() {
+            return Ok(());
+        }
+        let (parent, name) = match text.rsplit_once('\\') {
+            // the drives keep the backslash, `C:\`
+            Some((parent, name)) if parent.ends_with(':') => (Some(format!("{parent}\\")), name),
+            Some((parent, name)) => (Some(parent.to_string()), name),
+            None => (None, text),
+        };
+        let mut search = "folder:".to_string();
+        if let Some(parent) = parent {
+            search.push_str(&format!(" parent:\"{parent}\""));
+        }
+        if !name.is_empty() {
+            search.push_str(&format!(" startwith:\"{name}\""));
+        }
+        let query = Query {
+            search,
+            max: 64,
+            sort_type: SortType::EVERYTHING_SORT_NAME_ASCENDING,
+            request_flags: RequestFlags::EVERYTHING_REQUEST_FILE_NAME
+                | RequestFlags::EVERYTHING_REQUEST_PATH,
+            ..Default::default()
+        };
+        let (target, latest) = (
+            Arc::clone(&self.completion_results),
+            Arc::clone(&self.latest_completion_id),
+        );
+        self.submit(query, target, latest)
+    }
+
+    /// The full paths of the folders for the completion, none until they are back.
+    pub fn folder_completions(&self) -> Option<Vec<String>> {
+        let results = self.completion_results.load();
+        if results.id != self.latest_completion_id.load(Ordering::SeqCst) {
+            return None;
+        }
+        let paths = results
+            .entrys
+            .iter()
+            .filter_map(|entry| results.filepath(entry))
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect();
+        Some(paths)
+    }
+
+    /// Move or copy the file or folder to the target path as a task in the background, it is
+    /// replaced if it exists.
+    pub fn start_transfer(
+        &mut self,
+        kind: TransferKind,
+        source: PathBuf,
+        target: PathBuf,
+    ) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            !self.is_in_task(&source),
+            "{} is in a task already",
+            source.display()
+        );
+        transfer::start(&mut self.tasks, kind, source, target, self.sender.clone());
+        Ok(())
+    }
+
+    /// Extract the archive into the folder as a task in the background.
+    pub fn start_extract(&mut self, source: PathBuf, folder: PathBuf) -> anyhow::Result<()> {
+        let backend = archive::backend(&source, self.config.archive.seven_zip.as_deref())
+            .context("not an archive supported, zip, 7z or rar")?;
+        transfer::extract(
+            &mut self.tasks,
+            backend,
+            source,
+            folder,
+            self.sender.clone(),
+        );
+        Ok(())
+    }
+
+    /// Whether a running task works on the file, e.g. it is being moved.
+    fn is_in_task(&self, path: &Path) -> bool {
+        self.tasks
+            .running()
+            .any(|task| task.path.as_deref() == Some(path))
+    }
+
+    /// Take the finished tasks and their results, the files gone by them, e.g. moved, are
+    /// taken out of the results.
+    pub fn finish_tasks(&mut self) -> Vec<(Task, anyhow::Result<String>)> {
+        let finished = self.tasks.take_finished();
+        if finished.is_empty() {
+            return finished;
+        }
+        update_shared(&self.query_results, |results| {
+            for path in finished.iter().filter_map(|(task, _)| task.path.as_deref()) {
+                if !path.exists() {
+                    results.remove(path);
+                }
+            }
+        });
+        self.files_changed();
+        finished
+    }
+
+    /// Send the query to the everything thread, and put the results back into `target`,
+    /// unless a newer query for it has been submitted since (recorded in `latest`).
+    fn submit(
+        &mut self,
+        mut query: Query,
+        target: SharedResults,
+        latest: Arc<AtomicU64>,
+    ) -> anyhow::Result<()> {
+        query.id = self.next_query_id;
+        self.next_query_id += 1;
+        latest.store(query.id, Ordering::SeqCst);
+        let timeout = self.timeout_override.unwrap_or(self.config.query.timeout);
+        if timeout > 0 {
+            self.watch_timeout(query.id, query.search.clone(), Duration::from_secs(timeout));
+        }
+        let id = query.id;
+        let (back, rx) = mpsc::channel();
+        self.query_sender.send(Request::Query { query, back })?;
 
         // then wait for the query results back
-        let rx = Arc::clone(&self.back_recevier);
-        let tui_tx = self.tui_sender.clone();
-        let results_in_app = Arc::clone(&self.query_results);
+        let sender = self.sender.clone();
+        let results_in_app = target;
+        let querying = Arc::clone(&self.querying);
+        let replied_id = Arc::clone(&self.replied_id);
+        let query_error = Arc::clone(&self.query_error);
+        querying.lock().unwrap().insert(id);
         thread::spawn(move || {
-            if let Ok(results) = rx.lock().unwrap().recv() {
-                *results_in_app.write().unwrap() = results;
-                tui_tx.send(Event::Refresh).unwrap();
+            if let Ok(results) = rx.recv() {
+                querying.lock().unwrap().remove(&results.id);
+                replied_id.fetch_max(results.id, Ordering::SeqCst);
+                let mut error = query_error.lock().unwrap();
+                if matches!(&*error, Some(QueryError::Timeout { id, .. }) if *id <= results.id) {
+                    *error = None;
+                }
+                drop(error);
+                // stale if a newer query for the target is submitted, or canceled
+                if results.id == latest.load(Ordering::SeqCst) {
+                    results_in_app.store(Arc::new(results));
+                }
+                sender.send(AppEvent::Refresh);
             }
         });
         Ok(())
     }
+
+    /// Tell the timeout if the query is not back in time. It can not be interrupted in the
+    /// IPC, so the results are still taken if they come later.
+    fn watch_timeout(&self, id: u64, search: String, after: Duration) {
+        let sender = self.sender.clone();
+        let replied_id = Arc::clone(&self.replied_id);
+        let query_error = Arc::clone(&self.query_error);
+        thread::spawn(move || {
+            thread::sleep(after);
+            // Everything replies in order, so it is back if any later one is back
+            if replied_id.load(Ordering::SeqCst) < id {
+                *query_error.lock().unwrap() = Some(QueryError::Timeout { id, search, after });
+                sender.send(AppEvent::Refresh);
+            }
+        });
+    }
+
+    /// The query timed out, none since the results of it or a later one are back.
+    pub fn query_error(&self) -> Option<QueryError> {
+        self.query_error.lock().unwrap().clone()
+    }
+
+    /// The queries sent to Everything but not back yet, except the canceled ones.
+    pub fn queries_in_flight(&self) -> usize {
+        self.querying.lock().unwrap().len()
+    }
+
+    /// Whether any query is in flight, e.g. a slow sort without fast sort on a huge index.
+    pub fn is_querying(&self) -> bool {
+        !self.querying.lock().unwrap().is_empty()
+    }
+
+    /// Abandon the queries in flight, the results stay as they were.
+    ///
+    /// Everything IPC can not be interrupted, so the everything thread still waits for the
+    /// reply, but the results are dropped when they come back.
+    pub fn cancel_queries(&mut self) {
+        // no query has this id, so all the queries in flight are older
+        let id = self.next_query_id;
+        self.next_query_id += 1;
+        self.latest_query_id.store(id, Ordering::SeqCst);
+        self.latest_history_id.store(id, Ordering::SeqCst);
+        self.latest_count_id.store(id, Ordering::SeqCst);
+        self.latest_usage_id.store(id, Ordering::SeqCst);
+        self.latest_report_id.store(id, Ordering::SeqCst);
+        self.latest_completion_id.store(id, Ordering::SeqCst);
+        // not waited for any more
+        self.querying.lock().unwrap().clear();
+        *self.query_error.lock().unwrap() = None;
+    }
+
+    /// Query the last search again after the files are changed by ery, debounced so that a
+    /// bulk operation causes one refresh only.
+    pub fn files_changed(&self) {
+        let id = self.files_changed_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let latest = Arc::clone(&self.files_changed_id);
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            thread::sleep(FILES_CHANGED_DEBOUNCE);
+            if latest.load(Ordering::SeqCst) == id {
+                sender.send(AppEvent::FilesChanged);
+            }
+        });
+    }
+
+    /// Move the files to the Recycle Bin, they are taken out of the results at once.
+    pub fn recycle(&mut self, paths: &[PathBuf]) -> anyhow::Result<()> {
+        let result = shell::recycle(paths);
+        // some may be recycled before a failure
+        let recycled: Vec<&PathBuf> = paths.iter().filter(|path| !path.exists()).collect();
+        update_shared(&self.query_results, |results| {
+            for path in &recycled {
+                results.remove(path);
+            }
+        });
+        let time = format::filetime_now();
+        for path in recycled {
+            self.recycled.insert(0, (path.clone(), time));
+        }
+        self.files_changed();
+        result
+    }
+
+    /// Put the recycled one back from the Recycle Bin, it comes back in the results when they
+    /// are queried again.
+    pub fn restore(&mut self, index: usize) -> anyhow::Result<PathBuf> {
+        let Some((path, _)) = self.recycled.get(index) else {
+            anyhow::bail!("nothing to restore");
+        };
+        shell::restore(path)?;
+        let (path, _) = self.recycled.remove(index);
+        self.files_changed();
+        Ok(path)
+    }
+
+    /// Rename the file in its folder, it is renamed in the results at once.
+    pub fn rename(&mut self, path: &Path, filename: &str) -> anyhow::Result<PathBuf> {
+        anyhow::ensure!(
+            !filename.is_empty() && !filename.contains(['\\', '/']),
+            "invalid filename {filename:?}"
+        );
+        let new_path = path.with_file_name(filename);
+        // the names are case-insensitive, only changing the case is fine
+        let is_same_name = path
+            .file_name()
+            .and_then(OsStr::to_str)
+            .is_some_and(|name| name.to_lowercase() == filename.to_lowercase());
+        anyhow::ensure!(
+            !new_path.exists() || is_same_name,
+            "{} already exists",
+            new_path.display()
+        );
+        fs::rename(path, &new_path)
+            .with_context(|| format!("failed to rename {}", path.display()))?;
+        update_shared(&self.query_results, |results| {
+            results.rename(path, OsStr::new(filename))
+        });
+        self.files_changed();
+        Ok(new_path)
+    }
+
+    /// Rename the files as planned, the unchanged ones skipped, none if any has a problem.
+    ///
+    /// All of them are renamed to the temporary names first, then to the new ones, so they can
+    /// take the old names of each other. The ones not renamed yet are put back if any fails.
+    pub fn batch_rename(&mut self, renamings: &[Renaming]) -> anyhow::Result<usize> {
+        rename::check(renamings)?;
+        let changed: Vec<&Renaming> = renamings
+            .iter()
+            .filter(|renaming| renaming.is_changed())
+            .collect();
+        let mut temporary = Vec::with_capacity(changed.len());
+        for (i, renaming) in changed.iter().enumerate() {
+            match self.rename(&renaming.from, &rename::temporary_name(i)) {
+                Ok(path) => temporary.push(path),
+                Err(e) => {
+                    self.put_back(changed.iter().copied().zip(&temporary));
+                    return Err(e.context("0 renamed before"));
+                }
+            }
+        }
+        for (renamed, (renaming, path)) in changed.iter().zip(&temporary).enumerate() {
+            if let Err(e) = self.rename(path, &renaming.to) {
+                self.put_back(changed.iter().copied().zip(&temporary).skip(renamed));
+                return Err(e.context(format!("{renamed} renamed before")));
+            }
+        }
+        Ok(changed.len())
+    }
+
+    /// Rename the files of the temporary names back to the old names, the errors ignored, e.g.
+    /// the old name taken by another one renamed already.
+    fn put_back<'a>(&mut self, temporary: impl Iterator<Item = (&'a Renaming, &'a PathBuf)>) {
+        for (renaming, path) in temporary {
+            let _ = self.rename(path, &renaming.old_name());
+        }
+    }
+
+    /// Create a symlink or hardlink of the file at the path `link`, or in it if it is a folder.
+    pub fn create_link(
+        &mut self,
+        kind: LinkKind,
+        target: &Path,
+        link: &Path,
+    ) -> anyhow::Result<PathBuf> {
+        let link = if link.is_dir() {
+            link.join(target.file_name().unwrap_or_default())
+        } else {
+            link.to_path_buf()
+        };
+        link::create(kind, target, &link)?;
+        self.files_changed();
+        Ok(link)
+    }
+
+    /// Keep a copy of the current results as the baseline for comparing later.
+    pub fn take_snapshot(&mut self) {
+        let results = self.query_results.load();
+        self.baseline = Some(QueryResults::clone(&results));
+    }
+
+    /// The ways out of no results for the last search, none for the merged ones.
+    pub fn suggestions(&self) -> Vec<Suggestion> {
+        if self.is_offline() || self.merge.is_some() {
+            return vec![];
+        }
+        suggest::suggestions(&self.last_search, self.filter.is_some(), self.scope)
+    }
+
+    /// Query the last search of no results again relaxed, see [`suggest::relax`]. False if it
+    /// can not be relaxed.
+    pub fn query_fallback(&mut self) -> anyhow::Result<bool> {
+        if self.is_offline() || self.merge.is_some() {
+            return Ok(false);
+        }
+        let Some(relaxed) = suggest::relax(&self.last_search) else {
+            return Ok(false);
+        };
+        let original = self.last_search.clone();
+        self.send_query(&relaxed)?;
+        self.fallback = Some(original);
+        Ok(true)
+    }
+
+    /// Take the suggestion and query again, the new search text returned.
+    pub fn apply_suggestion(&mut self, suggestion: Suggestion) -> anyhow::Result<String> {
+        match suggestion {
+            Suggestion::NoFilter => self.filter = None,
+            Suggestion::MatchPath => self.scope = Scope::Path,
+            _ => {}
+        }
+        let text = suggestion.rewrite(&self.last_search);
+        self.send_query(&text)?;
+        Ok(text)
+    }
+
+    /// Query the last results of the search, if more match than loaded, false otherwise. The
+    /// queries after it load from the first ones again.
+    pub fn query_last_results(&mut self) -> anyhow::Result<bool> {
+        let (offset, number, total) = {
+            let results = self.query_results.load();
+            (results.offset, results.number, results.total)
+        };
+        if self.is_offline() || self.merge.is_some() || offset + number >= total {
+            return Ok(false);
+        }
+        self.window_offset = total.saturating_sub(self.max_results);
+        self.requery()?;
+        Ok(true)
+    }
+
+    /// Load some pages of the results of the height by the queries. The search is queried
+    /// again if the pages grow beyond the results loaded, and more of them match.
+    pub fn set_page_height(&mut self, height: u16) -> anyhow::Result<()> {
+        let max = (u32::from(height) * PAGES_LOADED).max(MIN_RESULTS);
+        let is_short = {
+            let results = self.query_results.load();
+            results.offset == 0 && results.number < max.min(results.total)
+        };
+        let grows = max > self.max_results;
+        self.max_results = max;
+        if grows && is_short && !self.is_offline() {
+            self.requery()?;
+        }
+        Ok(())
+    }
+
+    /// Query the first results again, if the last ones are loaded, false otherwise.
+    pub fn query_first_results(&mut self) -> anyhow::Result<bool> {
+        if self.is_offline() || self.query_results.load().offset == 0 {
+            return Ok(false);
+        }
+        self.requery()?;
+        Ok(true)
+    }
+
+    /// Send the last query again, e.g. periodically in watch mode.
+    pub fn requery(&mut self) -> anyhow::Result<()> {
+        if self.last_search.is_empty() && self.merge.is_none() {
+            return Ok(());
+        }
+        let text = self.last_search.clone();
+        self.send_query(&text)
+    }
+
+    /// New entries found by watch mode since the last refresh of the same search.
+    pub fn watch_new_entries(&mut self) -> Vec<QueryEntry> {
+        let Some(watch) = self.watch.as_mut() else {
+            return vec![];
+        };
+        let results = self.query_results.load();
+        watch.update(&results)
+    }
+
+    /// Use the results of a snapshot file as the baseline for comparing.
+    pub fn load_baseline(&mut self, path: PathBuf) -> anyhow::Result<()> {
+        self.baseline = Some(Snapshot::load(path)?.results);
+        Ok(())
+    }
+
+    /// Save the current results as a snapshot file in the current directory, as a task in
+    /// the background.
+    pub fn save_snapshot(&mut self) {
+        let results = QueryResults::clone(&self.query_results.load());
+        let snapshot = Snapshot::new(results, self.status.version);
+        let path = snapshot.default_file_name();
+        self.spawn_export(path, move |path, context| {
+            snapshot.save(path, context)?;
+            Ok(tr!(SnapshotSaved, path = path.display()))
+        });
+    }
+
+    /// Save the full paths of the current results as a file list in the current directory, as
+    /// a task in the background.
+    pub fn save_file_list(&mut self) {
+        let created = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let path = PathBuf::from(format!("ery-{created}.txt"));
+        let results = self.query_results.load_full();
+        self.spawn_export(path, move |path, context| {
+            export::save_file_list(&results, path, context)?;
+            Ok(tr!(FileListSaved, path = path.display()))
+        });
+    }
+
+    /// Save into the file as a task, which returns the notice of it. The file is removed if it
+    /// fails or is canceled, not to leave a partial one.
+    fn spawn_export(
+        &mut self,
+        path: PathBuf,
+        save: impl FnOnce(&Path, &mut TaskContext) -> anyhow::Result<String> + Send + 'static,
+    ) {
+        let name = format::os_str(path.file_name().unwrap_or_default());
+        let title = format!("{} {name}", tr!(Saving));
+        self.tasks.spawn(
+            title,
+            None,
+            Unit::Bytes,
+            self.sender.clone(),
+            move |context| {
+                let saved = save(&path, context);
+                if saved.is_err() {
+                    let _ = fs::remove_file(&path);
+                }
+                saved
+            },
+        );
+    }
+
+    /// Diff the baseline snapshot (if any) against the current results.
+    pub fn diff_with_baseline(&self) -> Option<ResultsDiff> {
+        let baseline = self.baseline.as_ref()?;
+        let results = self.query_results.load();
+        Some(ResultsDiff::new(baseline, &results))
+    }
+}
+
+/// Number of the matches of the search, without fetching any of them. It talks to Everything
+/// directly, no everything thread is started, so it is fast for scripts and shell prompts.
+pub fn count(search: &str) -> anyhow::Result<u32> {
+    let mut everything = global().lock().unwrap();
+    anyhow::ensure!(
+        everything.is_db_loaded()?,
+        "the database of Everything is not loaded yet"
+    );
+    let mut searcher = everything.searcher();
+    searcher
+        .set_search(search)
+        .set_max(0)
+        .set_request_flags(RequestFlags::EVERYTHING_REQUEST_FILE_NAME);
+    let total = searcher.query().total();
+    Ok(total)
+}
+
+/// The status of Everything, asked directly without the everything thread, e.g. for
+/// `ery doctor`.
+pub fn status() -> anyhow::Result<Status> {
+    App::load_status(&*global().lock().unwrap())
+}
+
+/// Whether Everything runs as administrator, asked directly without the everything thread, e.g.
+/// for `--elevate` before anything is started.
+pub fn is_admin() -> anyhow::Result<bool> {
+    let everything = global().lock().unwrap();
+    EverythingClient::is_admin(&*everything)
+}
+
+/// Whether Everything keeps the sort fast, asked directly without the everything thread, e.g.
+/// for warning about the sort of `ery --no-tui`.
+pub fn is_fast_sort(sort_type: SortType) -> anyhow::Result<bool> {
+    let everything = global().lock().unwrap();
+    EverythingClient::is_fast_sort(&*everything, sort_type)
+}
+
+/// The results of the query, fetched from Everything directly without the everything thread,
+/// e.g. for `ery serve` answering the requests one by one.
+pub fn query(query: &Query) -> anyhow::Result<QueryResults> {
+    let mut everything = global().lock().unwrap();
+    anyhow::ensure!(
+        everything.is_db_loaded()?,
+        "the database of Everything is not loaded yet"
+    );
+    let start = Instant::now();
+    let results = EverythingClient::query(&mut *everything, query, |reply| {
+        client::convert(query, reply, start.elapsed())
+    });
+    Ok(results)
 }
@@ -0,0 +1,37 @@
+use crate::tr;
+
+/// The version of Everything with the features of [`Feature`], `(major, minor)`.
+pub const EVERYTHING_1_5: (u32, u32) = (1, 5);
+
+/// Features of Everything 1.5, not in 1.4, hidden or disabled in ery by the version of the
+/// running Everything, see [`super::Status::supports`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// `content:` searching the indexed contents, instead of reading every file as 1.4 does.
+    ContentIndex,
+}
+
+impl Feature {
+    /// The version of Everything with the feature, `(major, minor)`.
+    pub fn since(self) -> (u32, u32) {
+        match self {
+            Feature::ContentIndex => EVERYTHING_1_5,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Feature::ContentIndex => tr!(FeatureContentIndex),
+        }
+    }
+
+    /// The message of the feature invoked on an older Everything.
+    pub fn unsupported(self) -> String {
+        let (major, minor) = self.since();
+        tr!(
+            RequiresEverything,
+            feature = self.name(),
+            version = format!("{major}.{minor}")
+        )
+    }
+}
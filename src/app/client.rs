@@ -0,0 +1,466 @@
+pub mod mock;
+
+use std::{
+    ffi::OsString,
+    path::Path,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use everything_sdk::{EverythingGlobal, EverythingResults, FileInfoType, RequestFlags, SortType};
+
+use super::ery::{
+    item_to_entry, update_shared, PathInterner, Query, QueryEntry, QueryResults, QueryTimings,
+    Request,
+};
+use super::event::{AppEvent, AppSender};
+use super::sort::sort_secondary;
+
+/// The calls to Everything used by ery, by the SDK, or by a mock in the tests without
+/// Everything installed.
+pub trait EverythingClient {
+    fn is_db_loaded(&self) -> anyhow::Result<bool>;
+
+    /// `(major, minor, revision, build)`
+    fn version(&self) -> anyhow::Result<(u32, u32, u32, u32)>;
+
+    fn is_admin(&self) -> anyhow::Result<bool>;
+
+    fn is_appdata(&self) -> anyhow::Result<bool>;
+
+    fn is_file_info_indexed(&self, info: FileInfoType) -> anyhow::Result<bool>;
+
+    fn is_fast_sort(&self, sort_type: SortType) -> anyhow::Result<bool>;
+
+    fn inc_run_count(&mut self, path: &Path) -> anyhow::Result<()>;
+
+    /// Query by the options of the query, the reply is alive in `f` only, e.g. for the
+    /// details of the items.
+    fn query<R>(&mut self, query: &Query, f: impl FnOnce(&dyn Reply) -> R) -> R;
+}
+
+/// The reply of a query, the items of it converted when needed.
+pub trait Reply {
+    /// The search as Everything takes it.
+    fn search(&self) -> OsString;
+
+    fn number(&self) -> u32;
+
+    fn total(&self) -> u32;
+
+    fn request_flags(&self) -> RequestFlags;
+
+    fn sort_type(&self) -> SortType;
+
+    /// The item at the index of the results, only the fields of `flags` converted.
+    fn entry(
+        &self,
+        index: u32,
+        flags: RequestFlags,
+        paths: &mut PathInterner,
+    ) -> Option<QueryEntry>;
+}
+
+impl EverythingClient for EverythingGlobal {
+    fn is_db_loaded(&self) -> anyhow::Result<bool> {
+        Ok(EverythingGlobal::is_db_loaded(self)?)
+    }
+
+    fn version(&self) -> anyhow::Result<(u32, u32, u32, u32)> {
+        let (major, minor, revision, build, _target) = EverythingGlobal::version(self)?;
+        Ok((major, minor, revision, build))
+    }
+
+    fn is_admin(&self) -> anyhow::Result<bool> {
+        Ok(EverythingGlobal::is_admin(self)?)
+    }
+
+    fn is_appdata(&self) -> anyhow::Result<bool> {
+        Ok(EverythingGlobal::is_appdata(self)?)
+    }
+
+    fn is_file_info_indexed(&self, info: FileInfoType) -> anyhow::Result<bool> {
+        Ok(EverythingGlobal::is_file_info_indexed(self, info)?)
+    }
+
+    fn is_fast_sort(&self, sort_type: SortType) -> anyhow::Result<bool> {
+        Ok(EverythingGlobal::is_fast_sort(self, sort_type)?)
+    }
+
+    fn inc_run_count(&mut self, path: &Path) -> anyhow::Result<()> {
+        self.inc_run_count_from_filename(path)?;
+        Ok(())
+    }
+
+    fn query<R>(&mut self, query: &Query, f: impl FnOnce(&dyn Reply) -> R) -> R {
+        let mut searcher = self.searcher();
+        searcher
+            .set_search(&query.search)
+            .set_match_path(query.match_path)
+            .set_match_case(query.match_case)
+            .set_match_whole_word(query.match_whole_word)
+            .set_regex(query.regex)
+            .set_max(query.max)
+            .set_offset(query.offset)
+            .set_sort(query.sort_type)
+            .set_request_flags(query.request_flags);
+        let search = searcher.get_search();
+        let results = searcher.query();
+        f(&SdkReply { search, results })
+    }
+}
+
+struct SdkReply<'a> {
+    search: OsString,
+    results: EverythingResults<'a>,
+}
+
+impl Reply for SdkReply<'_> {
+    fn search(&self) -> OsString {
+        self.search.clone()
+    }
+
+    fn number(&self) -> u32 {
+        self.results.num()
+    }
+
+    fn total(&self) -> u32 {
+        self.results.total()
+    }
+
+    fn request_flags(&self) -> RequestFlags {
+        self.results.request_flags()
+    }
+
+    fn sort_type(&self) -> SortType {
+        self.results.sort_type()
+    }
+
+    fn entry(
+        &self,
+        index: u32,
+        flags: RequestFlags,
+        paths: &mut PathInterner,
+    ) -> Option<QueryEntry> {
+        let item = self.results.at(index)?;
+        Some(item_to_entry(item, flags, paths))
+    }
+}
+
+/// The everything thread, doing the requests one by one until the app is gone.
+pub fn serve(
+    client: &mut impl EverythingClient,
+    requests: mpsc::Receiver<Request>,
+    refresh_sender: AppSender,
+) {
+    let mut pending = None;
+    while let Some(request) = pending.take().or_else(|| requests.recv().ok()) {
+        match request {
+            // the one which submitted it may be gone, e.g. it was canceled.
+            Request::Query { query, back } if query.search.is_empty() => {
+                // do not send IPC search, return empty result
+                let empty_result = QueryResults {
+                    id: query.id,
+                    ..Default::default()
+                };
+                let _ = back.send(empty_result);
+            }
+            Request::Query { query, back } => {
+                let start = Instant::now();
+                pending = client.query(&query, |reply| {
+                    let ipc = start.elapsed();
+                    let results = convert(&query, reply, ipc);
+                    let _ = back.send(results);
+                    // keep the results alive for the details, until any other request
+                    serve_details(reply, query.id, &requests, &refresh_sender)
+                });
+            }
+            // the results of it are gone already
+            Request::Details { .. } => {}
+            Request::IncRunCount(path, done) => {
+                // it's fine to fail, e.g. the file has been deleted just now.
+                let _ = client.inc_run_count(&path);
+                let _ = done.send(());
+            }
+        }
+    }
+}
+
+/// The results of the reply, only the eager fields, the others are converted when needed.
+pub(super) fn convert(query: &Query, reply: &dyn Reply, ipc: Duration) -> QueryResults {
+    let flags = reply.request_flags();
+    let is_partial = flags.intersects(query.lazy_flags);
+    let eager_flags = flags.difference(query.lazy_flags);
+    let start = Instant::now();
+    let mut paths = PathInterner::default();
+    let entrys: Vec<_> = (0..reply.number())
+        .filter_map(|index| reply.entry(index, eager_flags, &mut paths))
+        .map(|entry| QueryEntry {
+            is_partial,
+            ..entry
+        })
+        .collect();
+    let conversion = start.elapsed();
+    let mut results = QueryResults {
+        id: query.id,
+        search: reply.search(),
+        offset: query.offset,
+        number: reply.number(),
+        total: reply.total(),
+        request_flags: flags,
+        sort_type: reply.sort_type(),
+        entrys,
+        paths,
+        timings: QueryTimings { ipc, conversion },
+    };
+    if let Some(key) = query.secondary_sort {
+        sort_secondary(&mut results, key);
+    }
+    results
+}
+
+/// Answer the [`Request::Details`] of the alive results of the query `id`, until any other
+/// request comes, which is returned. None if all the senders are gone.
+fn serve_details(
+    reply: &dyn Reply,
+    id: u64,
+    requests: &mpsc::Receiver<Request>,
+    refresh_sender: &AppSender,
+) -> Option<Request> {
+    loop {
+        match requests.recv().ok()? {
+            Request::Details {
+                id: details_id,
+                index,
+                target,
+            } if details_id == id => {
+                let is_converted = update_shared(&target, |target| {
+                    let Some(entry) = reply.entry(index, reply.request_flags(), &mut target.paths)
+                    else {
+                        return false;
+                    };
+                    if let Some(x) = target.entrys.iter_mut().find(|x| x.index == index) {
+                        *x = entry;
+                    }
+                    true
+                });
+                if is_converted {
+                    refresh_sender.send(AppEvent::Refresh);
+                }
+            }
+            // of the results before
+            Request::Details { .. } => {}
+            request => return Some(request),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use arc_swap::ArcSwap;
+
+    use super::mock::{entry, MockClient};
+    use super::*;
+    use crate::app::App;
+
+    /// Serve the requests until all done, and the refresh events. The queries are built by
+    /// `search` with where their results are sent back.
+    fn serve_all(
+        client: &mut MockClient,
+        requests: impl FnOnce(&dyn Fn(Query) -> Request) -> Vec<Request>,
+    ) -> (Vec<QueryResults>, usize) {
+        let (sender, receiver) = mpsc::channel();
+        let (back, back_receiver) = mpsc::channel();
+        let (refresh_sender, refresh_receiver) = mpsc::channel::<AppEvent>();
+        let search = |query| Request::Query {
+            query,
+            back: back.clone(),
+        };
+        for request in requests(&search) {
+            sender.send(request).unwrap();
+        }
+        drop(sender);
+        drop(back);
+        serve(client, receiver, refresh_sender.into());
+        (
+            back_receiver.try_iter().collect(),
+            refresh_receiver.try_iter().count(),
+        )
+    }
+
+    fn query(id: u64, search: &str) -> Query {
+        Query {
+            id,
+            search: search.to_string(),
+            max: 512,
+            sort_type: SortType::EVERYTHING_SORT_SIZE_DESCENDING,
+            request_flags: RequestFlags::EVERYTHING_REQUEST_FILE_NAME
+                | RequestFlags::EVERYTHING_REQUEST_PATH
+                | RequestFlags::EVERYTHING_REQUEST_SIZE,
+            ..Default::default()
+        }
+    }
+
+    const FILES: &[(&str, u64)] = &[
+        (r"C:\data\report.docx", 100),
+        (r"C:\data\notes.txt", 20),
+        (r"C:\data\report.pdf", 300),
+    ];
+
+    #[test]
+    fn status_is_loaded() {
+        let client = MockClient {
+            fast_sorts: vec![SortType::EVERYTHING_SORT_SIZE_ASCENDING],
+            ..Default::default()
+        };
+        let status = App::load_status(&client).unwrap();
+        assert!(status.is_db_loaded);
+        assert_eq!(status.version, (1, 4, 1, 1024));
+        assert!(!status.is_admin);
+        assert!(status.is_appdata);
+        assert!(status.is_file_size_indexed);
+        assert!(!status.is_folder_size_indexed);
+        assert!(status.is_size_fast_sort);
+        assert!(!status.is_path_fast_sort);
+    }
+
+    #[test]
+    fn status_fails_without_everything() {
+        let client = MockClient {
+            offline: true,
+            ..Default::default()
+        };
+        let e = App::load_status(&client).unwrap_err();
+        assert_eq!(e.to_string(), "Everything is not running");
+    }
+
+    #[test]
+    fn empty_search_is_not_sent() {
+        let mut client = MockClient::with_files(FILES);
+        let (results, _) = serve_all(&mut client, |search| vec![search(query(1, ""))]);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, 1);
+        assert!(results[0].entrys.is_empty());
+        assert!(client.queries.is_empty());
+    }
+
+    #[test]
+    fn query_is_sent_with_its_options() {
+        let mut client = MockClient::with_files(FILES);
+        let (results, _) = serve_all(&mut client, |search| vec![search(query(1, "report"))]);
+        let (search, max, sort_type, request_flags) = &client.queries[0];
+        assert_eq!(search, "report");
+        assert_eq!(*max, 512);
+        assert_eq!(*sort_type, SortType::EVERYTHING_SORT_SIZE_DESCENDING);
+        assert!(request_flags.contains(RequestFlags::EVERYTHING_REQUEST_SIZE));
+
+        let results = &results[0];
+        assert_eq!(results.id, 1);
+        assert_eq!(results.search, OsString::from("report"));
+        assert_eq!((results.number, results.total), (2, 2));
+        let names: Vec<_> = results
+            .entrys
+            .iter()
+            .map(|entry| entry.filename.clone().unwrap())
+            .collect();
+        assert_eq!(names, ["report.docx", "report.pdf"]);
+        let path = results.filepath(&results.entrys[0]).unwrap();
+        assert_eq!(path, Path::new(r"C:\data\report.docx"));
+    }
+
+    #[test]
+    fn lazy_fields_are_not_converted() {
+        let mut client = MockClient::with_files(FILES);
+        let query = Query {
+            lazy_flags: RequestFlags::EVERYTHING_REQUEST_SIZE,
+            ..query(1, "report")
+        };
+        let (results, _) = serve_all(&mut client, |search| vec![search(query)]);
+        let entry = &results[0].entrys[0];
+        assert!(entry.is_partial);
+        assert!(entry.filename.is_some());
+        assert_eq!(entry.size, None);
+        // requested still, for the details later
+        assert!(results[0]
+            .request_flags
+            .contains(RequestFlags::EVERYTHING_REQUEST_SIZE));
+    }
+
+    #[test]
+    fn details_are_converted_in_full() {
+        let mut client = MockClient::with_files(FILES);
+        let query = Query {
+            lazy_flags: RequestFlags::EVERYTHING_REQUEST_SIZE,
+            ..query(2, "report")
+        };
+        let target = Arc::new(ArcSwap::from_pointee(QueryResults {
+            entrys: vec![entry(0), entry(1)],
+            ..Default::default()
+        }));
+        let details = |id, index| Request::Details {
+            id,
+            index,
+            target: Arc::clone(&target),
+        };
+        let (_, refreshes) = serve_all(&mut client, |search| {
+            vec![search(query), details(2, 1), details(1, 0)]
+        });
+        let target = target.load();
+        assert_eq!(target.entrys[1].size, Some(300));
+        assert!(!target.entrys[1].is_partial);
+        // the details of an older query are ignored
+        assert_eq!(target.entrys[0].size, None);
+        assert_eq!(refreshes, 1);
+    }
+
+    #[test]
+    fn run_count_fails_quietly() {
+        let path = PathBuf::from(r"C:\data\notes.txt");
+        let mut client = MockClient::with_files(FILES);
+        let (done, done_receiver) = mpsc::channel();
+        serve_all(&mut client, |_| {
+            vec![Request::IncRunCount(path.clone(), done)]
+        });
+        assert_eq!(client.run_counts, std::slice::from_ref(&path));
+        assert!(done_receiver.try_recv().is_ok());
+
+        let mut client = MockClient {
+            offline: true,
+            ..MockClient::with_files(FILES)
+        };
+        let (results, _) = serve_all(&mut client, |search| {
+            let (done, _) = mpsc::channel();
+            vec![Request::IncRunCount(path, done), search(query(1, "notes"))]
+        });
+        assert!(client.run_counts.is_empty());
+        assert_eq!(results[0].number, 1);
+    }
+
+    #[test]
+    fn results_are_sent_back_to_their_query() {
+        let mut client = MockClient::with_files(FILES);
+        let (sender, receiver) = mpsc::channel();
+        let (report_back, report_results) = mpsc::channel();
+        let (count_back, count_results) = mpsc::channel();
+        for (query, back) in [
+            (query(1, "report"), report_back),
+            (query(2, "notes"), count_back),
+        ] {
+            sender.send(Request::Query { query, back }).unwrap();
+        }
+        drop(sender);
+        let (refresh_sender, _) = mpsc::channel::<AppEvent>();
+        serve(&mut client, receiver, refresh_sender.into());
+        let ids = |results: mpsc::Receiver<QueryResults>| {
+            results
+                .try_iter()
+                .map(|results| results.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(report_results), [1]);
+        assert_eq!(ids(count_results), [2]);
+    }
+}
@@ -0,0 +1,198 @@
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+use everything_sdk::{FileInfoType, RequestFlags, SortType};
+
+use super::{EverythingClient, Reply};
+use crate::app::ery::{PathInterner, Query, QueryEntry};
+
+/// Everything with the given files, answering like Everything 1.4.1.1024 does roughly, for the
+/// tests without Everything installed.
+#[derive(Debug, Default)]
+pub struct MockClient {
+    /// The files indexed, as `(path, size)`.
+    pub files: Vec<(PathBuf, u64)>,
+    pub fast_sorts: Vec<SortType>,
+    /// Everything is not running, every call fails but the queries.
+    pub offline: bool,
+    /// The queries received, as `(search, max, sort_type, request_flags)`.
+    pub queries: Vec<(String, u32, SortType, RequestFlags)>,
+    pub run_counts: Vec<PathBuf>,
+    /// How long a query takes, e.g. a slow sort without fast sort.
+    pub delay: Duration,
+}
+
+impl MockClient {
+    /// Everything with the files, as `(path, size)`.
+    pub fn with_files(files: &[(&str, u64)]) -> Self {
+        Self {
+            files: files
+                .iter()
+                .map(|&(path, size)| (PathBuf::from(path), size))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn call<T>(&self, value: T) -> anyhow::Result<T> {
+        anyhow::ensure!(!self.offline, "Everything is not running");
+        Ok(value)
+    }
+
+    /// Whether the file is matched by the search, as a plain text only.
+    fn matches(query: &Query, path: &Path) -> bool {
+        let text = if query.match_path {
+            path.as_os_str()
+        } else {
+            path.file_name().unwrap_or_default()
+        };
+        let text = text.to_string_lossy();
+        if query.match_case {
+            text.contains(&query.search)
+        } else {
+            text.to_lowercase().contains(&query.search.to_lowercase())
+        }
+    }
+}
+
+impl EverythingClient for MockClient {
+    fn is_db_loaded(&self) -> anyhow::Result<bool> {
+        self.call(true)
+    }
+
+    fn version(&self) -> anyhow::Result<(u32, u32, u32, u32)> {
+        self.call((1, 4, 1, 1024))
+    }
+
+    fn is_admin(&self) -> anyhow::Result<bool> {
+        self.call(false)
+    }
+
+    fn is_appdata(&self) -> anyhow::Result<bool> {
+        self.call(true)
+    }
+
+    fn is_file_info_indexed(&self, info: FileInfoType) -> anyhow::Result<bool> {
+        self.call(info == FileInfoType::EVERYTHING_IPC_FILE_INFO_FILE_SIZE)
+    }
+
+    fn is_fast_sort(&self, sort_type: SortType) -> anyhow::Result<bool> {
+        self.call(self.fast_sorts.contains(&sort_type))
+    }
+
+    fn inc_run_count(&mut self, path: &Path) -> anyhow::Result<()> {
+        self.call(())?;
+        self.run_counts.push(path.to_path_buf());
+        Ok(())
+    }
+
+    fn query<R>(&mut self, query: &Query, f: impl FnOnce(&dyn Reply) -> R) -> R {
+        thread::sleep(self.delay);
+        self.queries.push((
+            query.search.clone(),
+            query.max,
+            query.sort_type,
+            query.request_flags,
+        ));
+        let matched: Vec<_> = self
+            .files
+            .iter()
+            .filter(|(path, _)| Self::matches(query, path))
+            .collect();
+        let files = matched
+            .iter()
+            .skip(query.offset as usize)
+            .take(query.max as usize)
+            .map(|&file| file.clone())
+            .collect();
+        f(&MockReply {
+            search: query.search.clone(),
+            files,
+            total: matched.len() as u32,
+            request_flags: query.request_flags,
+            sort_type: query.sort_type,
+        })
+    }
+}
+
+struct MockReply {
+    search: String,
+    files: Vec<(PathBuf, u64)>,
+    total: u32,
+    request_flags: RequestFlags,
+    sort_type: SortType,
+}
+
+impl Reply for MockReply {
+    fn search(&self) -> OsString {
+        self.search.clone().into()
+    }
+
+    fn number(&self) -> u32 {
+        self.files.len() as u32
+    }
+
+    fn total(&self) -> u32 {
+        self.total
+    }
+
+    fn request_flags(&self) -> RequestFlags {
+        self.request_flags
+    }
+
+    fn sort_type(&self) -> SortType {
+        self.sort_type
+    }
+
+    fn entry(
+        &self,
+        index: u32,
+        flags: RequestFlags,
+        paths: &mut PathInterner,
+    ) -> Option<QueryEntry> {
+        let (path, size) = self.files.get(index as usize)?;
+        let mut entry = entry(index);
+        if flags.contains(RequestFlags::EVERYTHING_REQUEST_FILE_NAME) {
+            entry.filename = path.file_name().map(Into::into);
+        }
+        if flags.contains(RequestFlags::EVERYTHING_REQUEST_PATH) {
+            let folder = path.parent().unwrap_or(path);
+            entry.path = Some(paths.intern(folder.to_path_buf()));
+        }
+        if flags.contains(RequestFlags::EVERYTHING_REQUEST_SIZE) {
+            entry.size = Some(*size);
+        }
+        Some(entry)
+    }
+}
+
+/// A file at the index with none of the fields requested.
+pub fn entry(index: u32) -> QueryEntry {
+    QueryEntry {
+        index,
+        is_volume: false,
+        is_folder: false,
+        is_file: true,
+        filename: None,
+        path: None,
+        full_path_name: None,
+        extension: None,
+        size: None,
+        date_created: None,
+        date_modified: None,
+        date_accessed: None,
+        attributes: None,
+        file_list_filename: None,
+        run_count: None,
+        date_run: None,
+        date_recently_changed: None,
+        highlighted_filename: None,
+        highlighted_path: None,
+        highlighted_full_path_and_filename: None,
+        is_partial: false,
+    }
+}
@@ -0,0 +1,162 @@
+use windows::Win32::{
+    Foundation::{HANDLE, HGLOBAL, HWND},
+    System::{
+        DataExchange::{CloseClipboard, GetClipboardData, OpenClipboard},
+        Memory::{GlobalLock, GlobalUnlock},
+        Ole::CF_UNICODETEXT,
+    },
+};
+
+use super::location;
+
+/// The quotes around the paths copied, e.g. by "Copy as path" of Explorer or from the code.
+const QUOTES: [char; 4] = ['"', '\'', '`', '\u{2019}'];
+
+/// The chars before the file names in the messages, e.g. `(main.rs:12)`.
+const OPENINGS: [char; 3] = ['(', '[', '<'];
+
+/// The chars after the file names in the messages, e.g. `main.rs,` or `main.rs:`.
+const CLOSINGS: [char; 7] = [')', ']', '>', ',', ';', ':', '.'];
+
+/// The text in the clipboard, empty if it has no text.
+pub fn read_text() -> anyhow::Result<String> {
+    unsafe {
+        OpenClipboard(HWND::default())?;
+        let text = read_unicode_text();
+        let _ = CloseClipboard();
+        text
+    }
+}
+
+unsafe fn read_unicode_text() -> anyhow::Result<String> {
+    let Ok(HANDLE(data)) = GetClipboardData(CF_UNICODETEXT.0 as u32) else {
+        return Ok(String::new());
+    };
+    let memory = HGLOBAL(data);
+    let text = GlobalLock(memory) as *const u16;
+    anyhow::ensure!(!text.is_null(), "failed to read the clipboard");
+    let mut len = 0;
+    while *text.add(len) != 0 {
+        len += 1;
+    }
+    let string = String::from_utf16_lossy(std::slice::from_raw_parts(text, len));
+    let _ = GlobalUnlock(memory);
+    Ok(string)
+}
+
+/// The search of the text copied, none if nothing is left of it.
+///
+/// The first line of it, without the quotes and the spaces around. The name of the file only if
+/// it is a path, e.g. `app.rs:12` of `"C:\ery\src\app.rs:12"`, and the one in the message if any,
+/// e.g. `main.rs` of `error: could not compile main.rs.`.
+pub fn to_search(text: &str) -> Option<String> {
+    let line = text.lines().map(str::trim).find(|line| !line.is_empty())?;
+    let line = line.trim_matches(QUOTES).trim();
+    let text = if is_path(line) {
+        line
+    } else if line.contains(char::is_whitespace) {
+        line.split_whitespace()
+            .map(|word| {
+                word.trim_start_matches(OPENINGS)
+                    .trim_end_matches(CLOSINGS)
+                    .trim_matches(QUOTES)
+            })
+            .find(|word| is_path(word) || is_file_name(word))
+            .unwrap_or(line)
+    } else {
+        line
+    };
+    let search = if is_path(text) {
+        let (path, location) = location::split(text);
+        let name = path
+            .trim_end_matches(['\\', '/'])
+            .rsplit(['\\', '/'])
+            .next()
+            .unwrap_or_default();
+        // the rest after the name is the location, e.g. `:12:5`
+        format!("{name}{}", location.map_or("", |_| &text[path.len()..]))
+    } else {
+        text.to_string()
+    };
+    Some(search).filter(|search| !search.is_empty())
+}
+
+/// Like `C:\ery\app.rs`, `src/app.rs` or `\\server\share`, the spaces allowed in it.
+fn is_path(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    let has_drive = bytes.len() >= 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && matches!(bytes[2], b'\\' | b'/');
+    (has_drive || text.starts_with(['\\', '/', '.']) || !text.contains(char::is_whitespace))
+        && text.contains(['\\', '/'])
+        && !text.contains("://")
+}
+
+/// Like `main.rs` or `main.rs:12`, an extension of letters or digits after a dot.
+fn is_file_name(word: &str) -> bool {
+    let (name, _) = location::split(word);
+    name.rsplit_once('.').is_some_and(|(stem, extension)| {
+        !stem.is_empty()
+            && !extension.is_empty()
+            && extension.len() <= 8
+            && extension.chars().all(|c| c.is_ascii_alphanumeric())
+            && extension.chars().any(|c| c.is_ascii_alphabetic())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_line_of_text_is_searched() {
+        assert_eq!(
+            to_search("\r\n  \r\nmain.rs\r\nlib.rs").as_deref(),
+            Some("main.rs")
+        );
+        assert_eq!(to_search("  \n\t").as_deref(), None);
+        assert_eq!(to_search("").as_deref(), None);
+    }
+
+    #[test]
+    fn quotes_around_are_dropped() {
+        assert_eq!(
+            to_search(r#""C:\ery\src\app.rs:12""#).as_deref(),
+            Some("app.rs:12")
+        );
+        assert_eq!(to_search("'notes.md'").as_deref(), Some("notes.md"));
+        assert_eq!(to_search("`build.rs`").as_deref(), Some("build.rs"));
+    }
+
+    #[test]
+    fn names_of_the_paths_are_searched() {
+        assert_eq!(to_search(r"C:\ery\src\").as_deref(), Some("src"));
+        assert_eq!(to_search("src/app.rs:12:5").as_deref(), Some("app.rs:12:5"));
+        // the spaces of the path kept, Everything matches all the words in the name
+        assert_eq!(
+            to_search(r#""C:\Program Files\ery\read me.txt""#).as_deref(),
+            Some("read me.txt")
+        );
+        assert_eq!(
+            to_search(r"D:\my docs\plan v2.md:3").as_deref(),
+            Some("plan v2.md:3")
+        );
+    }
+
+    #[test]
+    fn names_in_the_messages_are_searched() {
+        assert_eq!(
+            to_search("error: could not compile main.rs.").as_deref(),
+            Some("main.rs")
+        );
+        assert_eq!(
+            to_search("panicked at (src/app.rs:12:5)").as_deref(),
+            Some("app.rs:12:5")
+        );
+        assert_eq!(
+            to_search("just some words").as_deref(),
+            Some("just some words")
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use super::ery::{QueryEntry, QueryResults};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    /// in the current results only.
+    Added,
+    /// in the baseline only.
+    Removed,
+    /// in both, but the size or the date modified differs.
+    Changed,
+}
+
+#[derive(Debug)]
+pub struct DiffEntry {
+    pub kind: DiffKind,
+    /// the path id of it refers to the results it comes from, use `path` instead.
+    pub entry: QueryEntry,
+    /// parent folder of the entry.
+    pub path: Option<PathBuf>,
+}
+
+impl DiffEntry {
+    fn new(kind: DiffKind, entry: &QueryEntry, results: &QueryResults) -> Self {
+        Self {
+            kind,
+            entry: entry.clone(),
+            path: results.path(entry).map(Path::to_path_buf),
+        }
+    }
+}
+
+/// Entries added/removed/changed between a baseline snapshot and the current results.
+///
+/// Entries are matched by their full path. Note that both sides are limited by the `max`
+/// of their queries, so an entry out of the loaded window will be reported as removed/added.
+#[derive(Debug, Default)]
+pub struct ResultsDiff {
+    pub baseline_search: OsString,
+    pub current_search: OsString,
+    pub entrys: Vec<DiffEntry>,
+}
+
+impl ResultsDiff {
+    pub fn new(baseline: &QueryResults, current: &QueryResults) -> Self {
+        let mut old: HashMap<PathBuf, &QueryEntry> = baseline
+            .entrys
+            .iter()
+            .filter_map(|entry| Some((entry_key(entry, baseline)?, entry)))
+            .collect();
+
+        let mut entrys = vec![];
+        for entry in current.entrys.iter() {
+            let Some(key) = entry_key(entry, current) else {
+                continue;
+            };
+            match old.remove(&key) {
+                None => entrys.push(DiffEntry::new(DiffKind::Added, entry, current)),
+                Some(old_entry) if is_changed(old_entry, entry) => {
+                    entrys.push(DiffEntry::new(DiffKind::Changed, entry, current))
+                }
+                Some(_) => {}
+            }
+        }
+        // keep the removed ones in the baseline order
+        for entry in baseline.entrys.iter() {
+            if entry_key(entry, baseline).is_some_and(|key| old.contains_key(&key)) {
+                entrys.push(DiffEntry::new(DiffKind::Removed, entry, baseline));
+            }
+        }
+
+        Self {
+            baseline_search: baseline.search.clone(),
+            current_search: current.search.clone(),
+            entrys,
+        }
+    }
+
+    pub fn count(&self, kind: DiffKind) -> usize {
+        self.entrys.iter().filter(|x| x.kind == kind).count()
+    }
+}
+
+fn entry_key(entry: &QueryEntry, results: &QueryResults) -> Option<PathBuf> {
+    results
+        .filepath(entry)
+        .or_else(|| entry.full_path_name.clone())
+}
+
+fn is_changed(old: &QueryEntry, new: &QueryEntry) -> bool {
+    old.size != new.size || old.date_modified != new.date_modified
+}
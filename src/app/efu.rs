@@ -0,0 +1,119 @@
+use std::{
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use everything_sdk::RequestFlags;
+
+use super::ery::{QueryEntry, QueryResults};
+
+/// FILE_ATTRIBUTE_DIRECTORY
+const ATTRIBUTE_DIRECTORY: u32 = 0x10;
+
+/// Load an Everything file list (*.efu) as the results, to browse without Everything.
+///
+/// It is a CSV of `Filename,Size,Date Modified,Date Created,Attributes`, the dates in FILETIME
+/// and the attributes in decimal, every column but `Filename` optional.
+pub fn load(path: &Path) -> anyhow::Result<QueryResults> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("failed to read file list {}", path.display()))?;
+    let mut lines = text.trim_start_matches('\u{feff}').lines();
+    let header = split_csv(lines.next().unwrap_or_default());
+    let column = |name: &str| header.iter().position(|h| h.eq_ignore_ascii_case(name));
+    let filename = column("Filename")
+        .with_context(|| format!("no Filename column in file list {}", path.display()))?;
+    let size = column("Size");
+    let date_modified = column("Date Modified");
+    let date_created = column("Date Created");
+    let attributes = column("Attributes");
+
+    let mut request_flags = RequestFlags::EVERYTHING_REQUEST_FILE_NAME
+        | RequestFlags::EVERYTHING_REQUEST_PATH
+        | RequestFlags::EVERYTHING_REQUEST_FILE_LIST_FILE_NAME;
+    for (column, flag) in [
+        (size, RequestFlags::EVERYTHING_REQUEST_SIZE),
+        (
+            date_modified,
+            RequestFlags::EVERYTHING_REQUEST_DATE_MODIFIED,
+        ),
+        (date_created, RequestFlags::EVERYTHING_REQUEST_DATE_CREATED),
+        (attributes, RequestFlags::EVERYTHING_REQUEST_ATTRIBUTES),
+    ] {
+        if column.is_some() {
+            request_flags |= flag;
+        }
+    }
+
+    let list_name: OsString = path.file_name().unwrap_or_default().into();
+    let mut results = QueryResults {
+        search: list_name.clone(),
+        request_flags,
+        ..Default::default()
+    };
+    for (i, line) in lines.enumerate() {
+        let fields = split_csv(line);
+        let field = |column: Option<usize>| column.and_then(|i| fields.get(i)?.parse::<u64>().ok());
+        let Some(full_path) = fields.get(filename).filter(|name| !name.is_empty()) else {
+            continue;
+        };
+        let full_path = PathBuf::from(full_path.trim_end_matches('\\'));
+        let Some(name) = full_path.file_name() else {
+            // a drive, e.g. `C:`
+            continue;
+        };
+        let attributes = field(attributes).map(|attributes| attributes as u32);
+        let is_folder = attributes.is_some_and(|attributes| attributes & ATTRIBUTE_DIRECTORY != 0);
+        let entry = QueryEntry {
+            index: i as u32,
+            is_volume: false,
+            is_folder,
+            is_file: !is_folder,
+            filename: Some(name.to_os_string()),
+            path: full_path
+                .parent()
+                .map(|parent| results.paths.intern(parent.to_path_buf())),
+            full_path_name: None,
+            extension: None,
+            size: field(size),
+            date_created: field(date_created),
+            date_modified: field(date_modified),
+            date_accessed: None,
+            attributes,
+            file_list_filename: Some(list_name.clone()),
+            run_count: None,
+            date_run: None,
+            date_recently_changed: None,
+            highlighted_filename: None,
+            highlighted_path: None,
+            highlighted_full_path_and_filename: None,
+            is_partial: false,
+        };
+        results.entrys.push(entry);
+    }
+    results.number = results.entrys.len() as u32;
+    results.total = results.number;
+    Ok(results)
+}
+
+/// Fields of a CSV line, quoted with `"` and `""` for a quote in them.
+pub(super) fn split_csv(line: &str) -> Vec<String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
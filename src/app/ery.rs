@@ -1,9 +1,73 @@
-use std::{ffi::OsString, path::PathBuf};
+use std::{
+    collections::HashMap,
+    ffi::{OsStr, OsString},
+    fmt,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc},
+    time::Duration,
+};
 
+use arc_swap::ArcSwap;
 use everything_sdk::{EverythingItem, RequestFlags, SortType};
+use serde::{Deserialize, Serialize};
+
+use super::sort::SortKey;
+
+/// All the sort types of Everything, in the order of their values.
+pub const SORT_TYPES: [SortType; 26] = [
+    SortType::EVERYTHING_SORT_NAME_ASCENDING,
+    SortType::EVERYTHING_SORT_NAME_DESCENDING,
+    SortType::EVERYTHING_SORT_PATH_ASCENDING,
+    SortType::EVERYTHING_SORT_PATH_DESCENDING,
+    SortType::EVERYTHING_SORT_SIZE_ASCENDING,
+    SortType::EVERYTHING_SORT_SIZE_DESCENDING,
+    SortType::EVERYTHING_SORT_EXTENSION_ASCENDING,
+    SortType::EVERYTHING_SORT_EXTENSION_DESCENDING,
+    SortType::EVERYTHING_SORT_TYPE_NAME_ASCENDING,
+    SortType::EVERYTHING_SORT_TYPE_NAME_DESCENDING,
+    SortType::EVERYTHING_SORT_DATE_CREATED_ASCENDING,
+    SortType::EVERYTHING_SORT_DATE_CREATED_DESCENDING,
+    SortType::EVERYTHING_SORT_DATE_MODIFIED_ASCENDING,
+    SortType::EVERYTHING_SORT_DATE_MODIFIED_DESCENDING,
+    SortType::EVERYTHING_SORT_ATTRIBUTES_ASCENDING,
+    SortType::EVERYTHING_SORT_ATTRIBUTES_DESCENDING,
+    SortType::EVERYTHING_SORT_FILE_LIST_FILENAME_ASCENDING,
+    SortType::EVERYTHING_SORT_FILE_LIST_FILENAME_DESCENDING,
+    SortType::EVERYTHING_SORT_RUN_COUNT_ASCENDING,
+    SortType::EVERYTHING_SORT_RUN_COUNT_DESCENDING,
+    SortType::EVERYTHING_SORT_DATE_RECENTLY_CHANGED_ASCENDING,
+    SortType::EVERYTHING_SORT_DATE_RECENTLY_CHANGED_DESCENDING,
+    SortType::EVERYTHING_SORT_DATE_ACCESSED_ASCENDING,
+    SortType::EVERYTHING_SORT_DATE_ACCESSED_DESCENDING,
+    SortType::EVERYTHING_SORT_DATE_RUN_ASCENDING,
+    SortType::EVERYTHING_SORT_DATE_RUN_DESCENDING,
+];
+
+/// Requests handled by the everything thread, which holds the global Everything lock.
+#[derive(Debug)]
+pub enum Request {
+    /// search, and send the results back on `back`, to the one which submitted it only.
+    Query {
+        query: Query,
+        back: mpsc::Sender<QueryResults>,
+    },
+    /// convert all the requested fields of the entry at `index` of the last results (the
+    /// [`QueryEntry::index`]), then put it into `target` in place, if the last query is still
+    /// the query `id`.
+    Details {
+        id: u64,
+        index: u32,
+        target: SharedResults,
+    },
+    /// increase the run count of the file in Everything, e.g. after launching it, then tell it
+    /// is done on the sender.
+    IncRunCount(PathBuf, mpsc::Sender<()>),
+}
 
 #[derive(Debug)]
 pub struct Query {
+    /// increasing id of the queries, tagged on the results.
+    pub id: u64,
     pub search: String,
     pub match_path: bool,
     pub match_case: bool,
@@ -13,11 +77,16 @@ pub struct Query {
     pub offset: u32,
     pub sort_type: SortType,
     pub request_flags: RequestFlags,
+    /// requested fields not converted with the results, but fetched by [`Request::Details`].
+    pub lazy_flags: RequestFlags,
+    /// sort the results by it in the same primary sort, see [`super::sort::sort_secondary`].
+    pub secondary_sort: Option<SortKey>,
 }
 
 impl Default for Query {
     fn default() -> Self {
         Self {
+            id: 0,
             search: "".to_string(),
             match_path: false,
             match_case: false,
@@ -27,30 +96,225 @@ impl Default for Query {
             offset: 0,
             sort_type: Default::default(),
             request_flags: Default::default(),
+            lazy_flags: RequestFlags::empty(),
+            secondary_sort: None,
         }
     }
 }
 
-#[derive(Debug, Default)]
+/// A query failed but ery goes on, shown with the results.
+#[derive(Debug, Clone)]
+pub enum QueryError {
+    /// Everything did not reply in time, e.g. it hangs or is busy with a slow sort. The
+    /// results are still taken if they come later.
+    Timeout {
+        /// id of the query.
+        id: u64,
+        search: String,
+        after: Duration,
+    },
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::Timeout { search, after, .. } => write!(
+                f,
+                "Everything did not reply to 『{search}』 in {}s",
+                after.as_secs()
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct QueryResults {
+    /// id of the query, not saved in snapshots.
+    #[serde(skip)]
+    pub id: u64,
     pub search: OsString,
     pub offset: u32,
     pub number: u32,
     pub total: u32,
+    #[serde(with = "request_flags_serde")]
     pub request_flags: RequestFlags,
+    #[serde(with = "sort_type_serde")]
     pub sort_type: SortType,
     pub entrys: Vec<QueryEntry>,
+    /// parent folders of the entries.
+    pub paths: PathInterner,
+    /// how long the query took, not saved in snapshots.
+    #[serde(skip)]
+    pub timings: QueryTimings,
 }
 
-#[derive(Debug)]
+impl QueryResults {
+    /// Parent folder of the entry.
+    pub fn path(&self, entry: &QueryEntry) -> Option<&Path> {
+        self.paths.get(entry.path?)
+    }
+
+    /// Full path of the entry, the parent folder joined with the filename.
+    pub fn filepath(&self, entry: &QueryEntry) -> Option<PathBuf> {
+        Some(self.path(entry)?.join(entry.filename.as_ref()?))
+    }
+
+    /// Approximate bytes of the results in memory, the entries and the names and paths in them.
+    pub fn memory_size(&self) -> usize {
+        let len = |s: &Option<OsString>| s.as_ref().map_or(0, |s| s.len());
+        let names: usize = self
+            .entrys
+            .iter()
+            .map(|entry| {
+                len(&entry.filename)
+                    + len(&entry.extension)
+                    + len(&entry.file_list_filename)
+                    + len(&entry.highlighted_filename)
+                    + len(&entry.highlighted_path)
+                    + len(&entry.highlighted_full_path_and_filename)
+                    + entry
+                        .full_path_name
+                        .as_ref()
+                        .map_or(0, |path| path.as_os_str().len())
+            })
+            .sum();
+        // each path is shared by the list and the map of the interner
+        let paths: usize = self
+            .paths
+            .iter()
+            .map(|path| path.as_os_str().len() + 3 * size_of::<Arc<Path>>())
+            .sum();
+        self.entrys.capacity() * size_of::<QueryEntry>() + names + paths
+    }
+
+    /// Index of the entry of the path in the results.
+    pub fn position(&self, path: &Path) -> Option<usize> {
+        self.entrys
+            .iter()
+            .position(|entry| self.filepath(entry).as_deref() == Some(path))
+    }
+
+    /// Take the entry of the path out, e.g. it is deleted. False if it is not in the results.
+    pub fn remove(&mut self, path: &Path) -> bool {
+        let Some(i) = self.position(path) else {
+            return false;
+        };
+        self.entrys.remove(i);
+        self.number = self.number.saturating_sub(1);
+        self.total = self.total.saturating_sub(1);
+        true
+    }
+
+    /// Change the filename of the entry of the path in place, e.g. it is renamed in the same
+    /// folder. False if it is not in the results.
+    pub fn rename(&mut self, path: &Path, filename: &OsStr) -> bool {
+        let Some(i) = self.position(path) else {
+            return false;
+        };
+        let new_path = path.with_file_name(filename);
+        let entry = &mut self.entrys[i];
+        entry.filename = Some(filename.to_os_string());
+        if entry.full_path_name.is_some() {
+            entry.full_path_name = Some(new_path);
+        }
+        // the highlights are of the old name
+        entry.highlighted_filename = None;
+        entry.highlighted_full_path_and_filename = None;
+        true
+    }
+}
+
+/// The results shared with the threads of the queries. They are immutable snapshots swapped
+/// as a whole, so the UI reads them any time without waiting for the writers.
+pub type SharedResults = Arc<ArcSwap<QueryResults>>;
+
+/// Change a copy of the shared results and swap it in. It is done again on the newer results
+/// if they are swapped meanwhile, so `change` may be called more than once.
+pub fn update_shared<R>(
+    shared: &ArcSwap<QueryResults>,
+    mut change: impl FnMut(&mut QueryResults) -> R,
+) -> R {
+    let mut output = None;
+    shared.rcu(|results| {
+        let mut results = QueryResults::clone(results);
+        output = Some(change(&mut results));
+        results
+    });
+    output.unwrap()
+}
+
+/// Paths repeat massively in the results, e.g. the files in the same folder, so they are
+/// stored once here and referred by the ids.
+///
+/// They are serialized as `OsString`, since `PathBuf` fails on the paths which are not valid
+/// unicode, e.g. with unpaired surrogates on Windows.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(from = "Vec<OsString>", into = "Vec<OsString>")]
+pub struct PathInterner {
+    paths: Vec<Arc<Path>>,
+    ids: HashMap<Arc<Path>, u32>,
+}
+
+impl PathInterner {
+    pub fn intern(&mut self, path: PathBuf) -> u32 {
+        if let Some(&id) = self.ids.get(path.as_path()) {
+            return id;
+        }
+        let id = self.paths.len() as u32;
+        let path: Arc<Path> = path.into();
+        self.paths.push(Arc::clone(&path));
+        self.ids.insert(path, id);
+        id
+    }
+
+    pub fn get(&self, id: u32) -> Option<&Path> {
+        self.paths.get(id as usize).map(|path| path.as_ref())
+    }
+
+    /// All the distinct paths.
+    pub fn iter(&self) -> impl Iterator<Item = &Path> {
+        self.paths.iter().map(|path| path.as_ref())
+    }
+}
+
+impl From<Vec<OsString>> for PathInterner {
+    fn from(paths: Vec<OsString>) -> Self {
+        let mut interner = Self::default();
+        for path in paths {
+            interner.intern(path.into());
+        }
+        interner
+    }
+}
+
+impl From<PathInterner> for Vec<OsString> {
+    fn from(interner: PathInterner) -> Self {
+        interner
+            .paths
+            .iter()
+            .map(|path| path.as_os_str().to_os_string())
+            .collect()
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct QueryTimings {
+    /// the IPC query to Everything, until the results are received.
+    pub ipc: Duration,
+    /// converting the items into [`QueryEntry`]s.
+    pub conversion: Duration,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryEntry {
     pub index: u32,
     pub is_volume: bool,
     pub is_folder: bool,
     pub is_file: bool,
     pub filename: Option<OsString>,
-    pub path: Option<PathBuf>,
-    pub filepath: Option<PathBuf>,
+    /// id of the parent folder in [`QueryResults::paths`], resolve it by [`QueryResults::path`].
+    pub path: Option<u32>,
+    #[serde(with = "os_path_serde")]
     pub full_path_name: Option<PathBuf>,
     pub extension: Option<OsString>,
     pub size: Option<u64>,
@@ -65,9 +329,16 @@ pub struct QueryEntry {
     pub highlighted_filename: Option<OsString>,
     pub highlighted_path: Option<OsString>,
     pub highlighted_full_path_and_filename: Option<OsString>,
+    /// some requested fields are not converted yet, see [`Query::lazy_flags`].
+    #[serde(skip)]
+    pub is_partial: bool,
 }
 
-pub fn item_to_entry(item: EverythingItem<'_>, request_flags: RequestFlags) -> QueryEntry {
+pub fn item_to_entry(
+    item: EverythingItem<'_>,
+    request_flags: RequestFlags,
+    paths: &mut PathInterner,
+) -> QueryEntry {
     let index = item.index();
     let is_volume = item.is_volume();
     let is_folder = item.is_folder();
@@ -78,12 +349,7 @@ pub fn item_to_entry(item: EverythingItem<'_>, request_flags: RequestFlags) -> Q
         .then(|| item.filename().unwrap());
     let path = request_flags
         .contains(RequestFlags::EVERYTHING_REQUEST_PATH)
-        .then(|| item.path().unwrap());
-    let filepath = request_flags
-        .contains(
-            RequestFlags::EVERYTHING_REQUEST_PATH | RequestFlags::EVERYTHING_REQUEST_FILE_NAME,
-        )
-        .then(|| item.filepath().unwrap());
+        .then(|| paths.intern(item.path().unwrap()));
     let full_path_name = request_flags
         .contains(RequestFlags::EVERYTHING_REQUEST_FULL_PATH_AND_FILE_NAME)
         .then(|| item.full_path_name(None).unwrap());
@@ -134,7 +400,6 @@ pub fn item_to_entry(item: EverythingItem<'_>, request_flags: RequestFlags) -> Q
         is_file,
         filename,
         path,
-        filepath,
         full_path_name,
         extension,
         size,
@@ -149,5 +414,72 @@ pub fn item_to_entry(item: EverythingItem<'_>, request_flags: RequestFlags) -> Q
         highlighted_filename,
         highlighted_path,
         highlighted_full_path_and_filename,
+        is_partial: false,
+    }
+}
+
+/// (De)serialize the [`RequestFlags`] as its bits, ignoring the unknown ones.
+mod request_flags_serde {
+    use everything_sdk::RequestFlags;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        flags: &RequestFlags,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(flags.bits())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<RequestFlags, D::Error> {
+        Ok(RequestFlags::from_bits_truncate(u32::deserialize(
+            deserializer,
+        )?))
+    }
+}
+
+/// (De)serialize the path as `OsString`, losslessly, see [`PathInterner`].
+mod os_path_serde {
+    use std::{ffi::OsString, path::PathBuf};
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        path: &Option<PathBuf>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        path.as_ref()
+            .map(|path| path.as_os_str())
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<PathBuf>, D::Error> {
+        Ok(Option::<OsString>::deserialize(deserializer)?.map(PathBuf::from))
+    }
+}
+
+/// (De)serialize the [`SortType`] as its value.
+mod sort_type_serde {
+    use everything_sdk::SortType;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    use super::SORT_TYPES;
+
+    pub fn serialize<S: Serializer>(
+        sort_type: &SortType,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(*sort_type as u32)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SortType, D::Error> {
+        let value = u32::deserialize(deserializer)?;
+        SORT_TYPES
+            .into_iter()
+            .find(|x| *x as u32 == value)
+            .ok_or_else(|| de::Error::custom(format!("unknown sort type value {value}")))
     }
 }
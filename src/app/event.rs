@@ -0,0 +1,45 @@
+use std::{
+    fmt,
+    sync::{mpsc, Arc},
+};
+
+/// What the app tells the frontend from the threads of it, e.g. the results of a query are
+/// back. Any frontend may listen to them, the TUI, the CLI or a GUI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppEvent {
+    /// The state is changed, e.g. the results are back, show it again.
+    Refresh,
+    /// Files are changed by ery, e.g. renamed or deleted, query again for the fresh results.
+    FilesChanged,
+    /// A task in the background is done, e.g. a move or copy.
+    TaskDone,
+}
+
+/// Sends the [`AppEvent`]s to the frontend, in the way of it, e.g. into the event loop of the
+/// TUI. The ones sent after the frontend is gone are dropped.
+#[derive(Clone)]
+pub struct AppSender(Arc<dyn Fn(AppEvent) + Send + Sync>);
+
+impl AppSender {
+    pub fn new(send: impl Fn(AppEvent) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(send))
+    }
+
+    pub fn send(&self, event: AppEvent) {
+        (self.0)(event);
+    }
+}
+
+impl fmt::Debug for AppSender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AppSender")
+    }
+}
+
+impl From<mpsc::Sender<AppEvent>> for AppSender {
+    fn from(sender: mpsc::Sender<AppEvent>) -> Self {
+        Self::new(move |event| {
+            let _ = sender.send(event);
+        })
+    }
+}
@@ -0,0 +1,130 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+
+use crate::config::Config;
+
+/// The file of the excludes added in ery, in the config folder, one on a line.
+const EXCLUDES_FILE_NAME: &str = "excludes.txt";
+
+/// The folders excluded from all the queries by `x` in ery, besides `query.excludes` in config.
+#[derive(Debug, Default)]
+pub struct Excludes {
+    pub added: Vec<String>,
+}
+
+impl Excludes {
+    /// The excludes saved in the config folder, the empty lines and the ones of `#` skipped.
+    pub fn load() -> anyhow::Result<Self> {
+        let Some(path) = Self::path().filter(|path| path.is_file()) else {
+            return Ok(Self::default());
+        };
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read excludes {}", path.display()))?;
+        let added = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect();
+        Ok(Self { added })
+    }
+
+    /// Exclude the folder from now on and save it. False if it is excluded already.
+    pub fn add(&mut self, folder: &Path) -> anyhow::Result<bool> {
+        let Some(folder) = folder.to_str() else {
+            anyhow::bail!(
+                "{} is not of UTF-8, the search can not have it",
+                folder.display()
+            );
+        };
+        // the files in it, not the ones of the same name beside it
+        let folder = format!("{}\\", folder.trim_end_matches('\\'));
+        if self
+            .added
+            .iter()
+            .any(|added| added.eq_ignore_ascii_case(&folder))
+        {
+            return Ok(false);
+        }
+        self.append(&folder)?;
+        self.added.push(folder);
+        Ok(true)
+    }
+
+    /// The clauses of `query.excludes` of config and of these, for all the queries of ery.
+    pub fn clauses_with(&self, config: &Config) -> String {
+        clauses(config.query.excludes.iter().chain(&self.added))
+    }
+
+    fn path() -> Option<PathBuf> {
+        Some(Config::dir()?.join(EXCLUDES_FILE_NAME))
+    }
+
+    /// Add the line to the file, the lines edited by the user are kept as they are.
+    fn append(&self, line: &str) -> anyhow::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("failed to open excludes {}", path.display()))?;
+        writeln!(file, "{line}")
+            .with_context(|| format!("failed to save excludes {}", path.display()))
+    }
+}
+
+/// The search with the clauses of the excludes appended, the clauses alone if it is empty.
+pub fn append(search: &str, clauses: &str) -> String {
+    match (search.is_empty(), clauses.is_empty()) {
+        (_, true) => search.to_string(),
+        (true, false) => clauses.to_string(),
+        (false, false) => format!("{search} {clauses}"),
+    }
+}
+
+/// The search of Everything excluding the patterns, e.g. `!path:"node_modules"` for a folder
+/// name or path, and `!"*.tmp"` for a wildcard matching the names, or the full paths with `\`.
+pub fn clauses<'a>(patterns: impl IntoIterator<Item = &'a String>) -> String {
+    let clauses: Vec<String> = patterns
+        .into_iter()
+        .map(|pattern| pattern.trim().trim_matches('"'))
+        .filter(|pattern| !pattern.is_empty())
+        .map(|pattern| {
+            if pattern.contains(['*', '?']) {
+                format!("!\"{pattern}\"")
+            } else {
+                format!("!path:\"{pattern}\"")
+            }
+        })
+        .collect();
+    clauses.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clauses_are_appended_to_the_search() {
+        let patterns = ["node_modules".to_string(), "*.tmp".to_string()];
+        let clauses = clauses(&patterns);
+        assert_eq!(clauses, r#"!path:"node_modules" !"*.tmp""#);
+        assert_eq!(
+            append("report", &clauses),
+            r#"report !path:"node_modules" !"*.tmp""#
+        );
+        assert_eq!(append("", &clauses), clauses);
+        assert_eq!(append("report", ""), "report");
+    }
+}
@@ -0,0 +1,47 @@
+use std::{collections::HashMap, path::Path};
+
+use super::ery::{QueryEntry, QueryResults};
+
+/// The lowercase extension of the file, empty if it has none.
+pub fn extension(entry: &QueryEntry) -> String {
+    entry
+        .extension
+        .as_deref()
+        .or_else(|| Path::new(entry.filename.as_deref()?).extension())
+        .map_or(String::new(), |ext| ext.to_string_lossy().to_lowercase())
+}
+
+/// Files of the results by extension, the folders are not counted.
+#[derive(Debug, Default)]
+pub struct ExtensionCounts {
+    /// id and the number of the entries of the results counted.
+    counted: (u64, usize),
+    counts: HashMap<String, usize>,
+}
+
+impl ExtensionCounts {
+    /// Count the entries again if the results are others, e.g. more are loaded, which come
+    /// with all the loaded ones in a new query.
+    pub fn update(&mut self, results: &QueryResults) {
+        let counted = (results.id, results.entrys.len());
+        if counted == self.counted {
+            return;
+        }
+        self.counted = counted;
+        self.counts.clear();
+        for entry in results.entrys.iter().filter(|entry| !entry.is_folder) {
+            *self.counts.entry(extension(entry)).or_default() += 1;
+        }
+    }
+
+    /// The extensions and their counts, the most first.
+    pub fn sorted(&self) -> Vec<(&str, usize)> {
+        let mut counts: Vec<_> = self
+            .counts
+            .iter()
+            .map(|(ext, count)| (ext.as_str(), *count))
+            .collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        counts
+    }
+}
@@ -0,0 +1,81 @@
+use std::{env, fs, path::PathBuf};
+
+use crate::config::SearchFilter;
+use crate::shell;
+
+use super::efu::split_csv;
+
+/// The filters of Everything, in its folder in `%APPDATA%` or beside `Everything.exe`.
+const FILTERS_FILE_NAME: &str = "Filters.csv";
+
+/// The default filters of Everything, by the macros of them.
+const DEFAULT_FILTERS: &[(&str, &str)] = &[
+    ("Audio", "audio:"),
+    ("Compressed", "zip:"),
+    ("Document", "doc:"),
+    ("Executable", "exe:"),
+    ("Folder", "folder:"),
+    ("Picture", "pic:"),
+    ("Video", "video:"),
+];
+
+/// A named filter, e.g. `Audio`, the search of it is put before the query text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    pub name: String,
+    pub search: String,
+}
+
+/// The filters of Everything and the ones of `[[filters]]` in config, which replace the ones of
+/// Everything of the same names.
+pub fn load(config: &[SearchFilter], is_appdata: bool) -> Vec<Filter> {
+    let mut filters = everything_filters(is_appdata).unwrap_or_else(|| {
+        DEFAULT_FILTERS
+            .iter()
+            .map(|&(name, search)| Filter {
+                name: name.to_string(),
+                search: search.to_string(),
+            })
+            .collect()
+    });
+    for filter in config {
+        let filter = Filter {
+            name: filter.name.clone(),
+            search: filter.search.clone(),
+        };
+        match filters.iter_mut().find(|x| x.name == filter.name) {
+            Some(x) => *x = filter,
+            None => filters.push(filter),
+        }
+    }
+    filters
+}
+
+/// The filters in `Filters.csv` of Everything, none if it is not found. Only the searches of
+/// them are taken, not the match options.
+///
+/// It is a CSV of `Name,Case,Whole Word,Path,Diacritics,Regex,Search,...`, and the filter of
+/// everything has an empty search.
+fn everything_filters(is_appdata: bool) -> Option<Vec<Filter>> {
+    let folder = if is_appdata {
+        PathBuf::from(env::var_os("APPDATA")?).join("Everything")
+    } else {
+        shell::everything_exe().parent()?.to_path_buf()
+    };
+    let text = fs::read_to_string(folder.join(FILTERS_FILE_NAME)).ok()?;
+    let mut lines = text.trim_start_matches('\u{feff}').lines();
+    let header = split_csv(lines.next()?);
+    let column = |name: &str| header.iter().position(|h| h.eq_ignore_ascii_case(name));
+    let (name, search) = (column("Name")?, column("Search")?);
+    let filters = lines
+        .map(split_csv)
+        .filter_map(|fields| {
+            Some(Filter {
+                name: fields.get(name)?.clone(),
+                search: fields.get(search)?.trim().to_string(),
+            })
+        })
+        .filter(|filter| !filter.search.is_empty())
+        .collect();
+    Some(filters)
+}
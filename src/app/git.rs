@@ -0,0 +1,195 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use super::event::{AppEvent, AppSender};
+
+/// The statuses of a repository are queried again after it, when the results are refreshed.
+const STATUS_TTL: Duration = Duration::from_secs(10);
+
+/// The git status of a file of the results, shown as a badge before the name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Badge {
+    /// changed in the work tree or the index, added, renamed or deleted too.
+    Modified,
+    Untracked,
+    Ignored,
+}
+
+impl Badge {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Badge::Modified => "M",
+            Badge::Untracked => "?",
+            Badge::Ignored => "!",
+        }
+    }
+
+    /// The badge of the `XY` code of `git status --porcelain`.
+    fn of(code: &str) -> Self {
+        match code {
+            "??" => Badge::Untracked,
+            "!!" => Badge::Ignored,
+            _ => Badge::Modified,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Repo {
+    /// the badges of the files and the folders, by the lowercase full paths.
+    badges: HashMap<String, Badge>,
+    /// when the statuses are queried, none if never.
+    queried: Option<Instant>,
+    is_querying: bool,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// the root of the repository of the folders, none if they are in no repository.
+    roots: HashMap<PathBuf, Option<PathBuf>>,
+    repos: HashMap<PathBuf, Repo>,
+}
+
+/// The git statuses of the files in the results, queried by `git status` in the background for
+/// each repository, and cached for a while.
+#[derive(Debug, Default, Clone)]
+pub struct GitStatus {
+    state: Arc<Mutex<State>>,
+}
+
+impl GitStatus {
+    /// Query the repositories of the folders in the background, the ones not queried or
+    /// expired only, then refresh the results. Nothing if git is not installed.
+    pub fn update(&self, folders: Vec<PathBuf>, sender: &AppSender) {
+        let is_known = {
+            let state = self.state.lock().unwrap();
+            folders
+                .iter()
+                .all(|folder| state.roots.contains_key(folder))
+                && state.repos.values().all(|repo| !repo.is_expired())
+        };
+        if is_known {
+            return;
+        }
+        let state = Arc::clone(&self.state);
+        let sender = sender.clone();
+        thread::spawn(move || {
+            let new: Vec<PathBuf> = {
+                let state = state.lock().unwrap();
+                folders
+                    .into_iter()
+                    .filter(|folder| !state.roots.contains_key(folder))
+                    .collect()
+            };
+            // out of the lock, the disks may be slow
+            let roots: Vec<(PathBuf, Option<PathBuf>)> = new
+                .into_iter()
+                .map(|folder| {
+                    let root = repository_root(&folder);
+                    (folder, root)
+                })
+                .collect();
+            let expired: Vec<PathBuf> = {
+                let mut state = state.lock().unwrap();
+                for (folder, root) in roots {
+                    if let Some(root) = &root {
+                        state.repos.entry(root.clone()).or_default();
+                    }
+                    state.roots.insert(folder, root);
+                }
+                let expired: Vec<PathBuf> = state
+                    .repos
+                    .iter()
+                    .filter(|(_, repo)| repo.is_expired())
+                    .map(|(root, _)| root.clone())
+                    .collect();
+                for root in &expired {
+                    // not again meanwhile
+                    state.repos.get_mut(root).unwrap().is_querying = true;
+                }
+                expired
+            };
+            for root in expired {
+                let badges = query(&root).unwrap_or_default();
+                state.lock().unwrap().repos.insert(
+                    root,
+                    Repo {
+                        badges,
+                        queried: Some(Instant::now()),
+                        is_querying: false,
+                    },
+                );
+                sender.send(AppEvent::Refresh);
+            }
+        });
+    }
+
+    /// The badge of the file, or of the folder containing it, e.g. an untracked or ignored
+    /// folder. None if it is not known yet, without waiting for the queries.
+    pub fn badge(&self, path: &Path) -> Option<Badge> {
+        let state = self.state.try_lock().ok()?;
+        let root = state.roots.get(path.parent()?)?.as_ref()?;
+        let repo = state.repos.get(root)?;
+        path.ancestors()
+            .take_while(|ancestor| *ancestor != root.as_path())
+            .find_map(|ancestor| repo.badges.get(&key(ancestor)).copied())
+    }
+}
+
+impl Repo {
+    /// Never queried, or queried long ago, but not while querying.
+    fn is_expired(&self) -> bool {
+        !self.is_querying
+            && self
+                .queried
+                .is_none_or(|queried| queried.elapsed() >= STATUS_TTL)
+    }
+}
+
+/// The root of the repository containing the folder, by the `.git` in it.
+fn repository_root(folder: &Path) -> Option<PathBuf> {
+    folder
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// The badges of the changed, the untracked and the ignored files of the repository, the
+/// untracked and the ignored folders as a whole.
+fn query(root: &Path) -> Option<HashMap<String, Badge>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["status", "--porcelain=v1", "-z", "--ignored"])
+        .stdin(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut badges = HashMap::new();
+    let mut records = text.split('\0');
+    while let Some(record) = records.next() {
+        let (Some(code), Some(path)) = (record.get(..2), record.get(3..)) else {
+            continue;
+        };
+        // the original path of the renamed or copied follows
+        if code.starts_with(['R', 'C']) {
+            records.next();
+        }
+        let path = root.join(path.trim_end_matches('/').replace('/', "\\"));
+        badges.insert(key(&path), Badge::of(code));
+    }
+    Some(badges)
+}
+
+fn key(path: &Path) -> String {
+    path.to_string_lossy().trim_end_matches('\\').to_lowercase()
+}
@@ -0,0 +1,152 @@
+/// The search of globs or .gitignore patterns in the syntax of Everything, e.g. `src/**/*.rs`,
+/// the words one by one.
+///
+/// The words of only `*` and `?` are the wildcards of Everything already, matching the whole
+/// names. The ones of `**`, the classes `[...]`, the alternatives `{a,b}` or the folders by `/`
+/// are translated into regexes, of the full paths if there are folders, matched below any
+/// folder as .gitignore does. A trailing `/` matches the folders only, and a leading `!` is the
+/// NOT of Everything. The words of the functions, the modifiers and the operators are kept.
+pub fn translate(text: &str) -> String {
+    let words: Vec<String> = text.split_whitespace().map(translate_word).collect();
+    words.join(" ")
+}
+
+fn translate_word(word: &str) -> String {
+    if word.contains([':', '"', '<', '>', '|']) {
+        return word.to_string();
+    }
+    let (not, glob) = match word.strip_prefix('!') {
+        Some(glob) => ("!", glob),
+        None => ("", word),
+    };
+    let (folder, glob) = match glob.strip_suffix('/') {
+        Some(glob) => ("folder:", glob),
+        None => ("", glob),
+    };
+    // anchored to the root in .gitignore, to any folder here
+    let glob = glob.trim_start_matches('/');
+    if glob.is_empty() {
+        return word.to_string();
+    }
+    if !glob.contains(['/', '[', '{']) && !glob.contains("**") {
+        return format!("{not}{folder}{glob}");
+    }
+    let pattern = to_regex(glob);
+    if glob.contains('/') {
+        format!(r#"{not}{folder}path:regex:"(^|\\){pattern}$""#)
+    } else {
+        format!(r#"{not}{folder}regex:"^{pattern}$""#)
+    }
+}
+
+/// The regex of the glob, the `/` of it as the `\` of Windows.
+fn to_regex(glob: &str) -> String {
+    let mut regex = String::with_capacity(glob.len() * 2);
+    let mut chars = glob.chars().peekable();
+    // in the alternatives of `{a,b}`, nested ones too
+    let mut depth = 0;
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                // `**/` for any folders, none too
+                if chars.next_if_eq(&'/').is_some() {
+                    regex.push_str(r"(.*\\)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str(r"[^\\]*"),
+            '?' => regex.push_str(r"[^\\]"),
+            '/' => regex.push_str(r"\\"),
+            '[' => {
+                regex.push('[');
+                if chars.next_if(|&c| c == '!' || c == '^').is_some() {
+                    regex.push('^');
+                }
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    if c == '\\' {
+                        regex.push('\\');
+                    }
+                    regex.push(c);
+                }
+                regex.push(']');
+            }
+            '{' => {
+                depth += 1;
+                regex.push('(');
+            }
+            ',' if depth > 0 => regex.push('|'),
+            '}' if depth > 0 => {
+                depth -= 1;
+                regex.push(')');
+            }
+            c => {
+                if "\\.+()[]{}^$|".contains(c) {
+                    regex.push('\\');
+                }
+                regex.push(c);
+            }
+        }
+    }
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcards_only_are_kept() {
+        assert_eq!(translate("*.rs"), "*.rs");
+        assert_eq!(translate("a?c.txt  *.md"), "a?c.txt *.md");
+    }
+
+    #[test]
+    fn stars_and_question_marks_match_in_a_name() {
+        assert_eq!(
+            translate("src/*.rs"),
+            r#"path:regex:"(^|\\)src\\[^\\]*\.rs$""#
+        );
+        assert_eq!(
+            translate("src/?.rs"),
+            r#"path:regex:"(^|\\)src\\[^\\]\.rs$""#
+        );
+        // `**` through any folders, or none
+        assert_eq!(
+            translate("src/**/*.rs"),
+            r#"path:regex:"(^|\\)src\\(.*\\)?[^\\]*\.rs$""#
+        );
+        assert_eq!(translate("**.rs"), r#"regex:"^.*\.rs$""#);
+    }
+
+    #[test]
+    fn classes_and_alternatives_are_of_regex() {
+        assert_eq!(translate("*.[ch]"), r#"regex:"^[^\\]*\.[ch]$""#);
+        assert_eq!(translate("[!a-c]?.txt"), r#"regex:"^[^a-c][^\\]\.txt$""#);
+        assert_eq!(translate("[^0-9].txt"), r#"regex:"^[^0-9]\.txt$""#);
+        assert_eq!(translate("*.{rs,toml}"), r#"regex:"^[^\\]*\.(rs|toml)$""#);
+    }
+
+    #[test]
+    fn chars_of_regex_are_escaped() {
+        assert_eq!(
+            translate("a+b(1)[x]$.txt"),
+            r#"regex:"^a\+b\(1\)[x]\$\.txt$""#
+        );
+        // `,` and `}` out of the alternatives are plain
+        assert_eq!(translate("[a]b,c}"), r#"regex:"^[a]b,c\}$""#);
+    }
+
+    #[test]
+    fn folders_negations_and_functions() {
+        assert_eq!(translate("target/"), "folder:target");
+        assert_eq!(translate("!node_modules/"), "!folder:node_modules");
+        assert_eq!(translate("/build"), "build");
+        assert_eq!(translate("ext:rs dm:today"), "ext:rs dm:today");
+        assert_eq!(translate(r#""a b/*.rs""#), r#""a b/*.rs""#);
+    }
+}
@@ -0,0 +1,132 @@
+use std::{fs, ops::Range, path::Path};
+
+use anyhow::Context;
+
+/// The files larger are not searched in the preview, not to hang the render.
+const MAX_FILE_SIZE: u64 = 16 * 1024 * 1024;
+
+/// The lines matched at most, more are left out.
+const MAX_MATCHES: usize = 1000;
+
+/// The bytes looked for a NUL of the binary files, as ripgrep does.
+const BINARY_PROBE: usize = 8 * 1024;
+
+/// A line of the file matching any of the terms.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineMatch {
+    /// from 1, as the editors count.
+    pub line: usize,
+    pub text: String,
+    /// the bytes of the text matched, for highlighting.
+    pub ranges: Vec<Range<usize>>,
+}
+
+/// The terms of the search looked for in the contents of the files, lowercase.
+///
+/// The ones of the content functions like `content:todo` if any, or else the plain words, the
+/// wildcards splitting them, e.g. `todo` and `fixme` of `*.rs todo|fixme`. The words of the file
+/// names or the paths like `*.rs`, the other functions, the modifiers and the excluded words are
+/// not.
+pub fn terms(search: &str) -> Vec<String> {
+    let mut contents = vec![];
+    let mut words = vec![];
+    for word in split_words(search) {
+        let word = word.trim_matches(['<', '>']);
+        if word.is_empty() || word.starts_with(['!', '-']) {
+            continue;
+        }
+        match word.split_once(':') {
+            Some((function, value)) if function.to_ascii_lowercase().ends_with("content") => {
+                contents.push(value.replace('"', ""));
+            }
+            Some(_) => {}
+            None if word.contains(['.', '\\', '/']) => {}
+            None => words.push(word.replace('"', "")),
+        }
+    }
+    let terms = if contents.is_empty() { words } else { contents };
+    let mut terms: Vec<String> = terms
+        .into_iter()
+        .flat_map(|term| {
+            term.split(['*', '?'])
+                .filter(|term| !term.is_empty())
+                .map(str::to_ascii_lowercase)
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    terms.sort();
+    terms.dedup();
+    terms
+}
+
+/// Split by the spaces and the `|` out of double quotes, which are kept.
+fn split_words(search: &str) -> Vec<&str> {
+    let mut words = vec![];
+    let mut start = 0;
+    let mut in_quotes = false;
+    for (i, c) in search.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if (c.is_whitespace() || c == '|') && !in_quotes => {
+                words.push(&search[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    words.push(&search[start..]);
+    words
+}
+
+/// The lines of the text file matching any of the terms, ASCII case-insensitively.
+pub fn grep(path: &Path, terms: &[String]) -> anyhow::Result<Vec<LineMatch>> {
+    let size = fs::metadata(path)
+        .with_context(|| format!("failed to read {}", path.display()))?
+        .len();
+    anyhow::ensure!(size <= MAX_FILE_SIZE, "too large to search");
+    let bytes = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    anyhow::ensure!(
+        !bytes[..bytes.len().min(BINARY_PROBE)].contains(&0),
+        "binary file"
+    );
+    let text = String::from_utf8_lossy(&bytes);
+    let mut matches = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let ranges = find_terms(line, terms);
+        if ranges.is_empty() {
+            continue;
+        }
+        matches.push(LineMatch {
+            line: i + 1,
+            text: line.to_string(),
+            ranges,
+        });
+        if matches.len() == MAX_MATCHES {
+            break;
+        }
+    }
+    Ok(matches)
+}
+
+/// The ranges of the terms in the line, sorted and not overlapping.
+fn find_terms(line: &str, terms: &[String]) -> Vec<Range<usize>> {
+    // ASCII only, so the bytes are the same as the line
+    let lowercase = line.to_ascii_lowercase();
+    let mut ranges: Vec<Range<usize>> = terms
+        .iter()
+        .flat_map(|term| {
+            lowercase
+                .match_indices(term.as_str())
+                .map(|(start, term)| start..start + term.len())
+        })
+        .collect();
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = vec![];
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
@@ -0,0 +1,64 @@
+use std::{
+    fs, io,
+    os::windows::fs::{symlink_dir, symlink_file},
+    path::Path,
+};
+
+use crate::tr;
+
+/// The Windows errors for the clear messages.
+const ERROR_NOT_SAME_DEVICE: i32 = 17;
+const ERROR_PRIVILEGE_NOT_HELD: i32 = 1314;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkKind {
+    Symbolic,
+    Hard,
+}
+
+impl LinkKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            LinkKind::Symbolic => tr!(Symlink),
+            LinkKind::Hard => tr!(Hardlink),
+        }
+    }
+}
+
+/// Create the link at `link` pointing to the file or folder `target`.
+///
+/// Creating symlinks needs Developer Mode or the admin privilege on Windows, and the hardlinks
+/// are of the files on the same drive only.
+pub fn create(kind: LinkKind, target: &Path, link: &Path) -> anyhow::Result<()> {
+    anyhow::ensure!(!link.exists(), "{} already exists", link.display());
+    let created = match kind {
+        LinkKind::Symbolic if target.is_dir() => symlink_dir(target, link),
+        LinkKind::Symbolic => symlink_file(target, link),
+        LinkKind::Hard => {
+            anyhow::ensure!(
+                !target.is_dir(),
+                "folders can not be hardlinked, create a symlink instead"
+            );
+            fs::hard_link(target, link)
+        }
+    };
+    created.map_err(|e| explain(kind, link, e))
+}
+
+fn explain(kind: LinkKind, link: &Path, e: io::Error) -> anyhow::Error {
+    match e.raw_os_error() {
+        Some(ERROR_PRIVILEGE_NOT_HELD) => anyhow::anyhow!(
+            "creating symlinks needs Developer Mode on, or ery run as admin (ctrl+e)"
+        ),
+        Some(ERROR_NOT_SAME_DEVICE) => {
+            anyhow::anyhow!(
+                "a hardlink must be on the same drive as the file, create a symlink instead"
+            )
+        }
+        _ => anyhow::Error::new(e).context(format!(
+            "failed to create the {} {}",
+            kind.name(),
+            link.display()
+        )),
+    }
+}
@@ -0,0 +1,91 @@
+/// The line and the column in a file, e.g. of `main.rs:120:5` pasted from the compiler output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: u32,
+    pub column: Option<u32>,
+}
+
+/// Split the trailing `:line[:col]` off the search, the last word of it, if it is a file name
+/// or a path like `main.rs:120`, `src\app.rs:12:5` or `C:\ery\build.rs:3:`.
+///
+/// The functions of Everything like `size:100` are not taken, by the dot or the folders of the
+/// name before, and no more colons in it than the one of a drive.
+pub fn split(text: &str) -> (&str, Option<Location>) {
+    let trimmed = text.trim_end();
+    let word = trimmed
+        .rsplit(char::is_whitespace)
+        .next()
+        .unwrap_or_default();
+    let word_start = trimmed.len() - word.len();
+    // as grep prints them, `main.rs:120:`
+    let word = word.strip_suffix(':').unwrap_or(word);
+    let mut numbers = vec![];
+    let mut rest = word;
+    while let Some((before, number)) = rest.rsplit_once(':') {
+        match number.parse::<u32>() {
+            Ok(n) if numbers.len() < 2 && !number.starts_with('+') => {
+                numbers.push(n);
+                rest = before;
+            }
+            _ => break,
+        }
+    }
+    // the numbers are taken from the last one
+    let Some(line) = numbers.pop().filter(|_| is_file_name(rest)) else {
+        return (text, None);
+    };
+    let location = Location {
+        line,
+        column: numbers.pop(),
+    };
+    (&trimmed[..word_start + rest.len()], Some(location))
+}
+
+fn is_file_name(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    let has_drive = bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':';
+    let name = if has_drive { &name[2..] } else { name };
+    !name.is_empty() && !name.contains([':', '"', '<', '>', '|']) && name.contains(['.', '\\', '/'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(line: u32, column: Option<u32>) -> Option<Location> {
+        Some(Location { line, column })
+    }
+
+    #[test]
+    fn lines_and_columns_are_split_off() {
+        assert_eq!(split("main.rs:120"), ("main.rs", at(120, None)));
+        assert_eq!(split(r"src\app.rs:12:5"), (r"src\app.rs", at(12, Some(5))));
+        assert_eq!(split(r"C:\a.rs:10:2"), (r"C:\a.rs", at(10, Some(2))));
+        // as grep prints them
+        assert_eq!(
+            split(r"C:\ery\build.rs:3:"),
+            (r"C:\ery\build.rs", at(3, None))
+        );
+        assert_eq!(split("ext:rs main.rs:7  "), ("ext:rs main.rs", at(7, None)));
+    }
+
+    #[test]
+    fn searches_of_no_suffix_are_kept() {
+        for text in ["main.rs", r"C:\a.rs", "main rs", "", "main.rs:"] {
+            assert_eq!(split(text), (text, None), "{text}");
+        }
+    }
+
+    #[test]
+    fn functions_and_bad_numbers_are_not_locations() {
+        for text in [
+            "size:100",
+            "dm:2024",
+            "main.rs:+5",
+            "main.rs:1:2:3",
+            "main.rs:x",
+        ] {
+            assert_eq!(split(text), (text, None), "{text}");
+        }
+    }
+}
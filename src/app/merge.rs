@@ -0,0 +1,120 @@
+use std::{
+    collections::{hash_map, HashMap},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use super::ery::{QueryResults, SharedResults};
+use super::sort::sort_primary;
+
+/// A search of the merged view, e.g. a saved search, queried on its own.
+#[derive(Debug)]
+pub struct MergeSource {
+    pub name: String,
+    pub query: String,
+    pub(super) results: SharedResults,
+    pub(super) latest: Arc<AtomicU64>,
+}
+
+/// Several searches viewed together, e.g. `ext:psd` and `ext:ai`, the results of them merged
+/// into one list without the duplicates, each tagged by the searches it is from.
+#[derive(Debug)]
+pub struct Merge {
+    pub sources: Vec<MergeSource>,
+    /// ids of the results of the sources merged last time.
+    merged: Vec<u64>,
+    /// the indexes of the sources of each path in the merged results.
+    tags: HashMap<PathBuf, Vec<usize>>,
+}
+
+impl Merge {
+    /// The merge of the searches, by their names and queries.
+    pub fn new(searches: Vec<(String, String)>) -> Self {
+        let sources = searches
+            .into_iter()
+            .map(|(name, query)| MergeSource {
+                name,
+                query,
+                results: Default::default(),
+                latest: Default::default(),
+            })
+            .collect();
+        Self {
+            sources,
+            merged: vec![],
+            tags: HashMap::new(),
+        }
+    }
+
+    /// The names of all the searches, e.g. `psd + ai`.
+    pub fn label(&self) -> String {
+        let names: Vec<&str> = self.sources.iter().map(|x| x.name.as_str()).collect();
+        names.join(" + ")
+    }
+
+    /// The ids of the results of all the sources, none until all of them are back.
+    fn ready(&self) -> Option<Vec<u64>> {
+        self.sources
+            .iter()
+            .map(|source| {
+                let id = source.results.load().id;
+                (id == source.latest.load(Ordering::SeqCst)).then_some(id)
+            })
+            .collect()
+    }
+
+    /// Merge the results of the sources into one if all of them are back and not merged yet.
+    /// Each path is kept once, and they are sorted by the sort of the queries.
+    pub fn merge(&mut self) -> Option<QueryResults> {
+        let ids = self.ready()?;
+        if ids == self.merged {
+            return None;
+        }
+        self.merged = ids;
+        self.tags.clear();
+        let mut merged = QueryResults::default();
+        let mut duplicates = 0;
+        for (i, source) in self.sources.iter().enumerate() {
+            let results = source.results.load();
+            merged.request_flags = results.request_flags;
+            merged.sort_type = results.sort_type;
+            merged.total += results.total;
+            for entry in &results.entrys {
+                let Some(path) = results.filepath(entry) else {
+                    continue;
+                };
+                match self.tags.entry(path) {
+                    hash_map::Entry::Occupied(mut tags) => {
+                        tags.get_mut().push(i);
+                        duplicates += 1;
+                    }
+                    hash_map::Entry::Vacant(tags) => {
+                        tags.insert(vec![i]);
+                        let mut entry = entry.clone();
+                        entry.path = results
+                            .path(&entry)
+                            .map(|path| merged.paths.intern(path.to_path_buf()));
+                        merged.entrys.push(entry);
+                    }
+                }
+            }
+        }
+        sort_primary(&mut merged);
+        merged.number = merged.entrys.len() as u32;
+        merged.total = merged.total.saturating_sub(duplicates).max(merged.number);
+        Some(merged)
+    }
+
+    /// The names of the searches the path is from, e.g. `psd, ai`.
+    pub fn tag(&self, path: &Path) -> Option<String> {
+        let tags = self.tags.get(path)?;
+        let names: Vec<&str> = tags
+            .iter()
+            .map(|&i| self.sources[i].name.as_str())
+            .collect();
+        Some(names.join(", "))
+    }
+}
@@ -0,0 +1,187 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+
+use crate::config::Config;
+
+use super::ery::QueryResults;
+
+/// The file of the notes of the paths, in the config folder.
+const NOTES_FILE_NAME: &str = "notes.json";
+
+/// The term of the search narrowing the results by the notes, e.g. `note:todo`.
+const NOTE_TERM: &str = "note:";
+
+/// Short notes of the paths kept across the runs, e.g. for triaging the results of an audit
+/// over several sessions.
+#[derive(Debug, Default)]
+pub struct Notes {
+    notes: BTreeMap<PathBuf, String>,
+    /// the `note:` terms of the last query, none if it has no such terms.
+    filter: Option<Vec<String>>,
+}
+
+impl Notes {
+    /// The notes saved in the config folder, none if there is no file of them yet.
+    pub fn load() -> anyhow::Result<Self> {
+        let Some(path) = Self::path().filter(|path| path.is_file()) else {
+            return Ok(Self::default());
+        };
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read notes {}", path.display()))?;
+        let notes = serde_json::from_str(&text)
+            .with_context(|| format!("failed to parse notes {}", path.display()))?;
+        Ok(Self {
+            notes,
+            filter: None,
+        })
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&str> {
+        self.notes.get(path).map(String::as_str)
+    }
+
+    /// Set the note of the path and save the notes, an empty one removes it.
+    pub fn set(&mut self, path: PathBuf, note: &str) -> anyhow::Result<()> {
+        let note = note.trim();
+        if note.is_empty() {
+            self.notes.remove(&path);
+        } else {
+            self.notes.insert(path, note.to_string());
+        }
+        self.save()
+    }
+
+    /// Narrow the results by the `note:` terms of the query text from now on.
+    pub fn set_filter(&mut self, query_text: &str) {
+        let (_, terms) = split_terms(query_text);
+        self.filter = (!terms.is_empty()).then_some(terms);
+    }
+
+    /// Keep only the results with the notes containing all the terms of the filter, ignoring
+    /// the case. A bare `note:` keeps the ones with any note.
+    pub fn apply(&self, results: &mut QueryResults) {
+        let Some(terms) = &self.filter else {
+            return;
+        };
+        let mut entrys = std::mem::take(&mut results.entrys);
+        let loaded = entrys.len();
+        entrys.retain(|entry| {
+            let note = results.filepath(entry).and_then(|path| self.get(&path));
+            note.is_some_and(|note| matches(note, terms))
+        });
+        let dropped = (loaded - entrys.len()) as u32;
+        results.entrys = entrys;
+        results.number = results.entrys.len() as u32;
+        // only the noted paths are searched by `search`, so the total is right but for the ones
+        // dropped here
+        results.total = results.total.saturating_sub(dropped).max(results.number);
+    }
+
+    /// The search of the paths with the notes matching the terms, e.g. `<"C:\a.txt" | "D:\b">`,
+    /// none if no note matches. Everything matches them as parts of the paths, e.g. the files
+    /// in a noted folder too, which are dropped by [`Self::apply`].
+    pub fn search(&self, terms: &[String]) -> Option<String> {
+        let paths: Vec<_> = self
+            .notes
+            .iter()
+            .filter(|(_, note)| matches(note, terms))
+            .map(|(path, _)| format!(r#""{}""#, path.display()))
+            .collect();
+        (!paths.is_empty()).then(|| format!("<{}>", paths.join(" | ")))
+    }
+
+    fn path() -> Option<PathBuf> {
+        Some(Config::dir()?.join(NOTES_FILE_NAME))
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        let text = serde_json::to_string_pretty(&self.notes)?;
+        fs::write(&path, text).with_context(|| format!("failed to save notes {}", path.display()))
+    }
+}
+
+/// Whether the note contains all the lowercase terms, ignoring the case.
+fn matches(note: &str, terms: &[String]) -> bool {
+    let note = note.to_lowercase();
+    terms.iter().all(|term| note.contains(term.as_str()))
+}
+
+/// The query text without the `note:` terms, which are for ery only, and the lowercase text of
+/// those terms.
+pub fn split_terms(query_text: &str) -> (String, Vec<String>) {
+    let mut rest = vec![];
+    let mut terms = vec![];
+    for word in query_text.split_whitespace() {
+        match word.get(..NOTE_TERM.len()) {
+            Some(prefix) if prefix.eq_ignore_ascii_case(NOTE_TERM) => {
+                terms.push(word[NOTE_TERM.len()..].to_lowercase());
+            }
+            _ => rest.push(word),
+        }
+    }
+    if terms.is_empty() {
+        // as typed, the spaces are kept for the quoted words
+        return (query_text.to_string(), terms);
+    }
+    (rest.join(" "), terms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notes(notes: &[(&str, &str)]) -> Notes {
+        Notes {
+            notes: notes
+                .iter()
+                .map(|&(path, note)| (PathBuf::from(path), note.to_string()))
+                .collect(),
+            filter: None,
+        }
+    }
+
+    #[test]
+    fn terms_are_split_from_the_search() {
+        let (rest, terms) = split_terms("report Note:TODO ext:pdf");
+        assert_eq!(rest, "report ext:pdf");
+        assert_eq!(terms, ["todo"]);
+        assert_eq!(split_terms("note:"), (String::new(), vec![String::new()]));
+    }
+
+    #[test]
+    fn noted_paths_are_searched() {
+        let notes = notes(&[
+            (r"C:\a.txt", "todo: check"),
+            (r"D:\b", "done"),
+            (r"D:\c.rs", "TODO later"),
+        ]);
+        let search = |terms: &[&str]| {
+            notes.search(
+                &terms
+                    .iter()
+                    .map(|term| term.to_string())
+                    .collect::<Vec<_>>(),
+            )
+        };
+        assert_eq!(search(&["todo"]).unwrap(), r#"<"C:\a.txt" | "D:\c.rs">"#);
+        // a bare `note:`
+        assert_eq!(
+            search(&[""]).unwrap(),
+            r#"<"C:\a.txt" | "D:\b" | "D:\c.rs">"#
+        );
+        assert_eq!(search(&["todo", "later"]).unwrap(), r#"<"D:\c.rs">"#);
+        assert_eq!(search(&["nothing"]), None);
+    }
+}
@@ -0,0 +1,82 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::format;
+
+/// The file of the files opened through ery, in the config folder.
+const OPENED_FILE_NAME: &str = "opened.json";
+
+/// Files kept at most.
+const MAX_OPENED: usize = 100;
+
+/// A file opened through ery.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenedFile {
+    pub path: PathBuf,
+    /// the last time it is opened, as the FILETIME of the dates of Everything.
+    pub time: u64,
+}
+
+/// The files opened through ery lately, across the runs, the latest first. They are ery's own,
+/// not the run history of Everything, which counts the ones run by Everything too.
+#[derive(Debug, Default)]
+pub struct Opened {
+    pub files: Vec<OpenedFile>,
+}
+
+impl Opened {
+    /// The files saved in the config folder, none if there is no file.
+    pub fn load() -> anyhow::Result<Self> {
+        let Some(path) = Self::path().filter(|path| path.is_file()) else {
+            return Ok(Self::default());
+        };
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read opened files {}", path.display()))?;
+        let files = serde_json::from_str(&text)
+            .with_context(|| format!("failed to parse opened files {}", path.display()))?;
+        Ok(Self { files })
+    }
+
+    /// Put the file first with the time now, or move it first if it is there, and save them.
+    pub fn add(&mut self, path: &Path) -> anyhow::Result<()> {
+        self.files.retain(|file| file.path != path);
+        self.files.insert(
+            0,
+            OpenedFile {
+                path: path.to_path_buf(),
+                time: format::filetime_now(),
+            },
+        );
+        self.files.truncate(MAX_OPENED);
+        self.save()
+    }
+
+    /// Forget the file, e.g. it is gone.
+    pub fn remove(&mut self, path: &Path) -> anyhow::Result<()> {
+        self.files.retain(|file| file.path != path);
+        self.save()
+    }
+
+    fn path() -> Option<PathBuf> {
+        Some(Config::dir()?.join(OPENED_FILE_NAME))
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        let text = serde_json::to_string_pretty(&self.files)?;
+        fs::write(&path, text)
+            .with_context(|| format!("failed to save opened files {}", path.display()))
+    }
+}
@@ -0,0 +1,68 @@
+/// The search of the words given on the command line, the ones with spaces quoted as phrases,
+/// e.g. `"New Folder" report` of `ery "New Folder" report`, as the shell took the quotes.
+///
+/// The words in the search syntax of Everything already are kept as they are, e.g.
+/// `path:"C:\My Docs"`, `"ext:rs main"` or `"draft | final"`, as the syntax is meant.
+pub fn join_args(args: &[String]) -> String {
+    args.iter()
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn quote(arg: &str) -> String {
+    if !arg.contains(char::is_whitespace) || is_syntax(arg) {
+        return arg.to_string();
+    }
+    format!(r#""{arg}""#)
+}
+
+/// Whether any quotes, operators (`|`, `<` `>` and `!`), modifiers or functions are in it.
+fn is_syntax(arg: &str) -> bool {
+    arg.contains(['"', '|', '<', '>'])
+        || arg
+            .split_whitespace()
+            .any(|word| word.starts_with('!') || is_function(word))
+}
+
+/// Whether the word is a modifier or function like `case:` or `ext:rs`, but not a drive as
+/// `C:\My Docs`.
+fn is_function(word: &str) -> bool {
+    word.split_once(':')
+        .is_some_and(|(name, _)| name.len() > 1 && name.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn join(args: &[&str]) -> String {
+        join_args(&args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn words_are_joined_by_spaces() {
+        assert_eq!(join(&["ext:rs", "main"]), "ext:rs main");
+    }
+
+    #[test]
+    fn phrases_are_quoted() {
+        assert_eq!(join(&["New Folder", "report"]), r#""New Folder" report"#);
+        assert_eq!(join(&[r"C:\My Docs\", "report"]), r#""C:\My Docs\" report"#);
+    }
+
+    #[test]
+    fn syntax_is_not_quoted() {
+        assert_eq!(join(&["ext:rs main"]), "ext:rs main");
+        assert_eq!(join(&["report case:", "draft"]), "report case: draft");
+        assert_eq!(join(&["draft | final"]), "draft | final");
+        assert_eq!(join(&["<a b>", "!draft copy"]), "<a b> !draft copy");
+    }
+
+    #[test]
+    fn quotes_given_are_kept() {
+        assert_eq!(join(&[r#"path:"C:\My Docs""#]), r#"path:"C:\My Docs""#);
+        assert_eq!(join(&["", "-draft"]), "-draft");
+    }
+}
@@ -0,0 +1,146 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+
+use crate::config::Config;
+
+use super::ery::{QueryEntry, QueryResults};
+
+/// The file of the pins kept for the saved searches, in the config folder.
+const PINS_FILE_NAME: &str = "pins.json";
+
+/// Entries pinned at the top of the results across the queries, e.g. a few candidates being
+/// compared while changing the query.
+#[derive(Debug, Default)]
+pub struct Pins {
+    pins: Vec<Pin>,
+    /// the saved search the pins are kept for, none for this run only.
+    saved: Option<String>,
+}
+
+#[derive(Debug)]
+struct Pin {
+    path: PathBuf,
+    /// the entry last seen in the results, shown even if the query does not match it.
+    entry: Option<QueryEntry>,
+}
+
+impl Pins {
+    /// The pins kept for the saved search, they are saved again when changed.
+    pub fn saved(name: &str) -> anyhow::Result<Self> {
+        let mut all = Self::load_all()?;
+        let pins = all
+            .remove(name)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|path| Pin { path, entry: None })
+            .collect();
+        Ok(Self {
+            pins,
+            saved: Some(name.to_string()),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pins.is_empty()
+    }
+
+    pub fn is_pinned(&self, path: &Path) -> bool {
+        self.pins.iter().any(|pin| pin.path == path)
+    }
+
+    /// Pin the entry, or unpin it if pinned already. True if it is pinned now.
+    pub fn toggle(&mut self, path: PathBuf, entry: QueryEntry) -> anyhow::Result<bool> {
+        let pinned = match self.pins.iter().position(|pin| pin.path == path) {
+            Some(i) => {
+                self.pins.remove(i);
+                false
+            }
+            None => {
+                let entry = Some(entry);
+                self.pins.push(Pin { path, entry });
+                true
+            }
+        };
+        self.save()?;
+        Ok(pinned)
+    }
+
+    /// Move the pinned entries to the top of the results in the order pinned, and put back the
+    /// ones not in the results any more.
+    pub fn apply(&mut self, results: &mut QueryResults) {
+        if self.pins.is_empty() {
+            return;
+        }
+        let mut found: Vec<Option<QueryEntry>> = vec![None; self.pins.len()];
+        let mut rest = Vec::with_capacity(results.entrys.len());
+        for entry in std::mem::take(&mut results.entrys) {
+            let path = results.filepath(&entry);
+            match self
+                .pins
+                .iter()
+                .position(|pin| Some(&pin.path) == path.as_ref())
+            {
+                Some(i) if found[i].is_none() => found[i] = Some(entry),
+                _ => rest.push(entry),
+            }
+        }
+        for (pin, entry) in self.pins.iter_mut().zip(found) {
+            if let Some(entry) = entry {
+                pin.entry = Some(entry.clone());
+                results.entrys.push(entry);
+            } else if let Some(entry) = &pin.entry {
+                // of other results, not matched by this query
+                let mut entry = entry.clone();
+                entry.path = pin
+                    .path
+                    .parent()
+                    .map(|parent| results.paths.intern(parent.to_path_buf()));
+                // the details of it are not in these results
+                entry.is_partial = false;
+                results.entrys.push(entry);
+                results.number += 1;
+                results.total += 1;
+            }
+        }
+        results.entrys.extend(rest);
+    }
+
+    fn path() -> Option<PathBuf> {
+        Some(Config::dir()?.join(PINS_FILE_NAME))
+    }
+
+    /// The pins of all the saved searches, by the names.
+    fn load_all() -> anyhow::Result<BTreeMap<String, Vec<PathBuf>>> {
+        let Some(path) = Self::path().filter(|path| path.is_file()) else {
+            return Ok(BTreeMap::new());
+        };
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read pins {}", path.display()))?;
+        serde_json::from_str(&text)
+            .with_context(|| format!("failed to parse pins {}", path.display()))
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        let (Some(name), Some(path)) = (&self.saved, Self::path()) else {
+            return Ok(());
+        };
+        let mut all = Self::load_all()?;
+        let paths: Vec<PathBuf> = self.pins.iter().map(|pin| pin.path.clone()).collect();
+        if paths.is_empty() {
+            all.remove(name);
+        } else {
+            all.insert(name.clone(), paths);
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        let text = serde_json::to_string_pretty(&all)?;
+        fs::write(&path, text).with_context(|| format!("failed to save pins {}", path.display()))
+    }
+}
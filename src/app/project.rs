@@ -0,0 +1,54 @@
+use std::path::{Path, PathBuf};
+
+use super::excludes;
+
+/// The folders of the version control marking the root of a project, the outermost one.
+const VCS_MARKERS: &[&str] = &[".git"];
+
+/// The manifests marking the root of a project without version control, the nearest one.
+const MANIFEST_MARKERS: &[&str] = &["Cargo.toml", "package.json"];
+
+/// The folders of the builds, the dependencies and the version control, left out of the
+/// searches in the project.
+const PROJECT_EXCLUDES: &[&str] = &[r"\target\", r"\node_modules\", r"\.git\"];
+
+/// The project of the folder ery is launched in, for finding the files of it quickly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Project {
+    pub root: PathBuf,
+}
+
+impl Project {
+    /// The project containing the folder: the repository of it, or the nearest folder of a
+    /// manifest, e.g. `Cargo.toml`. None if it is in no project.
+    pub fn detect(dir: &Path) -> Option<Self> {
+        let has_any =
+            |dir: &Path, markers: &[&str]| markers.iter().any(|marker| dir.join(marker).exists());
+        let root = dir
+            .ancestors()
+            .find(|dir| has_any(dir, VCS_MARKERS))
+            .or_else(|| dir.ancestors().find(|dir| has_any(dir, MANIFEST_MARKERS)))?;
+        Some(Self {
+            root: root.to_path_buf(),
+        })
+    }
+
+    /// The name of the root folder, shown as the profile.
+    pub fn name(&self) -> String {
+        self.root.file_name().map_or_else(
+            || self.root.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        )
+    }
+
+    /// The search of the files in the project, without the builds and the dependencies.
+    pub fn search(&self) -> String {
+        let root = self.root.display().to_string();
+        let excludes: Vec<String> = PROJECT_EXCLUDES.iter().map(|e| e.to_string()).collect();
+        format!(
+            r#""{}\" {}"#,
+            root.trim_end_matches('\\'),
+            excludes::clauses(&excludes)
+        )
+    }
+}
@@ -0,0 +1,64 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::Context;
+
+use crate::config::Config;
+
+/// The file of the recent searches, in the config folder, the latest first, one on a line.
+const RECENT_FILE_NAME: &str = "recent.txt";
+
+/// Searches kept at most.
+const MAX_RECENT: usize = 8;
+
+/// The searches submitted lately, across the runs, shown before any query.
+#[derive(Debug, Default)]
+pub struct Recent {
+    pub searches: Vec<String>,
+}
+
+impl Recent {
+    /// The recent searches saved in the config folder, none if there is no file.
+    pub fn load() -> anyhow::Result<Self> {
+        let Some(path) = Self::path().filter(|path| path.is_file()) else {
+            return Ok(Self::default());
+        };
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read recent searches {}", path.display()))?;
+        let searches = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .take(MAX_RECENT)
+            .map(str::to_string)
+            .collect();
+        Ok(Self { searches })
+    }
+
+    /// Put the search first, or move it first if it is there, and save them.
+    pub fn add(&mut self, search: &str) -> anyhow::Result<()> {
+        if search.trim().is_empty() || self.searches.first().is_some_and(|s| s == search) {
+            return Ok(());
+        }
+        self.searches.retain(|s| s != search);
+        self.searches.insert(0, search.to_string());
+        self.searches.truncate(MAX_RECENT);
+        self.save()
+    }
+
+    fn path() -> Option<PathBuf> {
+        Some(Config::dir()?.join(RECENT_FILE_NAME))
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        let mut text = self.searches.join("\n");
+        text.push('\n');
+        fs::write(&path, text)
+            .with_context(|| format!("failed to save recent searches {}", path.display()))
+    }
+}
@@ -0,0 +1,387 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    process,
+};
+
+use anyhow::Context;
+use regex::Regex;
+
+use crate::tr;
+
+/// The chars not allowed in the file names of Windows.
+const INVALID_CHARS: [char; 9] = ['\\', '/', ':', '*', '?', '"', '<', '>', '|'];
+
+/// The case of the new names, the extensions kept as they are.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Case {
+    #[default]
+    Keep,
+    Lower,
+    Upper,
+    /// the first letter of each word in uppercase, the others in lowercase.
+    Title,
+}
+
+impl Case {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Keep => Self::Lower,
+            Self::Lower => Self::Upper,
+            Self::Upper => Self::Title,
+            Self::Title => Self::Keep,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Keep => tr!(CaseKeep),
+            Self::Lower => tr!(CaseLower),
+            Self::Upper => tr!(CaseUpper),
+            Self::Title => tr!(CaseTitle),
+        }
+    }
+
+    fn apply(self, s: &str) -> String {
+        match self {
+            Self::Keep => s.to_string(),
+            Self::Lower => s.to_lowercase(),
+            Self::Upper => s.to_uppercase(),
+            Self::Title => {
+                let mut title = String::with_capacity(s.len());
+                let mut is_word_start = true;
+                for c in s.chars() {
+                    if is_word_start {
+                        title.extend(c.to_uppercase());
+                    } else {
+                        title.extend(c.to_lowercase());
+                    }
+                    is_word_start = !c.is_alphanumeric() && c != '\'';
+                }
+                title
+            }
+        }
+    }
+}
+
+/// How the files are renamed, the same for all of them.
+#[derive(Debug, Clone, Default)]
+pub struct Rule {
+    /// the text replaced in the names, all of the name but the extension if empty.
+    pub find: String,
+    /// with the numbers of the files by `{n}`, or `{nnn}` padded to 3 digits, and the groups of
+    /// the regex by `$1` or `${name}`.
+    pub replace: String,
+    /// `find` is a regex.
+    pub regex: bool,
+    pub case: Case,
+}
+
+/// Why a file can't be renamed as planned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Problem {
+    /// empty, or with the chars not allowed in the names.
+    Invalid,
+    /// the same as the new name of another one in the folder.
+    Duplicate,
+    /// another file or folder of the name exists, and is not renamed in the same batch.
+    Exists,
+    /// the old name is not of Unicode, which the rule can not be applied to.
+    NotUnicode,
+}
+
+impl Problem {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Invalid => tr!(RenameInvalid),
+            Self::Duplicate => tr!(RenameDuplicate),
+            Self::Exists => tr!(RenameExists),
+            Self::NotUnicode => tr!(RenameNotUnicode),
+        }
+    }
+}
+
+/// The new name of a file, and the problem of it if any.
+#[derive(Debug, Clone)]
+pub struct Renaming {
+    pub from: PathBuf,
+    pub to: String,
+    pub problem: Option<Problem>,
+}
+
+impl Renaming {
+    /// The old name to show, `�` for the chars not of Unicode.
+    pub fn old_name(&self) -> String {
+        self.from
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.from.file_name() != Some(OsStr::new(&self.to))
+    }
+}
+
+/// The new names of the files by the rule, numbered in the order of them, with the conflicts.
+///
+/// The names of the files renamed in the same batch are free for the others, e.g. two files
+/// swapping their names, or `{n}` renumbering them; they are renamed through the temporary
+/// names by [`temporary_name`].
+pub fn plan(paths: &[PathBuf], rule: &Rule) -> anyhow::Result<Vec<Renaming>> {
+    let regex = if rule.regex && !rule.find.is_empty() {
+        Some(Regex::new(&rule.find).context("invalid regex")?)
+    } else {
+        None
+    };
+    let mut renamings: Vec<Renaming> = paths
+        .iter()
+        .enumerate()
+        .map(|(i, path)| {
+            let Some(name) = name_of(path) else {
+                // shown only, never renamed to
+                let to = path.file_name().unwrap_or_default().to_string_lossy();
+                return Renaming {
+                    from: path.clone(),
+                    to: to.into_owned(),
+                    problem: Some(Problem::NotUnicode),
+                };
+            };
+            let replace = number(&rule.replace, i + 1);
+            let renamed = match &regex {
+                Some(regex) => regex.replace_all(name, replace.as_str()).into_owned(),
+                None if rule.find.is_empty() && replace.is_empty() => name.to_string(),
+                None if rule.find.is_empty() => {
+                    let (_, extension) = split_extension(name, path);
+                    format!("{replace}{extension}")
+                }
+                None => name.replace(&rule.find, &replace),
+            };
+            let (stem, extension) = split_extension(&renamed, path);
+            Renaming {
+                from: path.clone(),
+                to: format!("{}{extension}", rule.case.apply(stem)),
+                problem: None,
+            }
+        })
+        .collect();
+
+    // the new names in each folder, case-insensitive as the names of Windows
+    let mut counts: HashMap<(PathBuf, String), usize> = HashMap::new();
+    for renaming in renamings
+        .iter()
+        .filter(|renaming| renaming.problem.is_none())
+    {
+        *counts.entry(target_key(renaming)).or_default() += 1;
+    }
+    // the old names given up in each folder
+    let freed: HashSet<(PathBuf, String)> = renamings
+        .iter()
+        .filter(|renaming| renaming.problem.is_none() && renaming.is_changed())
+        .map(source_key)
+        .collect();
+    for renaming in renamings
+        .iter_mut()
+        .filter(|renaming| renaming.problem.is_none())
+    {
+        renaming.problem = if renaming.to.is_empty() || renaming.to.contains(INVALID_CHARS) {
+            Some(Problem::Invalid)
+        } else if counts[&target_key(renaming)] > 1 {
+            Some(Problem::Duplicate)
+        } else if renaming.to.to_lowercase() != renaming.old_name().to_lowercase()
+            && !freed.contains(&target_key(renaming))
+            && renaming.from.with_file_name(&renaming.to).exists()
+        {
+            Some(Problem::Exists)
+        } else {
+            None
+        };
+    }
+    Ok(renamings)
+}
+
+/// Fail by the first one of a problem, so that none is renamed.
+pub fn check(renamings: &[Renaming]) -> anyhow::Result<()> {
+    match renamings
+        .iter()
+        .find_map(|renaming| Some((renaming, renaming.problem?)))
+    {
+        Some((renaming, problem)) => anyhow::bail!("{}: {}", renaming.to, problem.label()),
+        None => Ok(()),
+    }
+}
+
+/// The name of the file, none if it is not of Unicode.
+fn name_of(path: &Path) -> Option<&str> {
+    path.file_name()?.to_str()
+}
+
+/// The name without the extension and the extension with the dot, none for the folders.
+fn split_extension<'a>(name: &'a str, path: &Path) -> (&'a str, &'a str) {
+    match name.rfind('.') {
+        Some(dot) if dot > 0 && !path.is_dir() => name.split_at(dot),
+        _ => (name, ""),
+    }
+}
+
+/// The name of the file in the first pass of the batch, so that no new name is taken by an
+/// old one yet.
+pub fn temporary_name(i: usize) -> String {
+    format!("~ery-rename-{}-{i}.tmp", process::id())
+}
+
+fn target_key(renaming: &Renaming) -> (PathBuf, String) {
+    (folder_of(renaming), renaming.to.to_lowercase())
+}
+
+fn source_key(renaming: &Renaming) -> (PathBuf, String) {
+    (folder_of(renaming), renaming.old_name().to_lowercase())
+}
+
+fn folder_of(renaming: &Renaming) -> PathBuf {
+    renaming
+        .from
+        .parent()
+        .unwrap_or(Path::new(""))
+        .to_path_buf()
+}
+
+/// Replace the `{n}` and `{nn..}` of the text by the number, padded to the count of `n`.
+fn number(text: &str, n: usize) -> String {
+    let mut numbered = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        let (before, after) = rest.split_at(start);
+        numbered.push_str(before);
+        let width = after[1..].chars().take_while(|&c| c == 'n').count();
+        if width > 0 && after[1 + width..].starts_with('}') {
+            numbered.push_str(&format!("{n:0width$}"));
+            rest = &after[width + 2..];
+        } else {
+            numbered.push('{');
+            rest = &after[1..];
+        }
+    }
+    numbered.push_str(rest);
+    numbered
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::*;
+
+    /// A folder of the files for the test, empty files of the names.
+    fn folder(test: &str, names: &[&str]) -> PathBuf {
+        let folder = env::temp_dir().join(format!("ery-rename-{test}-{}", process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        for name in names {
+            fs::write(folder.join(name), "").unwrap();
+        }
+        folder
+    }
+
+    fn rule(find: &str, replace: &str) -> Rule {
+        Rule {
+            find: find.to_string(),
+            replace: replace.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn new_names(renamings: &[Renaming]) -> Vec<(&str, Option<Problem>)> {
+        renamings
+            .iter()
+            .map(|renaming| (renaming.to.as_str(), renaming.problem))
+            .collect()
+    }
+
+    #[test]
+    fn numbers_are_padded_to_the_count_of_n() {
+        assert_eq!(number("img_{n}", 7), "img_7");
+        assert_eq!(number("img_{nnn}", 7), "img_007");
+        assert_eq!(number("{nn}-{n}", 123), "123-123");
+        assert_eq!(number("{x} {n", 1), "{x} {n");
+        assert_eq!(number("{}", 1), "{}");
+    }
+
+    #[test]
+    fn names_are_replaced_by_the_rule() {
+        let paths = ["IMG_01.JPG", "IMG_02.JPG"].map(PathBuf::from);
+
+        let renamings = plan(&paths, &rule("", "photo {nn}")).unwrap();
+        let expected = [("photo 01.JPG", None), ("photo 02.JPG", None)];
+        assert_eq!(new_names(&renamings), expected);
+
+        let regex = Rule {
+            regex: true,
+            case: Case::Lower,
+            ..rule(r"IMG_(\d+)", "${1}_img")
+        };
+        let renamings = plan(&paths, &regex).unwrap();
+        assert_eq!(
+            new_names(&renamings),
+            [("01_img.JPG", None), ("02_img.JPG", None)]
+        );
+
+        let title = Rule {
+            case: Case::Title,
+            ..rule("IMG", "my photo")
+        };
+        let renamings = plan(&paths, &title).unwrap();
+        assert_eq!(new_names(&renamings)[0], ("My Photo_01.JPG", None));
+    }
+
+    #[test]
+    fn conflicts_are_found() {
+        let folder = folder("conflicts", &["a.txt", "b.txt", "c.txt"]);
+        let paths = [folder.join("a.txt"), folder.join("b.txt")];
+
+        let renamings = plan(&paths, &rule("", "same")).unwrap();
+        assert_eq!(renamings[0].problem, Some(Problem::Duplicate));
+
+        let renamings = plan(&paths, &rule("", "bad:name")).unwrap();
+        assert_eq!(renamings[0].problem, Some(Problem::Invalid));
+
+        // c.txt is not renamed in the batch
+        let renamings = plan(&paths[..1], &rule("a", "c")).unwrap();
+        assert_eq!(new_names(&renamings), [("c.txt", Some(Problem::Exists))]);
+        assert!(check(&renamings).is_err());
+
+        // only the case changed
+        let renamings = plan(&paths[..1], &rule("a", "A")).unwrap();
+        assert!(check(&renamings).is_ok());
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn names_of_the_batch_are_free_for_each_other() {
+        let folder = folder("swap", &["1.txt", "2.txt", "b.txt"]);
+
+        // swapped, 2.txt to 1.txt and 1.txt to 2.txt
+        let paths = [folder.join("2.txt"), folder.join("1.txt")];
+        let renamings = plan(&paths, &rule("", "{n}")).unwrap();
+        assert_eq!(new_names(&renamings), [("1.txt", None), ("2.txt", None)]);
+        assert!(check(&renamings).is_ok());
+
+        // renumbered, 1.txt and 2.txt are given up by the ones renamed to 2.txt and 3.txt
+        let paths = ["b.txt", "1.txt", "2.txt"].map(|name| folder.join(name));
+        let renamings = plan(&paths, &rule("", "{n}")).unwrap();
+        let expected = [("1.txt", None), ("2.txt", None), ("3.txt", None)];
+        assert_eq!(new_names(&renamings), expected);
+
+        // 1.txt is kept, so not free
+        let paths = [folder.join("1.txt"), folder.join("b.txt")];
+        let renamings = plan(&paths, &rule("", "1")).unwrap();
+        assert_eq!(renamings[1].problem, Some(Problem::Duplicate));
+        let renamings = plan(&paths[1..], &rule("b", "1")).unwrap();
+        assert_eq!(new_names(&renamings), [("1.txt", Some(Problem::Exists))]);
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -0,0 +1,120 @@
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    ffi::OsString,
+    path::{Component, Path, PathBuf},
+};
+
+use super::ery::{QueryEntry, QueryResults};
+use super::extensions::extension;
+
+/// Items in each list of the report.
+const TOP: usize = 5;
+/// Groups in the counts of the report.
+const TOP_GROUPS: usize = 10;
+
+#[derive(Debug)]
+pub struct ReportItem {
+    pub path: PathBuf,
+    /// the date modified (FILETIME) or the size, by the list.
+    pub value: u64,
+}
+
+/// Summary of a result set, computed over the loaded entries.
+#[derive(Debug, Default)]
+pub struct Report {
+    pub search: OsString,
+    /// entries summarized, and all the matches in Everything.
+    pub count: usize,
+    pub total: u32,
+    /// the dates modified or the sizes are not in the results, see `query.flags` in config.
+    pub has_dates: bool,
+    pub has_sizes: bool,
+    pub newest: Vec<ReportItem>,
+    pub oldest: Vec<ReportItem>,
+    pub largest: Vec<ReportItem>,
+    pub smallest: Vec<ReportItem>,
+    /// files by the lowercase extension, the most first.
+    pub extensions: Vec<(String, usize)>,
+    /// entries by the first folder under the drive, e.g. `C:\Users`, the most first.
+    pub folders: Vec<(PathBuf, usize)>,
+}
+
+impl Report {
+    pub fn new(results: &QueryResults) -> Self {
+        let entrys = &results.entrys;
+        let items = |value: fn(&QueryEntry) -> Option<u64>, newest_first: bool| {
+            let mut items: Vec<ReportItem> = entrys
+                .iter()
+                .filter_map(|entry| {
+                    Some(ReportItem {
+                        value: value(entry)?,
+                        path: results.filepath(entry)?,
+                    })
+                })
+                .collect();
+            if newest_first {
+                items.sort_by_key(|item| Reverse(item.value));
+            } else {
+                items.sort_by_key(|item| item.value);
+            }
+            items.truncate(TOP);
+            items
+        };
+        // the sizes of the folders are only there if indexed, skip them
+        let file_size = |entry: &QueryEntry| entry.size.filter(|_| !entry.is_folder);
+
+        let mut extensions: HashMap<String, usize> = HashMap::new();
+        let mut folders: HashMap<PathBuf, usize> = HashMap::new();
+        for entry in entrys {
+            if !entry.is_folder {
+                let mut extension = extension(entry);
+                if extension.is_empty() {
+                    extension = "(none)".to_string();
+                }
+                *extensions.entry(extension).or_default() += 1;
+            }
+            if let Some(path) = results.path(entry) {
+                *folders.entry(top_folder(path)).or_default() += 1;
+            }
+        }
+
+        Self {
+            search: results.search.clone(),
+            count: entrys.len(),
+            total: results.total,
+            has_dates: entrys.iter().any(|entry| entry.date_modified.is_some()),
+            has_sizes: entrys.iter().any(|entry| entry.size.is_some()),
+            newest: items(|entry| entry.date_modified, true),
+            oldest: items(|entry| entry.date_modified, false),
+            largest: items(file_size, true),
+            smallest: items(file_size, false),
+            extensions: most_first(extensions),
+            folders: most_first(folders),
+        }
+    }
+
+    /// Whether some matches are not loaded, so not in the report.
+    pub fn is_partial(&self) -> bool {
+        self.count < self.total as usize
+    }
+}
+
+/// The drive and the first folder under it, or the drive itself.
+fn top_folder(path: &Path) -> PathBuf {
+    let mut top = PathBuf::new();
+    for component in path.components() {
+        top.push(component);
+        if let Component::Normal(_) = component {
+            break;
+        }
+    }
+    top
+}
+
+fn most_first<K: Ord>(counts: HashMap<K, usize>) -> Vec<(K, usize)> {
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts.truncate(TOP_GROUPS);
+    counts
+}
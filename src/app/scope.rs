@@ -0,0 +1,69 @@
+use crate::tr;
+
+/// What the words of the search match, switched by `F4`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// the file names only, as Everything does by default.
+    #[default]
+    Name,
+    /// the full paths, the `match_path` of the query.
+    Path,
+    /// the file names and the names of the folders containing them, e.g. `src\main.rs`.
+    Tail,
+}
+
+impl Scope {
+    pub fn next(self) -> Self {
+        match self {
+            Scope::Name => Scope::Path,
+            Scope::Path => Scope::Tail,
+            Scope::Tail => Scope::Name,
+        }
+    }
+
+    /// The name shown before the search, none for the default one.
+    pub fn label(self) -> Option<&'static str> {
+        match self {
+            Scope::Name => None,
+            Scope::Path => Some(tr!(ScopePath)),
+            Scope::Tail => Some(tr!(ScopeTail)),
+        }
+    }
+
+    pub fn match_path(self) -> bool {
+        self == Scope::Path
+    }
+
+    /// The search for Everything matching the words in the scope. Only the tail scope rewrites
+    /// the words, each into the name or the folder containing it by a regex of the full path.
+    /// The words of the functions, the modifiers and the operators are kept as they are.
+    pub fn rewrite(self, query_text: &str) -> String {
+        if self != Scope::Tail {
+            return query_text.to_string();
+        }
+        let words: Vec<String> = query_text
+            .split_whitespace()
+            .map(|word| {
+                if word.contains([':', '"', '<', '>', '|', '!', '*', '?']) {
+                    word.to_string()
+                } else {
+                    let pattern = escape_regex(word);
+                    format!(r#"<{word} | path:regex:"{pattern}[^\\]*\\[^\\]*$">"#)
+                }
+            })
+            .collect();
+        words.join(" ")
+    }
+}
+
+/// The word matched literally in a regex of Everything.
+fn escape_regex(word: &str) -> String {
+    let mut escaped = String::with_capacity(word.len());
+    for c in word.chars() {
+        if "\\.+()[]{}^$".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
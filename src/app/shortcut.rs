@@ -0,0 +1,46 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use crate::shell::{self, Shortcut};
+
+use super::event::{AppEvent, AppSender};
+
+/// The target of a shortcut, none while it is being read.
+pub type Target = Option<Result<Shortcut, String>>;
+
+/// The targets of the shortcuts (`.lnk`) of the results, read by the shell link API in the
+/// background, as the targets on the network or the sleeping disks may be slow.
+#[derive(Debug, Default, Clone)]
+pub struct Shortcuts {
+    targets: Arc<Mutex<HashMap<PathBuf, Target>>>,
+}
+
+impl Shortcuts {
+    pub fn is_shortcut(path: &Path) -> bool {
+        path.extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("lnk"))
+    }
+
+    /// Read the target of the shortcut in the background, again if it is read before, as the
+    /// shortcut may be changed, then refresh.
+    pub fn resolve(&self, path: &Path, sender: &AppSender) {
+        let path = path.to_path_buf();
+        self.targets.lock().unwrap().insert(path.clone(), None);
+        let targets = Arc::clone(&self.targets);
+        let sender = sender.clone();
+        thread::spawn(move || {
+            let target = shell::read_shortcut(&path).map_err(|e| format!("{e:#}"));
+            targets.lock().unwrap().insert(path, Some(target));
+            sender.send(AppEvent::Refresh);
+        });
+    }
+
+    /// The target of the shortcut, none if it is not resolved.
+    pub fn target(&self, path: &Path) -> Option<Target> {
+        self.targets.lock().unwrap().get(path).cloned()
+    }
+}
@@ -0,0 +1,74 @@
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use super::ery::QueryResults;
+use super::task::TaskContext;
+
+/// File extension of the snapshot files.
+pub const SNAPSHOT_EXTENSION: &str = "ery";
+
+/// Bump it when the format of snapshot files changes incompatibly.
+const SNAPSHOT_FORMAT_VERSION: u32 = 3;
+
+/// A saved [`QueryResults`] with some metadata, which can be browsed later without Everything.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub format_version: u32,
+    /// seconds since the unix epoch when the snapshot was taken.
+    pub created: u64,
+    /// version of the Everything which the results come from.
+    pub everything_version: (u32, u32, u32, u32),
+    pub results: QueryResults,
+}
+
+impl Snapshot {
+    pub fn new(results: QueryResults, everything_version: (u32, u32, u32, u32)) -> Self {
+        let created = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Self {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            created,
+            everything_version,
+            results,
+        }
+    }
+
+    /// Default file name of the snapshot, like `ery-1700000000.ery`.
+    pub fn default_file_name(&self) -> PathBuf {
+        PathBuf::from(format!("ery-{}.{SNAPSHOT_EXTENSION}", self.created))
+    }
+
+    /// Save it in the task, the progress in the bytes written.
+    pub fn save(&self, path: impl AsRef<Path>, context: &mut TaskContext) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let bytes = serde_json::to_vec(self)?;
+        let mut file = File::create(path)
+            .with_context(|| format!("failed to create snapshot {}", path.display()))?;
+        context
+            .write_all(&mut file, &bytes)
+            .with_context(|| format!("failed to write snapshot {}", path.display()))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .with_context(|| format!("failed to open snapshot {}", path.display()))?;
+        let snapshot: Self = serde_json::from_reader(BufReader::new(file))
+            .with_context(|| format!("failed to read snapshot {}", path.display()))?;
+        anyhow::ensure!(
+            snapshot.format_version == SNAPSHOT_FORMAT_VERSION,
+            "unsupported snapshot format version {} in {}",
+            snapshot.format_version,
+            path.display()
+        );
+        Ok(snapshot)
+    }
+}
@@ -0,0 +1,349 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    ffi::OsStr,
+    path::Path,
+};
+
+use everything_sdk::{RequestFlags, SortType};
+
+use crate::config::{DefaultSort, QueryConfig};
+use crate::tr;
+
+use super::ery::{PathInterner, QueryEntry, QueryResults};
+
+/// Keys of the secondary sort, which is done by ery on the fetched results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Name,
+    Path,
+    Size,
+    Extension,
+    DateModified,
+    /// how well the entries match the search, see [`sort_relevance`].
+    Relevance,
+}
+
+/// Extensions ranked first by relevance, the programs and shortcuts as the launcher searches.
+const PRIORITY_EXTENSIONS: [&str; 2] = ["exe", "lnk"];
+
+/// The sorts switched by `Ctrl+O`: the primary one by Everything, then the secondary one.
+pub const SORT_CYCLE: [(SortType, Option<SortKey>); 8] = [
+    (SortType::EVERYTHING_SORT_NAME_ASCENDING, None),
+    (SortType::EVERYTHING_SORT_PATH_ASCENDING, None),
+    (SortType::EVERYTHING_SORT_SIZE_DESCENDING, None),
+    (SortType::EVERYTHING_SORT_DATE_MODIFIED_DESCENDING, None),
+    (
+        SortType::EVERYTHING_SORT_DATE_RECENTLY_CHANGED_DESCENDING,
+        None,
+    ),
+    (SortType::EVERYTHING_SORT_EXTENSION_ASCENDING, None),
+    (
+        SortType::EVERYTHING_SORT_EXTENSION_ASCENDING,
+        Some(SortKey::Name),
+    ),
+    (
+        SortType::EVERYTHING_SORT_NAME_ASCENDING,
+        Some(SortKey::Relevance),
+    ),
+];
+
+/// The sort of `query.sort` in config, one in [`SORT_CYCLE`].
+pub fn default_sort(sort: DefaultSort) -> (SortType, Option<SortKey>) {
+    match sort {
+        DefaultSort::Name => (SortType::EVERYTHING_SORT_NAME_ASCENDING, None),
+        DefaultSort::Path => (SortType::EVERYTHING_SORT_PATH_ASCENDING, None),
+        DefaultSort::Size => (SortType::EVERYTHING_SORT_SIZE_DESCENDING, None),
+        DefaultSort::DateModified => (SortType::EVERYTHING_SORT_DATE_MODIFIED_DESCENDING, None),
+        DefaultSort::RecentlyChanged => (
+            SortType::EVERYTHING_SORT_DATE_RECENTLY_CHANGED_DESCENDING,
+            None,
+        ),
+        DefaultSort::Extension => (SortType::EVERYTHING_SORT_EXTENSION_ASCENDING, None),
+        DefaultSort::Relevance => (
+            SortType::EVERYTHING_SORT_NAME_ASCENDING,
+            Some(SortKey::Relevance),
+        ),
+    }
+}
+
+/// The sort of the search by `query.smart_sort`, of the first function of it there, e.g. the
+/// largest first for `size:>1gb`, or `query.sort` otherwise. The excluded ones like `!size:` are
+/// not taken.
+pub fn sort_for(search: &str, config: &QueryConfig) -> (SortType, Option<SortKey>) {
+    let smart = search
+        .split_whitespace()
+        .filter(|word| !word.starts_with('!'))
+        .find_map(|word| {
+            let (function, _) = word.trim_start_matches('<').split_once(':')?;
+            config
+                .smart_sort
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(function))
+                .map(|(_, sort)| *sort)
+        });
+    default_sort(smart.unwrap_or(config.sort))
+}
+
+impl SortKey {
+    /// The key of the primary sort, if ery can compare by it.
+    fn of(sort_type: SortType) -> Option<Self> {
+        match sort_name(sort_type).0 {
+            "Name" => Some(SortKey::Name),
+            "Path" => Some(SortKey::Path),
+            "Size" => Some(SortKey::Size),
+            "Extension" => Some(SortKey::Extension),
+            "Date Modified" => Some(SortKey::DateModified),
+            _ => None,
+        }
+    }
+
+    /// The fields compared by the key, which must be converted with the results to sort them.
+    pub fn request_flags(self) -> RequestFlags {
+        match self {
+            SortKey::Name | SortKey::Extension => RequestFlags::EVERYTHING_REQUEST_FILE_NAME,
+            SortKey::Path => RequestFlags::EVERYTHING_REQUEST_PATH,
+            SortKey::Size => RequestFlags::EVERYTHING_REQUEST_SIZE,
+            SortKey::DateModified => RequestFlags::EVERYTHING_REQUEST_DATE_MODIFIED,
+            SortKey::Relevance => {
+                RequestFlags::EVERYTHING_REQUEST_FILE_NAME
+                    | RequestFlags::EVERYTHING_REQUEST_PATH
+                    | RequestFlags::EVERYTHING_REQUEST_RUN_COUNT
+            }
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SortKey::Name => tr!(SortName),
+            SortKey::Path => tr!(SortPath),
+            SortKey::Size => tr!(SortSize),
+            SortKey::Extension => tr!(SortExtension),
+            SortKey::DateModified => tr!(SortDateModified),
+            SortKey::Relevance => tr!(SortRelevance),
+        }
+    }
+
+    /// Ascending order by the key, case-insensitive for the names as Everything does.
+    fn compare(self, a: &QueryEntry, b: &QueryEntry, paths: &PathInterner) -> Ordering {
+        let lowercase = |s: Option<&Path>| s.map(|s| s.to_string_lossy().to_lowercase());
+        match self {
+            SortKey::Name => lowercase(a.filename.as_deref().map(Path::new))
+                .cmp(&lowercase(b.filename.as_deref().map(Path::new))),
+            SortKey::Path => {
+                let path = |entry: &QueryEntry| entry.path.and_then(|id| paths.get(id));
+                lowercase(path(a)).cmp(&lowercase(path(b)))
+            }
+            SortKey::Size => a.size.cmp(&b.size),
+            SortKey::Extension => {
+                let extension = |entry: &QueryEntry| {
+                    let extension = match &entry.extension {
+                        Some(extension) => extension.as_os_str(),
+                        None => Path::new(entry.filename.as_deref()?).extension()?,
+                    };
+                    Some(extension.to_string_lossy().to_lowercase())
+                };
+                extension(a).cmp(&extension(b))
+            }
+            SortKey::DateModified => a.date_modified.cmp(&b.date_modified),
+            // scored over the search by `sort_relevance`, not by pairs
+            SortKey::Relevance => Ordering::Equal,
+        }
+    }
+}
+
+/// Sort the entries by the primary sort of them, e.g. the results merged from several queries.
+/// Nothing if ery can not compare by it.
+pub fn sort_primary(results: &mut QueryResults) {
+    let Some(primary) = SortKey::of(results.sort_type) else {
+        return;
+    };
+    let descending = sort_name(results.sort_type).1;
+    let QueryResults { entrys, paths, .. } = results;
+    entrys.sort_by(|a, b| {
+        let ordering = primary.compare(a, b, paths);
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+/// Sort the entries which are equal in the primary sort by the secondary key. The sort is
+/// stable, and only over the fetched results, not all the matches in Everything.
+pub fn sort_secondary(results: &mut QueryResults, secondary: SortKey) {
+    if secondary == SortKey::Relevance {
+        return sort_relevance(results);
+    }
+    let Some(primary) = SortKey::of(results.sort_type) else {
+        return;
+    };
+    let QueryResults { entrys, paths, .. } = results;
+    for group in entrys.chunk_by_mut(|a, b| primary.compare(a, b, paths).is_eq()) {
+        group.sort_by(|a, b| secondary.compare(a, b, paths));
+    }
+}
+
+/// Sort the entries by how well they match the search, the best first as a launcher ranks
+/// them. The sort is stable, and only over the fetched results, not all the matches in
+/// Everything.
+pub fn sort_relevance(results: &mut QueryResults) {
+    let terms = search_terms(&results.search);
+    let QueryResults { entrys, paths, .. } = results;
+    entrys.sort_by_cached_key(|entry| Reverse(relevance(entry, &terms, paths)));
+}
+
+/// The words of the search to score the entries by, lowercase and without the functions,
+/// e.g. `ext:exe`, the excluded ones or the wildcards.
+fn search_terms(search: &OsStr) -> Vec<String> {
+    search
+        .to_string_lossy()
+        .split_whitespace()
+        .filter(|term| !term.contains(':') && !term.starts_with('!'))
+        .map(|term| {
+            term.trim_matches(|c| matches!(c, '"' | '*' | '?'))
+                .to_lowercase()
+        })
+        .filter(|term| !term.is_empty())
+        .collect()
+}
+
+/// The score of the entry: where the words match in the name, or in the path only, then the
+/// programs first and the more often run ones.
+fn relevance(entry: &QueryEntry, terms: &[String], paths: &PathInterner) -> u32 {
+    let name = entry
+        .filename
+        .as_deref()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let (stem, extension) = name.rsplit_once('.').unwrap_or((&name, ""));
+    let path = entry
+        .path
+        .and_then(|id| paths.get(id))
+        .map(|path| path.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let mut score = 0;
+    for term in terms {
+        score += match name.find(term.as_str()) {
+            // the whole name but the extension, e.g. `code` of `code.exe`
+            Some(_) if stem == term => 100,
+            Some(0) => 80,
+            // at the start of a word, e.g. `studio` of `visual studio.lnk`
+            Some(i) if name[..i].ends_with(|c: char| !c.is_alphanumeric()) => 60,
+            Some(_) => 40,
+            None if path.contains(term.as_str()) => 10,
+            None => 0,
+        };
+    }
+    if let Some(rank) = PRIORITY_EXTENSIONS.iter().position(|&x| x == extension) {
+        score += 20 - 5 * rank as u32;
+    }
+    // 5 for each doubling of the run count
+    score
+        + entry
+            .run_count
+            .map_or(0, |count| count.saturating_add(1).ilog2() * 5)
+}
+
+/// Short name of the sort, e.g. `size ↓` or `ext, name`.
+pub fn sort_label(sort_type: SortType, secondary: Option<SortKey>) -> String {
+    if secondary == Some(SortKey::Relevance) {
+        return SortKey::Relevance.label().to_string();
+    }
+    let (name, descending) = sort_name(sort_type);
+    let mut label = match SortKey::of(sort_type) {
+        Some(key) => key.label().to_string(),
+        None if is_recently_changed(sort_type) => tr!(SortRecentlyChanged).to_string(),
+        None => name.to_lowercase(),
+    };
+    if descending {
+        label.push_str(" ↓");
+    }
+    if let Some(secondary) = secondary {
+        label.push_str(", ");
+        label.push_str(secondary.label());
+    }
+    label
+}
+
+/// Whether the sort is by the date recently changed, which is shown instead of the date
+/// modified then.
+pub fn is_recently_changed(sort_type: SortType) -> bool {
+    sort_name(sort_type).0 == "Date Recently Changed"
+}
+
+/// Name of the sort in the command line of Everything, and whether it is descending.
+pub fn sort_name(sort_type: SortType) -> (&'static str, bool) {
+    // the sort types are in pairs of ascending and descending, from 1
+    const NAMES: [&str; 13] = [
+        "Name",
+        "Path",
+        "Size",
+        "Extension",
+        "Type",
+        "Date Created",
+        "Date Modified",
+        "Attributes",
+        "File List Filename",
+        "Run Count",
+        "Date Recently Changed",
+        "Date Accessed",
+        "Date Run",
+    ];
+    let index = (sort_type as usize).saturating_sub(1);
+    (NAMES[(index / 2).min(NAMES.len() - 1)], index % 2 == 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(index: u32, name: &str, run_count: Option<u32>) -> QueryEntry {
+        QueryEntry {
+            index,
+            is_volume: false,
+            is_folder: false,
+            is_file: true,
+            filename: Some(name.into()),
+            path: None,
+            full_path_name: None,
+            extension: None,
+            size: None,
+            date_created: None,
+            date_modified: None,
+            date_accessed: None,
+            attributes: None,
+            file_list_filename: None,
+            run_count,
+            date_run: None,
+            date_recently_changed: None,
+            highlighted_filename: None,
+            highlighted_path: None,
+            highlighted_full_path_and_filename: None,
+            is_partial: false,
+        }
+    }
+
+    #[test]
+    fn relevance_requests_the_run_count() {
+        assert!(SortKey::Relevance
+            .request_flags()
+            .contains(RequestFlags::EVERYTHING_REQUEST_RUN_COUNT));
+    }
+
+    #[test]
+    fn relevance_ranks_the_more_run_first() {
+        let mut results = QueryResults {
+            search: "code".into(),
+            entrys: vec![
+                entry(0, "code.exe", Some(1)),
+                entry(1, "code.exe", Some(30)),
+                entry(2, "code.exe", None),
+            ],
+            ..Default::default()
+        };
+        sort_relevance(&mut results);
+        let indexes: Vec<_> = results.entrys.iter().map(|entry| entry.index).collect();
+        assert_eq!(indexes, [1, 0, 2]);
+    }
+}
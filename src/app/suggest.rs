@@ -0,0 +1,204 @@
+use std::mem;
+
+use super::capability::Feature;
+use super::scope::Scope;
+use crate::tr;
+
+/// The modifiers of Everything matching the whole words only.
+const WHOLE_WORD_MODIFIERS: &[&str] = &["ww:", "wholeword:", "wholewords:"];
+
+/// A way out of no results, shown under the empty results and taken by `Alt+<n>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Suggestion {
+    /// drop the `ww:` modifiers, matching the parts of the words too.
+    NoWholeWord,
+    /// drop the `case:` modifiers.
+    NoCase,
+    /// drop the `regex:` modifiers, the regex may be wrong, and match the text as it is.
+    NoRegex,
+    /// clear the filter put before the search, the one of `Ctrl+L`.
+    NoFilter,
+    /// match the full paths, for the words of the folders.
+    MatchPath,
+    /// search the text in the contents of the files.
+    Content,
+}
+
+impl Suggestion {
+    pub fn label(self) -> &'static str {
+        match self {
+            Suggestion::NoWholeWord => tr!(SuggestNoWholeWord),
+            Suggestion::NoCase => tr!(SuggestNoCase),
+            Suggestion::NoRegex => tr!(SuggestNoRegex),
+            Suggestion::NoFilter => tr!(SuggestNoFilter),
+            Suggestion::MatchPath => tr!(SuggestMatchPath),
+            Suggestion::Content => tr!(SuggestContent),
+        }
+    }
+
+    /// The feature of Everything 1.5 it needs, none if 1.4 has it.
+    pub fn requires(self) -> Option<Feature> {
+        match self {
+            Suggestion::Content => Some(Feature::ContentIndex),
+            _ => None,
+        }
+    }
+
+    /// The search text with it taken, the same for the ones of the filter and the scope.
+    pub fn rewrite(self, text: &str) -> String {
+        match self {
+            Suggestion::NoWholeWord => without_modifiers(text, WHOLE_WORD_MODIFIERS),
+            Suggestion::NoCase => without_modifiers(text, &["case:"]),
+            Suggestion::NoRegex => without_modifiers(text, &["regex:"]),
+            Suggestion::NoFilter | Suggestion::MatchPath => text.to_string(),
+            Suggestion::Content => format!(r#"content:"{}""#, text.trim()),
+        }
+    }
+}
+
+/// The suggestions for the search text of no results, with the filter and the scope of it.
+pub fn suggestions(text: &str, has_filter: bool, scope: Scope) -> Vec<Suggestion> {
+    let mut suggestions = vec![];
+    if has_modifier(text, WHOLE_WORD_MODIFIERS) {
+        suggestions.push(Suggestion::NoWholeWord);
+    }
+    if has_modifier(text, &["case:"]) {
+        suggestions.push(Suggestion::NoCase);
+    }
+    if has_modifier(text, &["regex:"]) {
+        suggestions.push(Suggestion::NoRegex);
+    }
+    if has_filter {
+        suggestions.push(Suggestion::NoFilter);
+    }
+    let is_plain = !text.trim().is_empty() && !text.contains([':', '"', '<', '>', '|', '!']);
+    if is_plain && scope != Scope::Path {
+        suggestions.push(Suggestion::MatchPath);
+    }
+    if is_plain {
+        suggestions.push(Suggestion::Content);
+    }
+    suggestions
+}
+
+/// The search of no results relaxed for a retry: the words split at the punctuation and the
+/// camelCase, and matched in order with anything between, e.g. `*my*file*name*` of
+/// `myFile-name`. None for the searches of the modifiers, the functions or the operators, or
+/// the plain words, which Everything matches loosely already.
+pub fn relax(text: &str) -> Option<String> {
+    if text.contains([':', '"', '<', '>', '|', '!', '*', '?', '\\']) {
+        return None;
+    }
+    let mut words = vec![];
+    for part in text.split(|c: char| !c.is_alphanumeric()) {
+        let mut word = String::new();
+        let mut last: Option<char> = None;
+        for c in part.chars() {
+            let is_boundary = last.is_some_and(|last| {
+                (last.is_lowercase() && c.is_uppercase())
+                    || last.is_alphabetic() != c.is_alphabetic()
+            });
+            if is_boundary {
+                words.push(mem::take(&mut word));
+            }
+            word.push(c);
+            last = Some(c);
+        }
+        if !word.is_empty() {
+            words.push(word);
+        }
+    }
+    let plain: Vec<&str> = text.split_whitespace().collect();
+    if words.is_empty() || words == plain {
+        return None;
+    }
+    Some(format!("*{}*", words.join("*")))
+}
+
+/// The modifiers chained at the start of the word and the rest of it, e.g. `case:`, `ww:` and
+/// `main` of `case:ww:main`. The functions are taken too, but not the drives like `c:`.
+fn split_modifiers(word: &str) -> (Vec<&str>, &str) {
+    let mut modifiers = vec![];
+    let mut rest = word;
+    while let Some(end) = rest.find(':') {
+        let name = &rest[..end];
+        if name.len() <= 1 || !name.chars().all(|c| c.is_ascii_alphabetic()) {
+            break;
+        }
+        modifiers.push(&rest[..=end]);
+        rest = &rest[end + 1..];
+    }
+    (modifiers, rest)
+}
+
+fn is_one_of(modifier: &str, modifiers: &[&str]) -> bool {
+    modifiers.iter().any(|m| m.eq_ignore_ascii_case(modifier))
+}
+
+/// Whether any word has any of the modifiers, case-insensitive.
+fn has_modifier(text: &str, modifiers: &[&str]) -> bool {
+    text.split_whitespace().any(|word| {
+        let (chained, _) = split_modifiers(word);
+        chained.iter().any(|m| is_one_of(m, modifiers))
+    })
+}
+
+/// The text without the modifiers, the words of only them dropped.
+fn without_modifiers(text: &str, modifiers: &[&str]) -> String {
+    let words: Vec<String> = text
+        .split_whitespace()
+        .filter_map(|word| {
+            let (chained, rest) = split_modifiers(word);
+            let mut kept: String = chained
+                .into_iter()
+                .filter(|m| !is_one_of(m, modifiers))
+                .collect();
+            kept.push_str(rest);
+            (!kept.is_empty()).then_some(kept)
+        })
+        .collect();
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_are_split_at_camel_case_and_punctuation() {
+        assert_eq!(relax("myFile-name").as_deref(), Some("*my*File*name*"));
+        assert_eq!(relax("foo_bar.txt").as_deref(), Some("*foo*bar*txt*"));
+        assert_eq!(relax("getUserName").as_deref(), Some("*get*User*Name*"));
+    }
+
+    #[test]
+    fn numbers_are_split_from_the_letters() {
+        assert_eq!(
+            relax("report2024final").as_deref(),
+            Some("*report*2024*final*")
+        );
+        assert_eq!(relax("v1 notes").as_deref(), Some("*v*1*notes*"));
+    }
+
+    #[test]
+    fn plain_words_are_not_relaxed() {
+        assert_eq!(relax("hello"), None);
+        assert_eq!(relax("hello world"), None);
+        assert_eq!(relax("  "), None);
+    }
+
+    #[test]
+    fn searches_of_the_syntax_are_not_relaxed() {
+        for text in [
+            "ext:rs",
+            r#""my file""#,
+            "a|b",
+            "!tmp",
+            "*.rs",
+            "a?c",
+            r"src\main",
+        ] {
+            assert_eq!(relax(text), None, "{text}");
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use std::{
+    io::Write,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use super::event::{AppEvent, AppSender};
+
+/// The frontend is refreshed for the progress at most once in the interval.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Bytes written at a time by [`TaskContext::write_all`], the progress is updated between them.
+const WRITE_CHUNK: usize = 1 << 20;
+
+/// What the progress of a task counts, for showing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Bytes,
+    /// e.g. the files or the results.
+    Items,
+    /// only the percent is known, e.g. 7-Zip tells it only.
+    Percent,
+}
+
+/// The progress of a task, shared by the worker thread and the frontend.
+#[derive(Debug, Default)]
+pub struct Progress {
+    total: AtomicU64,
+    done: AtomicU64,
+    canceled: AtomicBool,
+}
+
+impl Progress {
+    /// The done and the total, the total is 0 until it is known.
+    pub fn get(&self) -> (u64, u64) {
+        (
+            self.done.load(Ordering::Relaxed),
+            self.total.load(Ordering::Relaxed),
+        )
+    }
+
+    /// The ratio done in `0.0..=1.0`, 0 until the total is known.
+    pub fn ratio(&self) -> f64 {
+        match self.get() {
+            (_, 0) => 0.0,
+            (done, total) => (done as f64 / total as f64).min(1.0),
+        }
+    }
+
+    pub fn set_total(&self, total: u64) {
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    pub fn set(&self, done: u64, total: u64) {
+        self.total.store(total, Ordering::Relaxed);
+        self.done.store(done, Ordering::Relaxed);
+    }
+
+    pub fn add(&self, done: u64) {
+        self.done.fetch_add(done, Ordering::Relaxed);
+    }
+
+    pub fn cancel(&self) {
+        self.canceled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_canceled(&self) -> bool {
+        self.canceled.load(Ordering::Relaxed)
+    }
+}
+
+/// Given to the work of a task, to tell the progress and to stop when it is canceled.
+pub struct TaskContext {
+    pub progress: Arc<Progress>,
+    sender: AppSender,
+    refreshed: Instant,
+}
+
+impl TaskContext {
+    /// Refresh the frontend for the progress now and then, or fail if it is canceled. Call it
+    /// between the chunks of the work.
+    pub fn update(&mut self) -> anyhow::Result<()> {
+        anyhow::ensure!(!self.progress.is_canceled(), "canceled");
+        if self.refreshed.elapsed() >= PROGRESS_INTERVAL {
+            self.refreshed = Instant::now();
+            self.sender.send(AppEvent::Refresh);
+        }
+        Ok(())
+    }
+
+    /// Write all the bytes in chunks, with the progress in bytes, or fail if it is canceled.
+    pub fn write_all(&mut self, w: &mut impl Write, bytes: &[u8]) -> anyhow::Result<()> {
+        self.progress.set(0, bytes.len() as u64);
+        for chunk in bytes.chunks(WRITE_CHUNK) {
+            self.update()?;
+            w.write_all(chunk)?;
+            self.progress.add(chunk.len() as u64);
+        }
+        w.flush()?;
+        Ok(())
+    }
+}
+
+/// A long operation in a worker thread, e.g. copying, extracting or exporting, with the
+/// progress and the way to cancel it.
+#[derive(Debug)]
+pub struct Task {
+    pub id: u64,
+    /// what it does, e.g. `Copying foo.iso`.
+    pub title: String,
+    /// the file or folder it works on, taken out of the results if it is gone after the task,
+    /// e.g. moved.
+    pub path: Option<PathBuf>,
+    pub unit: Unit,
+    pub progress: Arc<Progress>,
+    /// the notice of it when it is done.
+    result: Arc<Mutex<Option<anyhow::Result<String>>>>,
+}
+
+impl Task {
+    pub fn cancel(&self) {
+        self.progress.cancel();
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.result.lock().unwrap().is_some()
+    }
+}
+
+/// The tasks running in the background, and the finished ones until they are taken.
+#[derive(Debug, Default)]
+pub struct Tasks {
+    tasks: Vec<Task>,
+    next_id: u64,
+}
+
+impl Tasks {
+    /// Run the work in a worker thread, [`AppEvent::TaskDone`] is sent when it is done. The
+    /// work returns the notice of it, e.g. `Copied to D:\foo.iso`.
+    pub fn spawn(
+        &mut self,
+        title: String,
+        path: Option<PathBuf>,
+        unit: Unit,
+        sender: AppSender,
+        work: impl FnOnce(&mut TaskContext) -> anyhow::Result<String> + Send + 'static,
+    ) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        let progress = Arc::new(Progress::default());
+        let result = Arc::new(Mutex::new(None));
+        self.tasks.push(Task {
+            id,
+            title,
+            path,
+            unit,
+            progress: Arc::clone(&progress),
+            result: Arc::clone(&result),
+        });
+        thread::spawn(move || {
+            let mut context = TaskContext {
+                progress,
+                sender: sender.clone(),
+                refreshed: Instant::now(),
+            };
+            let done = work(&mut context);
+            *result.lock().unwrap() = Some(done);
+            sender.send(AppEvent::TaskDone);
+        });
+        id
+    }
+
+    /// The running ones, the earliest first.
+    pub fn running(&self) -> impl Iterator<Item = &Task> {
+        self.tasks.iter().filter(|task| !task.is_done())
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running().next().is_some()
+    }
+
+    pub fn cancel(&self, id: u64) {
+        if let Some(task) = self.tasks.iter().find(|task| task.id == id) {
+            task.cancel();
+        }
+    }
+
+    pub fn cancel_all(&self) {
+        self.tasks.iter().for_each(Task::cancel);
+    }
+
+    /// Take the finished ones out with their results.
+    pub fn take_finished(&mut self) -> Vec<(Task, anyhow::Result<String>)> {
+        let (finished, running) = std::mem::take(&mut self.tasks)
+            .into_iter()
+            .partition(Task::is_done);
+        self.tasks = running;
+        finished
+            .into_iter()
+            .filter_map(|task: Task| {
+                let result = task.result.lock().unwrap().take()?;
+                Some((task, result))
+            })
+            .collect()
+    }
+}
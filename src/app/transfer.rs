@@ -0,0 +1,299 @@
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+
+use crate::archive::ArchiveBackend;
+use crate::format;
+use crate::tr;
+
+use super::event::AppSender;
+use super::task::{TaskContext, Tasks, Unit};
+
+/// Bytes copied at a time, the progress is updated between them.
+const BUFFER_SIZE: usize = 1 << 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferKind {
+    Move,
+    Copy,
+    /// extracting the archive into the target folder.
+    Extract,
+}
+
+impl TransferKind {
+    pub fn verb(self) -> &'static str {
+        match self {
+            TransferKind::Move => tr!(Moving),
+            TransferKind::Copy => tr!(Copying),
+            TransferKind::Extract => tr!(Extracting),
+        }
+    }
+}
+
+/// Move or copy the file or folder to the target path as a task, with the progress in bytes.
+///
+/// The target is replaced if it exists, the folders are merged.
+pub fn start(
+    tasks: &mut Tasks,
+    kind: TransferKind,
+    source: PathBuf,
+    target: PathBuf,
+    sender: AppSender,
+) -> u64 {
+    spawn(tasks, kind, source, target, sender, None)
+}
+
+/// Extract the archive into the folder as a task, like [`start`].
+pub fn extract(
+    tasks: &mut Tasks,
+    backend: Box<dyn ArchiveBackend>,
+    source: PathBuf,
+    target: PathBuf,
+    sender: AppSender,
+) -> u64 {
+    spawn(
+        tasks,
+        TransferKind::Extract,
+        source,
+        target,
+        sender,
+        Some(backend),
+    )
+}
+
+fn spawn(
+    tasks: &mut Tasks,
+    kind: TransferKind,
+    source: PathBuf,
+    target: PathBuf,
+    sender: AppSender,
+    backend: Option<Box<dyn ArchiveBackend>>,
+) -> u64 {
+    let name = format::os_str(source.file_name().unwrap_or_default());
+    let title = format!("{} {name}", kind.verb());
+    // 7-Zip tells the percent only
+    let unit = if kind == TransferKind::Extract {
+        Unit::Percent
+    } else {
+        Unit::Bytes
+    };
+    tasks.spawn(title, Some(source.clone()), unit, sender, move |context| {
+        let mut worker = Worker { context };
+        match backend {
+            Some(backend) => worker.extract(backend.as_ref(), &source, &target)?,
+            None => worker.run(kind, &source, &target)?,
+        }
+        let path = target.display();
+        Ok(match kind {
+            TransferKind::Move => tr!(Moved, path = path),
+            TransferKind::Copy => tr!(Copied, path = path),
+            TransferKind::Extract => tr!(Extracted, path = path),
+        })
+    })
+}
+
+struct Worker<'a> {
+    context: &'a mut TaskContext,
+}
+
+impl Worker<'_> {
+    fn run(&mut self, kind: TransferKind, source: &Path, target: &Path) -> anyhow::Result<()> {
+        // the same file is in it too, which would be truncated by copying onto it
+        anyhow::ensure!(
+            !is_inside(target, source),
+            "can not put {} into itself",
+            source.display()
+        );
+        // it is instant on the same drive, unless a folder is there to merge into
+        if kind == TransferKind::Move && fs::rename(source, target).is_ok() {
+            return Ok(());
+        }
+        let total = size_of(source)?;
+        self.context.progress.set_total(total);
+        self.copy(source, target)?;
+        if kind == TransferKind::Move {
+            if source.is_dir() {
+                fs::remove_dir_all(source)
+            } else {
+                fs::remove_file(source)
+            }
+            .with_context(|| format!("failed to remove {}", source.display()))?;
+        }
+        Ok(())
+    }
+
+    fn extract(
+        &mut self,
+        backend: &dyn ArchiveBackend,
+        source: &Path,
+        target: &Path,
+    ) -> anyhow::Result<()> {
+        fs::create_dir_all(target)
+            .with_context(|| format!("failed to create {}", target.display()))?;
+        backend.extract(source, target, &mut |done, total| {
+            self.context.progress.set(done, total);
+            self.context.update()
+        })
+    }
+
+    fn copy(&mut self, source: &Path, target: &Path) -> anyhow::Result<()> {
+        if !source.is_dir() {
+            return self.copy_file(source, target);
+        }
+        fs::create_dir_all(target)
+            .with_context(|| format!("failed to create {}", target.display()))?;
+        let entries =
+            fs::read_dir(source).with_context(|| format!("failed to read {}", source.display()))?;
+        for entry in entries {
+            let entry = entry?;
+            self.copy(&entry.path(), &target.join(entry.file_name()))?;
+        }
+        Ok(())
+    }
+
+    fn copy_file(&mut self, source: &Path, target: &Path) -> anyhow::Result<()> {
+        let copied = self.copy_bytes(source, target);
+        if copied.is_err() {
+            // not leave a partial file
+            let _ = fs::remove_file(target);
+        }
+        copied
+    }
+
+    fn copy_bytes(&mut self, source: &Path, target: &Path) -> anyhow::Result<()> {
+        let mut reader =
+            File::open(source).with_context(|| format!("failed to open {}", source.display()))?;
+        let mut writer = File::create(target)
+            .with_context(|| format!("failed to create {}", target.display()))?;
+        let mut buffer = vec![0; BUFFER_SIZE];
+        loop {
+            self.context.update()?;
+            let n = reader
+                .read(&mut buffer)
+                .with_context(|| format!("failed to read {}", source.display()))?;
+            if n == 0 {
+                break;
+            }
+            writer
+                .write_all(&buffer[..n])
+                .with_context(|| format!("failed to write {}", target.display()))?;
+            self.context.progress.add(n as u64);
+        }
+        // keep the date modified like explorer does
+        if let Ok(modified) = reader.metadata().and_then(|m| m.modified()) {
+            let _ = writer.set_modified(modified);
+        }
+        Ok(())
+    }
+}
+
+/// Whether the paths are of the same file, e.g. `C:\a\x.txt` and `c:\A\X.txt` as Windows
+/// ignores the case.
+pub fn is_same_file(path: &Path, other: &Path) -> bool {
+    canonical(path) == canonical(other)
+}
+
+/// Whether the path is the folder or in it, like [`is_same_file`].
+pub fn is_inside(path: &Path, folder: &Path) -> bool {
+    canonical(path).starts_with(canonical(folder))
+}
+
+/// The path resolved by the file system, the part not existing yet, e.g. the target to
+/// copy to, kept as it is.
+fn canonical(path: &Path) -> PathBuf {
+    if let Ok(path) = fs::canonicalize(path) {
+        return path;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => canonical(parent).join(name),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Bytes of the file, or of all the files in the folder.
+fn size_of(path: &Path) -> anyhow::Result<u64> {
+    let metadata =
+        fs::metadata(path).with_context(|| format!("failed to read {}", path.display()))?;
+    if !metadata.is_dir() {
+        return Ok(metadata.len());
+    }
+    let mut size = 0;
+    for entry in fs::read_dir(path)? {
+        size += size_of(&entry?.path())?;
+    }
+    Ok(size)
+}
+
+/// The path with ` (2)`, ` (3)`... appended to the name until no such file, like explorer
+/// keeping both.
+pub fn free_path(path: &Path) -> PathBuf {
+    let name = |n: u32| {
+        let mut name = OsString::from(path.file_stem().unwrap_or_default());
+        name.push(format!(" ({n})"));
+        if let Some(extension) = path.extension() {
+            name.push(".");
+            name.push(extension);
+        }
+        name
+    };
+    (2..)
+        .map(|n| path.with_file_name(name(n)))
+        .find(|path| !path.exists())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    /// A folder of the files for the test, empty files of the names.
+    fn folder(test: &str, names: &[&str]) -> PathBuf {
+        let folder = env::temp_dir().join(format!("ery-transfer-{test}-{}", process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        for name in names {
+            fs::write(folder.join(name), "").unwrap();
+        }
+        folder
+    }
+
+    #[test]
+    fn free_paths_are_numbered_after_the_taken_ones() {
+        let folder = folder("free", &["a.txt", "a (2).txt", "b", "c.tar.gz"]);
+
+        assert_eq!(free_path(&folder.join("a.txt")), folder.join("a (3).txt"));
+        assert_eq!(free_path(&folder.join("b")), folder.join("b (2)"));
+        assert_eq!(
+            free_path(&folder.join("c.tar.gz")),
+            folder.join("c.tar (2).gz")
+        );
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn paths_inside_are_nested_or_the_same() {
+        let folder = folder("inside", &[]);
+        let sub = folder.join("sub");
+        fs::create_dir_all(sub.join("deeper")).unwrap();
+
+        assert!(is_inside(&sub, &folder));
+        assert!(is_inside(&sub.join("deeper"), &folder));
+        assert!(is_inside(&folder, &folder));
+        // not existing yet, e.g. the target to copy to
+        assert!(is_inside(&sub.join("new").join("file.txt"), &sub));
+        assert!(!is_inside(&folder, &sub));
+        // the same prefix of the name, not the same folder
+        assert!(!is_inside(&folder.join("subway"), &sub));
+        assert!(is_same_file(&sub.join("..").join("sub"), &sub));
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
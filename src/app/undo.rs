@@ -0,0 +1,60 @@
+use everything_sdk::SortType;
+
+use crate::config::FlagPreset;
+
+use super::scope::Scope;
+use super::sort::SortKey;
+
+/// States of the queries kept for going back, the oldest ones are dropped.
+const MAX_STATES: usize = 100;
+
+/// What a query is sent with, restored by the undo of the queries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryState {
+    pub text: String,
+    pub flags: Option<FlagPreset>,
+    pub sort_type: SortType,
+    pub secondary_sort: Option<SortKey>,
+    pub scope: Scope,
+}
+
+/// The states of the queries of the session, stepped back and forth by `Ctrl+Z` and `Ctrl+Y`,
+/// besides the undo of the text typed.
+#[derive(Debug, Default)]
+pub struct QueryUndo {
+    states: Vec<QueryState>,
+    /// the index of the state of the last query.
+    current: usize,
+}
+
+impl QueryUndo {
+    /// Keep the state of the query sent, the ones undone are dropped. Nothing if it is the
+    /// same as the current one, e.g. of a query undone.
+    pub fn push(&mut self, state: QueryState) {
+        if self.states.get(self.current) == Some(&state) {
+            return;
+        }
+        if !self.states.is_empty() {
+            self.states.truncate(self.current + 1);
+        }
+        self.states.push(state);
+        if self.states.len() > MAX_STATES {
+            self.states.remove(0);
+        }
+        self.current = self.states.len() - 1;
+    }
+
+    /// The state before the current one, none at the first.
+    pub fn undo(&mut self) -> Option<QueryState> {
+        self.current = self.current.checked_sub(1)?;
+        self.states.get(self.current).cloned()
+    }
+
+    /// The state undone last, none if there is none.
+    pub fn redo(&mut self) -> Option<QueryState> {
+        let next = self.current + 1;
+        let state = self.states.get(next).cloned()?;
+        self.current = next;
+        Some(state)
+    }
+}
@@ -0,0 +1,82 @@
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    path::{Component, Path, PathBuf},
+};
+
+use super::ery::QueryResults;
+
+#[derive(Debug)]
+pub struct UsageEntry {
+    pub name: OsString,
+    pub is_folder: bool,
+    pub size: u64,
+}
+
+/// Sizes of the children of a folder, the largest first, like ncdu.
+#[derive(Debug, Default)]
+pub struct DiskUsage {
+    pub folder: PathBuf,
+    pub entrys: Vec<UsageEntry>,
+    pub total: u64,
+    /// summed from the sizes of the files, since the folder sizes are not indexed.
+    pub is_aggregated: bool,
+}
+
+impl DiskUsage {
+    /// The usage of the children queried with their sizes, the folder sizes are indexed.
+    pub fn from_children(folder: &Path, results: &QueryResults) -> Self {
+        let entrys = results
+            .entrys
+            .iter()
+            .filter_map(|entry| {
+                Some(UsageEntry {
+                    name: entry.filename.clone()?,
+                    is_folder: entry.is_folder,
+                    size: entry.size.unwrap_or_default(),
+                })
+            })
+            .collect();
+        Self::new(folder, entrys, false)
+    }
+
+    /// The usage summed from all the files in the folder, the sizes of the files in a
+    /// subfolder are added to the first level one.
+    pub fn from_files(folder: &Path, results: &QueryResults) -> Self {
+        let mut sizes: HashMap<OsString, (bool, u64)> = HashMap::new();
+        for entry in &results.entrys {
+            let Some(path) = results.filepath(entry) else {
+                continue;
+            };
+            let Ok(relative) = path.strip_prefix(folder) else {
+                continue;
+            };
+            let mut components = relative.components();
+            let Some(Component::Normal(first)) = components.next() else {
+                continue;
+            };
+            let is_folder = components.next().is_some();
+            let (_, size) = sizes.entry(first.to_os_string()).or_insert((is_folder, 0));
+            *size += entry.size.unwrap_or_default();
+        }
+        let entrys = sizes
+            .into_iter()
+            .map(|(name, (is_folder, size))| UsageEntry {
+                name,
+                is_folder,
+                size,
+            })
+            .collect();
+        Self::new(folder, entrys, true)
+    }
+
+    fn new(folder: &Path, mut entrys: Vec<UsageEntry>, is_aggregated: bool) -> Self {
+        entrys.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+        Self {
+            folder: folder.to_path_buf(),
+            total: entrys.iter().map(|entry| entry.size).sum(),
+            entrys,
+            is_aggregated,
+        }
+    }
+}
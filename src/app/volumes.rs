@@ -0,0 +1,68 @@
+use std::{
+    collections::BTreeMap,
+    path::{Component, Path, Prefix},
+};
+
+use super::ery::QueryResults;
+
+/// The volume of the path, the uppercase drive like `C:` or the share like `\\server\share`,
+/// none for the relative ones.
+pub fn volume(path: &Path) -> Option<String> {
+    let Some(Component::Prefix(prefix)) = path.components().next() else {
+        return None;
+    };
+    match prefix.kind() {
+        Prefix::Disk(letter) | Prefix::VerbatimDisk(letter) => {
+            Some(format!("{}:", (letter as char).to_ascii_uppercase()))
+        }
+        Prefix::UNC(server, share) | Prefix::VerbatimUNC(server, share) => Some(format!(
+            r"\\{}\{}",
+            server.to_string_lossy(),
+            share.to_string_lossy()
+        )),
+        _ => None,
+    }
+}
+
+/// The results by volume, the folders too.
+#[derive(Debug, Default)]
+pub struct VolumeCounts {
+    /// id and the number of the entries of the results counted.
+    counted: (u64, usize),
+    /// sorted by the volumes, the drives by letter before the shares.
+    counts: BTreeMap<String, usize>,
+}
+
+impl VolumeCounts {
+    /// Count the entries again if the results are others, e.g. more are loaded, which come
+    /// with all the loaded ones in a new query.
+    pub fn update(&mut self, results: &QueryResults) {
+        let counted = (results.id, results.entrys.len());
+        if counted == self.counted {
+            return;
+        }
+        self.counted = counted;
+        self.counts.clear();
+        for entry in &results.entrys {
+            if let Some(volume) = results.path(entry).and_then(volume) {
+                *self.counts.entry(volume).or_default() += 1;
+            }
+        }
+    }
+
+    /// The volumes and their counts, in order.
+    pub fn sorted(&self) -> Vec<(&str, usize)> {
+        self.counts
+            .iter()
+            .map(|(volume, count)| (volume.as_str(), *count))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+}
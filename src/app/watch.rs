@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+use super::diff::{DiffKind, ResultsDiff};
+use super::ery::{QueryEntry, QueryResults};
+
+/// Watch mode, query the same search periodically and find out the new entries.
+#[derive(Debug)]
+pub struct Watch {
+    pub interval: Duration,
+    /// emit a desktop notification for the new entries.
+    pub notify: bool,
+    last_results: Option<QueryResults>,
+}
+
+impl Watch {
+    pub fn new(interval: Duration, notify: bool) -> Self {
+        Self {
+            interval,
+            notify,
+            last_results: None,
+        }
+    }
+
+    /// Remember the refreshed results, and return the entries added since the last refresh
+    /// of the same search.
+    pub fn update(&mut self, results: &QueryResults) -> Vec<QueryEntry> {
+        let added = match &self.last_results {
+            Some(last) if last.search == results.search => ResultsDiff::new(last, results)
+                .entrys
+                .into_iter()
+                .filter(|x| x.kind == DiffKind::Added)
+                .map(|x| x.entry)
+                .collect(),
+            _ => vec![],
+        };
+        self.last_results = Some(results.clone());
+        added
+    }
+}
@@ -0,0 +1,275 @@
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+};
+
+use anyhow::Context;
+use zip::ZipArchive;
+
+/// Called with the bytes (or the percent) done and the total while extracting, an error
+/// stops it, e.g. canceled.
+pub type Progress<'a> = &'a mut dyn FnMut(u64, u64) -> anyhow::Result<()>;
+
+/// 7-Zip where it is installed by default, if it is not on `PATH`.
+const SEVEN_ZIP_INSTALLED: &str = r"C:\Program Files\7-Zip\7z.exe";
+
+#[derive(Debug)]
+pub struct ArchiveEntry {
+    /// the path in the archive.
+    pub name: String,
+    pub size: u64,
+    pub is_folder: bool,
+}
+
+/// A way to read the archives of some formats.
+pub trait ArchiveBackend: Send {
+    /// The files and folders in the archive.
+    fn list(&self, path: &Path) -> anyhow::Result<Vec<ArchiveEntry>>;
+
+    /// Extract all of the archive into the folder, the existing files are overwritten.
+    fn extract(&self, path: &Path, folder: &Path, progress: Progress) -> anyhow::Result<()>;
+}
+
+/// The backend for the archive by its extension, none if it is not an archive supported.
+///
+/// The zip files are read by ery itself, the 7z and rar ones by 7-Zip, `seven_zip` in config
+/// or found on `PATH`.
+pub fn backend(path: &Path, seven_zip: Option<&Path>) -> Option<Box<dyn ArchiveBackend>> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "zip" => Some(Box::new(Zip)),
+        "7z" | "rar" => {
+            let exe = seven_zip.map_or_else(
+                || {
+                    if Path::new(SEVEN_ZIP_INSTALLED).is_file() {
+                        PathBuf::from(SEVEN_ZIP_INSTALLED)
+                    } else {
+                        PathBuf::from("7z")
+                    }
+                },
+                Path::to_path_buf,
+            );
+            Some(Box::new(SevenZip { exe }))
+        }
+        _ => None,
+    }
+}
+
+/// The zip files, read by the `zip` crate.
+pub struct Zip;
+
+impl Zip {
+    fn open(path: &Path) -> anyhow::Result<ZipArchive<File>> {
+        let file =
+            File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+        ZipArchive::new(file).with_context(|| format!("failed to read zip {}", path.display()))
+    }
+}
+
+impl ArchiveBackend for Zip {
+    fn list(&self, path: &Path) -> anyhow::Result<Vec<ArchiveEntry>> {
+        let mut archive = Self::open(path)?;
+        (0..archive.len())
+            .map(|i| {
+                let file = archive.by_index_raw(i)?;
+                Ok(ArchiveEntry {
+                    name: file.name().to_string(),
+                    size: file.size(),
+                    is_folder: file.is_dir(),
+                })
+            })
+            .collect()
+    }
+
+    fn extract(&self, path: &Path, folder: &Path, progress: Progress) -> anyhow::Result<()> {
+        let mut archive = Self::open(path)?;
+        let mut total = 0;
+        for i in 0..archive.len() {
+            total += archive.by_index_raw(i)?.size();
+        }
+        let mut done = 0;
+        let mut buffer = vec![0; 1 << 16];
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i)?;
+            // not outside of the folder, e.g. `..\..\evil.exe`
+            let Some(name) = file.enclosed_name() else {
+                continue;
+            };
+            let target = folder.join(name);
+            if file.is_dir() {
+                fs::create_dir_all(&target)
+                    .with_context(|| format!("failed to create {}", target.display()))?;
+                continue;
+            }
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("failed to create {}", parent.display()))?;
+            }
+            let mut writer = File::create(&target)
+                .with_context(|| format!("failed to create {}", target.display()))?;
+            let written = loop {
+                let n = match file.read(&mut buffer) {
+                    Ok(0) => break Ok(()),
+                    Ok(n) => n,
+                    Err(e) => break Err(anyhow::Error::new(e)),
+                };
+                if let Err(e) = writer.write_all(&buffer[..n]) {
+                    break Err(e.into());
+                }
+                done += n as u64;
+                if let Err(e) = progress(done, total) {
+                    break Err(e);
+                }
+            };
+            if let Err(e) = written {
+                // not leave a partial file
+                drop(writer);
+                let _ = fs::remove_file(&target);
+                return Err(e.context(format!("failed to extract {}", target.display())));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The archives read by the command line of 7-Zip, `7z.exe`.
+pub struct SevenZip {
+    exe: PathBuf,
+}
+
+impl SevenZip {
+    fn command(&self) -> Command {
+        let mut cmd = Command::new(&self.exe);
+        cmd.stdin(Stdio::null()).stderr(Stdio::piped());
+        cmd
+    }
+
+    fn not_found(&self) -> String {
+        format!(
+            "failed to run {}, install 7-Zip or set seven_zip in [archive] of config",
+            self.exe.display()
+        )
+    }
+}
+
+impl ArchiveBackend for SevenZip {
+    fn list(&self, path: &Path) -> anyhow::Result<Vec<ArchiveEntry>> {
+        // the technical listing, blocks of `key = value` lines without the headers
+        let output = self
+            .command()
+            .args(["l", "-slt", "-ba", "-sccUTF-8"])
+            .arg(path)
+            .output()
+            .with_context(|| self.not_found())?;
+        anyhow::ensure!(
+            output.status.success(),
+            "7-Zip failed to list {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        let mut entries = vec![];
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let Some((key, value)) = line.split_once(" = ") else {
+                continue;
+            };
+            match key {
+                "Path" => entries.push(ArchiveEntry {
+                    name: value.to_string(),
+                    size: 0,
+                    is_folder: false,
+                }),
+                "Size" => {
+                    if let Some(entry) = entries.last_mut() {
+                        entry.size = value.parse().unwrap_or_default();
+                    }
+                }
+                "Folder" | "Attributes" if value.starts_with(['+', 'D']) => {
+                    if let Some(entry) = entries.last_mut() {
+                        entry.is_folder = true;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(entries)
+    }
+
+    fn extract(&self, path: &Path, folder: &Path, progress: Progress) -> anyhow::Result<()> {
+        // only the progress in percent to stdout
+        let mut child = self
+            .command()
+            .args(["x", "-y", "-bso0", "-bsp1"])
+            .arg(output_switch(folder))
+            .arg(path)
+            .stdout(Stdio::piped())
+            .spawn()
+            .with_context(|| self.not_found())?;
+        let mut stdout = child.stdout.take().context("no stdout of 7-Zip")?;
+        // drained meanwhile, 7-Zip blocks if the pipe is full
+        let mut stderr = child.stderr.take().context("no stderr of 7-Zip")?;
+        let errors = thread::spawn(move || {
+            let mut errors = String::new();
+            let _ = stderr.read_to_string(&mut errors);
+            errors
+        });
+        let mut buffer = [0; 256];
+        let mut pending = vec![];
+        loop {
+            let n = stdout.read(&mut buffer)?;
+            if n == 0 {
+                break;
+            }
+            pending.extend_from_slice(&buffer[..n]);
+            // the progress is redrawn by backspaces, e.g. ` 42% 3 - name`
+            while let Some(end) = pending
+                .iter()
+                .position(|b| matches!(b, b'\x08' | b'\r' | b'\n'))
+            {
+                let line: Vec<u8> = pending.drain(..=end).collect();
+                let line = String::from_utf8_lossy(&line);
+                let percent = line
+                    .trim()
+                    .split_once('%')
+                    .and_then(|(percent, _)| percent.trim().parse::<u64>().ok());
+                if let Some(percent) = percent {
+                    if let Err(e) = progress(percent, 100) {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        let status = child.wait()?;
+        let errors = errors.join().unwrap_or_default();
+        anyhow::ensure!(
+            status.success(),
+            "7-Zip failed to extract {}: {}",
+            path.display(),
+            errors.trim()
+        );
+        Ok(())
+    }
+}
+
+/// The `-o` switch of 7-Zip to extract into the folder, `-o` and the folder as one argument.
+fn output_switch(folder: &Path) -> OsString {
+    let mut switch = OsString::from("-o");
+    switch.push(folder);
+    switch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_switch_is_of_the_folder_given() {
+        assert_eq!(output_switch(Path::new(r"C:\dest")), r"-oC:\dest");
+        assert_eq!(output_switch(Path::new(r"dest\sub")), r"-odest\sub");
+    }
+}
@@ -0,0 +1,55 @@
+use std::{sync::mpsc, time::Duration};
+
+use anyhow::Context;
+
+use crate::app::{event::AppEvent, App};
+use crate::config::Config;
+
+/// Run the query `times` times through the everything thread, the same way as the TUI does,
+/// then print the statistics of the timings.
+pub fn run(query: &str, times: u32, config: Config) -> anyhow::Result<()> {
+    anyhow::ensure!(!query.is_empty(), "the query to bench should not be empty");
+    anyhow::ensure!(times > 0, "the times to run should be positive");
+
+    let (tx, rx) = mpsc::channel::<AppEvent>();
+    let mut app = App::with_sender(tx, config);
+    let mut ipc = Vec::with_capacity(times as usize);
+    let mut conversion = Vec::with_capacity(times as usize);
+    let mut items = 0;
+    for _ in 0..times {
+        app.send_query(query)?;
+        // the only event is the refresh when the results are back
+        rx.recv().context("the everything thread is gone")?;
+        let results = app.query_results.load();
+        ipc.push(results.timings.ipc);
+        conversion.push(results.timings.conversion);
+        items += results.entrys.len();
+    }
+
+    let results = app.query_results.load();
+    println!(
+        "query: 『{}』, runs: {times}, results: {} of {}",
+        results.search.to_string_lossy(),
+        results.number,
+        results.total
+    );
+    println!("IPC latency: {}", stats(&mut ipc));
+    println!("conversion:  {}", stats(&mut conversion));
+    let conversion_secs = conversion.iter().sum::<Duration>().as_secs_f64();
+    if conversion_secs > 0.0 {
+        println!(
+            "conversion throughput: {:.0} items/s",
+            items as f64 / conversion_secs
+        );
+    }
+    Ok(())
+}
+
+/// min, avg and p95 of the samples, which are not empty.
+fn stats(samples: &mut [Duration]) -> String {
+    samples.sort();
+    let min = samples[0];
+    let avg = samples.iter().sum::<Duration>() / samples.len() as u32;
+    let p95 = samples[(samples.len() * 95).div_ceil(100) - 1];
+    format!("min {min:.2?}, avg {avg:.2?}, p95 {p95:.2?}")
+}
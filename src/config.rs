@@ -0,0 +1,430 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    env, fs,
+    path::PathBuf,
+    sync::OnceLock,
+};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use crate::i18n::Lang;
+
+/// The config file given by `ery --config`, over the one in the config directory.
+static CONFIG_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// The file beside the executable which makes ery portable, keeping its files there.
+const PORTABLE_MARKER: &str = "ery.portable";
+
+/// User config, loaded from `config.toml` in the config directory of ery.
+///
+/// e.g. `%APPDATA%\ery\config.toml` on Windows. All the fields are optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub actions: ActionsConfig,
+    pub archive: ArchiveConfig,
+    /// user commands by name, run them on the selected by their keys or the menu (`F9`).
+    pub commands: BTreeMap<String, UserCommand>,
+    pub daemon: DaemonConfig,
+    pub display: DisplayConfig,
+    pub icons: IconsConfig,
+    pub keys: KeysConfig,
+    pub preview: PreviewConfig,
+    pub query: QueryConfig,
+    pub theme: ThemeConfig,
+    pub watch: WatchConfig,
+    /// named searches, run them by `ery --search <name>`.
+    pub searches: Vec<SavedSearch>,
+    /// named filters cycled by `Ctrl+L`, over the ones of Everything of the same names.
+    pub filters: Vec<SearchFilter>,
+    /// snippets of the search by name, inserted from the templates menu (`Alt+T`).
+    pub templates: BTreeMap<String, String>,
+}
+
+/// Commands replacing explorer, with the placeholders `{path}`, `{dir}` and `{name}`, e.g.
+/// `file_manager = 'TotalCMD64.exe /O /T "{dir}"'`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ActionsConfig {
+    /// for opening folders, and revealing files if `reveal` is not set.
+    pub file_manager: Option<String>,
+    /// for opening files and folders (`Enter`).
+    pub open: Option<String>,
+    /// for opening the folder containing the file (`Ctrl+Enter`).
+    pub reveal: Option<String>,
+    /// for opening files at the `:line[:col]` of the search, e.g. `main.rs:120` (`Enter`).
+    pub goto: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ArchiveConfig {
+    /// `7z.exe` for the 7z and rar archives, found on `PATH` or in Program Files by default.
+    pub seven_zip: Option<PathBuf>,
+}
+
+/// A user command, a template of the same placeholders as `[actions]`, e.g.
+/// `open-in-vlc = "vlc {path}"`, or a table with the options, e.g.
+/// `hexdump = { run = "hexyl {path}", key = "alt+h", console = true }`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum UserCommand {
+    Run(String),
+    Options(UserCommandOptions),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserCommandOptions {
+    pub run: String,
+    /// key to run it, e.g. `alt+v`, `ctrl+shift+k` or `f6`.
+    pub key: Option<String>,
+    /// a console program, ery leaves the terminal to it and waits for it to exit.
+    #[serde(default)]
+    pub console: bool,
+}
+
+impl UserCommand {
+    pub fn options(&self) -> UserCommandOptions {
+        match self {
+            UserCommand::Run(run) => UserCommandOptions {
+                run: run.clone(),
+                key: None,
+                console: false,
+            },
+            UserCommand::Options(options) => options.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DaemonConfig {
+    /// the global hotkey popping up ery in `ery --daemon`, e.g. `"ctrl+alt+space"`.
+    pub hotkey: String,
+}
+
+impl Default for DaemonConfig {
+    fn default() -> Self {
+        Self {
+            hotkey: "win+shift+e".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DisplayConfig {
+    /// how to show the paths which are too long for the results list.
+    pub path: PathMode,
+    /// no icons, the high-contrast colors and a status line of the selected for the screen
+    /// readers, the same as `ery --accessible`.
+    pub accessible: bool,
+    /// redraws per second at most, the events in between are drawn together.
+    pub fps: u32,
+    /// the language of the texts, `"en"` or `"zh-cn"`, the one of Windows by default.
+    /// Overridden by `ery --lang`.
+    pub lang: Option<Lang>,
+    /// the badges of the git status before the names of the files in the repositories.
+    pub git: bool,
+    /// the folders of the selected one in a footer, `1`-`9` or a click to search in one.
+    pub breadcrumbs: bool,
+    /// the column of the volume of each result, e.g. `C:`, for the searches of several disks.
+    pub volume: bool,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            path: PathMode::default(),
+            accessible: false,
+            fps: 60,
+            lang: None,
+            git: true,
+            breadcrumbs: true,
+            volume: false,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PathMode {
+    /// the whole path, scroll it by `←`/`→`.
+    Full,
+    /// ellipsized in the middle, keep the drive and the last folders.
+    #[default]
+    Middle,
+    /// only the last folders.
+    Tail,
+    /// relative to the deepest folder containing all the results.
+    Relative,
+}
+
+impl PathMode {
+    pub fn next(self) -> Self {
+        match self {
+            PathMode::Full => PathMode::Middle,
+            PathMode::Middle => PathMode::Tail,
+            PathMode::Tail => PathMode::Relative,
+            PathMode::Relative => PathMode::Full,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IconsConfig {
+    pub mode: IconMode,
+    /// custom icons keyed by extension, or `folder`/`volume`/`file`, e.g. `rs = "🦀"`.
+    pub extensions: HashMap<String, String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IconMode {
+    #[default]
+    Emoji,
+    /// glyphs of Nerd Fonts, a patched font is required.
+    Nerd,
+    None,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeysConfig {
+    pub profile: KeyProfile,
+    /// `↓` on the last result goes to the first one.
+    pub wrap: bool,
+    /// edits of the search bar kept for `Ctrl+Z`, 0 for no undo.
+    pub undo_depth: usize,
+}
+
+impl Default for KeysConfig {
+    fn default() -> Self {
+        Self {
+            profile: KeyProfile::default(),
+            wrap: false,
+            undo_depth: 50,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyProfile {
+    #[default]
+    Default,
+    /// and `j`/`k`/`g`/`G`/`h`/`l` in the results, `i` to search, `q` to quit.
+    Vim,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PreviewConfig {
+    /// image thumbnails in the preview pane, if the terminal supports kitty, iTerm or sixel.
+    pub images: bool,
+    /// the lines of the text files matching the words of the search, picked by `n` and `N`.
+    pub grep: bool,
+}
+
+impl Default for PreviewConfig {
+    fn default() -> Self {
+        Self {
+            images: true,
+            grep: false,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct QueryConfig {
+    /// fields of the results requested from Everything, overridden by `ery --flags`.
+    pub flags: FlagPreset,
+    /// the sort when ery starts, switch it by `Ctrl+O`.
+    pub sort: DefaultSort,
+    /// query while typing instead of on `Enter`, better with the fast sorts of Everything.
+    pub as_you_type: bool,
+    /// query the search again when the terminal is focused again, for the fresh results.
+    pub refresh_on_focus: bool,
+    /// seconds to wait for the reply of Everything before showing the timeout, 0 to wait
+    /// silently. Overridden by `ery --timeout`.
+    pub timeout: u64,
+    /// folder names, paths or wildcards excluded from all the queries, e.g. `node_modules`.
+    pub excludes: Vec<String>,
+    /// query the search of no results again relaxed, e.g. `*my*file*name*` for `myFile-name`.
+    pub fallback: bool,
+    /// the sorts of the searches with the functions, e.g. the largest first for `size:`, unless
+    /// the sort is switched by `Ctrl+O`. Empty for `sort` always.
+    pub smart_sort: BTreeMap<String, DefaultSort>,
+}
+
+impl Default for QueryConfig {
+    fn default() -> Self {
+        Self {
+            flags: FlagPreset::default(),
+            sort: DefaultSort::default(),
+            as_you_type: false,
+            refresh_on_focus: false,
+            timeout: 10,
+            excludes: vec![],
+            fallback: false,
+            smart_sort: BTreeMap::from([
+                ("size".to_string(), DefaultSort::Size),
+                ("dm".to_string(), DefaultSort::DateModified),
+                ("datemodified".to_string(), DefaultSort::DateModified),
+            ]),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DefaultSort {
+    #[default]
+    Name,
+    Path,
+    /// the largest first.
+    Size,
+    /// the newest first.
+    DateModified,
+    /// the latest changed first, as Everything saw the changes.
+    RecentlyChanged,
+    Extension,
+    /// how well they match the search, scored by ery over the fetched results.
+    Relevance,
+}
+
+/// Presets of the request flags, trading the IPC payload size for the metadata.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum FlagPreset {
+    /// only the names and the paths, the smallest payload for huge results.
+    Minimal,
+    /// and the sizes and the dates, the ones only in the preview are fetched when shown.
+    #[default]
+    Standard,
+    /// and the run counts and the recently changed dates, fetched when shown too.
+    FullMetadata,
+}
+
+/// Colors of the TUI, a preset and the overrides of it, in the names like `"yellow"`, the
+/// hex like `"#ff8000"` or the 256-color indexes like `"208"`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    pub preset: ThemePreset,
+    pub main: Option<String>,
+    pub font: Option<String>,
+    pub light_font: Option<String>,
+    pub gray: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemePreset {
+    /// the orange of Everything.
+    #[default]
+    Everything,
+    Ocean,
+    /// the colors of the terminal, for the light backgrounds too.
+    Mono,
+    /// the basic colors only, and the selected reversed.
+    #[serde(rename = "high-contrast")]
+    HighContrast,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WatchConfig {
+    /// seconds between two queries in watch mode.
+    pub interval: u64,
+    /// emit a desktop notification when new results appear in watch mode.
+    pub notify: bool,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            interval: 60,
+            notify: false,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+    /// overrides `watch.notify` for this search, but not `ery --notify`.
+    pub notify: Option<bool>,
+    /// overrides `query.flags` for this search, but not `ery --flags`.
+    pub flags: Option<FlagPreset>,
+    /// keep the pins of it in `pins.json` of the config folder.
+    #[serde(default)]
+    pub keep_pins: bool,
+}
+
+/// A filter of the queries, e.g. `name = "Code"` and `search = "ext:rs;py;ts"`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SearchFilter {
+    pub name: String,
+    pub search: String,
+}
+
+impl Config {
+    /// Load the config file, or the default config if there is no config file.
+    pub fn load() -> anyhow::Result<Self> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read config {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("failed to parse config {}", path.display()))
+    }
+
+    /// Use the config file instead of the one in the config directory, the scripts are beside
+    /// it too. Set it before loading the config.
+    pub fn set_path(path: PathBuf) {
+        let _ = CONFIG_PATH.set(path);
+    }
+
+    /// The config directory of ery, for the config file and the scripts. It is the folder of
+    /// the config file given, or of the executable in portable mode, or `%APPDATA%\ery`.
+    pub fn dir() -> Option<PathBuf> {
+        if let Some(path) = CONFIG_PATH.get() {
+            return Some(path.parent()?.to_path_buf());
+        }
+        Self::portable_dir().or_else(|| Some(dirs::config_dir()?.join("ery")))
+    }
+
+    pub fn path() -> Option<PathBuf> {
+        match CONFIG_PATH.get() {
+            Some(path) => Some(path.clone()),
+            None => Some(Self::dir()?.join("config.toml")),
+        }
+    }
+
+    /// The folder of the executable if an `ery.portable` file is in it, e.g. on a USB stick
+    /// with a portable Everything.
+    pub fn portable_dir() -> Option<PathBuf> {
+        let dir = env::current_exe().ok()?.parent()?.to_path_buf();
+        dir.join(PORTABLE_MARKER).is_file().then_some(dir)
+    }
+
+    /// No config file yet, e.g. the first run of ery.
+    pub fn is_first_run() -> bool {
+        Self::path().is_some_and(|path| !path.exists())
+    }
+
+    pub fn saved_search(&self, name: &str) -> Option<&SavedSearch> {
+        self.searches.iter().find(|x| x.name == name)
+    }
+}
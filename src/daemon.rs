@@ -0,0 +1,123 @@
+use std::{
+    env,
+    ffi::OsString,
+    os::windows::process::CommandExt,
+    process::{Child, Command},
+};
+
+use anyhow::Context;
+use windows::{
+    core::{HSTRING, PCWSTR},
+    Win32::{
+        Foundation::HWND,
+        UI::{
+            Input::KeyboardAndMouse::{
+                RegisterHotKey, HOT_KEY_MODIFIERS, MOD_ALT, MOD_CONTROL, MOD_NOREPEAT, MOD_SHIFT,
+                MOD_WIN,
+            },
+            WindowsAndMessaging::{
+                FindWindowW, GetMessageW, IsIconic, SetForegroundWindow, ShowWindow, MSG,
+                SW_RESTORE, WM_HOTKEY,
+            },
+        },
+    },
+};
+
+/// The title of the terminal of the TUI popped up by the hotkey, for finding the window again.
+pub const WINDOW_TITLE: &str = "ery - Everything";
+
+/// The id of the hotkey of the daemon, the only one of it.
+const HOTKEY_ID: i32 = 1;
+
+/// The process creation flag of Windows for a console of its own, a new terminal window.
+const CREATE_NEW_CONSOLE: u32 = 0x0000_0010;
+
+/// Stay resident and pop up the TUI in a new terminal window by the global hotkey, or bring the
+/// window back to the front if it is open, as the hotkey of the Everything GUI does.
+///
+/// The arguments are passed to the TUI, e.g. `--config`.
+pub fn run(hotkey: &str, args: &[OsString]) -> anyhow::Result<()> {
+    let (modifiers, key) = parse_hotkey(hotkey)?;
+    // SAFETY: the hotkey is posted to the message queue of this thread.
+    unsafe { RegisterHotKey(HWND::default(), HOTKEY_ID, modifiers | MOD_NOREPEAT, key) }
+        .with_context(|| {
+            format!("failed to register the hotkey {hotkey}, taken by another program?")
+        })?;
+    println!("ery is waiting for {hotkey}, ctrl+c to quit");
+
+    let mut tui = None;
+    let mut msg = MSG::default();
+    // SAFETY: the message is written by it, and it returns 0 on WM_QUIT and -1 on errors.
+    while unsafe { GetMessageW(&mut msg, HWND::default(), 0, 0) }.0 > 0 {
+        if msg.message == WM_HOTKEY {
+            if let Err(e) = summon(&mut tui, args) {
+                eprintln!("{e:#}");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Bring the window of the TUI to the front, or start a new one if it is closed.
+fn summon(tui: &mut Option<Child>, args: &[OsString]) -> anyhow::Result<()> {
+    if let Some(child) = tui {
+        if child.try_wait()?.is_none() {
+            return foreground();
+        }
+    }
+    let exe = env::current_exe().context("failed to get the path of ery")?;
+    let child = Command::new(exe)
+        .args(args)
+        .arg("--summoned")
+        .creation_flags(CREATE_NEW_CONSOLE)
+        .spawn()
+        .context("failed to start ery")?;
+    *tui = Some(child);
+    Ok(())
+}
+
+fn foreground() -> anyhow::Result<()> {
+    // SAFETY: the window is found by the title, and restored and activated only.
+    unsafe {
+        let hwnd = FindWindowW(PCWSTR::null(), &HSTRING::from(WINDOW_TITLE))
+            .context("the window of ery is not found, the title is changed?")?;
+        if IsIconic(hwnd).as_bool() {
+            let _ = ShowWindow(hwnd, SW_RESTORE);
+        }
+        // allowed, the foreground is handed to the process receiving the hotkey
+        let _ = SetForegroundWindow(hwnd);
+    }
+    Ok(())
+}
+
+/// The modifiers and the virtual key of a hotkey like `win+shift+e`, `ctrl+alt+space` or
+/// `ctrl+f12`.
+fn parse_hotkey(s: &str) -> anyhow::Result<(HOT_KEY_MODIFIERS, u32)> {
+    let lowercase = s.to_lowercase();
+    let mut parts: Vec<&str> = lowercase.split('+').map(str::trim).collect();
+    let key = parts.pop().unwrap_or_default();
+    let mut modifiers = HOT_KEY_MODIFIERS(0);
+    for modifier in parts {
+        modifiers |= match modifier {
+            "win" => MOD_WIN,
+            "ctrl" => MOD_CONTROL,
+            "alt" => MOD_ALT,
+            "shift" => MOD_SHIFT,
+            _ => anyhow::bail!("unknown modifier {modifier:?} in hotkey {s:?}"),
+        };
+    }
+    let held = modifiers & (MOD_WIN | MOD_CONTROL | MOD_ALT);
+    anyhow::ensure!(held.0 != 0, "hotkey {s:?} needs win, ctrl or alt");
+    let key = match key {
+        "space" => 0x20,
+        _ if key.len() == 1 && key.chars().all(|c| c.is_ascii_alphanumeric()) => {
+            // the virtual keys of the letters and the digits are their uppercase ASCII
+            key.to_ascii_uppercase().as_bytes()[0] as u32
+        }
+        _ => match key.strip_prefix('f').and_then(|n| n.parse::<u32>().ok()) {
+            Some(n @ 1..=24) => 0x70 + n - 1,
+            _ => anyhow::bail!("unknown key {key:?} in hotkey {s:?}"),
+        },
+    };
+    Ok((modifiers, key))
+}
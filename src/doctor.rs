@@ -0,0 +1,214 @@
+use std::{
+    env,
+    io::{self, IsTerminal},
+};
+
+use crossterm::style;
+
+use crate::app::{self, capability::Feature, Status};
+use crate::config::Config;
+use crate::tui::graphics::Protocol;
+
+/// Print a checklist of Everything, the config and the terminal, for troubleshooting and the
+/// bug reports.
+pub fn run() -> anyhow::Result<()> {
+    println!("Everything");
+    check_everything();
+    println!();
+    println!("Config");
+    check_config();
+    println!();
+    println!("Terminal");
+    check_terminal();
+    Ok(())
+}
+
+/// A line of the checklist, `ok`, `!!` for the problems, or `--` for the features off.
+fn item(mark: Mark, text: impl AsRef<str>) {
+    let mark = match mark {
+        Mark::Ok => "ok",
+        Mark::Problem => "!!",
+        Mark::Off => "--",
+    };
+    println!("  [{mark}] {}", text.as_ref());
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Mark {
+    Ok,
+    Problem,
+    Off,
+}
+
+fn mark(ok: bool, or: Mark) -> Mark {
+    if ok {
+        Mark::Ok
+    } else {
+        or
+    }
+}
+
+fn check_everything() {
+    let status = match app::status() {
+        Ok(status) => status,
+        Err(e) => {
+            item(
+                Mark::Problem,
+                format!("IPC: Everything is not running, or not reachable ({e:#})"),
+            );
+            return;
+        }
+    };
+    let (major, minor, revision, build) = status.version;
+    item(
+        Mark::Ok,
+        format!("IPC: Everything {major}.{minor}.{revision}.{build} is running"),
+    );
+    item(
+        mark(status.is_db_loaded, Mark::Problem),
+        if status.is_db_loaded {
+            "database: loaded"
+        } else {
+            "database: not loaded yet, wait for the indexing"
+        },
+    );
+    let supports = status.supports(Feature::ContentIndex);
+    item(
+        mark(supports, Mark::Off),
+        format!("features of Everything 1.5: {}", yes_or_no(supports)),
+    );
+    item(
+        mark(status.is_admin, Mark::Off),
+        format!(
+            "administrator: {}, some operations need it, see --elevate",
+            yes_or_no(status.is_admin)
+        ),
+    );
+    item(
+        Mark::Ok,
+        format!(
+            "settings and data in %APPDATA%: {}",
+            yes_or_no(status.is_appdata)
+        ),
+    );
+    check_indexes(&status);
+}
+
+fn check_indexes(status: &Status) {
+    let fields = [
+        (
+            "file size",
+            Some(status.is_file_size_indexed),
+            status.is_size_fast_sort,
+        ),
+        (
+            "folder size",
+            Some(status.is_folder_size_indexed),
+            status.is_size_fast_sort,
+        ),
+        (
+            "date modified",
+            Some(status.is_date_modified_indexed),
+            status.is_date_modified_fast_sort,
+        ),
+        (
+            "date created",
+            Some(status.is_date_created_indexed),
+            status.is_date_created_fast_sort,
+        ),
+        (
+            "date accessed",
+            Some(status.is_date_accessed_indexed),
+            status.is_date_accessed_fast_sort,
+        ),
+        (
+            "attributes",
+            Some(status.is_attributes_indexed),
+            status.is_attributes_fast_sort,
+        ),
+        ("path", None, status.is_path_fast_sort),
+        ("extension", None, status.is_extension_fast_sort),
+    ];
+    for (name, indexed, fast_sort) in fields {
+        let indexed = match indexed {
+            Some(indexed) => format!("indexed: {}, ", yes_or_no(indexed)),
+            None => String::new(),
+        };
+        item(
+            mark(fast_sort, Mark::Off),
+            format!("{name}: {indexed}fast sort: {}", yes_or_no(fast_sort)),
+        );
+    }
+}
+
+fn check_config() {
+    let Some(path) = Config::path() else {
+        item(Mark::Problem, "no config directory, %APPDATA% is not found");
+        return;
+    };
+    if !path.exists() {
+        item(
+            Mark::Off,
+            format!("{} is not created, the defaults are used", path.display()),
+        );
+        return;
+    }
+    match Config::load() {
+        Ok(_) => item(Mark::Ok, format!("{} is valid", path.display())),
+        Err(e) => item(Mark::Problem, format!("{e:#}")),
+    }
+}
+
+fn check_terminal() {
+    let var = |key| env::var(key).unwrap_or_default();
+    let terminal = if env::var_os("WT_SESSION").is_some() {
+        "Windows Terminal".to_string()
+    } else if !var("TERM_PROGRAM").is_empty() {
+        var("TERM_PROGRAM")
+    } else if !var("TERM").is_empty() {
+        var("TERM")
+    } else {
+        "the Windows console".to_string()
+    };
+    item(
+        mark(io::stdout().is_terminal(), Mark::Problem),
+        format!(
+            "terminal: {terminal}{}",
+            if io::stdout().is_terminal() {
+                ""
+            } else {
+                ", but the output is redirected"
+            }
+        ),
+    );
+    let colors = style::available_color_count();
+    let truecolor = colors == u16::MAX
+        || env::var_os("WT_SESSION").is_some()
+        || matches!(var("COLORTERM").as_str(), "truecolor" | "24bit");
+    item(
+        mark(truecolor, Mark::Off),
+        if truecolor {
+            "colors: true color".to_string()
+        } else {
+            format!("colors: {colors}, the themes may look off without true color")
+        },
+    );
+    // crossterm takes the mouse events from the console input, QuickEdit off while running
+    item(Mark::Ok, "mouse: supported");
+    let graphics = Protocol::detect();
+    item(
+        mark(graphics.is_some(), Mark::Off),
+        match graphics {
+            Some(protocol) => format!("graphics: {protocol:?}, image thumbnails in the preview"),
+            None => "graphics: none detected, no image thumbnails in the preview".to_string(),
+        },
+    );
+}
+
+fn yes_or_no(yes: bool) -> &'static str {
+    if yes {
+        "yes"
+    } else {
+        "no"
+    }
+}
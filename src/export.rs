@@ -0,0 +1,56 @@
+use std::{
+    ffi::OsStr,
+    fs::File,
+    io::{self, Write},
+    path::Path,
+};
+
+use anyhow::Context;
+
+use crate::app::ery::QueryResults;
+use crate::app::task::TaskContext;
+
+/// Write the string losslessly, by its raw bytes.
+///
+/// The file names on Windows may have unpaired surrogates, which are not valid unicode, they
+/// are kept in the WTF-8 encoding instead of replaced by `�`, so the paths read back refer to
+/// the same files. The valid ones are just UTF-8.
+pub fn write_os_str(w: &mut impl Write, s: &OsStr) -> io::Result<()> {
+    w.write_all(s.as_encoded_bytes())
+}
+
+/// Write the full paths of the results, one per line.
+pub fn write_file_list(w: &mut impl Write, results: &QueryResults) -> io::Result<()> {
+    write_paths(w, results, b"\r\n")
+}
+
+/// Write the full paths of the results, each followed by the terminator, e.g. NUL for
+/// `xargs -0`, which is safe for any path.
+pub fn write_paths(
+    w: &mut impl Write,
+    results: &QueryResults,
+    terminator: &[u8],
+) -> io::Result<()> {
+    for path in results.entrys.iter().filter_map(|x| results.filepath(x)) {
+        write_os_str(w, path.as_os_str())?;
+        w.write_all(terminator)?;
+    }
+    Ok(())
+}
+
+/// Save the full paths of the results into a text file, which Everything can open as a file
+/// list too, in the task.
+pub fn save_file_list(
+    results: &QueryResults,
+    path: impl AsRef<Path>,
+    context: &mut TaskContext,
+) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    let mut bytes = vec![];
+    write_file_list(&mut bytes, results)?;
+    let mut file = File::create(path)
+        .with_context(|| format!("failed to create file list {}", path.display()))?;
+    context
+        .write_all(&mut file, &bytes)
+        .with_context(|| format!("failed to write file list {}", path.display()))
+}
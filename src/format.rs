@@ -0,0 +1,107 @@
+use std::{borrow::Cow, ffi::OsStr};
+
+use chrono::{DateTime, Datelike, Local};
+
+/// Marker after the text which is not valid unicode, the invalid parts are replaced by `�`.
+const LOSSY_MARKER: &str = "⁉";
+
+/// Seconds from 1601-01-01, the epoch of Windows FILETIME, to the unix epoch.
+const FILETIME_UNIX_EPOCH_SECS: i64 = 11_644_473_600;
+
+/// FILETIME of Everything (100-nanosecond intervals since 1601-01-01 UTC) to the local time.
+pub fn filetime_to_local(filetime: u64) -> Option<DateTime<Local>> {
+    let secs = (filetime / 10_000_000) as i64 - FILETIME_UNIX_EPOCH_SECS;
+    let nanos = (filetime % 10_000_000) as u32 * 100;
+    Some(DateTime::from_timestamp(secs, nanos)?.with_timezone(&Local))
+}
+
+/// The time now as a FILETIME, the same as the dates of Everything.
+pub fn filetime_now() -> u64 {
+    let now = Local::now();
+    let secs = (now.timestamp() + FILETIME_UNIX_EPOCH_SECS) as u64;
+    secs * 10_000_000 + u64::from(now.timestamp_subsec_nanos() / 100)
+}
+
+/// Date and time like `2024-01-31 23:59`, the same as Everything shows.
+pub fn date(filetime: u64) -> String {
+    filetime_to_local(filetime)
+        .map(|date| date.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+/// Date and time to the second like `2024-01-31 23:59:59`, for the exact time.
+pub fn date_exact(filetime: u64) -> String {
+    filetime_to_local(filetime)
+        .map(|date| date.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+/// How old a date is by the calendar, for coloring the dates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Age {
+    /// today, or in the future by a clock ahead.
+    Today,
+    ThisWeek,
+    ThisMonth,
+    Older,
+}
+
+/// The age of the date at the time `now`.
+pub fn age(filetime: u64, now: DateTime<Local>) -> Option<Age> {
+    let date = filetime_to_local(filetime)?.date_naive();
+    let today = now.date_naive();
+    let age = if date >= today {
+        Age::Today
+    } else if date.iso_week() == today.iso_week() {
+        Age::ThisWeek
+    } else if (date.year(), date.month()) == (today.year(), today.month()) {
+        Age::ThisMonth
+    } else {
+        Age::Older
+    };
+    Some(age)
+}
+
+/// Text of the file name or path for display, with [`LOSSY_MARKER`] if it is converted lossily,
+/// so the user can tell it is not exactly the name on disk. Only for display, the `OsStr` is
+/// kept for the operations.
+pub fn os_str(s: &OsStr) -> Cow<'_, str> {
+    match s.to_str() {
+        Some(s) => Cow::Borrowed(s),
+        None => Cow::Owned(format!("{}{LOSSY_MARKER}", s.to_string_lossy())),
+    }
+}
+
+/// Human readable size in binary units, like `1.5 MB`.
+pub fn size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["KB", "MB", "GB", "TB", "PB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// File attributes in letters like Everything, e.g. `RHA` for a readonly hidden archive.
+pub fn attributes(attributes: u32) -> String {
+    // Ref: https://learn.microsoft.com/en-us/windows/win32/fileio/file-attribute-constants
+    const LETTERS: [(u32, char); 7] = [
+        (0x1, 'R'),    // FILE_ATTRIBUTE_READONLY
+        (0x2, 'H'),    // FILE_ATTRIBUTE_HIDDEN
+        (0x4, 'S'),    // FILE_ATTRIBUTE_SYSTEM
+        (0x10, 'D'),   // FILE_ATTRIBUTE_DIRECTORY
+        (0x20, 'A'),   // FILE_ATTRIBUTE_ARCHIVE
+        (0x800, 'C'),  // FILE_ATTRIBUTE_COMPRESSED
+        (0x4000, 'E'), // FILE_ATTRIBUTE_ENCRYPTED
+    ];
+    LETTERS
+        .iter()
+        .filter(|(flag, _)| attributes & flag != 0)
+        .map(|(_, letter)| letter)
+        .collect()
+}
@@ -0,0 +1,258 @@
+use std::sync::mpsc;
+
+use eframe::egui::{self, Key, Modifiers, Sense, TextEdit};
+use egui_extras::{Column, TableBuilder};
+
+use crate::app::event::{AppEvent, AppSender};
+use crate::app::excludes::Excludes;
+use crate::app::opened::Opened;
+use crate::app::App;
+use crate::config::Config;
+use crate::format;
+use crate::i18n::{self, Lang};
+use crate::shell;
+use crate::tr;
+
+/// Height of the rows of the results, in points.
+const ROW_HEIGHT: f32 = 20.0;
+
+/// Browse the results in a native window instead of the terminal, by the same app as the TUI.
+/// The search is queried as typed, `Enter` or a double click opens the selected one.
+pub fn run(config: Config, search: Option<String>, lang: Option<Lang>) -> anyhow::Result<()> {
+    i18n::set_lang(lang.or(config.display.lang).unwrap_or_else(Lang::system));
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_title("ery")
+            .with_inner_size([960.0, 600.0]),
+        ..Default::default()
+    };
+    eframe::run_native(
+        "ery",
+        options,
+        Box::new(move |cc| Ok(Box::new(Gui::new(&cc.egui_ctx, config, search)?))),
+    )
+    .map_err(|e| anyhow::anyhow!("failed to open the window: {e}"))
+}
+
+struct Gui {
+    app: App,
+    /// the events of the app, taken at each frame.
+    events: mpsc::Receiver<AppEvent>,
+    search: String,
+    selected: Option<usize>,
+    /// scroll the selected one into view in the next frame, e.g. moved by the keys.
+    is_scroll_to_selected: bool,
+    /// the error of the last action, until the search changes.
+    notice: Option<String>,
+}
+
+impl Gui {
+    fn new(ctx: &egui::Context, config: Config, search: Option<String>) -> anyhow::Result<Self> {
+        let (sender, events) = mpsc::channel();
+        let ctx = ctx.clone();
+        // the window is repainted only on the input otherwise
+        let sender = AppSender::new(move |event| {
+            let _ = sender.send(event);
+            ctx.request_repaint();
+        });
+        let mut app = App::with_sender(sender, config);
+        app.excludes = Excludes::load()?;
+        app.opened = Opened::load()?;
+        let search = search.unwrap_or_default();
+        if !search.is_empty() {
+            app.send_query(&search)?;
+        }
+        Ok(Self {
+            app,
+            events,
+            search,
+            selected: None,
+            is_scroll_to_selected: false,
+            notice: None,
+        })
+    }
+
+    /// Handle the events of the app since the last frame, as the event loop of the TUI does.
+    fn handle_events(&mut self) -> anyhow::Result<()> {
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                AppEvent::Refresh => {
+                    // the results may be fewer now
+                    let len = self.app.query_results.load().entrys.len();
+                    self.selected = self.selected.filter(|&i| i < len);
+                }
+                AppEvent::FilesChanged => self.app.requery()?,
+                // no moves or copies in the window
+                AppEvent::TaskDone => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// The keys of the results, taken before the search box gets them.
+    fn handle_keys(&mut self, ctx: &egui::Context) -> anyhow::Result<()> {
+        let len = self.app.query_results.load().entrys.len();
+        let (down, up, enter) = ctx.input_mut(|input| {
+            (
+                input.consume_key(Modifiers::NONE, Key::ArrowDown),
+                input.consume_key(Modifiers::NONE, Key::ArrowUp),
+                input.consume_key(Modifiers::NONE, Key::Enter),
+            )
+        });
+        if len > 0 && (down || up) {
+            self.selected = Some(match self.selected {
+                Some(i) if down => (i + 1).min(len - 1),
+                Some(i) => i.saturating_sub(1),
+                None => 0,
+            });
+            self.is_scroll_to_selected = true;
+        }
+        if enter {
+            self.open_selected()?;
+        }
+        Ok(())
+    }
+
+    /// Open the selected one, or the first one if none is selected.
+    fn open_selected(&mut self) -> anyhow::Result<()> {
+        let path = {
+            let results = self.app.query_results.load();
+            let index = self.selected.unwrap_or(0);
+            results
+                .entrys
+                .get(index)
+                .and_then(|entry| results.filepath(entry))
+        };
+        let Some(path) = path else {
+            return Ok(());
+        };
+        if !self.app.allow_open(&path) {
+            self.notice = Some(tr!(Vetoed).to_string());
+            return Ok(());
+        }
+        shell::open(&path, &self.app.config.actions)?;
+        self.app.add_opened(&path)?;
+        self.app.inc_run_count(path)
+    }
+
+    fn search_bar(&mut self, ui: &mut egui::Ui) -> anyhow::Result<()> {
+        let response = ui.add(
+            TextEdit::singleline(&mut self.search)
+                .hint_text(tr!(SearchPlaceholder))
+                .desired_width(f32::INFINITY),
+        );
+        // typing always goes into the search, e.g. after clicking a result
+        if ui.memory(|memory| memory.focused().is_none()) {
+            response.request_focus();
+        }
+        if response.changed() {
+            self.selected = None;
+            self.notice = None;
+            self.app.send_query(&self.search)?;
+        }
+        Ok(())
+    }
+
+    fn status_bar(&self, ui: &mut egui::Ui) {
+        let results = self.app.query_results.load();
+        let status = if let Some(notice) = &self.notice {
+            notice.clone()
+        } else if let Some(error) = self.app.query_error() {
+            error.to_string()
+        } else if self.app.is_querying() {
+            format!("{}…", tr!(Searching))
+        } else {
+            tr!(
+                ResultsStatus,
+                number = results.number,
+                total = results.total,
+                search = format::os_str(&results.search)
+            )
+        };
+        ui.label(status);
+    }
+
+    /// The results in a table, only the rows in view are laid out. The next results are loaded
+    /// when the last one comes into view.
+    fn results_table(&mut self, ui: &mut egui::Ui) -> anyhow::Result<()> {
+        let results = self.app.query_results.load();
+        let mut table = TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .sense(Sense::click())
+            .column(Column::initial(280.0).at_least(80.0).clip(true))
+            .column(Column::remainder().at_least(120.0).clip(true))
+            .column(Column::auto().at_least(80.0))
+            .column(Column::auto().at_least(130.0));
+        if std::mem::take(&mut self.is_scroll_to_selected) {
+            if let Some(i) = self.selected {
+                table = table.scroll_to_row(i, None);
+            }
+        }
+        let (mut clicked, mut opened, mut is_end_shown) = (None, None, false);
+        table
+            .header(ROW_HEIGHT, |mut header| {
+                for title in [tr!(Name), tr!(Path), tr!(Size), tr!(Modified)] {
+                    header.col(|ui| {
+                        ui.strong(title);
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(ROW_HEIGHT, results.entrys.len(), |mut row| {
+                    let i = row.index();
+                    let entry = &results.entrys[i];
+                    is_end_shown |= i + 1 == results.entrys.len();
+                    row.set_selected(self.selected == Some(i));
+                    row.col(|ui| {
+                        let name = entry.filename.as_deref().map(format::os_str);
+                        ui.label(name.as_deref().unwrap_or_default());
+                    });
+                    row.col(|ui| {
+                        let path = results.path(entry).map(|path| path.to_string_lossy());
+                        ui.label(path.as_deref().unwrap_or_default());
+                    });
+                    row.col(|ui| {
+                        if let Some(size) = entry.size {
+                            ui.label(format::size(size));
+                        }
+                    });
+                    row.col(|ui| {
+                        if let Some(date) = entry.date_modified {
+                            ui.label(format::date(date));
+                        }
+                    });
+                    let response = row.response();
+                    if response.clicked() {
+                        clicked = Some(i);
+                    }
+                    if response.double_clicked() {
+                        opened = Some(i);
+                    }
+                });
+            });
+        if let Some(i) = clicked.or(opened) {
+            self.selected = Some(i);
+        }
+        if opened.is_some() {
+            self.open_selected()?;
+        }
+        if is_end_shown {
+            self.app.prefetch()?;
+        }
+        Ok(())
+    }
+}
+
+impl eframe::App for Gui {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let mut done = vec![self.handle_events(), self.handle_keys(ctx)];
+        egui::TopBottomPanel::top("search").show(ctx, |ui| done.push(self.search_bar(ui)));
+        egui::TopBottomPanel::bottom("status").show(ctx, |ui| self.status_bar(ui));
+        egui::CentralPanel::default().show(ctx, |ui| done.push(self.results_table(ui)));
+        if let Some(e) = done.into_iter().find_map(Result::err) {
+            self.notice = Some(format!("{e:#}"));
+            ctx.request_repaint();
+        }
+    }
+}
@@ -0,0 +1,463 @@
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use serde::Deserialize;
+use windows::Win32::Globalization::GetUserDefaultUILanguage;
+
+/// The language of the texts, set once the config is loaded and again when it is reloaded.
+static LANG: AtomicU8 = AtomicU8::new(Lang::En as u8);
+
+/// The primary language id of Chinese in the language ids of Windows.
+const LANG_CHINESE: u16 = 0x04;
+
+/// The languages of the texts of the TUI, chosen by `ery --lang`, `display.lang` in config, or
+/// the language of Windows.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+#[repr(u8)]
+pub enum Lang {
+    #[default]
+    En,
+    /// Simplified Chinese.
+    ZhCn,
+}
+
+impl Lang {
+    /// The language of the user, by `LC_ALL` or `LANG` as the terminals of Unix tools set it,
+    /// or else by the display language of Windows.
+    pub fn system() -> Self {
+        let env = ["LC_ALL", "LANG"]
+            .into_iter()
+            .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()));
+        if let Some(value) = env {
+            return if value.to_lowercase().starts_with("zh") {
+                Lang::ZhCn
+            } else {
+                Lang::En
+            };
+        }
+        // SAFETY: no arguments, it only reads the setting of the user.
+        let id = unsafe { GetUserDefaultUILanguage() };
+        if id & 0x3ff == LANG_CHINESE {
+            Lang::ZhCn
+        } else {
+            Lang::En
+        }
+    }
+}
+
+pub fn set_lang(lang: Lang) {
+    LANG.store(lang as u8, Ordering::Relaxed);
+}
+
+pub fn lang() -> Lang {
+    match LANG.load(Ordering::Relaxed) {
+        x if x == Lang::ZhCn as u8 => Lang::ZhCn,
+        _ => Lang::En,
+    }
+}
+
+/// The texts with the `{name}` placeholders filled by [`tr!`](crate::tr), in English and
+/// Simplified Chinese.
+macro_rules! messages {
+    ($($name:ident => $en:literal, $zh:literal;)*) => {
+        /// A text of the TUI, in all the languages.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum Msg {
+            $($name,)*
+        }
+
+        impl Msg {
+            /// The text in the current language.
+            pub fn text(self) -> &'static str {
+                match (lang(), self) {
+                    $(
+                        (Lang::En, Msg::$name) => $en,
+                        (Lang::ZhCn, Msg::$name) => $zh,
+                    )*
+                }
+            }
+        }
+    };
+}
+
+impl Msg {
+    /// The text with the placeholders of the names replaced by the values, in one pass so
+    /// the braces in the values are kept as they are.
+    pub fn fill(self, args: &[(&str, &dyn Display)]) -> String {
+        let mut text = String::new();
+        let mut rest = self.text();
+        while let Some(start) = rest.find('{') {
+            text.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let value = after.find('}').and_then(|end| {
+                let (_, value) = args.iter().find(|(name, _)| *name == &after[..end])?;
+                Some((value, end))
+            });
+            match value {
+                Some((value, end)) => {
+                    text.push_str(&value.to_string());
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    text.push('{');
+                    rest = after;
+                }
+            }
+        }
+        text.push_str(rest);
+        text
+    }
+}
+
+/// The text of the message in the current language, e.g. `tr!(Renamed, path = path.display())`
+/// for a `String`, or `tr!(NoFilter)` for a `&'static str`.
+#[macro_export]
+macro_rules! tr {
+    ($msg:ident) => {
+        $crate::i18n::Msg::$msg.text()
+    };
+    ($msg:ident, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::Msg::$msg.fill(&[$((stringify!($name), &$value as &dyn std::fmt::Display)),+])
+    };
+}
+
+messages! {
+    // notices
+    Offline => "Searching is unavailable when browsing a snapshot or a file list",
+        "浏览快照或文件列表时无法搜索";
+    Canceled => "Query canceled", "已取消查询";
+    Vetoed => "Opening it is vetoed by on_open of the scripts", "脚本的 on_open 阻止了打开";
+    ScriptError => "script error: {error}", "脚本错误：{error}";
+    ConfigReloaded => "Config reloaded", "已重新加载配置";
+    NotifyFailed => "failed to notify: {error}", "通知失败：{error}";
+    NewResults => "{count} new results of 『{search}』", "『{search}』有 {count} 个新结果";
+    Recycled => "Moved to the Recycle Bin: {path}, alt+del to restore",
+        "已移到回收站：{path}，alt+del 可还原";
+    RecycledCount => "Moved {count} files to the Recycle Bin, alt+del to restore",
+        "已将 {count} 个文件移到回收站，alt+del 可还原";
+    Restored => "Restored {path}", "已还原 {path}";
+    ConfirmRecycle => "Move {path} to the Recycle Bin", "将 {path} 移到回收站";
+    ConfirmRecycleCount => "Move {count} files to the Recycle Bin", "将 {count} 个文件移到回收站";
+    ConfirmTransfer => "{verb} {path} to {target}", "{verb} {path} 到 {target}";
+    ConfirmBatchRename => "Rename {count} files", "重命名 {count} 个文件";
+    ConfirmExtract => "Extract {path} into {target}", "将 {path} 解压到 {target}";
+    DryRun => "Dry run, not done: {what}", "演练，未执行：{what}";
+    Excluded => "Excluded {path}", "已排除 {path}";
+    ExcludedAlready => "Excluded already {path}", "已经排除了 {path}";
+    Pinned => "Pinned {path}", "已置顶 {path}";
+    Unpinned => "Unpinned {path}", "已取消置顶 {path}";
+    AdminAlready => "Running as administrator already", "已经以管理员身份运行";
+    PathDisplay => "Path display: {mode}", "路径显示：{mode}";
+    PathFull => "Full", "完整";
+    PathMiddle => "Middle", "省略中间";
+    PathTail => "Tail", "仅末尾";
+    PathRelative => "Relative", "相对";
+    NoCommands => "No commands, add them to [commands] in config",
+        "没有命令，请在配置的 [commands] 中添加";
+    SelectFolderForUsage => "Select a folder for the disk usage of it",
+        "请选择一个文件夹以查看其磁盘占用";
+    SortBy => "Sort by {sort}", "排序：{sort}";
+    FilterIs => "Filter: {name} ({search})", "筛选器：{name}（{search}）";
+    NoFilter => "No filter", "无筛选器";
+    RecentlyChangedView => "Recently changed, the latest first", "最近更改，最新的在前";
+    RecentlyChangedFilter => "Recently changed", "最近更改";
+    GlobOn => "The words are globs, e.g. src/**/*.rs", "词为 glob，例如 src/**/*.rs";
+    GlobOff => "The words are of the syntax of Everything", "词为 Everything 的语法";
+    InProject => "Searching the project {path}", "搜索项目 {path}";
+    NotInProject => "Searching all the files", "搜索所有文件";
+    NoProject => "No project here, launch ery in a folder of .git, Cargo.toml or package.json",
+        "这里没有项目，请在含 .git、Cargo.toml 或 package.json 的文件夹中启动 ery";
+    MatchScope => "Match the {scope}", "匹配{scope}";
+    UndoneQuery => "Undo: 『{text}』, sort by {sort}", "撤销：『{text}』，排序：{sort}";
+    RedoneQuery => "Redo: 『{text}』, sort by {sort}", "重做：『{text}』，排序：{sort}";
+    NoQueryToUndo => "No query to undo", "没有可撤销的查询";
+    NoQueryToRedo => "No query to redo", "没有可重做的查询";
+    ClipboardEmpty => "No text in the clipboard to search", "剪贴板中没有可搜索的文本";
+    FileListSaved => "File list saved to {path}", "文件列表已保存到 {path}";
+    SnapshotSaved => "Snapshot saved to {path}", "快照已保存到 {path}";
+    SelectToRun => "Select a result to run {name}", "请选择一个结果来运行 {name}";
+    NoExtFilter => "No ext: filter for the files without an extension",
+        "没有扩展名的文件无法按 ext: 筛选";
+    Renamed => "Renamed to {path}", "已重命名为 {path}";
+    BatchRenamed => "Renamed {count} files", "已重命名 {count} 个文件";
+    Marked => "{count} marked, shift+f2 to rename them, del to recycle them",
+        "已标记 {count} 个，shift+f2 批量重命名，del 移到回收站";
+    LinkCreated => "Created the {kind} {path}", "已创建{kind} {path}";
+    Noted => "Noted {path}", "已备注 {path}";
+    NoteRemoved => "Removed the note of {path}", "已删除 {path} 的备注";
+    Skipped => "Skipped {path}", "已跳过 {path}";
+    Exists => "{path} exists", "{path} 已存在";
+    InBackground => "{verb} in the background", "正在后台{verb}";
+    Moved => "Moved to {path}", "已移动到 {path}";
+    Copied => "Copied to {path}", "已复制到 {path}";
+    Extracted => "Extracted to {path}", "已解压到 {path}";
+    TaskFailed => "{title}: {error}", "{title} 失败：{error}";
+    Moving => "Moving", "移动";
+    Copying => "Copying", "复制";
+    Extracting => "Extracting", "解压";
+    Saving => "Saving", "保存";
+    Symlink => "symlink", "符号链接";
+    Hardlink => "hardlink", "硬链接";
+
+    // prompts
+    MoveTo => "Move {path} to the folder, tab to complete", "将 {path} 移动到文件夹，tab 补全";
+    CopyTo => "Copy {path} to the folder, tab to complete", "将 {path} 复制到文件夹，tab 补全";
+    LinkAt => "Create a {kind} of {path} at the path or in the folder",
+        "在该路径或文件夹中创建 {path} 的{kind}";
+    ExtractInto => "Extract {path} into the folder", "将 {path} 解压到文件夹";
+    RenameTo => "Rename {path}", "重命名 {path}";
+    NoteOf => "Note of {path}, empty to remove it", "{path} 的备注，留空则删除";
+    PromptHint => " enter to confirm, esc to cancel ", " enter 确认，esc 取消 ";
+    BatchRename => "Rename {count} files: {changed} changed, {problems} problems",
+        "重命名 {count} 个文件：{changed} 个更改，{problems} 个问题";
+    RenameFind => "Find, empty for the name without the extension", "查找，留空则为不含扩展名的名称";
+    RenameReplace => "Replace with, {n} or {nnn} for the numbers, $1 for the groups",
+        "替换为，{n} 或 {nnn} 为序号，$1 为分组";
+    BatchRenameHint => " tab to switch, alt+r regex: {regex}, alt+c case: {case}, enter to rename ",
+        " tab 切换，alt+r 正则：{regex}，alt+c 大小写：{case}，enter 重命名 ";
+    CaseKeep => "keep", "不变";
+    CaseLower => "lowercase", "小写";
+    CaseUpper => "UPPERCASE", "大写";
+    CaseTitle => "Title Case", "首字母大写";
+    RenameInvalid => "invalid name", "名称无效";
+    RenameDuplicate => "duplicate name", "名称重复";
+    RenameExists => "exists", "已存在";
+    RenameNotUnicode => "name not of Unicode", "名称不是 Unicode";
+    On => "on", "开";
+    Off => "off", "关";
+
+    // search bar and results
+    TitleFileList => "Everything (File List: {path})", "Everything（文件列表：{path}）";
+    TitleSnapshot => "Everything (Snapshot: {path})", "Everything（快照：{path}）";
+    TitleLauncherFilter => "Everything (Launcher, {filter})", "Everything（启动器，{filter}）";
+    TitleLauncher => "Everything (Launcher)", "Everything（启动器）";
+    TitleFilter => "Everything ({filter})", "Everything（{filter}）";
+    ScopeName => "name", "名称";
+    ScopePath => "path", "路径";
+    ScopeTail => "folder\\name", "文件夹\\名称";
+    SearchPlaceholder => "Type to search Everything…", "输入以搜索 Everything…";
+    Examples => "Examples", "示例";
+    ExampleToday => "Rust files modified today", "今天修改的 Rust 文件";
+    ExampleInvoice => "PDFs with invoice in the name", "名称含 invoice 的 PDF";
+    ExampleLarge => "files over 1 GB", "大于 1 GB 的文件";
+    ExampleFolder => "zips in the folders named downloads", "名为 downloads 的文件夹中的 zip";
+    RecentSearches => "Recent searches", "最近的搜索";
+    NoResults => "No results, try:", "没有结果，试试：";
+    SuggestNoWholeWord => "matching the parts of the words, without ww:", "匹配词的一部分，去掉 ww:";
+    SuggestNoCase => "ignoring the case, without case:", "忽略大小写，去掉 case:";
+    SuggestNoRegex => "the text as it is, the regex may be wrong, without regex:",
+        "按原文匹配，正则可能有误，去掉 regex:";
+    SuggestNoFilter => "without the filter", "去掉筛选器";
+    SuggestMatchPath => "matching the full paths", "匹配完整路径";
+    SuggestContent => "searching the contents of the files", "搜索文件内容";
+    DidYouMean => "No results for {original}, did you mean {search}? alt+d to keep it",
+        "{original} 没有结果，是不是要找 {search}？alt+d 保留";
+    TotalResults => "Total Results: {total} (Offset: {offset} Selected: {selected})",
+        "结果总数：{total}（偏移：{offset} 选中：{selected}）";
+    SortIs => "(Sort: {sort}) ", "（排序：{sort}）";
+    SearchingCancel => "(Searching... esc to cancel)", "（搜索中... esc 取消）";
+    Merged => "(Merged: {label})", "（合并：{label}）";
+    Baseline => "(Baseline: 『{search}』)", "（基准：『{search}』）";
+    ScopeIs => "(Scope: {folder})", "（范围：{folder}）";
+    LiveCount => "({count} matches, enter to show) ", "（{count} 个匹配，enter 显示）";
+    QueryError => " {error}, still waiting, esc to cancel ", " {error}，仍在等待，esc 取消 ";
+    TransferAmount => "{done} of {total}", "{done} / {total}";
+    TransferCancel => " {amount}, esc to cancel ", " {amount}，esc 取消 ";
+    MoreTasks => "(+{count}, f8) ", "（+{count}，f8）";
+    Admin => " Admin ", " 管理员 ";
+    NotAdmin => " Not Admin (ctrl+e) ", " 非管理员 (ctrl+e) ";
+    Searching => "Searching", "搜索中";
+    Folder => "folder", "文件夹";
+    File => "file", "文件";
+    SelectedStatus => "{index} of {number}: {kind} {name}, in {path}",
+        "第 {index} 个，共 {number} 个：{kind} {name}，位于 {path}";
+    ResultsStatus => "{number} of {total} results for {search}",
+        "{search} 的结果：{number} / {total}";
+    DateModifiedShort => "modified", "修改";
+    DateCreatedShort => "created", "创建";
+    DateAccessedShort => "accessed", "访问";
+    DateChangedShort => "changed", "更改";
+    SortName => "name", "名称";
+    SortPath => "path", "路径";
+    SortSize => "size", "大小";
+    SortExtension => "ext", "扩展名";
+    SortDateModified => "date modified", "修改日期";
+    SortRecentlyChanged => "date recently changed", "最近更改日期";
+    SortRelevance => "relevance", "相关度";
+
+    // status of Everything
+    StatusTitle => "Everything Status (ctrl+.)", "Everything 状态 (ctrl+.)";
+    Version => "Version", "版本";
+    IsAdmin => "Admin", "管理员";
+    AppData => "AppData", "AppData";
+    Indexed => "Indexed", "已索引";
+    FileSize => "File Size", "文件大小";
+    FolderSize => "Folder Size", "文件夹大小";
+    DateModified => "Date Modified", "修改日期";
+    DateCreated => "Date Created", "创建日期";
+    DateAccessed => "Date Accessed", "访问日期";
+    FastSort => "(fast sort)", "（快速排序）";
+
+    // preview
+    PreviewTitle => "Preview (F3)", "预览 (F3)";
+    Size => "Size", "大小";
+    Modified => "Modified", "修改时间";
+    Created => "Created", "创建时间";
+    Accessed => "Accessed", "访问时间";
+    Attributes => "Attributes", "属性";
+    RecentlyChanged => "Recently changed", "最近更改";
+    RunCount => "Run count", "运行次数";
+    LastRun => "Last run", "上次运行";
+    FileList => "File list", "文件列表";
+    Loading => "Loading...", "加载中...";
+    Archive => "Archive: {count} entries, {size} (alt+x to extract)",
+        "压缩包：{count} 项，{size}（alt+x 解压）";
+    ContentMatches => "{count} lines matched (n/N)", "{count} 行匹配 (n/N)";
+
+    // panels and popups
+    Compare => "Compare: +{added} -{removed} ~{changed} ", "对比：+{added} -{removed} ~{changed} ";
+    ExtensionsTitle => "Extensions (alt+e)", "扩展名 (alt+e)";
+    DiskUsage => "Disk Usage (ctrl+u): {total} ", "磁盘占用 (ctrl+u)：{total} ";
+    DiskUsageSummed => "(summed from the files, folder sizes are not indexed)",
+        "（由文件累加，文件夹大小未索引）";
+    DiskUsageScanning => "Disk Usage (ctrl+u): Scanning... esc to cancel",
+        "磁盘占用 (ctrl+u)：扫描中... esc 取消";
+    RunHistory => "Run History (ctrl+r) ", "运行历史 (ctrl+r) ";
+    Frequent => "Frequent", "常用";
+    Recent => "Recent", "最近";
+    DateFilter => "Date Filter (ctrl+d) ", "日期筛选 (ctrl+d) ";
+    DateFilterHint => " enter to insert, ←/→ to change the date by a day ",
+        " enter 插入，←/→ 按天调整日期 ";
+    From => "From", "从";
+    To => "To", "到";
+    SizeFilter => "Size Filter (ctrl+f) ", "大小筛选 (ctrl+f) ";
+    SizeFilterHint => " enter to insert, ←/→ to change the size, tab to change the unit ",
+        " enter 插入，←/→ 调整大小，tab 切换单位 ";
+    Min => "Min", "最小";
+    Max => "Max", "最大";
+    Today => "Today", "今天";
+    Yesterday => "Yesterday", "昨天";
+    Last7Days => "Last 7 days", "最近 7 天";
+    ThisMonth => "This month", "本月";
+    EmptyFiles => "Empty files", "空文件";
+    LargerThan1Gb => "Larger than 1 GB", "大于 1 GB";
+    TypeDate => "type a date, like 2024-01-31", "请输入日期，如 2024-01-31";
+    TypeSize => "type a size, like 100", "请输入大小，如 100";
+    InvalidDate => "invalid date {date}, use YYYY-MM-DD", "无效的日期 {date}，请使用 YYYY-MM-DD";
+    DateAfter => "{from} is after {to}", "{from} 晚于 {to}";
+    SizeLarger => "{min} is larger than {max}", "{min} 大于 {max}";
+    CommandsTitle => "Commands (F9)", "命令 (F9)";
+    CommandsHint => " enter to run on the selected result ", " enter 对选中的结果运行 ";
+    TemplatesTitle => "Templates (alt+t)", "模板 (alt+t)";
+    TemplatesHint => " enter to insert into the search ", " enter 插入到搜索中 ";
+    ReportTitle => "Report (ctrl+t) ", "报告 (ctrl+t) ";
+    ReportPending => "fetching all the matches...", "正在获取全部匹配...";
+    ReportPartial => "of the loaded results, f for all the matches", "已加载的结果，f 统计全部匹配";
+    ReportAll => "of all the matches", "全部匹配";
+    NotRequested => "   (not in the results, see query.flags in config)",
+        "   （结果中没有，见配置中的 query.flags）";
+    Newest => "Newest", "最新";
+    Oldest => "Oldest", "最旧";
+    Largest => "Largest", "最大";
+    Smallest => "Smallest", "最小";
+    Extensions => "Extensions", "扩展名";
+    Folders => "Folders", "文件夹";
+    FileExists => "File Exists", "文件已存在";
+    Confirm => "Confirm", "确认";
+    ConfirmDryRun => "Confirm (dry run, nothing is changed)", "确认（演练，不做改动）";
+    ConfirmHint => " y to go on, n or esc to cancel ", " y 继续，n 或 esc 取消 ";
+    ConfirmCountHint => " type the count and enter to go on, esc to cancel ",
+        " 输入数量并按 enter 继续，esc 取消 ";
+    TypeCount => " type {count} to confirm: {typed}", " 输入 {count} 以确认：{typed}";
+    ConflictHint => " o to overwrite, r to keep both, s or esc to skip ",
+        " o 覆盖，r 保留两者，s 或 esc 跳过 ";
+    ExistingFolder => "folder, the folders are merged if overwritten", "文件夹，覆盖时将合并";
+    ExistingFile => "file", "文件";
+    ConflictTo => " to {path}", " 到 {path}";
+    ConflictExisting => " which is an existing {what}.", " 该处已有同名{what}。";
+    Shortcut => "Shortcut", "快捷方式";
+    ShortcutHint => " enter to open the target, ctrl+enter its folder, esc to close ",
+        " enter 打开目标，ctrl+enter 打开其文件夹，esc 关闭 ";
+    ShortcutReading => " Reading the target...", " 正在读取目标...";
+    ShortcutTarget => " Target: {path}", " 目标：{path}";
+    ShortcutArguments => " Arguments: {arguments}", " 参数：{arguments}";
+    ShortcutStartIn => " Start in: {path}", " 起始位置：{path}";
+    NotShortcut => "Not a shortcut (.lnk)", "不是快捷方式（.lnk）";
+    Volumes => "Volumes", "卷";
+    VolumesHint => " enter or the drive letter to search in it, backspace for all, esc to close ",
+        " enter 或盘符在其中搜索，backspace 搜索全部，esc 关闭 ";
+    AllVolumes => "All volumes", "所有卷";
+    OpenedFiles => "Opened Files (ctrl+h)", "打开过的文件 (ctrl+h)";
+    OpenedHint => " enter to open, ctrl+enter its folder, tab to search it, del to forget, esc to close ",
+        " enter 打开，ctrl+enter 打开其文件夹，tab 搜索它，del 移除，esc 关闭 ";
+    NoOpened => "No files opened through ery yet", "还没有通过 ery 打开过文件";
+    RecycledFiles => "Recycled by ery (alt+del)", "ery 移到回收站的文件 (alt+del)";
+    RecycledHint => " enter to restore, esc to close ", " enter 还原，esc 关闭 ";
+    NoRecycled => "Nothing recycled by ery in this run", "本次运行还没有移到回收站的文件";
+    Tasks => "Tasks (f8)", "后台任务 (f8)";
+    TasksHint => " del to cancel, esc to close ", " del 取消，esc 关闭 ";
+    NoTasks => "No tasks in the background", "没有后台任务";
+    SlowSort => "Slow Sort", "排序较慢";
+    SlowSortHint => " y or enter to continue, n or esc to cancel, s for the status ",
+        " y 或 enter 继续，n 或 esc 取消，s 查看状态 ";
+    SlowSortWarning => " Sorting by {sort} without fast sort may take a long time, continue?",
+        " {sort}未启用快速排序，排序可能需要很长时间，是否继续？";
+    SlowSortTip => " Fast sort is enabled in Everything: Tools → Options → Indexes.",
+        " 可在 Everything 的 工具 → 选项 → 索引 中启用快速排序。";
+    ConfigError => "Config Error", "配置错误";
+    ConfigErrorHint => " fix it and save the file, or press any key to close ",
+        " 修正后保存文件，或按任意键关闭 ";
+
+    // templates
+    TemplateModifiedToday => "modified today", "今天修改的";
+    TemplateModifiedThisWeek => "modified this week", "本周修改的";
+    TemplateLargerThan100Mb => "larger than 100 MB", "大于 100 MB";
+    TemplateEmptyFolders => "empty folders", "空文件夹";
+    TemplateSameNames => "same names", "同名的";
+    TemplateExtensions => "extensions", "扩展名";
+    TemplateInFolder => "in the folder", "在文件夹中";
+    TemplateRegex => "regex", "正则表达式";
+    TemplateNotInWindows => "not in Windows", "不在 Windows 中";
+    TemplateContent => "containing the text", "包含文本的";
+
+    // features of Everything 1.5
+    FeatureContentIndex => "the indexed content search", "内容索引搜索";
+    RequiresEverything => "{feature} requires Everything {version}",
+        "{feature}需要 Everything {version}";
+
+    // setup
+    Welcome => "Welcome to ery ", "欢迎使用 ery ";
+    WizardHint => " ↑/↓ to choose, enter for the next, backspace for the last, esc to skip ",
+        " ↑/↓ 选择，enter 下一步，backspace 上一步，esc 跳过 ";
+    WizardIntro => " Set up ery, the config file is written at the end.",
+        " 设置 ery，最后会写入配置文件。";
+    StepTheme => "Theme", "主题";
+    ThemeEverything => "Everything, the orange one", "Everything，橙色";
+    ThemeOcean => "Ocean, the blue one", "Ocean，蓝色";
+    ThemeMono => "Mono, the colors of the terminal", "Mono，终端的颜色";
+    StepKeys => "Keys", "按键";
+    KeysDefault => "Default", "默认";
+    KeysVim => "Vim, j/k/g/G/h/l in the results, i to search, q to quit",
+        "Vim，结果中 j/k/g/G/h/l，i 搜索，q 退出";
+    StepIcons => "Icons", "图标";
+    IconsEmoji => "Emoji", "Emoji";
+    IconsNerd => "Nerd Font glyphs, a patched font is required", "Nerd Font 字形，需要打过补丁的字体";
+    IconsNone => "None", "无";
+    StepSort => "Sort", "排序";
+    SortByName => "Name", "名称";
+    SortByPath => "Path", "路径";
+    SortBySize => "Size, the largest first", "大小，最大的在前";
+    SortByDateModified => "Date modified, the newest first", "修改日期，最新的在前";
+    SortByExtension => "Extension", "扩展名";
+    SortByRelevance => "Relevance, the best matches first as a launcher does",
+        "相关度，像启动器一样最匹配的在前";
+    StepAsYouType => "Search as you type", "边输入边搜索";
+    AsYouTypeNo => "No, search on enter and count the matches while typing",
+        "否，按 enter 搜索，输入时统计匹配数";
+    AsYouTypeYes => "Yes, better with the fast sorts of Everything", "是，配合 Everything 的快速排序更好";
+
+    // the window of --gui
+    Name => "Name", "名称";
+    Path => "Path", "路径";
+}
@@ -1,2 +1,18 @@
 pub mod app;
+pub mod archive;
+pub mod bench;
+pub mod config;
+pub mod daemon;
+pub mod doctor;
+pub mod export;
+pub mod format;
+#[cfg(feature = "gui")]
+pub mod gui;
+pub mod i18n;
+pub mod notify;
+pub mod output;
+pub mod pipe;
+pub mod script;
+pub mod serve;
+pub mod shell;
 pub mod tui;
@@ -1,34 +1,440 @@
-use clap::Parser;
-use ery::app::App;
-use ery::tui::Tui;
+use clap::{Parser, Subcommand};
+use ery::app::clipboard;
+use ery::app::excludes::{self, Excludes};
+use ery::app::glob;
+use ery::app::merge::Merge;
+use ery::app::notes::Notes;
+use ery::app::opened::Opened;
+use ery::app::phrase;
+use ery::app::pins::Pins;
+use ery::app::project::Project;
+use ery::app::recent::Recent;
+use ery::app::watch::Watch;
+use ery::app::{self, App};
+use ery::bench;
+use ery::config::{Config, FlagPreset};
+use ery::daemon;
+use ery::doctor;
+use ery::i18n::Lang;
+use ery::output;
+use ery::pipe;
+use ery::serve;
+use ery::shell;
+use ery::tui::{Overrides, Tui};
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
-use std::io;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process::{self, ExitCode};
+use std::time::Duration;
+use std::{env, io};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Cli {
-    /// search text for Everything
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// search text for Everything, the words with spaces are searched as phrases. Put it after
+    /// `--` if it starts with `-`, e.g. `ery -- -draft`
     text: Option<Vec<String>>,
+
+    /// use the config file instead of the one in %APPDATA%\ery (or beside ery in portable mode)
+    #[arg(long, value_name = "FILE", global = true)]
+    config: Option<PathBuf>,
+
+    /// browse a saved snapshot file (*.ery) offline, Everything is not needed
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["text", "baseline", "search", "watch", "launch"])]
+    open: Option<PathBuf>,
+
+    /// browse an Everything file list (*.efu) offline, Everything is not needed
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["text", "open", "baseline", "search", "watch", "launch"])]
+    file_list: Option<PathBuf>,
+
+    /// compare the results with a saved snapshot file (*.ery), toggle the view by ctrl+k
+    #[arg(long, value_name = "FILE")]
+    baseline: Option<PathBuf>,
+
+    /// run the saved search with the name in config, or several ones merged into one list
+    #[arg(long, value_name = "NAME", conflicts_with = "text")]
+    search: Vec<String>,
+
+    /// search the text in the clipboard, e.g. an error message or a path copied, by the name of
+    /// the file in it only
+    #[arg(long, conflicts_with_all = ["text", "search", "open", "file_list"])]
+    from_clipboard: bool,
+
+    /// query again periodically (`watch.interval` seconds in config, 60 by default)
+    #[arg(long)]
+    watch: bool,
+
+    /// emit a desktop notification when new results appear in watch mode
+    #[arg(long, requires = "watch")]
+    notify: bool,
+
+    /// launcher mode, search programs and shortcuts, the most frequently run first
+    #[arg(long)]
+    launch: bool,
+
+    /// the words of the search are globs or .gitignore patterns, e.g. `src/**/*.rs`
+    #[arg(long)]
+    glob: bool,
+
+    /// search the project of the current folder only, found by .git, Cargo.toml or
+    /// package.json, without target, node_modules and .git
+    #[arg(long, conflicts_with = "no_tui")]
+    project: bool,
+
+    /// run as administrator in a new window if Everything is not, some operations need it
+    #[arg(long)]
+    elevate: bool,
+
+    /// recycling, moving, renaming at once and extracting only tell what they would do, nothing
+    /// is changed
+    #[arg(long)]
+    dry_run: bool,
+
+    /// do not show icons, for the terminals lacking the glyphs
+    #[arg(long)]
+    no_icons: bool,
+
+    /// no icons, the high-contrast colors and a status line of the selected for screen readers
+    #[arg(long)]
+    accessible: bool,
+
+    /// seconds to wait for the reply of Everything before telling the timeout, 0 to wait
+    /// silently, `query.timeout` in config by default
+    #[arg(long, value_name = "SECS")]
+    timeout: Option<u64>,
+
+    /// fields of the results requested from Everything, `query.flags` in config by default
+    #[arg(long, value_name = "PRESET")]
+    flags: Option<FlagPreset>,
+
+    /// language of the texts, `display.lang` in config or the one of Windows by default
+    #[arg(long, value_name = "LANG")]
+    lang: Option<Lang>,
+
+    /// serve the commands of other processes, e.g. `search <text>`, `get-selection` and `quit`,
+    /// on the named pipe \\.\pipe\NAME (ery by default)
+    #[arg(long, value_name = "NAME", num_args = 0..=1, default_missing_value = pipe::DEFAULT_NAME)]
+    pipe: Option<String>,
+
+    /// print the results of the search instead of browsing them, e.g. for scripts
+    #[arg(long, conflicts_with_all = ["open", "file_list", "baseline", "watch", "launch", "elevate"])]
+    no_tui: bool,
+
+    /// browse the results in a native window instead of the terminal
+    #[cfg(feature = "gui")]
+    #[arg(long, conflicts_with_all = ["search", "open", "file_list", "baseline", "watch", "launch", "no_tui", "daemon"])]
+    gui: bool,
+
+    /// format of the results printed by --no-tui
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        default_value = "paths",
+        requires = "no_tui"
+    )]
+    format: output::Format,
+
+    /// columns of --format csv and tsv, separated by commas
+    #[arg(
+        long,
+        value_name = "COLUMNS",
+        value_delimiter = ',',
+        default_value = "name,path,size,dm",
+        requires = "no_tui"
+    )]
+    columns: Vec<output::Column>,
+
+    /// no header row of the column names for --format csv and tsv
+    #[arg(long, requires = "no_tui")]
+    no_header: bool,
+
+    /// separate the paths by NUL instead of the line breaks, for `xargs -0`
+    #[arg(short = '0', long, requires = "no_tui")]
+    print0: bool,
+
+    /// print nothing for --no-tui, the exit code tells whether there are any matches
+    #[arg(short, long, requires = "no_tui")]
+    quiet: bool,
+
+    /// print the results at most for --no-tui
+    #[arg(long, value_name = "N", requires = "no_tui")]
+    limit: Option<u32>,
+
+    /// skip the results from the first for --no-tui
+    #[arg(long, value_name = "N", default_value_t = 0, requires = "no_tui")]
+    offset: u32,
+
+    /// query a batch at a time for --no-tui, printing the results as they come instead of
+    /// holding all of them, for the huge results
+    #[arg(long, requires = "no_tui", conflicts_with = "limit")]
+    all: bool,
+
+    /// stay resident, and pop up ery in a new terminal by the hotkey (`daemon.hotkey` in
+    /// config, win+shift+e by default), or bring it to the front
+    #[arg(long, conflicts_with_all = ["text", "open", "file_list", "watch", "elevate", "no_tui"])]
+    daemon: bool,
+
+    /// started by the hotkey of the daemon, which finds the window by the title
+    #[arg(long, hide = true)]
+    summoned: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// run the query repeatedly, then report the latency of Everything IPC and entry conversion
+    Bench {
+        /// search text for Everything
+        query: String,
+
+        /// times to run the query
+        #[arg(short = 'n', long, default_value_t = 10)]
+        times: u32,
+    },
+    /// print only the number of the matches, e.g. for scripts and shell prompts
+    Count {
+        /// search text for Everything
+        query: String,
+    },
+    /// check Everything, the config and the terminal, for troubleshooting and the bug reports
+    Doctor,
+    /// answer the searches of other tools, e.g. AI assistants, by JSON-RPC 2.0 (and MCP) on
+    /// stdin and stdout, a message per line
+    Serve,
 }
 
-fn main() -> anyhow::Result<()> {
-    let cli = Cli::parse();
+fn main() -> ExitCode {
+    let cli = Cli::try_parse().unwrap_or_else(|e| {
+        // the usage errors of --no-tui exit with 3 as the bad queries, 2 is Everything
+        // unavailable there; the others with the code of clap
+        if !env::args_os().any(|arg| arg == "--no-tui") {
+            e.exit();
+        }
+        let _ = e.print();
+        process::exit(if e.use_stderr() { 3 } else { 0 })
+    });
+    let no_tui = cli.no_tui;
+    match run(cli) {
+        Ok(exit) => exit,
+        Err(e) => {
+            eprintln!("Error: {e:?}");
+            if no_tui {
+                output::Exit::of_error(&e).into()
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+    }
+}
 
-    let search_text = cli.text.as_ref();
+fn run(cli: Cli) -> anyhow::Result<ExitCode> {
+    if let Some(path) = &cli.config {
+        // the full path in the messages, e.g. the errors of parsing it
+        Config::set_path(std::path::absolute(path)?);
+    }
+    if let Some(Command::Doctor) = cli.command {
+        // before loading the config, which it checks
+        doctor::run()?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    let config = Config::load()?;
+
+    match cli.command {
+        Some(Command::Bench { query, times }) => {
+            bench::run(&query, times, config)?;
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Command::Count { query }) => {
+            let clauses = Excludes::load()?.clauses_with(&config);
+            println!("{}", app::count(&excludes::append(&query, &clauses))?);
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Command::Serve) => {
+            serve::run(&config)?;
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Command::Doctor) | None => {}
+    }
+
+    // before any pipe, thread or query is started, which the elevated one starts again
+    if cli.elevate && !app::is_admin()? {
+        let args: Vec<OsString> = env::args_os()
+            .skip(1)
+            .filter(|arg| arg != "--elevate")
+            .collect();
+        shell::relaunch_elevated(&args)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if cli.daemon {
+        let args: Vec<OsString> = env::args_os()
+            .skip(1)
+            .filter(|arg| arg != "--daemon")
+            .collect();
+        daemon::run(&config.daemon.hotkey, &args)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let mut search_text = cli.text.as_deref().map(phrase::join_args);
+    if cli.from_clipboard {
+        let Some(text) = clipboard::to_search(&clipboard::read_text()?) else {
+            anyhow::bail!("no text in the clipboard to search");
+        };
+        search_text = Some(text);
+    }
+    // `--notify` and `--flags` first, then the first of the saved searches setting them
+    let mut notify = cli.notify.then_some(true);
+    let mut flags = cli.flags;
+    let mut merge = vec![];
+    let mut pins = None;
+    for name in &cli.search {
+        let Some(saved) = config.saved_search(name) else {
+            anyhow::bail!("no saved search named {name:?} in config");
+        };
+        notify = notify.or(saved.notify);
+        flags = flags.or(saved.flags);
+        merge.push((saved.name.clone(), saved.query.clone()));
+    }
+    if let [(name, query)] = merge.as_slice() {
+        search_text = Some(query.clone());
+        if config
+            .saved_search(name)
+            .is_some_and(|saved| saved.keep_pins)
+        {
+            pins = Some(Pins::saved(name)?);
+        }
+        merge.clear();
+    }
+    let notify = notify.unwrap_or(config.watch.notify);
+
+    if cli.no_tui {
+        anyhow::ensure!(
+            merge.is_empty(),
+            output::Invalid("--no-tui prints one search only".to_string())
+        );
+        anyhow::ensure!(
+            !cli.print0 || cli.format == output::Format::Paths,
+            output::Invalid("--print0 is for --format paths only".to_string())
+        );
+        let Some(mut text) = search_text else {
+            anyhow::bail!(output::Invalid(
+                "the search text is needed for --no-tui".to_string()
+            ));
+        };
+        if cli.glob {
+            text = glob::translate(&text);
+        }
+        let options = output::Options {
+            format: cli.format,
+            columns: cli.columns,
+            header: !cli.no_header,
+            print0: cli.print0,
+            quiet: cli.quiet,
+            limit: cli.limit,
+            offset: cli.offset,
+            all: cli.all,
+        };
+        return Ok(output::run(&text, &options, &config)?.into());
+    }
+
+    #[cfg(feature = "gui")]
+    if cli.gui {
+        ery::gui::run(config, search_text, cli.lang)?;
+        return Ok(ExitCode::SUCCESS);
+    }
 
     let backend = CrosstermBackend::new(io::stdout());
     let terminal = Terminal::new(backend)?;
     let mut tui = Tui::new(terminal);
+    let overrides = Overrides {
+        no_icons: cli.no_icons,
+        accessible: cli.accessible,
+        lang: cli.lang,
+    };
+    tui.set_config(&config, overrides)?;
+    if cli.summoned {
+        tui.set_summoned()?;
+    }
+    if let Some(name) = &cli.pipe {
+        pipe::serve(name, tui.sender.clone())?;
+    }
+    if Config::is_first_run() {
+        tui.start_wizard();
+    }
+    let notes = Notes::load()?;
+
+    if let Some(path) = cli.file_list {
+        let app = &mut App::with_file_list(tui.sender.clone(), config, path)?;
+        app.notes = notes;
+        app.dry_run = cli.dry_run;
+        tui.run_loop(app)?;
+        return Ok(ExitCode::SUCCESS);
+    }
 
-    let mut app = App::with_sender(tui.sender.clone());
-    if let Some(text) = search_text {
-        let text = &text.join(" "); // multi params separated by spaces
+    if let Some(path) = cli.open {
+        let app = &mut App::with_snapshot(tui.sender.clone(), config, path)?;
+        app.notes = notes;
+        app.dry_run = cli.dry_run;
+        let search = app.query_results.load().search.clone();
+        tui.set_search_text(&search.to_string_lossy());
+        tui.run_loop(app)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let interval = Duration::from_secs(config.watch.interval.max(1));
+    let mut app = App::with_sender(tui.sender.clone(), config);
+    app.flags_override = flags;
+    app.timeout_override = cli.timeout;
+    app.notes = notes;
+    app.excludes = Excludes::load()?;
+    app.glob = cli.glob;
+    app.dry_run = cli.dry_run;
+    app.project = env::current_dir()
+        .ok()
+        .and_then(|dir| Project::detect(&dir));
+    if cli.project {
+        anyhow::ensure!(
+            app.project.is_some(),
+            "no project in the current folder, of .git, Cargo.toml or package.json"
+        );
+        app.in_project = true;
+    }
+    app.recent = Recent::load()?;
+    app.opened = Opened::load()?;
+    tui.set_page_height(&mut app)?;
+    if let Some(pins) = pins {
+        app.pins = pins;
+    }
+    if !merge.is_empty() {
+        app.merge = Some(Merge::new(merge));
+        if search_text.is_none() {
+            // all the results of the searches from start
+            app.send_query("")?;
+        }
+    }
+    if let Some(path) = cli.baseline {
+        app.load_baseline(path)?;
+    }
+    if cli.watch {
+        app.watch = Some(Watch::new(interval, notify));
+    }
+    if cli.launch {
+        app.set_launcher();
+        if search_text.is_none() {
+            // show the most frequently run programs from start
+            app.send_query("")?;
+        }
+    }
+    if let Some(text) = &search_text {
         tui.set_search_text(text); // set search text from start
         app.send_query(text)?; // then search it automatically
     }
 
     tui.run_loop(&mut app)?;
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
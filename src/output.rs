@@ -0,0 +1,598 @@
+use std::{
+    borrow::Cow,
+    ffi::{OsStr, OsString},
+    fmt,
+    io::{self, BufWriter, Write},
+    os::windows::ffi::OsStrExt,
+    path::Path,
+    process::ExitCode,
+};
+
+use anyhow::Context;
+use chrono::Local;
+
+use everything_sdk::RequestFlags;
+
+use crate::app::{
+    self,
+    ery::{Query, QueryEntry, QueryResults},
+    excludes::{self, Excludes},
+    sort::{sort_for, sort_label},
+};
+use crate::config::Config;
+use crate::export;
+use crate::format;
+
+/// Exit codes of `ery --no-tui`, for the scripts using it as a predicate, e.g. whether any file
+/// matching the search exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    /// any matches.
+    Found = 0,
+    /// no matches.
+    NotFound = 1,
+    /// Everything is not running, or the database of it is not loaded yet.
+    Unavailable = 2,
+    /// the search or the arguments are invalid.
+    BadQuery = 3,
+    /// anything else failed, e.g. the config can not be read or the output can not be written.
+    Failed = 4,
+}
+
+impl From<Exit> for ExitCode {
+    fn from(exit: Exit) -> Self {
+        ExitCode::from(exit as u8)
+    }
+}
+
+impl Exit {
+    /// The exit code of the error, Everything unavailable, the bad query, or else failed.
+    pub fn of_error(e: &anyhow::Error) -> Self {
+        if e.downcast_ref::<Unavailable>().is_some() {
+            Exit::Unavailable
+        } else if e.downcast_ref::<Invalid>().is_some() {
+            Exit::BadQuery
+        } else {
+            Exit::Failed
+        }
+    }
+}
+
+/// The search or the arguments are invalid, told apart from the other errors for
+/// [`Exit::BadQuery`].
+#[derive(Debug)]
+pub struct Invalid(pub String);
+
+impl fmt::Display for Invalid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Invalid {}
+
+/// The error of querying Everything, told apart from the errors of the search.
+#[derive(Debug)]
+struct Unavailable;
+
+impl fmt::Display for Unavailable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Everything is not available, is it running?")
+    }
+}
+
+/// The results of a query of `--all`, few round trips of IPC, but not many in memory at a time.
+const BATCH: u32 = 10_000;
+
+/// Formats of the results printed by `ery --no-tui`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// the full paths, one per line.
+    #[default]
+    Paths,
+    /// CLIXML of the objects with Path, Size, LastWriteTime and IsFolder, for `Import-Clixml` of
+    /// PowerShell.
+    Psobject,
+    /// comma-separated values of the columns, quoted when needed, for the spreadsheets.
+    Csv,
+    /// tab-separated values of the columns.
+    Tsv,
+    /// a report of the search and a table of the results, for pasting into the tickets.
+    Markdown,
+    /// a standalone page of the report, for the mails and the browsers.
+    Html,
+}
+
+/// Columns of the results printed as CSV or TSV.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Column {
+    /// the file name.
+    Name,
+    /// the parent folder.
+    Path,
+    /// the full path, the parent folder joined with the name.
+    FullPath,
+    /// the extension.
+    Ext,
+    /// the size in bytes.
+    Size,
+    /// the date modified.
+    Dm,
+    /// the date created.
+    Dc,
+    /// the date accessed.
+    Da,
+    /// the attributes in letters, e.g. `RHA`.
+    Attr,
+}
+
+impl Column {
+    /// The header of the column, the same as the value of `--columns`.
+    fn name(self) -> &'static str {
+        match self {
+            Column::Name => "name",
+            Column::Path => "path",
+            Column::FullPath => "full-path",
+            Column::Ext => "ext",
+            Column::Size => "size",
+            Column::Dm => "dm",
+            Column::Dc => "dc",
+            Column::Da => "da",
+            Column::Attr => "attr",
+        }
+    }
+
+    /// The field requested from Everything for the column.
+    fn flag(self) -> RequestFlags {
+        match self {
+            Column::Name | Column::Path | Column::FullPath => {
+                RequestFlags::EVERYTHING_REQUEST_FILE_NAME | RequestFlags::EVERYTHING_REQUEST_PATH
+            }
+            Column::Ext => RequestFlags::EVERYTHING_REQUEST_EXTENSION,
+            Column::Size => RequestFlags::EVERYTHING_REQUEST_SIZE,
+            Column::Dm => RequestFlags::EVERYTHING_REQUEST_DATE_MODIFIED,
+            Column::Dc => RequestFlags::EVERYTHING_REQUEST_DATE_CREATED,
+            Column::Da => RequestFlags::EVERYTHING_REQUEST_DATE_ACCESSED,
+            Column::Attr => RequestFlags::EVERYTHING_REQUEST_ATTRIBUTES,
+        }
+    }
+
+    /// The value of the column for the entry, empty if unknown. The names and the paths are
+    /// kept as they are, not of UTF-8 maybe.
+    fn value(self, results: &QueryResults, entry: &QueryEntry) -> OsString {
+        let date = |filetime: Option<u64>| filetime.map(format::date_exact).unwrap_or_default();
+        let name = |s: Option<&OsStr>| s.map(OsStr::to_os_string).unwrap_or_default();
+        match self {
+            Column::Name => name(entry.filename.as_deref()),
+            Column::Path => name(results.path(entry).map(Path::as_os_str)),
+            Column::FullPath => results.filepath(entry).map(Into::into).unwrap_or_default(),
+            Column::Ext => name(entry.extension.as_deref()),
+            Column::Size => entry
+                .size
+                .map(|size| size.to_string())
+                .unwrap_or_default()
+                .into(),
+            Column::Dm => date(entry.date_modified).into(),
+            Column::Dc => date(entry.date_created).into(),
+            Column::Da => date(entry.date_accessed).into(),
+            Column::Attr => entry
+                .attributes
+                .map(format::attributes)
+                .unwrap_or_default()
+                .into(),
+        }
+    }
+}
+
+/// Options of `ery --no-tui`.
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    pub format: Format,
+    /// columns of CSV and TSV.
+    pub columns: Vec<Column>,
+    /// the row of the names of the columns first, for CSV and TSV.
+    pub header: bool,
+    /// the paths separated by NUL instead of the line breaks.
+    pub print0: bool,
+    /// print nothing, only the exit code tells whether there are any matches.
+    pub quiet: bool,
+    /// the results printed at most, all of them if none.
+    pub limit: Option<u32>,
+    /// the results skipped from the first.
+    pub offset: u32,
+    /// query a batch at a time until all the results are printed.
+    pub all: bool,
+}
+
+/// Print the results of the search, sorted by `query.sort` of config, instead of browsing them
+/// in the TUI.
+pub fn run(search: &str, options: &Options, config: &Config) -> anyhow::Result<Exit> {
+    anyhow::ensure!(
+        !search.trim().is_empty(),
+        Invalid("the search text is empty".to_string())
+    );
+    anyhow::ensure!(
+        search.matches('"').count().is_multiple_of(2),
+        Invalid(format!("unclosed quote in the search {search:?}"))
+    );
+    // the header tells the search as given
+    let excluded = excludes::append(search, &Excludes::load()?.clauses_with(config));
+    if options.quiet {
+        // the number of the matches only
+        let query = Query {
+            search: excluded,
+            max: 0,
+            request_flags: RequestFlags::EVERYTHING_REQUEST_FILE_NAME,
+            ..Default::default()
+        };
+        let results = app::query(&query).context(Unavailable)?;
+        return Ok(exit(&results));
+    }
+    let (sort_type, secondary_sort) = sort_for(search, &config.query);
+    if !app::is_fast_sort(sort_type).context(Unavailable)? {
+        let label = sort_label(sort_type, secondary_sort);
+        eprintln!("warning: sorting by {label} without fast sort may take a long time");
+    }
+    let mut request_flags = RequestFlags::EVERYTHING_REQUEST_FILE_NAME
+        | RequestFlags::EVERYTHING_REQUEST_PATH
+        | RequestFlags::EVERYTHING_REQUEST_SIZE
+        | RequestFlags::EVERYTHING_REQUEST_DATE_MODIFIED;
+    if matches!(options.format, Format::Csv | Format::Tsv) {
+        request_flags = options
+            .columns
+            .iter()
+            .fold(RequestFlags::empty(), |flags, column| flags | column.flag());
+    }
+    if let Some(key) = secondary_sort {
+        request_flags |= key.request_flags();
+    }
+    let mut query = Query {
+        search: excluded,
+        max: if options.all {
+            BATCH
+        } else {
+            options.limit.unwrap_or(u32::MAX)
+        },
+        offset: options.offset,
+        sort_type,
+        request_flags,
+        secondary_sort,
+        ..Default::default()
+    };
+    let mut w = BufWriter::new(io::stdout().lock());
+    let sort = sort_label(sort_type, secondary_sort);
+    write_header(&mut w, options, search, &sort)?;
+    let mut printed = 0;
+    // with --all, a batch at a time printed as it comes, instead of all the results in memory
+    let (exit, total) = loop {
+        let results = app::query(&query).context(Unavailable)?;
+        write_results(&mut w, &results, options, printed)?;
+        w.flush()?;
+        printed += results.entrys.len();
+        if !options.all || results.number < query.max {
+            break (exit(&results), results.total);
+        }
+        query.offset += results.number;
+    };
+    write_footer(&mut w, options, printed, total)?;
+    w.flush()?;
+    Ok(exit)
+}
+
+fn exit(results: &QueryResults) -> Exit {
+    if results.total > 0 {
+        Exit::Found
+    } else {
+        Exit::NotFound
+    }
+}
+
+/// Write what comes before the results, e.g. the header row of CSV, or the search and the sort
+/// of the report.
+fn write_header(w: &mut impl Write, options: &Options, search: &str, sort: &str) -> io::Result<()> {
+    // what the results are of, the limit and the offset if any
+    let mut summary = vec![
+        ("Date", Local::now().format("%Y-%m-%d %H:%M:%S").to_string()),
+        ("Sort", sort.to_string()),
+    ];
+    if let Some(limit) = options.limit.filter(|_| !options.all) {
+        summary.push(("Limit", limit.to_string()));
+    }
+    if options.offset > 0 {
+        summary.push(("Offset", options.offset.to_string()));
+    }
+    match options.format {
+        Format::Markdown => {
+            writeln!(w, "# ery: {}\n", markdown_code(search))?;
+            for (name, value) in summary {
+                writeln!(w, "- {name}: {value}")?;
+            }
+            writeln!(w, "\n| Name | Folder | Size | Date modified |")?;
+            writeln!(w, "| --- | --- | ---: | --- |")
+        }
+        Format::Html => {
+            let search = html_text(search);
+            writeln!(w, "<!DOCTYPE html>\n<html>\n<head>")?;
+            writeln!(w, r#"<meta charset="utf-8">"#)?;
+            writeln!(w, "<title>ery: {search}</title>")?;
+            writeln!(w, "<style>{REPORT_STYLE}</style>\n</head>\n<body>")?;
+            writeln!(w, "<h1>ery: <code>{search}</code></h1>\n<ul>")?;
+            for (name, value) in summary {
+                writeln!(w, "<li>{name}: {}</li>", html_text(&value))?;
+            }
+            writeln!(w, "</ul>\n<table>")?;
+            writeln!(
+                w,
+                "<tr><th>Name</th><th>Folder</th><th>Size</th><th>Date modified</th></tr>"
+            )
+        }
+        Format::Psobject => writeln!(
+            w,
+            r#"<Objs Version="1.1.0.1" xmlns="http://schemas.microsoft.com/powershell/2004/04">"#
+        ),
+        Format::Csv | Format::Tsv if options.header => {
+            let names = options.columns.iter().map(|c| c.name().into());
+            write_row(w, names, options.format)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Write the results of a query, after the `printed` ones of the queries before.
+fn write_results(
+    w: &mut impl Write,
+    results: &QueryResults,
+    options: &Options,
+    printed: usize,
+) -> io::Result<()> {
+    match options.format {
+        Format::Paths if options.print0 => export::write_paths(w, results, b"\0"),
+        Format::Paths => export::write_file_list(w, results),
+        Format::Psobject => {
+            for (i, entry) in results.entrys.iter().enumerate() {
+                write_clixml_object(w, printed + i, results, entry)?;
+            }
+            Ok(())
+        }
+        Format::Csv | Format::Tsv => {
+            for entry in &results.entrys {
+                let fields = options.columns.iter().map(|c| c.value(results, entry));
+                write_row(w, fields, options.format)?;
+            }
+            Ok(())
+        }
+        Format::Markdown | Format::Html => {
+            for entry in &results.entrys {
+                write_report_row(w, options.format, results, entry)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Write what comes after the results, e.g. the end of the objects of CLIXML, or the count of
+/// the report.
+fn write_footer(
+    w: &mut impl Write,
+    options: &Options,
+    printed: usize,
+    total: u32,
+) -> io::Result<()> {
+    match options.format {
+        Format::Psobject => writeln!(w, "</Objs>"),
+        Format::Markdown => writeln!(w, "\n{printed} of {total} results"),
+        Format::Html => {
+            writeln!(w, "</table>\n<p>{printed} of {total} results</p>")?;
+            writeln!(w, "</body>\n</html>")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Write a row of CSV or TSV, the fields escaped as the format needs, the names and the paths
+/// written as `export::write_os_str` does.
+fn write_row(
+    w: &mut impl Write,
+    fields: impl Iterator<Item = OsString>,
+    format: Format,
+) -> io::Result<()> {
+    let separator = match format {
+        Format::Tsv => b'\t',
+        _ => b',',
+    };
+    for (i, field) in fields.enumerate() {
+        if i > 0 {
+            w.write_all(&[separator])?;
+        }
+        match format {
+            // the names of Windows can not have the tabs or the line breaks
+            Format::Tsv => export::write_os_str(w, &field)?,
+            _ => w.write_all(&csv_field(field.as_encoded_bytes()))?,
+        }
+    }
+    w.write_all(b"\r\n")
+}
+
+/// The field quoted as RFC 4180 if it has the separator, the quotes or the line breaks. Only
+/// the ASCII bytes are looked at, so the rest of the encoding is kept as it is.
+fn csv_field(field: &[u8]) -> Cow<'_, [u8]> {
+    if !field
+        .iter()
+        .any(|b| matches!(b, b',' | b'"' | b'\r' | b'\n'))
+    {
+        return Cow::Borrowed(field);
+    }
+    let mut quoted = Vec::with_capacity(field.len() + 2);
+    quoted.push(b'"');
+    for &b in field {
+        if b == b'"' {
+            quoted.push(b'"');
+        }
+        quoted.push(b);
+    }
+    quoted.push(b'"');
+    Cow::Owned(quoted)
+}
+
+/// The style of the HTML report, plain and readable in the mails too.
+const REPORT_STYLE: &str = "body { font-family: sans-serif; } \
+    table { border-collapse: collapse; } \
+    th, td { border: 1px solid #ccc; padding: 2px 8px; text-align: left; } \
+    td:nth-child(3) { text-align: right; }";
+
+/// Write a row of the table of the report, the name, the folder, the size and the date modified.
+fn write_report_row(
+    w: &mut impl Write,
+    format: Format,
+    results: &QueryResults,
+    entry: &QueryEntry,
+) -> io::Result<()> {
+    let name = lossy(entry.filename.as_deref());
+    let folder = lossy(results.path(entry).map(Path::as_os_str));
+    let size = entry.size.map(format::size).unwrap_or_default();
+    let date = entry.date_modified.map(format::date).unwrap_or_default();
+    if format == Format::Html {
+        let [name, folder] = [name, folder].map(|s| html_text(&s));
+        writeln!(
+            w,
+            "<tr><td>{name}</td><td>{folder}</td><td>{size}</td><td>{date}</td></tr>"
+        )
+    } else {
+        let [name, folder] = [name, folder].map(|s| markdown_code(&s));
+        writeln!(w, "| {name} | {folder} | {size} | {date} |")
+    }
+}
+
+/// The text as code of Markdown, so the names with `*` or `_` are not emphasized, and the `|`
+/// escaped for the tables.
+fn markdown_code(s: &str) -> String {
+    let s = s.replace('|', "\\|");
+    if s.contains('`') {
+        format!("`` {s} ``")
+    } else {
+        format!("`{s}`")
+    }
+}
+
+/// The text escaped for HTML.
+fn html_text(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The text to show in the reports, which are read instead of used as the paths.
+fn lossy(s: Option<&OsStr>) -> String {
+    s.map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Write the entry as an object of the CLIXML of PowerShell, deserialized with the types, e.g.
+/// the size as a number and the date modified as a `DateTime`.
+fn write_clixml_object(
+    w: &mut impl Write,
+    ref_id: usize,
+    results: &QueryResults,
+    entry: &QueryEntry,
+) -> io::Result<()> {
+    writeln!(w, r#"  <Obj RefId="{ref_id}">"#)?;
+    // the type names are written once, and referred to by the others
+    if ref_id == 0 {
+        writeln!(w, r#"    <TN RefId="0">"#)?;
+        writeln!(
+            w,
+            "      <T>System.Management.Automation.PSCustomObject</T>"
+        )?;
+        writeln!(w, "      <T>System.Object</T>")?;
+        writeln!(w, "    </TN>")?;
+    } else {
+        writeln!(w, r#"    <TNRef RefId="0" />"#)?;
+    }
+    writeln!(w, "    <MS>")?;
+    if let Some(path) = results.filepath(entry) {
+        writeln!(
+            w,
+            r#"      <S N="Path">{}</S>"#,
+            clixml_text(path.as_os_str())
+        )?;
+    }
+    if let Some(size) = entry.size {
+        writeln!(w, r#"      <I64 N="Size">{size}</I64>"#)?;
+    }
+    if let Some(date) = entry.date_modified.and_then(format::filetime_to_local) {
+        let date = date.format("%Y-%m-%dT%H:%M:%S%.7f%:z");
+        writeln!(w, r#"      <DT N="LastWriteTime">{date}</DT>"#)?;
+    }
+    writeln!(w, r#"      <B N="IsFolder">{}</B>"#, entry.is_folder)?;
+    writeln!(w, "    </MS>")?;
+    writeln!(w, "  </Obj>")
+}
+
+/// The text escaped for XML, and the chars XML can not hold encoded as `_xHHHH_` as PowerShell
+/// does, e.g. the unpaired surrogates of the file names, so they are read back losslessly.
+fn clixml_text(s: &OsStr) -> String {
+    let mut text = String::new();
+    let chars: Vec<_> = char::decode_utf16(s.encode_wide()).collect();
+    for (i, c) in chars.iter().enumerate() {
+        match *c {
+            Ok('&') => text.push_str("&amp;"),
+            Ok('<') => text.push_str("&lt;"),
+            Ok('>') => text.push_str("&gt;"),
+            // `_x` is escaped too, not to be taken as an encoded char
+            Ok('_') if matches!(chars.get(i + 1), Some(Ok('x'))) => text.push_str("_x005F_"),
+            Ok(c) if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {
+                text.push_str(&format!("_x{:04X}_", c as u32));
+            }
+            Ok(c) => text.push(c),
+            Err(ref e) => text.push_str(&format!("_x{:04X}_", e.unpaired_surrogate())),
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_invalid_search_is_a_bad_query() {
+        let invalid = anyhow::Error::new(Invalid("the search text is empty".to_string()));
+        assert_eq!(Exit::of_error(&invalid), Exit::BadQuery);
+
+        let unavailable = Err::<(), _>(io::Error::other("no window")).context(Unavailable);
+        assert_eq!(Exit::of_error(&unavailable.unwrap_err()), Exit::Unavailable);
+
+        let broken_pipe = anyhow::Error::new(io::Error::from(io::ErrorKind::BrokenPipe));
+        assert_eq!(Exit::of_error(&broken_pipe), Exit::Failed);
+    }
+
+    #[test]
+    fn fields_of_csv_are_quoted_only_if_needed() {
+        let mut w = vec![];
+        let fields = ["a.rs", "a, b.rs", r#"say "hi".txt"#, "中文.txt"].map(OsString::from);
+        write_row(&mut w, fields.into_iter(), Format::Csv).unwrap();
+        assert_eq!(
+            String::from_utf8(w).unwrap(),
+            "a.rs,\"a, b.rs\",\"say \"\"hi\"\".txt\",中文.txt\r\n"
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn names_not_of_utf8_are_written_as_they_are() {
+        use std::os::windows::ffi::OsStringExt;
+
+        // an unpaired surrogate, kept in WTF-8 instead of replaced by `�`
+        let name = OsString::from_wide(&[0x61, 0xD800, 0x2C]);
+        for format in [Format::Csv, Format::Tsv] {
+            let mut w = vec![];
+            write_row(&mut w, [name.clone()].into_iter(), format).unwrap();
+            let quoted = format == Format::Csv;
+            let mut expected = if quoted { b"\"".to_vec() } else { vec![] };
+            expected.extend_from_slice(name.as_encoded_bytes());
+            expected.extend_from_slice(if quoted { b"\"\r\n" } else { b"\r\n" });
+            assert_eq!(w, expected);
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Write},
+    os::windows::io::{AsRawHandle, FromRawHandle},
+    str::FromStr,
+    sync::mpsc,
+    thread,
+};
+
+use anyhow::Context;
+use windows::{
+    core::HSTRING,
+    Win32::{
+        Foundation::{ERROR_PIPE_CONNECTED, HANDLE},
+        Storage::FileSystem::{FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_DUPLEX},
+        System::Pipes::{
+            ConnectNamedPipe, CreateNamedPipeW, DisconnectNamedPipe, PIPE_REJECT_REMOTE_CLIENTS,
+            PIPE_TYPE_BYTE, PIPE_WAIT,
+        },
+    },
+};
+
+use crate::tui::Event;
+
+/// The name of the pipe by default, i.e. `\\.\pipe\ery`.
+pub const DEFAULT_NAME: &str = "ery";
+
+/// The size of the buffers of the pipe, enough for the lines of the commands and the paths.
+const BUFFER_SIZE: u32 = 4096;
+
+/// A command from another process through the pipe, one per line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// `search <text>`, search the text as typed into the search bar.
+    Search(String),
+    /// `get-selection`, reply the full path of the selected, or an empty line if none.
+    GetSelection,
+    /// `quit`, quit ery.
+    Quit,
+}
+
+impl FromStr for Command {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let (name, arg) = s.split_once(' ').unwrap_or((s, ""));
+        match name {
+            "search" => Ok(Self::Search(arg.to_string())),
+            "get-selection" => Ok(Self::GetSelection),
+            "quit" => Ok(Self::Quit),
+            _ => anyhow::bail!("unknown command {name:?}"),
+        }
+    }
+}
+
+/// A command sent to the TUI, which replies a line to the client through it.
+#[derive(Debug)]
+pub struct Request {
+    pub command: Command,
+    reply: mpsc::Sender<String>,
+}
+
+impl Request {
+    /// Reply to the client, nothing if it is gone already.
+    pub fn reply(&self, line: impl Into<String>) {
+        let _ = self.reply.send(line.into());
+    }
+}
+
+/// Serve the commands on the pipe `\\.\pipe\<name>` of the local machine in the background, for
+/// the editor plugins and the scripts, e.g. of AutoHotkey, driving ery.
+///
+/// The clients are served one by one, each writing the commands line by line and reading a line
+/// replied for each: `ok`, the path for `get-selection`, or `error: <message>`.
+pub fn serve(name: &str, sender: mpsc::Sender<Event>) -> anyhow::Result<()> {
+    let path = HSTRING::from(format!(r"\\.\pipe\{name}"));
+    // fails if the name is taken, e.g. by another ery
+    let pipe = create(&path)
+        .with_context(|| format!("failed to create the pipe {path}, taken by another ery?"))?;
+    thread::spawn(move || loop {
+        if let Err(e) = accept(&pipe, &sender) {
+            if e.downcast_ref::<mpsc::SendError<Event>>().is_some() {
+                break; // the TUI is gone
+            }
+        }
+        // the only instance is reused for the next client, which waits until it is closed
+        // SAFETY: the handle is a pipe instance owned by the file.
+        if unsafe { DisconnectNamedPipe(HANDLE(pipe.as_raw_handle())) }.is_err() {
+            break;
+        }
+    });
+    Ok(())
+}
+
+/// Create the only instance of the pipe, owned by the file closing it.
+fn create(path: &HSTRING) -> windows::core::Result<File> {
+    // SAFETY: the name is a valid wide string, and the handle is checked before owned.
+    unsafe {
+        let handle = CreateNamedPipeW(
+            path,
+            PIPE_ACCESS_DUPLEX | FILE_FLAG_FIRST_PIPE_INSTANCE,
+            PIPE_TYPE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+            1,
+            BUFFER_SIZE,
+            BUFFER_SIZE,
+            0,
+            None,
+        );
+        if handle.is_invalid() {
+            return Err(windows::core::Error::from_win32());
+        }
+        Ok(File::from_raw_handle(handle.0))
+    }
+}
+
+/// Wait for a client, then handle its commands until it closes the pipe.
+fn accept(pipe: &File, sender: &mpsc::Sender<Event>) -> anyhow::Result<()> {
+    let handle = HANDLE(pipe.as_raw_handle());
+    // SAFETY: the handle is a pipe instance owned by the file, and waited synchronously.
+    match unsafe { ConnectNamedPipe(handle, None) } {
+        // connected before waiting, not an error
+        Err(e) if e.code() != ERROR_PIPE_CONNECTED.to_hresult() => return Err(e.into()),
+        _ => {}
+    }
+    let mut writer = pipe;
+    for line in BufReader::new(pipe).lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let reply = match line.parse() {
+            Ok(command) => {
+                let (tx, rx) = mpsc::channel();
+                sender.send(Event::Pipe(Request { command, reply: tx }))?;
+                // the request is dropped without reply if the TUI quits meanwhile
+                rx.recv().unwrap_or_else(|_| "error: ery quit".to_string())
+            }
+            Err(e) => format!("error: {e:#}"),
+        };
+        writeln!(writer, "{reply}")?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::OpenOptions, io::ErrorKind, time::Duration};
+
+    use super::*;
+
+    /// Connect to the pipe, waiting for it while it is busy with the client before.
+    fn connect(name: &str) -> File {
+        for _ in 0..100 {
+            match OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(format!(r"\\.\pipe\{name}"))
+            {
+                Ok(file) => return file,
+                Err(e) if e.kind() == ErrorKind::NotFound => panic!("no pipe {name}"),
+                Err(_) => thread::sleep(Duration::from_millis(10)),
+            }
+        }
+        panic!("the pipe {name} is busy")
+    }
+
+    fn send(name: &str, line: &str) -> String {
+        let mut pipe = connect(name);
+        writeln!(pipe, "{line}").unwrap();
+        let mut reply = String::new();
+        BufReader::new(pipe).read_line(&mut reply).unwrap();
+        reply.trim_end().to_string()
+    }
+
+    #[test]
+    fn clients_are_served_one_after_another() {
+        let name = format!("ery-test-{}", std::process::id());
+        let (sender, receiver) = mpsc::channel();
+        serve(&name, sender).unwrap();
+        thread::spawn(move || {
+            for event in receiver {
+                if let Event::Pipe(request) = event {
+                    request.reply(format!("{:?}", request.command));
+                }
+            }
+        });
+        assert_eq!(send(&name, "search report"), r#"Search("report")"#);
+        assert_eq!(send(&name, "get-selection"), "GetSelection");
+        assert_eq!(send(&name, "open"), r#"error: unknown command "open""#);
+    }
+}
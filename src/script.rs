@@ -0,0 +1,232 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use rhai::{Dynamic, Engine, FuncArgs, Map, Scope, AST};
+
+use crate::app::ery::{QueryEntry, QueryResults};
+use crate::config::Config;
+use crate::format;
+
+/// The scripts are checked for changes at most once in the interval.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Stop the runaway scripts, e.g. an endless loop, instead of hanging the TUI.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// User scripts in [Rhai](https://rhai.rs), `*.rhai` in the `scripts` folder of the config
+/// directory, run in the order of the file names. They can define the hooks:
+///
+/// - `transform_query(text)` returns the search text sent to Everything instead.
+/// - `on_result(entry)` returns a short note shown after the name of the result, or `()`.
+///   The entry is a map of `name`, `path`, `full_path`, `ext`, `size`, `date_modified`
+///   (FILETIME), `is_folder` and `is_file`.
+/// - `on_open(path)` returns `false` to veto opening it.
+///
+/// The scripts are reloaded when they are changed, added or removed. The hooks never fail
+/// the TUI, the errors are kept for showing, see [`Scripts::take_error`].
+#[derive(Debug)]
+pub struct Scripts {
+    dir: PathBuf,
+    engine: Engine,
+    state: RefCell<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    scripts: Vec<AST>,
+    /// the script files and their modified times, for finding out the changes.
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+    checked: Instant,
+    /// notes of the results by the results id and the entry index.
+    notes: HashMap<(u64, u32), Option<String>>,
+    error: Option<String>,
+}
+
+impl Scripts {
+    /// Load the scripts, none if there is no scripts folder.
+    pub fn load() -> Option<Self> {
+        let dir = Config::dir()?.join("scripts");
+        if !dir.is_dir() {
+            return None;
+        }
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        let scripts = Self {
+            dir,
+            engine,
+            state: RefCell::new(State {
+                scripts: vec![],
+                files: vec![],
+                checked: Instant::now(),
+                notes: HashMap::new(),
+                error: None,
+            }),
+        };
+        scripts.reload();
+        Some(scripts)
+    }
+
+    /// The last error of the scripts, if any since the last call.
+    pub fn take_error(&self) -> Option<String> {
+        self.state.borrow_mut().error.take()
+    }
+
+    pub fn transform_query(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for result in self.call("transform_query", || (text.clone(),)) {
+            match result.into_string() {
+                Ok(transformed) => text = transformed,
+                Err(type_name) => self.set_error(format!(
+                    "transform_query returned {type_name} instead of a string"
+                )),
+            }
+        }
+        text
+    }
+
+    /// The notes of the entry by `on_result`, joined by spaces.
+    pub fn on_result(&self, results: &QueryResults, entry: &QueryEntry) -> Option<String> {
+        let key = (results.id, entry.index);
+        if let Some(note) = self.state.borrow().notes.get(&key) {
+            return note.clone();
+        }
+        let notes: Vec<String> = self
+            .call("on_result", || (entry_map(results, entry),))
+            .into_iter()
+            .filter(|note| !note.is_unit())
+            .map(|note| note.to_string())
+            .collect();
+        let note = (!notes.is_empty()).then(|| notes.join(" "));
+        let mut state = self.state.borrow_mut();
+        // the notes of the old results are useless now
+        if state
+            .notes
+            .keys()
+            .next()
+            .is_some_and(|(id, _)| *id != key.0)
+        {
+            state.notes.clear();
+        }
+        state.notes.insert(key, note.clone());
+        note
+    }
+
+    /// Whether opening the path is allowed, any `false` of `on_open` vetoes it.
+    pub fn on_open(&self, path: &Path) -> bool {
+        let path = format::os_str(path.as_os_str()).into_owned();
+        self.call("on_open", || (path.clone(),))
+            .into_iter()
+            .all(|allowed| allowed.as_bool().unwrap_or(true))
+    }
+
+    /// Call the hook in every script defining it, and collect the results.
+    fn call<A: FuncArgs>(&self, hook: &str, args: impl Fn() -> A) -> Vec<Dynamic> {
+        self.reload_if_changed();
+        let state = self.state.borrow();
+        let mut results = vec![];
+        let mut errors = vec![];
+        for ast in &state.scripts {
+            if !ast.iter_functions().any(|f| f.name == hook) {
+                continue;
+            }
+            match self
+                .engine
+                .call_fn::<Dynamic>(&mut Scope::new(), ast, hook, args())
+            {
+                Ok(result) => results.push(result),
+                Err(e) => errors.push(format!("{hook}: {e}")),
+            }
+        }
+        drop(state);
+        if let Some(e) = errors.pop() {
+            self.set_error(e);
+        }
+        results
+    }
+
+    fn set_error(&self, e: String) {
+        self.state.borrow_mut().error = Some(e);
+    }
+
+    fn reload_if_changed(&self) {
+        if self.state.borrow().checked.elapsed() < RELOAD_INTERVAL {
+            return;
+        }
+        self.state.borrow_mut().checked = Instant::now();
+        if self.script_files() != self.state.borrow().files {
+            self.reload();
+        }
+    }
+
+    /// Compile the scripts again, the broken ones are skipped with the error kept.
+    fn reload(&self) {
+        let files = self.script_files();
+        let mut scripts = vec![];
+        let mut error = None;
+        for (path, _) in &files {
+            match self.engine.compile_file(path.clone()) {
+                Ok(ast) => scripts.push(ast),
+                Err(e) => error = Some(format!("{}: {e}", path.display())),
+            }
+        }
+        let mut state = self.state.borrow_mut();
+        state.scripts = scripts;
+        state.files = files;
+        state.notes.clear();
+        state.error = error.or(state.error.take());
+    }
+
+    fn script_files(&self) -> Vec<(PathBuf, Option<SystemTime>)> {
+        let Ok(dir) = fs::read_dir(&self.dir) else {
+            return vec![];
+        };
+        let mut files: Vec<_> = dir
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rhai"))
+            .map(|path| {
+                let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+                (path, modified)
+            })
+            .collect();
+        files.sort();
+        files
+    }
+}
+
+fn entry_map(results: &QueryResults, entry: &QueryEntry) -> Map {
+    let text = |s: Option<&std::ffi::OsStr>| {
+        s.map_or(Dynamic::UNIT, |s| format::os_str(s).into_owned().into())
+    };
+    let number = |n: Option<u64>| n.map_or(Dynamic::UNIT, |n| (n as i64).into());
+    let full_path = results.filepath(entry);
+    let mut map = Map::new();
+    map.insert("name".into(), text(entry.filename.as_deref()));
+    map.insert(
+        "path".into(),
+        text(results.path(entry).map(Path::as_os_str)),
+    );
+    map.insert(
+        "full_path".into(),
+        text(full_path.as_deref().map(Path::as_os_str)),
+    );
+    map.insert(
+        "ext".into(),
+        text(
+            entry
+                .extension
+                .as_deref()
+                .or_else(|| Path::new(entry.filename.as_deref()?).extension()),
+        ),
+    );
+    map.insert("size".into(), number(entry.size));
+    map.insert("date_modified".into(), number(entry.date_modified));
+    map.insert("is_folder".into(), entry.is_folder.into());
+    map.insert("is_file".into(), entry.is_file.into());
+    map
+}
@@ -0,0 +1,309 @@
+use std::io::{self, BufRead, Write};
+
+use anyhow::Context;
+use everything_sdk::RequestFlags;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::app::{
+    self,
+    ery::{Query, QueryEntry, QueryResults},
+    excludes::{self, Excludes},
+    sort::{default_sort, SortKey},
+};
+use crate::config::{Config, DefaultSort};
+use crate::format;
+
+/// The version of MCP spoken, with the tools over the stdio transport only.
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// The results replied at most by default, a few screens of them, not to flood the clients.
+const DEFAULT_MAX: u32 = 100;
+
+/// Error codes of JSON-RPC 2.0.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// The first of the codes reserved for the errors of the server, e.g. Everything not running.
+const SERVER_ERROR: i64 = -32000;
+
+/// The params of `search` and `count`, the options of the query as the search bar has.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SearchParams {
+    query: String,
+    max: u32,
+    offset: u32,
+    sort: DefaultSort,
+    match_path: bool,
+    match_case: bool,
+    match_whole_word: bool,
+    regex: bool,
+}
+
+impl Default for SearchParams {
+    fn default() -> Self {
+        Self {
+            query: String::new(),
+            max: DEFAULT_MAX,
+            offset: 0,
+            sort: DefaultSort::default(),
+            match_path: false,
+            match_case: false,
+            match_whole_word: false,
+            regex: false,
+        }
+    }
+}
+
+impl SearchParams {
+    /// The query of the params, the search with the clauses of the excludes appended.
+    fn to_query(&self, request_flags: RequestFlags, excludes: &str) -> Query {
+        let (sort_type, secondary_sort) = default_sort(self.sort);
+        Query {
+            search: excludes::append(&self.query, excludes),
+            match_path: self.match_path,
+            match_case: self.match_case,
+            match_whole_word: self.match_whole_word,
+            regex: self.regex,
+            max: self.max,
+            offset: self.offset,
+            sort_type,
+            request_flags: request_flags
+                | secondary_sort.map_or(RequestFlags::empty(), SortKey::request_flags),
+            secondary_sort,
+            ..Default::default()
+        }
+    }
+}
+
+/// Answer the JSON-RPC 2.0 requests on stdin, one message per line, until it is closed.
+///
+/// The methods `search` and `count` query Everything, and the ones of MCP (`initialize`,
+/// `tools/list` and `tools/call`) offer the same as tools, for the AI assistants. The excludes
+/// are applied as in the TUI.
+pub fn run(config: &Config) -> anyhow::Result<()> {
+    let excludes = Excludes::load()?.clauses_with(config);
+    let mut stdout = io::stdout().lock();
+    for line in io::stdin().lock().lines() {
+        let line = line.context("failed to read the request")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = match serde_json::from_str(&line) {
+            Ok(message) => handle(message, &excludes),
+            Err(e) => Some(error(Value::Null, PARSE_ERROR, e)),
+        };
+        // nothing replied to the notifications
+        if let Some(reply) = reply {
+            serde_json::to_writer(&mut stdout, &reply)?;
+            writeln!(stdout)?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// The reply of the request, or none for a notification, which has no id.
+fn handle(mut message: Value, excludes: &str) -> Option<Value> {
+    // e.g. a batch, which is not supported
+    if !message.is_object() {
+        return Some(error(
+            Value::Null,
+            INVALID_REQUEST,
+            "the request is not an object",
+        ));
+    }
+    let id = message.get("id")?.clone();
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        return Some(error(id, INVALID_REQUEST, "the method is missing"));
+    };
+    let method = method.to_string();
+    let params = message
+        .get_mut("params")
+        .map(Value::take)
+        .unwrap_or_default();
+    let result = match method.as_str() {
+        "initialize" => Ok(json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "ery", "version": env!("CARGO_PKG_VERSION") },
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools() })),
+        "tools/call" => call_tool(params, excludes),
+        "search" => search(params, excludes),
+        "count" => count(params, excludes),
+        _ => {
+            let message = format!("unknown method {method:?}");
+            return Some(error(id, METHOD_NOT_FOUND, message));
+        }
+    };
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(e) if e.downcast_ref::<serde_json::Error>().is_some() => {
+            error(id, INVALID_PARAMS, format!("{e:#}"))
+        }
+        Err(e) => error(id, SERVER_ERROR, format!("{e:#}")),
+    })
+}
+
+fn error(id: Value, code: i64, message: impl ToString) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message.to_string() },
+    })
+}
+
+/// The params of the method, all of them optional if there are none.
+fn parse_params<T: DeserializeOwned>(params: Value) -> anyhow::Result<T> {
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).context("invalid params")
+}
+
+/// The tools of MCP, the same as the methods, described for the AI assistants.
+fn tools() -> Value {
+    let properties = json!({
+        "query": {
+            "type": "string",
+            "description": "search text of Everything, e.g. `*.rs dm:thisweek` or `ext:pdf invoice`",
+        },
+        "max": { "type": "integer", "description": "results at most, 100 by default" },
+        "offset": { "type": "integer", "description": "results skipped from the first" },
+        "sort": {
+            "type": "string",
+            "enum": [
+                "name", "path", "size", "date-modified", "recently-changed", "extension",
+                "relevance",
+            ],
+        },
+        "match_path": { "type": "boolean", "description": "match the full paths, not the names" },
+        "match_case": { "type": "boolean" },
+        "match_whole_word": { "type": "boolean" },
+        "regex": { "type": "boolean", "description": "the query is a regular expression" },
+    });
+    json!([
+        {
+            "name": "search",
+            "description": "Search the files and folders of the local disks by name with Everything, \
+                            replying the full paths, the sizes and the dates modified.",
+            "inputSchema": { "type": "object", "properties": properties, "required": ["query"] },
+        },
+        {
+            "name": "count",
+            "description": "Count the files and folders matching the search of Everything.",
+            "inputSchema": { "type": "object", "properties": properties, "required": ["query"] },
+        },
+    ])
+}
+
+/// Run the tool of MCP, replying the result as JSON text, or the error of it as the result too,
+/// so the AI assistant sees it.
+fn call_tool(params: Value, excludes: &str) -> anyhow::Result<Value> {
+    #[derive(Deserialize)]
+    struct Call {
+        name: String,
+        #[serde(default)]
+        arguments: Value,
+    }
+    let call: Call = parse_params(params)?;
+    let result = match call.name.as_str() {
+        "search" => search(call.arguments, excludes),
+        "count" => count(call.arguments, excludes),
+        name => anyhow::bail!("unknown tool {name:?}"),
+    };
+    Ok(match result {
+        Ok(result) => json!({
+            "content": [{ "type": "text", "text": serde_json::to_string_pretty(&result)? }],
+        }),
+        Err(e) => json!({
+            "content": [{ "type": "text", "text": format!("{e:#}") }],
+            "isError": true,
+        }),
+    })
+}
+
+fn search(params: Value, excludes: &str) -> anyhow::Result<Value> {
+    let params: SearchParams = parse_params(params)?;
+    let flags = RequestFlags::EVERYTHING_REQUEST_FILE_NAME
+        | RequestFlags::EVERYTHING_REQUEST_PATH
+        | RequestFlags::EVERYTHING_REQUEST_SIZE
+        | RequestFlags::EVERYTHING_REQUEST_DATE_MODIFIED;
+    let results = app::query(&params.to_query(flags, excludes))?;
+    let items: Vec<Value> = results
+        .entrys
+        .iter()
+        .map(|entry| item(&results, entry))
+        .collect();
+    Ok(json!({ "total": results.total, "offset": results.offset, "results": items }))
+}
+
+/// The result as JSON, the path not of UTF-8 converted lossily and marked as
+/// [`format::os_str`] does, as JSON strings can not have it.
+fn item(results: &QueryResults, entry: &QueryEntry) -> Value {
+    let path = results
+        .filepath(entry)
+        .map(|path| format::os_str(path.as_os_str()).into_owned());
+    json!({
+        "path": path,
+        "is_folder": entry.is_folder,
+        "size": entry.size,
+        "date_modified": entry
+            .date_modified
+            .and_then(format::filetime_to_local)
+            .map(|date| date.to_rfc3339()),
+    })
+}
+
+fn count(params: Value, excludes: &str) -> anyhow::Result<Value> {
+    let params: SearchParams = parse_params(params)?;
+    let query = Query {
+        max: 0,
+        ..params.to_query(RequestFlags::EVERYTHING_REQUEST_FILE_NAME, excludes)
+    };
+    Ok(json!({ "total": app::query(&query)?.total }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+    use std::os::windows::ffi::OsStringExt;
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn requests_not_objects_are_invalid() {
+        for message in [
+            json!([{ "jsonrpc": "2.0", "id": 1, "method": "ping" }]),
+            json!(1),
+        ] {
+            let reply = handle(message, "").unwrap();
+            assert_eq!(reply["id"], Value::Null);
+            assert_eq!(reply["error"]["code"], INVALID_REQUEST);
+        }
+        // a notification
+        assert_eq!(
+            handle(json!({ "jsonrpc": "2.0", "method": "ping" }), ""),
+            None
+        );
+    }
+
+    #[test]
+    fn paths_not_of_utf8_are_replied() {
+        let mut results = QueryResults::default();
+        // an unpaired surrogate, which NTFS allows in the names
+        let folder = PathBuf::from(OsString::from_wide(&[b'C' as u16, b':' as u16, 0xD800]));
+        let entry = QueryEntry {
+            filename: Some("a.txt".into()),
+            path: Some(results.paths.intern(folder)),
+            ..app::client::mock::entry(0)
+        };
+        let path = item(&results, &entry)["path"].as_str().unwrap().to_string();
+        assert!(path.starts_with("C:\u{FFFD}"), "{path}");
+        assert!(path.contains("a.txt"), "{path}");
+    }
+}
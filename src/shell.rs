@@ -0,0 +1,550 @@
+use std::{
+    env,
+    ffi::{OsStr, OsString},
+    fs, mem,
+    os::windows::ffi::{OsStrExt, OsStringExt},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    ptr,
+};
+
+use anyhow::Context;
+use windows::{
+    core::{Interface, HSTRING, PCSTR, PCWSTR},
+    Win32::{
+        Foundation::{HWND, POINT},
+        System::{
+            Com::{
+                CoCreateInstance, CoInitializeEx, CoUninitialize, IPersistFile,
+                CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED, COINIT_DISABLE_OLE1DDE, STGM_READ,
+            },
+            Console::GetConsoleWindow,
+        },
+        UI::{
+            Shell::{
+                Common::ITEMIDLIST, IContextMenu, ILFree, IShellFolder, IShellLinkW,
+                SHBindToParent, SHFileOperationW, SHObjectProperties, SHParseDisplayName,
+                ShellLink, CMF_NORMAL, CMINVOKECOMMANDINFO, FOF_ALLOWUNDO, FOF_NOCONFIRMATION,
+                FOF_SILENT, FOF_WANTNUKEWARNING, FO_DELETE, SHFILEOPSTRUCTW, SHOP_FILEPATH,
+            },
+            WindowsAndMessaging::{
+                CreatePopupMenu, DestroyMenu, GetCursorPos, SetForegroundWindow, TrackPopupMenuEx,
+                SW_SHOWNORMAL, TPM_RETURNCMD, TPM_RIGHTBUTTON,
+            },
+        },
+    },
+};
+
+use crate::config::ActionsConfig;
+
+/// Open the file or folder, by the `open` command in config, or the file manager in config for
+/// folders, or explorer by default.
+pub fn open(path: &Path, actions: &ActionsConfig) -> anyhow::Result<()> {
+    match (&actions.open, &actions.file_manager) {
+        (Some(open), _) => run_template(open, path),
+        (None, Some(file_manager)) if path.is_dir() => run_template(file_manager, path),
+        _ => explorer(path, false),
+    }
+}
+
+/// Open the file at the line and the column, by the `goto` command in config with the
+/// placeholders `{line}` and `{col}`, or open it as usual if not set.
+pub fn goto(path: &Path, line: u32, col: u32, actions: &ActionsConfig) -> anyhow::Result<()> {
+    match &actions.goto {
+        Some(goto) if path.is_file() => {
+            let (line, col) = (
+                OsString::from(line.to_string()),
+                OsString::from(col.to_string()),
+            );
+            let vars = [("{line}", line.as_os_str()), ("{col}", col.as_os_str())];
+            template_command_with(goto, path, &vars)?
+                .spawn()
+                .with_context(|| format!("failed to run {goto:?}"))?;
+            Ok(())
+        }
+        _ => open(path, actions),
+    }
+}
+
+/// Open the folder by the file manager in config, or explorer by default.
+pub fn open_folder(path: &Path, actions: &ActionsConfig) -> anyhow::Result<()> {
+    match &actions.file_manager {
+        Some(file_manager) => run_template(file_manager, path),
+        None => explorer(path, false),
+    }
+}
+
+/// Open the folder containing the file and select it, by the `reveal` command in config, or the
+/// file manager in config, or explorer by default.
+pub fn reveal(path: &Path, actions: &ActionsConfig) -> anyhow::Result<()> {
+    match (&actions.reveal, &actions.file_manager) {
+        (Some(reveal), _) | (None, Some(reveal)) => run_template(reveal, path),
+        _ => explorer(path, true),
+    }
+}
+
+/// Open the file or folder by explorer, or open its folder and select it if `reveal`.
+pub fn explorer(path: &Path, reveal: bool) -> anyhow::Result<()> {
+    let mut cmd = Command::new("explorer");
+    if reveal && path.is_file() {
+        // Ref: https://stackoverflow.com/a/13625225
+        cmd.arg(OsStr::new("/select,"));
+    }
+    cmd.arg(path.as_os_str());
+    cmd.spawn()
+        .context("explorer command failed to start")?
+        .wait()
+        .context("failed to wait explorer")?;
+    Ok(())
+}
+
+/// Show the Properties dialog of the file or folder, the same as `Alt+Enter` in explorer.
+///
+/// The dialog is modeless and runs in its own thread of ery, so it is closed when ery exits.
+pub fn properties(path: &Path) -> anyhow::Result<()> {
+    let _com = Com::init()?;
+    // SAFETY: the path is a null-terminated wide string living across the call.
+    let shown = unsafe {
+        SHObjectProperties(
+            GetConsoleWindow(),
+            SHOP_FILEPATH,
+            &HSTRING::from(path),
+            PCWSTR::null(),
+        )
+    };
+    anyhow::ensure!(
+        shown.as_bool(),
+        "failed to show the properties of {}",
+        path.display()
+    );
+    Ok(())
+}
+
+/// Move the files or folders to the Recycle Bin, without asking but warning if one can not
+/// be recycled, e.g. too large or on a network drive, and would be deleted for good.
+pub fn recycle(paths: &[PathBuf]) -> anyhow::Result<()> {
+    // the paths ended by nulls, then one more null for the end of the list
+    let mut from: Vec<u16> = vec![];
+    for path in paths {
+        from.extend(path.as_os_str().encode_wide());
+        from.push(0);
+    }
+    from.push(0);
+    let mut operation = SHFILEOPSTRUCTW {
+        // SAFETY: no arguments, it is null if there is no console.
+        hwnd: unsafe { GetConsoleWindow() },
+        wFunc: FO_DELETE,
+        pFrom: PCWSTR(from.as_ptr()),
+        fFlags: (FOF_ALLOWUNDO | FOF_NOCONFIRMATION | FOF_SILENT | FOF_WANTNUKEWARNING).0 as u16,
+        ..Default::default()
+    };
+    // SAFETY: the list of the paths is double-null terminated and lives across the call.
+    let code = unsafe { SHFileOperationW(&mut operation) };
+    anyhow::ensure!(code == 0, "failed to recycle, error code {code:#x}");
+    anyhow::ensure!(
+        !operation.fAnyOperationsAborted.as_bool(),
+        "recycling is canceled"
+    );
+    Ok(())
+}
+
+/// Put the file or folder back from the Recycle Bin to where it was, the last one recycled from
+/// there if there are several. The bin of the volume keeps each one as a `$R` file, with a `$I`
+/// file of the same suffix holding its original path.
+pub fn restore(path: &Path) -> anyhow::Result<()> {
+    anyhow::ensure!(!path.exists(), "{} already exists", path.display());
+    let Some(root) = path.ancestors().last() else {
+        anyhow::bail!("no volume of {}", path.display());
+    };
+    let wanted = path.to_string_lossy().to_lowercase();
+    let mut last: Option<(u64, PathBuf)> = None;
+    // a folder for each user, the ones of the others are denied
+    let bins = fs::read_dir(root.join("$Recycle.Bin"))
+        .with_context(|| format!("failed to read the Recycle Bin of {}", root.display()))?;
+    for bin in bins.flatten() {
+        let Ok(entries) = fs::read_dir(bin.path()) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let info = entry.path();
+            let is_info = entry.file_name().to_string_lossy().starts_with("$I");
+            let Some((original, time)) = is_info
+                .then(|| fs::read(&info).ok())
+                .flatten()
+                .and_then(|data| parse_recycle_info(&data))
+            else {
+                continue;
+            };
+            if original.to_string_lossy().to_lowercase() == wanted
+                && last.as_ref().is_none_or(|(last_time, _)| time > *last_time)
+            {
+                last = Some((time, info));
+            }
+        }
+    }
+    let Some((_, info)) = last else {
+        anyhow::bail!("{} is not in the Recycle Bin", path.display());
+    };
+    let name = info.file_name().unwrap_or_default().to_string_lossy();
+    let recycled = info.with_file_name(format!("$R{}", &name[2..]));
+    fs::rename(&recycled, path).with_context(|| format!("failed to restore {}", path.display()))?;
+    fs::remove_file(&info).with_context(|| format!("failed to remove {}", info.display()))
+}
+
+/// The original path and the FILETIME of recycling in a `$I` file: the version, the size and
+/// the time in 8 bytes each, then the path in UTF-16 ended by a null, in 520 bytes for the
+/// version 1, or after its length in chars for the version 2.
+fn parse_recycle_info(data: &[u8]) -> Option<(PathBuf, u64)> {
+    let u64_at = |i: usize| Some(u64::from_le_bytes(data.get(i..i + 8)?.try_into().ok()?));
+    let time = u64_at(16)?;
+    let bytes = match u64_at(0)? {
+        1 => data.get(24..24 + 520)?,
+        2 => {
+            let len = u32::from_le_bytes(data.get(24..28)?.try_into().ok()?) as usize;
+            data.get(28..28 + len * 2)?
+        }
+        _ => return None,
+    };
+    let wide: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|&c| c != 0)
+        .collect();
+    Some((PathBuf::from(OsString::from_wide(&wide)), time))
+}
+
+/// Show the shell context menu (`IContextMenu`) of the file or folder at the mouse cursor, and
+/// run the chosen command. It blocks until the menu is closed.
+pub fn context_menu(path: &Path) -> anyhow::Result<()> {
+    let _com = Com::init()?;
+    let mut pidl = ptr::null_mut();
+    // SAFETY: the pidl is allocated by the shell and freed by `ILFree` after use.
+    unsafe {
+        SHParseDisplayName(&HSTRING::from(path), None, &mut pidl, 0, None)
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        let result = track_context_menu(GetConsoleWindow(), pidl);
+        ILFree(Some(pidl));
+        result
+    }
+}
+
+unsafe fn track_context_menu(hwnd: HWND, pidl: *const ITEMIDLIST) -> anyhow::Result<()> {
+    // the range of the command ids in the menu, 0 is for nothing chosen
+    const FIRST_ID: u32 = 1;
+    const LAST_ID: u32 = 0x7FFF;
+
+    let mut child = ptr::null_mut();
+    let folder: IShellFolder = SHBindToParent(pidl, Some(&mut child))?;
+    let menu: IContextMenu = folder.GetUIObjectOf(hwnd, &[child.cast_const()], None)?;
+    let popup = CreatePopupMenu()?;
+    let chosen = menu
+        .QueryContextMenu(popup, 0, FIRST_ID, LAST_ID, CMF_NORMAL)
+        .map(|()| {
+            let mut cursor = POINT::default();
+            let _ = GetCursorPos(&mut cursor);
+            // or the menu is not dismissed by clicking outside of it
+            let _ = SetForegroundWindow(hwnd);
+            let flags = TPM_RETURNCMD | TPM_RIGHTBUTTON;
+            TrackPopupMenuEx(popup, flags.0, cursor.x, cursor.y, hwnd, None).0
+        });
+    let _ = DestroyMenu(popup);
+    let Ok(offset) = u32::try_from(chosen?) else {
+        return Ok(());
+    };
+    let Some(offset) = offset.checked_sub(FIRST_ID) else {
+        // canceled
+        return Ok(());
+    };
+    let info = CMINVOKECOMMANDINFO {
+        cbSize: mem::size_of::<CMINVOKECOMMANDINFO>() as u32,
+        hwnd,
+        // the offset of the command as the verb, i.e. `MAKEINTRESOURCEA(offset)`
+        lpVerb: PCSTR(offset as usize as *const u8),
+        nShow: SW_SHOWNORMAL.0,
+        ..Default::default()
+    };
+    menu.InvokeCommand(&info)
+        .context("failed to run the command of the context menu")?;
+    Ok(())
+}
+
+/// What a shortcut (`.lnk`) runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shortcut {
+    pub target: PathBuf,
+    pub arguments: String,
+    /// the "Start in" folder, none if not set.
+    pub working_dir: Option<PathBuf>,
+}
+
+/// Read the target of the shortcut by the shell link API (`IShellLinkW`), without resolving
+/// the moved targets, which may search the disks for a long while.
+pub fn read_shortcut(path: &Path) -> anyhow::Result<Shortcut> {
+    let _com = Com::init()?;
+    // SAFETY: the buffers outlive the calls, which write at most their lengths.
+    unsafe {
+        let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
+        link.cast::<IPersistFile>()?
+            .Load(&HSTRING::from(path), STGM_READ)
+            .with_context(|| format!("failed to read the shortcut {}", path.display()))?;
+        let mut buffer = [0u16; 1024];
+        link.GetPath(&mut buffer, ptr::null_mut(), 0)?;
+        let target = wide_string(&buffer);
+        // the shortcuts of the shell items, e.g. the Control Panel, have no path
+        anyhow::ensure!(
+            !target.is_empty(),
+            "the shortcut is not to a file or folder"
+        );
+        link.GetArguments(&mut buffer)?;
+        let arguments = wide_string(&buffer);
+        link.GetWorkingDirectory(&mut buffer)?;
+        let working_dir = wide_string(&buffer);
+        Ok(Shortcut {
+            target: PathBuf::from(target),
+            arguments,
+            working_dir: (!working_dir.is_empty()).then(|| PathBuf::from(working_dir)),
+        })
+    }
+}
+
+/// The string of the null-terminated buffer.
+fn wide_string(buffer: &[u16]) -> String {
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    String::from_utf16_lossy(&buffer[..len])
+}
+
+/// COM initialized for the current thread, uninitialized when dropped.
+struct Com;
+
+impl Com {
+    fn init() -> anyhow::Result<Self> {
+        // SAFETY: balanced by `CoUninitialize` in drop, if it succeeds.
+        unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED | COINIT_DISABLE_OLE1DDE) }
+            .ok()
+            .context("failed to initialize COM")?;
+        Ok(Self)
+    }
+}
+
+impl Drop for Com {
+    fn drop(&mut self) {
+        // SAFETY: COM is initialized by `Com::init`.
+        unsafe { CoUninitialize() };
+    }
+}
+
+/// Launch the Everything desktop app with the arguments, e.g. `-search <text>`, which are sent
+/// to the running one if any, without waiting for it.
+///
+/// Ref: https://www.voidtools.com/support/everything/command_line_options/
+pub fn everything_gui(args: &[OsString]) -> anyhow::Result<()> {
+    let exe = everything_exe();
+    Command::new(&exe)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("failed to start {}", exe.display()))?;
+    Ok(())
+}
+
+/// `Everything.exe` in the default install folders, or in `PATH`.
+pub fn everything_exe() -> PathBuf {
+    ["ProgramFiles", "ProgramFiles(x86)"]
+        .into_iter()
+        .filter_map(env::var_os)
+        .map(|dir| PathBuf::from(dir).join("Everything").join("Everything.exe"))
+        .find(|exe| exe.is_file())
+        .unwrap_or_else(|| PathBuf::from("Everything.exe"))
+}
+
+/// Run ery again as administrator with the arguments, by ShellExecute "runas" (the
+/// `Start-Process -Verb RunAs` of PowerShell), it runs in a new console window.
+pub fn relaunch_elevated(args: &[OsString]) -> anyhow::Result<()> {
+    let exe = env::current_exe().context("failed to get the path of ery")?;
+    // the script is a string, refuse the invalid unicode rather than change the paths
+    let exe = exe
+        .to_str()
+        .context("the path of ery is not valid unicode")?;
+    let mut script = format!(
+        "Start-Process -Verb RunAs -FilePath {}",
+        quote_powershell(exe)
+    );
+    if !args.is_empty() {
+        // Start-Process joins the arguments by spaces, so quote them for the command line
+        let args = args
+            .iter()
+            .map(|arg| {
+                let arg = arg
+                    .to_str()
+                    .with_context(|| format!("the argument {arg:?} is not valid unicode"))?;
+                Ok(quote_powershell(&quote_windows_arg(arg)))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        script.push_str(" -ArgumentList ");
+        script.push_str(&args.join(","));
+    }
+    let status = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command"])
+        .arg(script)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .context("failed to run powershell")?;
+    anyhow::ensure!(
+        status.success(),
+        "failed to run as administrator, canceled?"
+    );
+    Ok(())
+}
+
+/// Single-quoted string literal of PowerShell, nothing is expanded in it.
+pub fn quote_powershell(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+/// Quote the argument for the Windows command line, as `CommandLineToArgvW` parses it.
+///
+/// Ref: https://learn.microsoft.com/en-us/cpp/c-language/parsing-c-command-line-arguments
+fn quote_windows_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+        return arg.to_string();
+    }
+    let mut quoted = String::from('"');
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                // double the backslashes before a quote, and escape the quote
+                quoted.push_str(&"\\".repeat(backslashes * 2 + 1));
+                quoted.push('"');
+                backslashes = 0;
+            }
+            c => {
+                quoted.push_str(&"\\".repeat(backslashes));
+                quoted.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+    // the backslashes before the closing quote are doubled too
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+/// Run the command template for the path, without waiting for it.
+///
+/// The template is split into arguments by spaces, unless in double quotes, then the
+/// placeholders in each argument are replaced, so the paths with spaces are kept as one
+/// argument: `{path}` the full path, `{dir}` the folder containing it (or itself for folders),
+/// `{name}` the file name.
+pub fn run_template(template: &str, path: &Path) -> anyhow::Result<()> {
+    template_command(template, path)?
+        .spawn()
+        .with_context(|| format!("failed to run {template:?}"))?;
+    Ok(())
+}
+
+/// Run the command template for the path in this console, and wait for it to exit, for the
+/// console programs like the pagers and the editors in terminal.
+pub fn run_template_console(template: &str, path: &Path) -> anyhow::Result<()> {
+    let status = template_command(template, path)?
+        .status()
+        .with_context(|| format!("failed to run {template:?}"))?;
+    anyhow::ensure!(status.success(), "{template:?} exited with {status}");
+    Ok(())
+}
+
+fn template_command(template: &str, path: &Path) -> anyhow::Result<Command> {
+    template_command_with(template, path, &[])
+}
+
+/// The command of the template, with the placeholders of the path and the extra ones.
+fn template_command_with(
+    template: &str,
+    path: &Path,
+    vars: &[(&str, &OsStr)],
+) -> anyhow::Result<Command> {
+    let args = expand_template(template, path, vars);
+    let (program, args) = args
+        .split_first()
+        .with_context(|| format!("empty command {template:?}"))?;
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    Ok(cmd)
+}
+
+fn expand_template(template: &str, path: &Path, vars: &[(&str, &OsStr)]) -> Vec<OsString> {
+    let dir = if path.is_dir() {
+        path
+    } else {
+        path.parent().unwrap_or(path)
+    };
+    let name = path.file_name().unwrap_or(path.as_os_str());
+    split_args(template)
+        .into_iter()
+        .map(|arg| {
+            // paths may be not valid unicode, so replace them in OsString
+            let mut expanded = OsString::new();
+            let mut rest = arg.as_str();
+            while let Some(start) = rest.find('{') {
+                let (before, after) = rest.split_at(start);
+                expanded.push(before);
+                let placeholder = [
+                    ("{path}", path.as_os_str()),
+                    ("{dir}", dir.as_os_str()),
+                    ("{name}", name),
+                ]
+                .into_iter()
+                .chain(vars.iter().copied())
+                .find(|(key, _)| after.starts_with(key));
+                match placeholder {
+                    Some((key, value)) => {
+                        expanded.push(value);
+                        rest = &after[key.len()..];
+                    }
+                    None => {
+                        expanded.push("{");
+                        rest = &after[1..];
+                    }
+                }
+            }
+            expanded.push(rest);
+            expanded
+        })
+        .collect()
+}
+
+/// Split by the spaces out of double quotes, and the quotes are removed.
+fn split_args(template: &str) -> Vec<String> {
+    let mut args = vec![];
+    let mut arg = String::new();
+    let mut in_quotes = false;
+    let mut has_arg = false;
+    for c in template.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_arg = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_arg {
+                    args.push(std::mem::take(&mut arg));
+                    has_arg = false;
+                }
+            }
+            c => {
+                arg.push(c);
+                has_arg = true;
+            }
+        }
+    }
+    if has_arg {
+        args.push(arg);
+    }
+    args
+}
@@ -1,34 +1,161 @@
+mod action;
+mod commands;
+mod confirm;
+mod filter;
+pub mod graphics;
+pub mod icons;
+mod loader;
+mod prompt;
+mod rename;
+mod stats;
+mod templates;
+mod theme;
 mod ui;
+mod width;
+mod wizard;
 
+use self::action::Action;
+use self::confirm::{Confirm, ConfirmAction};
+use self::prompt::{Conflict, Prompt, PromptAction};
+use self::rename::BatchRename;
+use self::theme::Theme;
+use self::ui::Jump;
+
+use crate::app::clipboard;
+use crate::app::event::{AppEvent, AppSender};
+use crate::app::link::LinkKind;
+use crate::app::shortcut::Shortcuts;
+use crate::app::sort::{default_sort, sort_label, SortKey};
+use crate::app::transfer::{self, TransferKind};
 use crate::app::App;
+use crate::config::{Config, KeyProfile, PathMode};
+use crate::daemon;
+use crate::format;
+use crate::i18n::{self, Lang};
+use crate::notify;
+use crate::pipe;
+use crate::shell;
+use crate::tr;
 use crossterm::event::{
-    DisableMouseCapture, EnableMouseCapture, KeyCode, KeyEventKind, KeyModifiers, MouseButton,
+    DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+    EnableFocusChange, EnableMouseCapture, KeyCode, KeyEventKind, KeyModifiers, MouseButton,
     MouseEventKind,
 };
 use crossterm::event::{KeyEvent, MouseEvent};
 use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use everything_sdk::SortType;
 use ratatui::backend::Backend;
+use ratatui::widgets::ListState;
 use ratatui::Terminal;
-use std::ffi::{OsStr, OsString};
+use std::cmp::min;
+use std::ffi::OsString;
+use std::fs;
 use std::panic;
-use std::str::FromStr;
-use std::sync::mpsc;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
 use std::time::{Duration, Instant};
 use std::{io, thread};
+use tui_textarea::CursorMove;
 
 use crossterm::event::{self, Event as CrosstermEvent};
 
 use anyhow::Result;
 
+/// The polling and the drawing are this times slower while the terminal is not focused.
+const UNFOCUSED_SLOWDOWN: u32 = 4;
+
+/// Events handled at most at a time before drawing, so a flood of them does not hold the frame.
+const MAX_COALESCED_EVENTS: usize = 256;
+
 #[derive(Debug)]
 pub struct Tui<'a, B: Backend> {
     terminal: Terminal<B>,
     is_running: bool,
     pub sender: mpsc::Sender<Event>,
     receiver: mpsc::Receiver<Event>,
+    input: Arc<InputGate>,
+    focus: Arc<Focus>,
+    /// options of the command line, over the config.
+    overrides: Overrides,
+    /// popped up by the hotkey of `ery --daemon`.
+    is_summoned: bool,
     ui: ui::UI<'a>,
 }
 
+/// Options of the command line, over the config.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Overrides {
+    pub no_icons: bool,
+    pub accessible: bool,
+    pub lang: Option<Lang>,
+}
+
+/// Whether the terminal is focused, reported by the terminals supporting the focus events. The
+/// polling and the drawing slow down, and the queries again and the prefetching pause while not
+/// focused.
+#[derive(Debug)]
+struct Focus {
+    focused: AtomicBool,
+    /// a query again of watch mode or of the files changed is skipped while not focused, run it
+    /// when focused again.
+    requery_skipped: AtomicBool,
+}
+
+impl Default for Focus {
+    fn default() -> Self {
+        Self {
+            focused: AtomicBool::new(true),
+            requery_skipped: AtomicBool::new(false),
+        }
+    }
+}
+
+impl Focus {
+    fn is_focused(&self) -> bool {
+        self.focused.load(Ordering::Relaxed)
+    }
+
+    /// The interval of polling or drawing, longer while not focused.
+    fn interval(&self, interval: Duration) -> Duration {
+        if self.is_focused() {
+            interval
+        } else {
+            interval * UNFOCUSED_SLOWDOWN
+        }
+    }
+}
+
+/// Stops the thread reading the terminal events, while a console program reads the console.
+#[derive(Debug, Default)]
+struct InputGate {
+    paused: AtomicBool,
+    /// held by the thread while reading.
+    reading: Mutex<()>,
+}
+
+impl InputGate {
+    /// Pause the reading until the guard is dropped, after the current read is done.
+    fn pause(&self) -> InputPaused<'_> {
+        self.paused.store(true, Ordering::Release);
+        InputPaused {
+            gate: self,
+            _reading: self.reading.lock().unwrap(),
+        }
+    }
+}
+
+struct InputPaused<'a> {
+    gate: &'a InputGate,
+    _reading: MutexGuard<'a, ()>,
+}
+
+impl Drop for InputPaused<'_> {
+    fn drop(&mut self) {
+        self.gate.paused.store(false, Ordering::Release);
+    }
+}
+
 #[derive(Debug)]
 pub enum Event {
     /// App refresh request.
@@ -39,6 +166,39 @@ pub enum Event {
     Mouse(MouseEvent),
     /// Terminal resize.
     Resize(u16, u16),
+    /// Time to query again in watch mode.
+    Watch,
+    /// The config file is changed.
+    ConfigChanged,
+    /// Files are changed by ery, e.g. renamed or deleted, query again for the fresh results.
+    FilesChanged,
+    /// A task in the background is done, e.g. a move or copy.
+    TaskDone,
+    /// The terminal gains or loses the focus.
+    Focus(bool),
+    /// Text pasted into the terminal at once, or a phrase committed by an IME.
+    Paste(String),
+    /// A command from another process through the pipe of `ery --pipe`.
+    Pipe(pipe::Request),
+}
+
+impl From<AppEvent> for Event {
+    fn from(event: AppEvent) -> Self {
+        match event {
+            AppEvent::Refresh => Self::Refresh,
+            AppEvent::FilesChanged => Self::FilesChanged,
+            AppEvent::TaskDone => Self::TaskDone,
+        }
+    }
+}
+
+/// The events of the app go into the event loop of the TUI.
+impl From<mpsc::Sender<Event>> for AppSender {
+    fn from(sender: mpsc::Sender<Event>) -> Self {
+        Self::new(move |event| {
+            let _ = sender.send(event.into());
+        })
+    }
 }
 
 impl<B: Backend> Tui<'_, B> {
@@ -49,6 +209,10 @@ impl<B: Backend> Tui<'_, B> {
             is_running: false,
             sender: tx,
             receiver: rx,
+            input: Arc::default(),
+            focus: Arc::default(),
+            overrides: Overrides::default(),
+            is_summoned: false,
             ui: ui::UI::new(),
         }
     }
@@ -58,16 +222,45 @@ impl<B: Backend> Tui<'_, B> {
 
         self.term()?;
 
+        if let Some(watch) = &app.watch {
+            self.watch(watch.interval);
+        }
+        self.watch_config();
+
         self.is_running = true;
+        let mut drawn: Option<Instant> = None;
         while self.is_running() {
-            // Render the user interface.
-            self.draw(app)?;
-            // Handle events.
-            match self.receiver.recv()? {
-                Event::Refresh => self.handle_refresh_event(app)?,
-                Event::Key(key_event) => self.handle_key_events(key_event, app)?,
-                Event::Mouse(mouse_event) => self.handle_mouse_events(mouse_event, app)?,
-                Event::Resize(_, _) => {}
+            // Render the user interface, if changed and not drawn in this frame.
+            let frame = self
+                .focus
+                .interval(Duration::from_secs(1) / app.config.display.fps.max(1));
+            let next_frame = drawn.map_or_else(Instant::now, |drawn| drawn + frame);
+            if self.ui.dirty && Instant::now() >= next_frame {
+                self.draw(app)?;
+                self.ui.dirty = false;
+                drawn = Some(Instant::now());
+                continue;
+            }
+            // Wait for an event, or for the next frame to draw the pending changes.
+            let event = if self.ui.dirty {
+                match self
+                    .receiver
+                    .recv_timeout(next_frame.saturating_duration_since(Instant::now()))
+                {
+                    Ok(event) => event,
+                    Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                    Err(e) => return Err(e.into()),
+                }
+            } else {
+                self.receiver.recv()?
+            };
+            self.handle_event(event, app)?;
+            // Handle the burst of events together, e.g. fast scrolling, then draw once.
+            for _ in 1..MAX_COALESCED_EVENTS {
+                let Ok(event) = self.receiver.try_recv() else {
+                    break;
+                };
+                self.handle_event(event, app)?;
             }
         }
 
@@ -75,13 +268,58 @@ impl<B: Backend> Tui<'_, B> {
         Ok(())
     }
 
+    fn handle_event(&mut self, event: Event, app: &mut App) -> Result<()> {
+        self.ui.stats.record_event();
+        // nothing to draw for the mouse moving, the keys released or the focus
+        self.ui.dirty |= match &event {
+            Event::Key(key_event) => key_event.kind != KeyEventKind::Release,
+            Event::Mouse(mouse_event) => mouse_event.kind != MouseEventKind::Moved,
+            Event::Focus(_) => false,
+            _ => true,
+        };
+        match event {
+            Event::Refresh => self.handle_refresh_event(app)?,
+            Event::Key(key_event) => self.handle_key_events(key_event, app)?,
+            Event::Mouse(mouse_event) => self.handle_mouse_events(mouse_event, app)?,
+            Event::Resize(_, height) => self.handle_resize_event(height, app)?,
+            Event::Watch => app.requery()?,
+            Event::ConfigChanged => self.handle_config_changed(app)?,
+            Event::FilesChanged => self.requery_if_focused(app)?,
+            Event::TaskDone => self.handle_task_done(app),
+            Event::Focus(focused) => self.handle_focus_event(focused, app)?,
+            Event::Paste(text) => self.handle_paste_event(&text, app)?,
+            Event::Pipe(request) => self.handle_pipe_request(request, app)?,
+        }
+        self.ui.remember_selected(app);
+        // the next page is fetched once scrolled to, or focused again
+        if self.focus.is_focused() && self.ui.is_near_end(app) {
+            app.prefetch()?;
+        }
+        Ok(())
+    }
+
+    /// Query again for the fresh results, or later when focused again.
+    fn requery_if_focused(&mut self, app: &mut App) -> Result<()> {
+        if !self.focus.is_focused() {
+            self.focus.requery_skipped.store(true, Ordering::Relaxed);
+            return Ok(());
+        }
+        app.requery()
+    }
+
     /// Initializes the TUI.
     ///
     /// get ready for TUI, enable the raw mode and set terminal props.
     pub fn init(&mut self) -> Result<()> {
         terminal::enable_raw_mode()?;
         // Use stdout instead of stderr for refresh efficiency. (I don't know why stderr is slow)
-        crossterm::execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        crossterm::execute!(
+            io::stdout(),
+            EnterAlternateScreen,
+            EnableMouseCapture,
+            EnableFocusChange
+        )?;
+        Self::enable_bracketed_paste();
 
         // deal with panic
         let panic_hook = panic::take_hook();
@@ -100,26 +338,34 @@ impl<B: Backend> Tui<'_, B> {
     pub fn term(&mut self) -> Result<()> {
         const TICK_RATE: Duration = Duration::from_millis(250);
         let sender = self.sender.clone();
+        let input = Arc::clone(&self.input);
+        let focus = Arc::clone(&self.focus);
         thread::spawn(move || {
             let mut last_tick = Instant::now();
             loop {
-                let timeout = TICK_RATE
+                let tick_rate = focus.interval(TICK_RATE);
+                let timeout = tick_rate
                     .checked_sub(last_tick.elapsed())
-                    .unwrap_or(TICK_RATE);
+                    .unwrap_or(tick_rate);
 
+                if input.paused.load(Ordering::Acquire) {
+                    thread::sleep(TICK_RATE);
+                    continue;
+                }
+                let _reading = input.reading.lock().unwrap();
                 if event::poll(timeout).expect("failed to poll events") {
                     match event::read().expect("failed to read the event") {
-                        CrosstermEvent::FocusGained => Ok(()),
-                        CrosstermEvent::FocusLost => Ok(()),
+                        CrosstermEvent::FocusGained => sender.send(Event::Focus(true)),
+                        CrosstermEvent::FocusLost => sender.send(Event::Focus(false)),
                         CrosstermEvent::Key(e) => sender.send(Event::Key(e)),
                         CrosstermEvent::Mouse(e) => sender.send(Event::Mouse(e)),
-                        CrosstermEvent::Paste(_) => Ok(()),
+                        CrosstermEvent::Paste(text) => sender.send(Event::Paste(text)),
                         CrosstermEvent::Resize(w, h) => sender.send(Event::Resize(w, h)),
                     }
                     .expect("failed to send terminal event")
                 }
 
-                if last_tick.elapsed() >= TICK_RATE {
+                if last_tick.elapsed() >= tick_rate {
                     // it seems that we may not need the tick, just do nothing when user do nothing
                     // sender.send(Event::Tick).expect("failed to send tick event");
                     last_tick = Instant::now();
@@ -129,9 +375,62 @@ impl<B: Backend> Tui<'_, B> {
         Ok(())
     }
 
+    /// Send the watch event periodically, skipped while the terminal is not focused.
+    fn watch(&mut self, interval: Duration) {
+        let sender = self.sender.clone();
+        let focus = Arc::clone(&self.focus);
+        thread::spawn(move || loop {
+            thread::sleep(interval);
+            if !focus.is_focused() {
+                focus.requery_skipped.store(true, Ordering::Relaxed);
+                continue;
+            }
+            if sender.send(Event::Watch).is_err() {
+                break;
+            }
+        });
+    }
+
+    /// Catch up on the skipped query of watch mode or of the files changed when focused again,
+    /// or query again if `query.refresh_on_focus`.
+    fn handle_focus_event(&mut self, focused: bool, app: &mut App) -> Result<()> {
+        self.focus.focused.store(focused, Ordering::Relaxed);
+        if !focused {
+            return Ok(());
+        }
+        if self.is_summoned {
+            self.ui.is_focus_search_bar = true;
+            self.ui.is_focus_ext_sidebar = false;
+            self.ui.textarea.select_all();
+        }
+        let requery_skipped = self.focus.requery_skipped.swap(false, Ordering::Relaxed);
+        if (requery_skipped || app.config.query.refresh_on_focus) && !app.is_querying() {
+            app.requery()?;
+        }
+        Ok(())
+    }
+
     /// Render UI with app state.
     pub fn draw(&mut self, app: &mut App) -> Result<()> {
+        if let Some(e) = app
+            .scripts
+            .as_ref()
+            .and_then(|scripts| scripts.take_error())
+        {
+            self.ui.notice = Some(tr!(ScriptError, error = e));
+        }
+        // the preview shows the lazy fields of the selected one
+        if let Some(index) = self.ui.partial_selected(app) {
+            app.request_details(index)?;
+        }
+        let start = Instant::now();
         self.terminal.draw(|frame| self.ui.render(app, frame))?;
+        if self.ui.update_image(app)? {
+            self.terminal.clear()?;
+            self.terminal.draw(|frame| self.ui.render(app, frame))?;
+            self.ui.update_image(app)?;
+        }
+        self.ui.stats.record_frame(start.elapsed());
         Ok(())
     }
 
@@ -139,6 +438,11 @@ impl<B: Backend> Tui<'_, B> {
         self.is_running
     }
 
+    /// The backend of the terminal, e.g. for the buffer drawn on `TestBackend` in tests.
+    pub fn backend(&self) -> &B {
+        self.terminal.backend()
+    }
+
     fn quit(&mut self) {
         self.is_running = false;
     }
@@ -147,10 +451,42 @@ impl<B: Backend> Tui<'_, B> {
     fn reset() -> Result<()> {
         terminal::disable_raw_mode()?;
         // It's the same here for stdout.
-        crossterm::execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+        crossterm::execute!(
+            io::stdout(),
+            LeaveAlternateScreen,
+            DisableMouseCapture,
+            DisableFocusChange
+        )?;
+        // not supported by the legacy console of Windows, nothing to disable then
+        let _ = crossterm::execute!(io::stdout(), DisableBracketedPaste);
         Ok(())
     }
 
+    /// Get the pasted text at once, for the long texts and the phrases of the IMEs. The legacy
+    /// console of Windows does not support it, the text comes as the keys then.
+    fn enable_bracketed_paste() {
+        let _ = crossterm::execute!(io::stdout(), EnableBracketedPaste);
+    }
+
+    /// Leave the terminal to a console program, and take it back after the program exits.
+    fn suspend<T>(&mut self, f: impl FnOnce() -> T) -> Result<T> {
+        let input = Arc::clone(&self.input);
+        let _paused = input.pause();
+        self.exit()?;
+        let result = f();
+        terminal::enable_raw_mode()?;
+        crossterm::execute!(
+            io::stdout(),
+            EnterAlternateScreen,
+            EnableMouseCapture,
+            EnableFocusChange
+        )?;
+        Self::enable_bracketed_paste();
+        self.terminal.hide_cursor()?;
+        self.terminal.clear()?;
+        Ok(result)
+    }
+
     /// Exits the TUI.
     ///
     /// cleanup for TUI, disable the raw mode and set terminal props.
@@ -160,18 +496,228 @@ impl<B: Backend> Tui<'_, B> {
         Ok(())
     }
 
+    /// Load the results by the pages of the terminal height, before the first query.
+    pub fn set_page_height(&mut self, app: &mut App) -> Result<()> {
+        let height = self.terminal.size()?.height;
+        self.ui.resize(height, app);
+        app.set_page_height(self.ui.last_page_height.unwrap_or(1))
+    }
+
     pub fn set_search_text(&mut self, text: &str) {
         self.ui.set_search_text(text);
     }
 
-    pub fn handle_refresh_event(&mut self, _app: &mut App) -> Result<()> {
+    pub fn set_icons(&mut self, icons: icons::Icons) {
+        self.ui.icons = icons;
+    }
+
+    /// Apply the config to the TUI, or nothing if any of it is invalid.
+    ///
+    /// The overrides by the command line are kept for the reloaded config too.
+    pub fn set_config(&mut self, config: &Config, overrides: Overrides) -> Result<()> {
+        let commands = commands::load(&config.commands)?;
+        let templates = templates::load(&config.templates);
+        let accessible = overrides.accessible || config.display.accessible;
+        let theme = if accessible {
+            Theme::HIGH_CONTRAST
+        } else {
+            Theme::new(&config.theme)?
+        };
+        i18n::set_lang(
+            overrides
+                .lang
+                .or(config.display.lang)
+                .unwrap_or_else(Lang::system),
+        );
+        self.overrides = overrides;
+        self.ui.commands = commands;
+        self.ui.templates = templates;
+        self.ui.theme = theme;
+        self.ui.accessible = accessible;
+        self.ui.is_breadcrumbs_show = config.display.breadcrumbs;
+        self.ui.is_volume_show = config.display.volume;
+        self.ui.textarea.set_max_histories(config.keys.undo_depth);
+        self.set_icons(icons::Icons::new(
+            &config.icons,
+            overrides.no_icons || accessible,
+        ));
+        self.set_path_mode(config.display.path);
+        if !config.preview.images {
+            self.set_graphics(None);
+        } else if self.ui.graphics.is_none() {
+            self.set_graphics(graphics::Protocol::detect());
+        }
+        Ok(())
+    }
+
+    /// Popped up by the hotkey of `ery --daemon`, which finds the window by the title. The
+    /// search bar is focused whenever the window is, for typing another search at once.
+    pub fn set_summoned(&mut self) -> Result<()> {
+        crossterm::execute!(io::stdout(), terminal::SetTitle(daemon::WINDOW_TITLE))?;
+        self.is_summoned = true;
+        Ok(())
+    }
+
+    /// Show the setup, e.g. on the first run.
+    pub fn start_wizard(&mut self) {
+        self.ui.wizard = Some(wizard::Wizard::default());
+    }
+
+    /// Load the config again after the file is changed, or show the error of it.
+    fn handle_config_changed(&mut self, app: &mut App) -> Result<()> {
+        let reloaded = Config::load().and_then(|config| {
+            self.set_config(&config, self.overrides)?;
+            Ok(config)
+        });
+        match reloaded {
+            Ok(config) => {
+                app.config = config;
+                self.ui.config_error = None;
+                self.ui.notice = Some(tr!(ConfigReloaded).to_string());
+            }
+            Err(e) => self.ui.config_error = Some(format!("{e:#}")),
+        }
+        // the old image may be covered by the popup, or with the preview disabled
+        if let Some(graphics) = self.ui.graphics.as_mut() {
+            graphics.clear()?;
+        }
+        self.terminal.clear()?;
+        Ok(())
+    }
+
+    /// Send the config changed event when the modified time of the config file changes.
+    fn watch_config(&mut self) {
+        const INTERVAL: Duration = Duration::from_secs(1);
+        let Some(path) = Config::path() else {
+            return;
+        };
+        let modified = move || fs::metadata(&path).and_then(|m| m.modified()).ok();
+        let sender = self.sender.clone();
+        let focus = Arc::clone(&self.focus);
+        thread::spawn(move || {
+            let mut last = modified();
+            loop {
+                thread::sleep(focus.interval(INTERVAL));
+                let current = modified();
+                if current != last {
+                    last = current;
+                    if sender.send(Event::ConfigChanged).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+    }
+
+    pub fn set_path_mode(&mut self, path_mode: PathMode) {
+        self.ui.path_mode = path_mode;
+    }
+
+    /// Enable the image thumbnails in the preview pane with the graphics protocol.
+    pub fn set_graphics(&mut self, protocol: Option<graphics::Protocol>) {
+        self.ui.graphics = protocol.map(graphics::Graphics::new);
+    }
+
+    pub fn handle_refresh_event(&mut self, app: &mut App) -> Result<()> {
+        app.update_merge();
+        app.update_notes();
+        app.update_pins();
+        app.update_git();
+        // retry the search of no results relaxed, once
+        if app.config.query.fallback && app.fallback.is_none() && self.ui.is_no_results(app) {
+            app.query_fallback()?;
+        }
+        // the results may be fewer now, e.g. re-queried or sorted
+        self.ui.keep_selected(app);
+        self.ui.ensure_visible(app);
+        if !app.is_querying() {
+            match self.ui.jump.take() {
+                Some(Jump::First) => self.ui.select_first(app),
+                Some(Jump::Last) => self.ui.select_last(app),
+                None => {}
+            }
+        }
+        if self.ui.is_compare_show() {
+            self.ui.show_compare(app);
+        }
+        if self.ui.is_usage_show {
+            self.ui.show_usage(app);
+        }
+        if self.ui.report.is_some() {
+            self.ui.report = Some(app.report());
+        }
+        if let Some(prompt) = self.ui.prompt.as_mut() {
+            if prompt.completing.as_deref() == Some(prompt.text()) {
+                if let Some(completions) = app.folder_completions() {
+                    prompt.complete(completions);
+                }
+            }
+        }
+        let new_entries = app.watch_new_entries();
+        if !new_entries.is_empty() {
+            let title = tr!(
+                NewResults,
+                count = new_entries.len(),
+                search = app.last_search
+            );
+            if app.watch.as_ref().is_some_and(|watch| watch.notify) {
+                let body = new_entries
+                    .iter()
+                    .filter_map(|entry| entry.filename.as_ref())
+                    .take(3)
+                    .map(|filename| format::os_str(filename))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                if let Err(e) = notify::toast(&title, &body) {
+                    self.ui.notice = Some(tr!(NotifyFailed, error = format!("{e:#}")));
+                    return Ok(());
+                }
+            }
+            self.ui.notice = Some(title);
+        }
+        Ok(())
+    }
+
+    pub fn handle_resize_event(&mut self, height: u16, app: &mut App) -> Result<()> {
+        self.ui.resize(height, app);
+        app.set_page_height(self.ui.last_page_height.unwrap_or(1))?;
+        if let Some(graphics) = self.ui.graphics.as_mut() {
+            graphics.clear()?;
+        }
+        // repaint the whole screen, some terminals leave the old frame garbled after resizing
+        self.terminal.clear()?;
         Ok(())
     }
 
     pub fn handle_mouse_events(&mut self, mouse_event: MouseEvent, app: &mut App) -> Result<()> {
         match mouse_event.kind {
-            MouseEventKind::Down(MouseButton::Left) => {}
-            MouseEventKind::Down(MouseButton::Right) => {}
+            // Filter the results by the extension clicked in the sidebar
+            MouseEventKind::Down(MouseButton::Left)
+                if self
+                    .ui
+                    .click_ext_sidebar(mouse_event.column, mouse_event.row) =>
+            {
+                self.filter_by_selected_extension(app)?;
+            }
+            // Search in the folder clicked in the breadcrumbs, or open it by the right button
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(folder) = self
+                    .ui
+                    .click_breadcrumb(mouse_event.column, mouse_event.row)
+                {
+                    self.search_in_folder(&folder, app)?;
+                }
+            }
+            MouseEventKind::Down(MouseButton::Right) => {
+                if let Some(folder) = self
+                    .ui
+                    .click_breadcrumb(mouse_event.column, mouse_event.row)
+                {
+                    if let Err(e) = shell::open_folder(&folder, &app.config.actions) {
+                        self.ui.notice = Some(format!("{e:#}"));
+                    }
+                }
+            }
             MouseEventKind::ScrollUp => {
                 self.up(app)?;
             }
@@ -188,131 +734,1736 @@ impl<B: Backend> Tui<'_, B> {
         if key_event.kind == KeyEventKind::Release {
             return Ok(());
         }
-        match key_event.code {
-            // Quit application on `Esc`
-            KeyCode::Esc => {
-                self.quit();
-                // if self.ui.is_focus_search_bar {
-                //     self.quit();
-                // } else {
-                //     // self.ui.unselect();
-                //     self.ui.is_focus_search_bar = true;
-                // }
-            }
-            // Quit application on `Ctrl+C`
-            KeyCode::Char('c') | KeyCode::Char('C')
-                if key_event.modifiers == KeyModifiers::CONTROL =>
+        // the notice is shown until the next key press
+        self.ui.notice = None;
+        // so is the error of the config
+        if self.ui.config_error.take().is_some() {
+            return Ok(());
+        }
+        if self.ui.wizard.is_some() {
+            return self.handle_wizard_key_events(key_event, app);
+        }
+        if self.ui.is_history_show {
+            return self.handle_history_key_events(key_event, app);
+        }
+        if self.ui.filter_picker.is_some() {
+            return self.handle_filter_picker_key_events(key_event);
+        }
+        if self.ui.commands_menu.is_some() {
+            return self.handle_commands_menu_key_events(key_event, app);
+        }
+        if self.ui.templates_menu.is_some() {
+            return self.handle_templates_menu_key_events(key_event, app);
+        }
+        if self.ui.is_usage_show {
+            return self.handle_usage_key_events(key_event, app);
+        }
+        if self.ui.report.is_some() {
+            return self.handle_report_key_events(key_event, app);
+        }
+        if self.ui.is_focus_ext_sidebar {
+            return self.handle_ext_sidebar_key_events(key_event, app);
+        }
+        if self.ui.confirm.is_some() {
+            return self.handle_confirm_key_events(key_event, app);
+        }
+        if self.ui.prompt.is_some() {
+            return self.handle_prompt_key_events(key_event, app);
+        }
+        if self.ui.conflict.is_some() {
+            return self.handle_conflict_key_events(key_event, app);
+        }
+        if self.ui.batch_rename.is_some() {
+            return self.handle_batch_rename_key_events(key_event);
+        }
+        if self.ui.shortcut.is_some() {
+            return self.handle_shortcut_key_events(key_event, app);
+        }
+        if self.ui.volumes.is_some() {
+            return self.handle_volumes_key_events(key_event, app);
+        }
+        if self.ui.opened.is_some() {
+            return self.handle_opened_key_events(key_event, app);
+        }
+        if self.ui.recycled.is_some() {
+            return self.handle_recycled_key_events(key_event, app);
+        }
+        if self.ui.tasks.is_some() {
+            return self.handle_tasks_key_events(key_event, app);
+        }
+        if self.ui.slow_sort.is_some() {
+            return self.handle_slow_sort_key_events(key_event, app);
+        }
+        // the keys of the user commands go before the built-in ones
+        let bound = self.ui.commands.iter().position(|command| {
+            command
+                .key
+                .as_ref()
+                .is_some_and(|key| key.matches(&key_event))
+        });
+        if let Some(index) = bound {
+            return self.run_command(index, app);
+        }
+        if app.config.keys.profile == KeyProfile::Vim
+            && self.handle_vim_key_events(key_event, app)?
+        {
+            return Ok(());
+        }
+        // keep the relaxed search shown, Alt+D deletes the next word in the search bar otherwise
+        if key_event.modifiers == KeyModifiers::ALT
+            && key_event.code == KeyCode::Char('d')
+            && app.fallback.take().is_some()
+        {
+            let search = app.last_search.clone();
+            self.ui.set_search_text(&search);
+            return Ok(());
+        }
+        // the lines matched in the preview, `n` writes the note otherwise
+        if !self.ui.is_focus_search_bar
+            && matches!(
+                (key_event.modifiers, key_event.code),
+                (KeyModifiers::NONE, KeyCode::Char('n'))
+                    | (KeyModifiers::SHIFT, KeyCode::Char('N'))
+            )
+            && self
+                .ui
+                .next_content_match(key_event.code == KeyCode::Char('n'))
+        {
+            return Ok(());
+        }
+        // the folders in the breadcrumbs of the selected one
+        if let (KeyModifiers::NONE, KeyCode::Char(c @ '1'..='9')) =
+            (key_event.modifiers, key_event.code)
+        {
+            let number = c as usize - '0' as usize;
+            if let Some(folder) = self
+                .ui
+                .breadcrumb(number)
+                .filter(|_| !self.ui.is_focus_search_bar)
             {
+                return self.search_in_folder(&folder, app);
+            }
+        }
+        // the suggestions shown for no results
+        if let (KeyModifiers::ALT, KeyCode::Char(c @ '1'..='9')) =
+            (key_event.modifiers, key_event.code)
+        {
+            if self.ui.is_no_results(app) {
+                return self.take_suggestion(c as usize - '1' as usize, app);
+            }
+        }
+        if self.ui.is_multiline
+            && self.ui.is_focus_search_bar
+            && key_event.modifiers == KeyModifiers::NONE
+        {
+            // a new line on `Enter`, which is OR-combined with the others
+            match key_event.code {
+                KeyCode::Enter => {
+                    self.ui.textarea.insert_newline();
+                    return Ok(());
+                }
+                KeyCode::Up => {
+                    self.ui.textarea.move_cursor(CursorMove::Up);
+                    return Ok(());
+                }
+                KeyCode::Down => {
+                    self.ui.textarea.move_cursor(CursorMove::Down);
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+        match Action::of(&key_event, self.ui.is_focus_search_bar) {
+            Some(action) => self.run_action(action, app),
+            // Other keys passthrough to tui-textarea
+            None => self.type_in_search_bar(key_event, app),
+        }
+    }
+
+    /// Do the action of a key in the main view.
+    pub fn run_action(&mut self, action: Action, app: &mut App) -> Result<()> {
+        match action {
+            // Stop the tasks in the background on `Esc`, e.g. a move or copy
+            Action::Back if app.tasks.is_running() => app.tasks.cancel_all(),
+            // Abandon the slow query on `Esc`
+            Action::Back if app.is_querying() => {
+                app.cancel_queries();
+                self.ui.notice = Some(tr!(Canceled).to_string());
+            }
+            // Close the compare view on `Esc`
+            Action::Back if self.ui.is_compare_show() => {
+                self.ui.hide_compare();
+            }
+            // Unmark all on `Esc`
+            Action::Back if !self.ui.marked.is_empty() => {
+                self.ui.marked.clear();
+            }
+            // Quit application on `Esc` or `Ctrl+C`
+            Action::Back | Action::Quit => {
                 self.quit();
             }
+            // Alt+Enter shows the properties of the selected, as explorer does
+            Action::Properties => {
+                if let Some(path) = self.ui.get_selected_full_path(app) {
+                    if let Err(e) = shell::properties(&path) {
+                        self.ui.notice = Some(format!("{e:#}"));
+                    }
+                }
+            }
+            // Shift+F10 or the Menu key shows the context menu of the selected, as explorer does
+            Action::ContextMenu => {
+                if let Some(path) = self.ui.get_selected_full_path(app) {
+                    if let Err(e) = shell::context_menu(&path) {
+                        self.ui.notice = Some(format!("{e:#}"));
+                    }
+                    // the file may be renamed or deleted by the menu
+                    app.files_changed();
+                }
+            }
             // Do query on `Enter`
-            KeyCode::Enter => {
-                if self.ui.is_focus_search_bar {
-                    let s = self.ui.textarea.lines()[0].as_str();
-                    let is_query_already = if let Ok(results) = app.query_results.try_read() {
-                        results.search == OsString::from_str(s).unwrap()
-                    } else {
-                        false
-                    };
-                    if is_query_already {
-                        self.ui.select_first(app);
-                        self.ui.is_focus_search_bar = false;
-                    } else {
-                        app.send_query(s)?;
-                        self.ui.unselect();
+            Action::Submit if self.ui.is_focus_search_bar => {
+                let s = &self.ui.search_text();
+                // not loaded for the snapshots and the file lists, so not saved over
+                if !app.is_offline() {
+                    if let Err(e) = app.recent.add(s) {
+                        self.ui.notice = Some(format!("{e:#}"));
                     }
+                }
+                let is_query_already =
+                    app.query_results.load().search == OsString::from(app.search_for(s));
+                if is_query_already && app.launcher {
+                    // launch the top one directly, as a launcher does
+                    self.ui.select_first(app);
+                    self.launch_selected(app)?;
+                } else if is_query_already {
+                    self.ui.select_first(app);
+                    self.ui.is_focus_search_bar = false;
+                } else if app.is_offline() {
+                    self.ui.notice = Some(tr!(Offline).to_string());
                 } else {
-                    if self.ui.is_selected() {
-                        if let Some(path) = self.ui.get_selected_full_path(app) {
-                            let mut cmd = std::process::Command::new("explorer");
-                            // Ctrl+Enter will open the folder and select the file, if it is.
-                            if key_event.modifiers == KeyModifiers::CONTROL && path.is_file() {
-                                // Ref: https://stackoverflow.com/a/13625225
-                                cmd.arg(OsStr::new("/select,"));
-                            }
-                            cmd.arg(path.as_os_str());
-                            cmd.spawn()
-                                .expect("explorer command failed to start")
-                                .wait()
-                                .expect("failed to wait");
-                        }
+                    app.send_query(s)?;
+                    self.ui.unselect();
+                }
+            }
+            Action::Submit | Action::Reveal if app.launcher => {
+                self.launch_selected(app)?;
+            }
+            Action::Submit | Action::Reveal => {
+                if let Some(path) = self.ui.get_selected_full_path(app) {
+                    // Ctrl+Enter will open the folder and select the file, if it is.
+                    let opened = if !app.allow_open(&path) {
+                        Err(anyhow::anyhow!(tr!(Vetoed)))
+                    } else if action == Action::Reveal {
+                        shell::reveal(&path, &app.config.actions)
+                    } else if let Some(location) = app.location {
+                        let col = location.column.unwrap_or(1);
+                        shell::goto(&path, location.line, col, &app.config.actions)
+                            .and_then(|()| app.add_opened(&path))
+                    } else {
+                        shell::open(&path, &app.config.actions).and_then(|()| app.add_opened(&path))
+                    };
+                    if let Err(e) = opened {
+                        self.ui.notice = Some(format!("{e:#}"));
                     }
                 }
             }
-            KeyCode::Backspace if !self.ui.is_focus_search_bar => {
+            Action::FocusSearch => {
                 self.ui.is_focus_search_bar = true;
             }
-            KeyCode::Char('/') if !self.ui.is_focus_search_bar => {
+            Action::ReplaceSearch => {
                 self.ui.is_focus_search_bar = true;
                 self.ui.textarea.select_all();
             }
+            Action::PasteSearch if app.is_offline() => {
+                self.ui.notice = Some(tr!(Offline).to_string());
+            }
+            Action::PasteSearch => match clipboard::read_text() {
+                Ok(text) => match clipboard::to_search(&text) {
+                    Some(search) => {
+                        self.ui.set_search_text(&search);
+                        app.send_query(&search)?;
+                        self.ui.unselect();
+                    }
+                    None => self.ui.notice = Some(tr!(ClipboardEmpty).to_string()),
+                },
+                Err(e) => self.ui.notice = Some(format!("{e:#}")),
+            },
             // Shift focus in different widgets
-            KeyCode::Tab => {
-                // TODO: do nothing now, we will support the results list selection for it.
+            Action::NextFocus => {
                 if self.ui.is_focus_search_bar {
                     self.ui.is_focus_search_bar = false;
                     if !self.ui.is_selected() {
                         self.ui.select_first(app);
                     }
+                } else if self.ui.is_ext_sidebar_show {
+                    self.ui.is_focus_ext_sidebar = true;
                 } else {
                     self.ui.is_focus_search_bar = true;
                 }
             }
-            KeyCode::Up => {
-                self.up(app)?;
+            Action::Up => self.up(app)?,
+            Action::Down => self.down(app)?,
+            Action::PageUp => self.page_up(app)?,
+            Action::PageDown => self.page_down(app)?,
+            Action::SelectFirst => self.select_first(app)?,
+            Action::SelectLast => self.select_last(app)?,
+            // Scroll the list horizontally for the long paths
+            Action::ScrollLeft => self.ui.scroll_left(),
+            Action::ScrollRight => self.ui.scroll_right(),
+            // Move the marked ones to the Recycle Bin, or the selected one if none
+            Action::Recycle => {
+                let mut paths = self.ui.marked_paths(app);
+                if paths.is_empty() {
+                    paths.extend(self.ui.get_selected_full_path(app));
+                }
+                if !paths.is_empty() {
+                    self.ui.confirm = Some(Confirm::new(ConfirmAction::Recycle(paths)));
+                }
             }
-            KeyCode::Down => {
-                self.down(app)?;
+            // Move or copy the selected one into a folder
+            Action::Move | Action::Copy => {
+                if let Some(path) = self.ui.get_selected_full_path(app) {
+                    let (title, action) = if action == Action::Move {
+                        (
+                            tr!(MoveTo, path = path.display()),
+                            PromptAction::Move(path.clone()),
+                        )
+                    } else {
+                        (
+                            tr!(CopyTo, path = path.display()),
+                            PromptAction::Copy(path.clone()),
+                        )
+                    };
+                    let folder = path.parent().unwrap_or(&path).to_string_lossy();
+                    let text = format!("{}\\", folder.trim_end_matches('\\'));
+                    self.ui.prompt = Some(Prompt::new(title, action, &text));
+                }
             }
-            KeyCode::PageUp => {
-                self.page_up(app)?;
+            // Create a symlink or hardlink of the selected one
+            Action::Symlink | Action::Hardlink => {
+                if let Some(path) = self.ui.get_selected_full_path(app) {
+                    let kind = if action == Action::Symlink {
+                        LinkKind::Symbolic
+                    } else {
+                        LinkKind::Hard
+                    };
+                    let folder = path.parent().unwrap_or(&path).to_string_lossy();
+                    let text = format!("{}\\", folder.trim_end_matches('\\'));
+                    let title = tr!(LinkAt, kind = kind.name(), path = path.display());
+                    let action = PromptAction::Link(kind, path.clone());
+                    self.ui.prompt = Some(Prompt::new(title, action, &text));
+                }
             }
-            KeyCode::PageDown => {
-                self.page_down(app)?;
+            // Extract the selected archive into a folder
+            Action::Extract => {
+                if let Some(path) = self.ui.get_selected_full_path(app) {
+                    // into the folder of the same name beside it by default
+                    let folder = path.with_extension("");
+                    let title = tr!(ExtractInto, path = path.display());
+                    let action = PromptAction::Extract(path.clone());
+                    let text = format!("{}\\", folder.display());
+                    self.ui.prompt = Some(Prompt::new(title, action, &text));
+                }
             }
-            KeyCode::Char('.') | KeyCode::Char('d') | KeyCode::Char('D')
-                if key_event.modifiers == KeyModifiers::CONTROL =>
-            {
-                self.ui.is_popup_show = !self.ui.is_popup_show;
+            // Rename the selected one
+            Action::Rename => {
+                if let Some(path) = self.ui.get_selected_full_path(app) {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    let prompt = Prompt::new(
+                        tr!(RenameTo, path = path.display()),
+                        PromptAction::Rename(path.clone()),
+                        &name,
+                    );
+                    self.ui.prompt = Some(prompt);
+                }
             }
-            // Other handlers passthrough to tui-textarea
-            _ => {
-                if self.ui.is_focus_search_bar {
-                    ui::key_map_for_textarea(key_event.into(), &mut self.ui.textarea);
+            Action::BatchRename => {
+                let mut paths = self.ui.marked_paths(app);
+                if paths.is_empty() {
+                    paths.extend(self.ui.get_selected_full_path(app));
+                }
+                if !paths.is_empty() {
+                    self.ui.batch_rename = Some(BatchRename::new(paths));
                 }
             }
-        }
-        Ok(())
-    }
-
-    fn up(&mut self, app: &mut App) -> Result<()> {
-        if !self.ui.is_focus_search_bar {
-            if self.ui.is_first_selected() {
-                self.ui.unselect();
-                self.ui.is_focus_search_bar = true;
-            } else {
-                self.ui.select_previous_n(1, app);
+            Action::ToggleMark => {
+                if let Some(path) = self.ui.get_selected_full_path(app) {
+                    if !self.ui.marked.remove(&path) {
+                        self.ui.marked.insert(path);
+                    }
+                    self.ui.notice = Some(tr!(Marked, count = self.ui.marked.len()));
+                    self.down(app)?;
+                }
             }
-        }
-        Ok(())
-    }
-
-    fn down(&mut self, app: &mut App) -> Result<()> {
-        if self.ui.is_focus_search_bar && app.query_results.try_read().is_ok_and(|x| x.number > 0) {
-            self.ui.select_first(app);
-            self.ui.is_focus_search_bar = false;
-        } else {
-            if self.ui.is_selected() {
-                self.ui.select_next_n(1, app);
+            Action::ShortcutTarget => {
+                if let Some(path) = self.ui.get_selected_full_path(app) {
+                    if Shortcuts::is_shortcut(&path) {
+                        app.shortcuts.resolve(&path, &app.sender);
+                        self.ui.shortcut = Some(path);
+                    } else {
+                        self.ui.notice = Some(tr!(NotShortcut).to_string());
+                    }
+                }
+            }
+            // Write a note of the selected one
+            Action::EditNote => {
+                if let Some(path) = self.ui.get_selected_full_path(app) {
+                    let note = app.notes.get(&path).unwrap_or_default().to_string();
+                    let prompt = Prompt::new(
+                        tr!(NoteOf, path = path.display()),
+                        PromptAction::Note(path.clone()),
+                        &note,
+                    );
+                    self.ui.prompt = Some(prompt);
+                }
+            }
+            Action::Exclude if app.is_offline() => {
+                self.ui.notice = Some(tr!(Offline).to_string());
+            }
+            // Exclude the folder of the selected one from all the queries
+            Action::Exclude => {
+                if let Some(path) = self.ui.get_selected_full_path(app) {
+                    self.ui.notice = Some(match app.exclude(&path) {
+                        Ok(Some(folder)) => tr!(Excluded, path = folder.display()),
+                        Ok(None) => tr!(ExcludedAlready, path = path.display()),
+                        Err(e) => format!("{e:#}"),
+                    });
+                }
+            }
+            // Edit the search in several lines, or back in one line
+            Action::ToggleMultiline => self.ui.toggle_multiline(),
+            // Pick a snippet of the search to insert
+            Action::Templates => {
+                self.ui.templates_menu = Some(ListState::default().with_selected(Some(0)));
+            }
+            // Toggle the overlay of the rendering and query statistics, for profiling
+            Action::ToggleDebug => {
+                self.ui.is_debug_show = !self.ui.is_debug_show;
+            }
+            // Pin the selected one at the top across the queries, or unpin it
+            Action::TogglePin => {
+                if let Some(path) = self.ui.get_selected_full_path(app) {
+                    self.ui.notice = Some(match app.toggle_pin(&path) {
+                        Ok(pinned) => {
+                            // keep it selected at the new position
+                            let position = app.query_results.load().position(&path);
+                            self.ui.list_state.select(position);
+                            if pinned {
+                                tr!(Pinned, path = path.display())
+                            } else {
+                                tr!(Unpinned, path = path.display())
+                            }
+                        }
+                        Err(e) => format!("{e:#}"),
+                    });
+                }
+            }
+            // Toggle the preview pane of the selected one
+            Action::TogglePreview => {
+                self.ui.is_preview_show = !self.ui.is_preview_show;
+            }
+            // Run ery again as administrator
+            Action::Elevate => {
+                if app.is_offline() {
+                    self.ui.notice = Some(tr!(Offline).to_string());
+                } else if app.status.is_admin {
+                    self.ui.notice = Some(tr!(AdminAlready).to_string());
+                } else {
+                    let text = self.ui.search_text();
+                    let args: Vec<OsString> = if text.is_empty() {
+                        vec![]
+                    } else {
+                        vec![text.into()]
+                    };
+                    match shell::relaunch_elevated(&args) {
+                        Ok(()) => self.quit(),
+                        Err(e) => self.ui.notice = Some(format!("{e:#}")),
+                    }
+                }
+            }
+            // Switch how the paths are shown
+            Action::CyclePathMode => {
+                self.ui.path_mode = self.ui.path_mode.next();
+                let mode = match self.ui.path_mode {
+                    PathMode::Full => tr!(PathFull),
+                    PathMode::Middle => tr!(PathMiddle),
+                    PathMode::Tail => tr!(PathTail),
+                    PathMode::Relative => tr!(PathRelative),
+                };
+                self.ui.notice = Some(tr!(PathDisplay, mode = mode));
+            }
+            Action::ToggleStatus => {
+                self.ui.is_popup_show = !self.ui.is_popup_show;
+            }
+            // Pick a date or size filter for the search text
+            Action::DateFilter => {
+                let picker = filter::DatePicker::default();
+                self.ui.filter_picker = Some(filter::FilterPicker::Date(picker));
+            }
+            Action::SizeFilter => {
+                let picker = filter::SizePicker::default();
+                self.ui.filter_picker = Some(filter::FilterPicker::Size(picker));
+            }
+            // Show the menu of the user commands
+            Action::CommandsMenu if self.ui.commands.is_empty() => {
+                self.ui.notice = Some(tr!(NoCommands).to_string());
+            }
+            Action::CommandsMenu => {
+                self.ui.commands_menu = Some(ListState::default().with_selected(Some(0)));
+            }
+            // Show the disk usage of the selected folder, or the folder of the selected file
+            Action::DiskUsage => {
+                if app.is_offline() {
+                    self.ui.notice = Some(tr!(Offline).to_string());
+                } else if let Some(path) = self.ui.get_selected_full_path(app) {
+                    let folder = if path.is_dir() {
+                        path
+                    } else {
+                        path.parent().map_or(path.clone(), Path::to_path_buf)
+                    };
+                    self.open_usage(folder, app)?;
+                } else {
+                    self.ui.notice = Some(tr!(SelectFolderForUsage).to_string());
+                }
+            }
+            Action::Volumes => self.ui.volumes = Some(0),
+            Action::OpenedFiles => self.ui.opened = Some(0),
+            Action::RecycledFiles => self.ui.recycled = Some(0),
+            Action::Tasks => self.ui.tasks = Some(0),
+            // Show the sidebar of the results counted by extension
+            Action::ToggleExtSidebar => {
+                self.ui.is_ext_sidebar_show = !self.ui.is_ext_sidebar_show;
+            }
+            // Show the report of the results
+            Action::Report => {
+                self.ui.report = Some(app.report());
+            }
+            // Show the run history of Everything
+            Action::RunHistory => {
+                self.ui.is_history_show = true;
+                app.query_run_history(self.ui.is_history_by_recent)?;
+            }
+            // Take the current results as the baseline for comparing
+            Action::TakeBaseline => {
+                app.take_snapshot();
+            }
+            // Toggle the compare view between the baseline and the current results
+            Action::ToggleCompare => {
+                if self.ui.is_compare_show() {
+                    self.ui.hide_compare();
+                } else {
+                    self.ui.show_compare(app);
+                }
+            }
+            // Switch the sort, and query again by it
+            Action::CycleSort => {
+                if app.is_offline() {
+                    self.ui.notice = Some(tr!(Offline).to_string());
+                } else {
+                    let next = app.next_sort();
+                    if app.status.is_fast_sort(next.0) {
+                        self.set_sort(next, app)?;
+                    } else {
+                        // confirmed first, it may keep Everything busy for long
+                        self.ui.slow_sort = Some(next);
+                    }
+                }
+            }
+            // Switch to the next named filter of the queries
+            Action::CycleFilter => {
+                if app.is_offline() {
+                    self.ui.notice = Some(tr!(Offline).to_string());
+                } else {
+                    app.cycle_filter();
+                    self.ui.notice = Some(match &app.filter {
+                        Some(filter) => tr!(FilterIs, name = filter.name, search = filter.search),
+                        None => tr!(NoFilter).to_string(),
+                    });
+                    let search = app.last_search.clone();
+                    app.send_query(&search)?;
+                }
+            }
+            // Show the files changed today, or back to the view before
+            Action::RecentlyChanged => {
+                if app.is_offline() {
+                    self.ui.notice = Some(tr!(Offline).to_string());
+                } else {
+                    self.ui.notice = Some(if app.toggle_recently_changed() {
+                        tr!(RecentlyChangedView).to_string()
+                    } else {
+                        let label = sort_label(app.sort_type, app.secondary_sort);
+                        tr!(SortBy, sort = label)
+                    });
+                    let search = app.last_search.clone();
+                    app.send_query(&search)?;
+                }
+            }
+            // Switch what the words of the search match
+            Action::CycleScope => {
+                if app.is_offline() {
+                    self.ui.notice = Some(tr!(Offline).to_string());
+                } else {
+                    app.scope = app.scope.next();
+                    let label = app.scope.label().unwrap_or(tr!(ScopeName));
+                    self.ui.notice = Some(tr!(MatchScope, scope = label));
+                    let search = app.last_search.clone();
+                    app.send_query(&search)?;
+                }
+            }
+            Action::ToggleGlob => {
+                if app.is_offline() {
+                    self.ui.notice = Some(tr!(Offline).to_string());
+                } else {
+                    app.glob = !app.glob;
+                    self.ui.notice =
+                        Some(if app.glob { tr!(GlobOn) } else { tr!(GlobOff) }.to_string());
+                    let search = app.last_search.clone();
+                    app.send_query(&search)?;
+                }
+            }
+            Action::ToggleProject => {
+                if app.is_offline() {
+                    self.ui.notice = Some(tr!(Offline).to_string());
+                } else if let Some(project) = &app.project {
+                    app.in_project = !app.in_project;
+                    self.ui.notice = Some(if app.in_project {
+                        tr!(InProject, path = project.root.display())
+                    } else {
+                        tr!(NotInProject).to_string()
+                    });
+                    let search = app.last_search.clone();
+                    app.send_query(&search)?;
+                } else {
+                    self.ui.notice = Some(tr!(NoProject).to_string());
+                }
+            }
+            // Continue the search in the Everything desktop app
+            Action::OpenInEverything => {
+                let s = &self.ui.search_text();
+                if let Err(e) = app.open_in_everything(s) {
+                    self.ui.notice = Some(format!("{e:#}"));
+                }
+            }
+            // Query the search text again, e.g. for comparing the same query at two times
+            Action::Requery if app.is_offline() => {
+                self.ui.notice = Some(tr!(Offline).to_string());
+            }
+            Action::Requery => {
+                let s = &self.ui.search_text();
+                app.send_query(s)?;
+            }
+            // Step back or forth through the queries of the session
+            Action::UndoQuery | Action::RedoQuery if app.is_offline() => {
+                self.ui.notice = Some(tr!(Offline).to_string());
+            }
+            Action::UndoQuery | Action::RedoQuery => {
+                let restored = if action == Action::UndoQuery {
+                    app.undo_query()?
+                } else {
+                    app.redo_query()?
+                };
+                self.ui.notice = Some(match restored {
+                    Some(text) => {
+                        self.ui.set_search_text(&text);
+                        self.ui.unselect();
+                        let sort = sort_label(app.sort_type, app.secondary_sort);
+                        if action == Action::UndoQuery {
+                            tr!(UndoneQuery, text = text, sort = sort)
+                        } else {
+                            tr!(RedoneQuery, text = text, sort = sort)
+                        }
+                    }
+                    None if action == Action::UndoQuery => tr!(NoQueryToUndo).to_string(),
+                    None => tr!(NoQueryToRedo).to_string(),
+                });
+            }
+            // Save the paths of the current results as a file list, losslessly, in the
+            // background and told when it is done
+            Action::SaveFileList => app.save_file_list(),
+            // Save the current results as a snapshot file, the same
+            Action::SaveSnapshot => app.save_snapshot(),
+        }
+        Ok(())
+    }
+
+    /// Type the key in the search bar, if it is focused.
+    fn type_in_search_bar(&mut self, key_event: KeyEvent, app: &mut App) -> Result<()> {
+        if self.ui.is_focus_search_bar {
+            let old = self.ui.search_text();
+            ui::key_map_for_textarea(key_event.into(), &mut self.ui.textarea);
+            self.search_text_edited(&old, app)?;
+        }
+        Ok(())
+    }
+
+    /// Count the matches while typing, the results are fetched on `Enter`, or query them if
+    /// searching as typing.
+    fn search_text_edited(&mut self, old: &str, app: &mut App) -> Result<()> {
+        let s = self.ui.search_text();
+        if s != old && app.config.query.as_you_type && !app.is_offline() {
+            app.send_query(&s)?;
+            self.ui.unselect();
+        } else if s != old && !s.is_empty() {
+            app.send_count(&s)?;
+        }
+        Ok(())
+    }
+
+    /// Insert the pasted text into the prompt, or into the search bar in the main view, as one
+    /// edit instead of the keys of every char.
+    fn handle_paste_event(&mut self, text: &str, app: &mut App) -> Result<()> {
+        self.ui.notice = None;
+        if let Some(prompt) = self.ui.prompt.as_mut() {
+            let old = prompt.text().to_string();
+            prompt.textarea.insert_str(ui::one_line(text));
+            if prompt.text() != old {
+                prompt.clear_completions();
+            }
+            return Ok(());
+        }
+        let is_popup = self.ui.wizard.is_some()
+            || self.ui.config_error.is_some()
+            || self.ui.is_history_show
+            || self.ui.filter_picker.is_some()
+            || self.ui.commands_menu.is_some()
+            || self.ui.templates_menu.is_some()
+            || self.ui.is_usage_show
+            || self.ui.report.is_some()
+            || self.ui.conflict.is_some()
+            || self.ui.confirm.is_some()
+            || self.ui.shortcut.is_some()
+            || self.ui.volumes.is_some()
+            || self.ui.opened.is_some()
+            || self.ui.recycled.is_some()
+            || self.ui.tasks.is_some()
+            || self.ui.slow_sort.is_some();
+        if let Some(batch) = self.ui.batch_rename.as_mut() {
+            let field = if batch.is_focus_replace {
+                &mut batch.replace
             } else {
+                &mut batch.find
+            };
+            field.insert_str(ui::one_line(text));
+            batch.update();
+            return Ok(());
+        }
+        if is_popup {
+            return Ok(());
+        }
+        self.ui.is_focus_search_bar = true;
+        self.ui.is_focus_ext_sidebar = false;
+        let old = self.ui.search_text();
+        self.ui.insert_search_text(text);
+        self.search_text_edited(&old, app)
+    }
+
+    /// Run a command from another process, e.g. an editor plugin or a script, and reply to it.
+    fn handle_pipe_request(&mut self, request: pipe::Request, app: &mut App) -> Result<()> {
+        match &request.command {
+            pipe::Command::Search(_) if app.is_offline() => {
+                request.reply("error: offline, searching is not available");
+            }
+            pipe::Command::Search(text) => {
+                self.ui.notice = None;
+                self.set_search_text(text);
+                app.send_query(text)?;
+                self.ui.unselect();
+                request.reply("ok");
+            }
+            pipe::Command::GetSelection => {
+                let path = self.ui.get_selected_full_path(app).unwrap_or_default();
+                request.reply(path.to_string_lossy());
+            }
+            pipe::Command::Quit => {
+                request.reply("ok");
+                self.quit();
+            }
+        }
+        Ok(())
+    }
+
+    /// Launch the selected program and quit, in launcher mode.
+    fn launch_selected(&mut self, app: &mut App) -> Result<()> {
+        if let Some(path) = self.ui.get_selected_full_path(app) {
+            if !app.allow_open(&path) {
+                self.ui.notice = Some(tr!(Vetoed).to_string());
+                return Ok(());
+            }
+            // explorer starts it detached from the terminal, the same as the Start menu.
+            if let Err(e) = shell::explorer(&path, false) {
+                self.ui.notice = Some(format!("{e:#}"));
+                return Ok(());
+            }
+            if let Err(e) = app.add_opened(&path) {
+                self.ui.notice = Some(format!("{e:#}"));
+            }
+            app.inc_run_count_and_wait(path)?;
+            self.quit();
+        }
+        Ok(())
+    }
+
+    /// The keys of the vim profile, true if the key is handled.
+    fn handle_vim_key_events(&mut self, key_event: KeyEvent, app: &mut App) -> Result<bool> {
+        if key_event
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            return Ok(false);
+        }
+        if self.ui.is_focus_search_bar {
+            // leave the search bar for the results, instead of quitting
+            if key_event.code != KeyCode::Esc || app.is_querying() || self.ui.is_compare_show() {
+                return Ok(false);
+            }
+            self.ui.is_focus_search_bar = false;
+            if !self.ui.is_selected() {
                 self.ui.select_first(app);
             }
+            return Ok(true);
+        }
+        let action = match key_event.code {
+            KeyCode::Char('j') => Action::Down,
+            KeyCode::Char('k') => Action::Up,
+            KeyCode::Char('g') => Action::SelectFirst,
+            KeyCode::Char('G') => Action::SelectLast,
+            KeyCode::Char('h') => Action::ScrollLeft,
+            KeyCode::Char('l') => Action::ScrollRight,
+            KeyCode::Char('i') => Action::FocusSearch,
+            KeyCode::Char('q') => Action::Quit,
+            _ => return Ok(false),
+        };
+        self.run_action(action, app)?;
+        Ok(true)
+    }
+
+    fn handle_wizard_key_events(&mut self, key_event: KeyEvent, app: &mut App) -> Result<()> {
+        let Some(wizard) = self.ui.wizard.as_mut() else {
+            return Ok(());
+        };
+        match key_event.code {
+            // skip it, and it shows again until there is a config file
+            KeyCode::Esc => {
+                self.ui.wizard = None;
+                self.set_config(&app.config, self.overrides)?;
+            }
+            KeyCode::Char('c') | KeyCode::Char('C')
+                if key_event.modifiers == KeyModifiers::CONTROL =>
+            {
+                self.quit();
+            }
+            KeyCode::Up => wizard.select_previous(),
+            KeyCode::Down => wizard.select_next(),
+            KeyCode::Backspace | KeyCode::Left => wizard.back(),
+            KeyCode::Enter if wizard.next() => {}
+            KeyCode::Enter => match wizard.save() {
+                Ok(()) => {
+                    self.ui.wizard = None;
+                    self.handle_config_changed(app)?;
+                    (app.sort_type, app.secondary_sort) = default_sort(app.config.query.sort);
+                    app.is_sort_chosen = false;
+                }
+                Err(e) => self.ui.notice = Some(format!("{e:#}")),
+            },
+            _ => {}
+        }
+        // show the theme being chosen
+        if let Some(wizard) = &self.ui.wizard {
+            if wizard.step == 0 && !self.ui.accessible {
+                self.ui.theme = Theme::new(&wizard.theme())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Run the user command on the selected one.
+    fn run_command(&mut self, index: usize, app: &mut App) -> Result<()> {
+        let command = self.ui.commands[index].clone();
+        let Some(path) = self.ui.get_selected_full_path(app) else {
+            self.ui.notice = Some(tr!(SelectToRun, name = command.name));
+            return Ok(());
+        };
+        let result = if command.console {
+            self.suspend(|| shell::run_template_console(&command.run, &path))?
+        } else {
+            shell::run_template(&command.run, &path)
+        };
+        if let Err(e) = result {
+            self.ui.notice = Some(format!("{}: {e:#}", command.name));
+        }
+        // the command may change the files
+        app.files_changed();
+        Ok(())
+    }
+
+    fn handle_commands_menu_key_events(
+        &mut self,
+        key_event: KeyEvent,
+        app: &mut App,
+    ) -> Result<()> {
+        let Some(menu) = self.ui.commands_menu.as_mut() else {
+            return Ok(());
+        };
+        let rows = self.ui.commands.len();
+        let selected = menu.selected().unwrap_or_default();
+        match key_event.code {
+            KeyCode::Esc | KeyCode::F(9) => {
+                self.ui.commands_menu = None;
+            }
+            KeyCode::Char('c') | KeyCode::Char('C')
+                if key_event.modifiers == KeyModifiers::CONTROL =>
+            {
+                self.quit();
+            }
+            KeyCode::Up => menu.select(Some(selected.checked_sub(1).unwrap_or(rows - 1))),
+            KeyCode::Down => menu.select(Some((selected + 1) % rows)),
+            KeyCode::Enter => {
+                self.ui.commands_menu = None;
+                self.run_command(selected, app)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_templates_menu_key_events(&mut self, key_event: KeyEvent, app: &App) -> Result<()> {
+        let Some(menu) = self.ui.templates_menu.as_mut() else {
+            return Ok(());
+        };
+        let rows = self.ui.templates.len();
+        let selected = menu.selected().unwrap_or_default();
+        match key_event.code {
+            KeyCode::Esc => {
+                self.ui.templates_menu = None;
+            }
+            KeyCode::Char('t') | KeyCode::Char('T') if key_event.modifiers == KeyModifiers::ALT => {
+                self.ui.templates_menu = None;
+            }
+            KeyCode::Char('c') | KeyCode::Char('C')
+                if key_event.modifiers == KeyModifiers::CONTROL =>
+            {
+                self.quit();
+            }
+            KeyCode::Up => menu.select(Some(selected.checked_sub(1).unwrap_or(rows - 1))),
+            KeyCode::Down => menu.select(Some((selected + 1) % rows)),
+            // Insert the snippet at the cursor, then it can be edited before querying
+            KeyCode::Enter => {
+                let template = &self.ui.templates[selected];
+                if let Some(feature) = template.requires.filter(|&f| !app.status.supports(f)) {
+                    self.ui.notice = Some(feature.unsupported());
+                    return Ok(());
+                }
+                self.ui.templates_menu = None;
+                let snippet = template.snippet.clone();
+                self.ui.textarea.insert_str(snippet);
+                self.ui.is_focus_search_bar = true;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_filter_picker_key_events(&mut self, key_event: KeyEvent) -> Result<()> {
+        let Some(picker) = self.ui.filter_picker.as_mut() else {
+            return Ok(());
+        };
+        match key_event.code {
+            KeyCode::Esc => {
+                self.ui.filter_picker = None;
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') | KeyCode::Char('f') | KeyCode::Char('F')
+                if key_event.modifiers == KeyModifiers::CONTROL =>
+            {
+                self.ui.filter_picker = None;
+            }
+            KeyCode::Char('c') | KeyCode::Char('C')
+                if key_event.modifiers == KeyModifiers::CONTROL =>
+            {
+                self.quit();
+            }
+            KeyCode::Tab => picker.tab(),
+            KeyCode::Up => picker.select_previous(),
+            KeyCode::Down => picker.select_next(),
+            KeyCode::Left => picker.step(-1),
+            KeyCode::Right => picker.step(1),
+            KeyCode::Char(c) => picker.push(c),
+            KeyCode::Backspace => picker.pop(),
+            // Insert the filter into the search text, then it can be edited before querying
+            KeyCode::Enter => match picker.filter() {
+                Ok(filter) => {
+                    let search = filter::set_filter(&self.ui.search_text(), &filter);
+                    self.ui.set_search_text(&search);
+                    self.ui.is_focus_search_bar = true;
+                    self.ui.filter_picker = None;
+                }
+                Err(e) => self.ui.notice = Some(e),
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Scan the folder for the disk usage view.
+    fn open_usage(&mut self, folder: PathBuf, app: &mut App) -> Result<()> {
+        match app.query_disk_usage(folder) {
+            Ok(()) => {
+                self.ui.usage = None;
+                self.ui.usage_state = ListState::default();
+                self.ui.is_usage_show = true;
+            }
+            Err(e) => self.ui.notice = Some(format!("{e:#}")),
+        }
+        Ok(())
+    }
+
+    fn handle_usage_key_events(&mut self, key_event: KeyEvent, app: &mut App) -> Result<()> {
+        match key_event.code {
+            KeyCode::Esc if app.is_querying() => {
+                app.cancel_queries();
+                self.ui.hide_usage();
+                self.ui.notice = Some(tr!(Canceled).to_string());
+            }
+            KeyCode::Esc => self.ui.hide_usage(),
+            KeyCode::Char('u') | KeyCode::Char('U')
+                if key_event.modifiers == KeyModifiers::CONTROL =>
+            {
+                self.ui.hide_usage();
+            }
+            KeyCode::Char('c') | KeyCode::Char('C')
+                if key_event.modifiers == KeyModifiers::CONTROL =>
+            {
+                self.quit();
+            }
+            KeyCode::Up => {
+                let selected = self.ui.usage_state.selected().unwrap_or_default();
+                self.ui.usage_state.select(Some(selected.saturating_sub(1)));
+            }
+            KeyCode::Down => {
+                let len = self.ui.usage.as_ref().map_or(0, |usage| usage.entrys.len());
+                let selected = self.ui.usage_state.selected().unwrap_or_default();
+                self.ui
+                    .usage_state
+                    .select(len.checked_sub(1).map(|last| min(selected + 1, last)));
+            }
+            // Go into the selected folder, or up to the parent folder
+            KeyCode::Enter => {
+                if let Some((path, true)) = self.ui.get_selected_usage_path() {
+                    self.open_usage(path, app)?;
+                }
+            }
+            KeyCode::Backspace => {
+                let parent = app
+                    .usage_folder
+                    .as_deref()
+                    .and_then(Path::parent)
+                    .map(Path::to_path_buf);
+                if let Some(parent) = parent {
+                    self.open_usage(parent, app)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_ext_sidebar_key_events(&mut self, key_event: KeyEvent, app: &mut App) -> Result<()> {
+        match key_event.code {
+            KeyCode::Esc => self.ui.is_focus_ext_sidebar = false,
+            KeyCode::Tab => {
+                self.ui.is_focus_ext_sidebar = false;
+                self.ui.is_focus_search_bar = true;
+            }
+            KeyCode::Char('e') | KeyCode::Char('E') if key_event.modifiers == KeyModifiers::ALT => {
+                self.ui.is_focus_ext_sidebar = false;
+                self.ui.is_ext_sidebar_show = false;
+            }
+            KeyCode::Char('c') | KeyCode::Char('C')
+                if key_event.modifiers == KeyModifiers::CONTROL =>
+            {
+                self.quit();
+            }
+            KeyCode::Up => {
+                let selected = self.ui.ext_state.selected().unwrap_or_default();
+                self.ui.ext_state.select(Some(selected.saturating_sub(1)));
+            }
+            KeyCode::Down => {
+                let len = self.ui.ext_sidebar_len();
+                let selected = self.ui.ext_state.selected().unwrap_or_default();
+                self.ui
+                    .ext_state
+                    .select(len.checked_sub(1).map(|last| min(selected + 1, last)));
+            }
+            KeyCode::Enter => self.filter_by_selected_extension(app)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Put the `ext:` filter of the selected extension in the sidebar into the search, and
+    /// query it.
+    fn filter_by_selected_extension(&mut self, app: &mut App) -> Result<()> {
+        let Some(ext) = self.ui.get_selected_extension() else {
+            return Ok(());
+        };
+        if ext.is_empty() {
+            self.ui.notice = Some(tr!(NoExtFilter).to_string());
+            return Ok(());
+        }
+        let search = filter::set_filter(&self.ui.search_text(), &format!("ext:{ext}"));
+        self.ui.set_search_text(&search);
+        self.ui.is_focus_ext_sidebar = false;
+        self.ui.is_focus_search_bar = true;
+        self.ui.unselect();
+        app.send_query(&search)
+    }
+
+    fn handle_prompt_key_events(&mut self, key_event: KeyEvent, app: &mut App) -> Result<()> {
+        let Some(mut prompt) = self.ui.prompt.take() else {
+            return Ok(());
+        };
+        match key_event.code {
+            KeyCode::Esc => return Ok(()),
+            KeyCode::Char('c') | KeyCode::Char('C')
+                if key_event.modifiers == KeyModifiers::CONTROL =>
+            {
+                self.quit();
+            }
+            KeyCode::Enter => {
+                let text = prompt.text().to_string();
+                let result = match &prompt.action {
+                    PromptAction::Rename(path) => app
+                        .rename(path, &text)
+                        .map(|new_path| tr!(Renamed, path = new_path.display())),
+                    PromptAction::Move(path) => {
+                        self.transfer_to(TransferKind::Move, path.clone(), &text, app)
+                    }
+                    PromptAction::Copy(path) => {
+                        self.transfer_to(TransferKind::Copy, path.clone(), &text, app)
+                    }
+                    PromptAction::Extract(path) => {
+                        let folder = PathBuf::from(text.trim());
+                        let action = ConfirmAction::Extract(path.clone(), folder);
+                        let notice = action.describe();
+                        self.ui.confirm = Some(Confirm::new(action));
+                        Ok(notice)
+                    }
+                    PromptAction::Link(kind, path) => app
+                        .create_link(*kind, path, Path::new(text.trim()))
+                        .map(|link| tr!(LinkCreated, kind = kind.name(), path = link.display())),
+                    PromptAction::Note(path) => app.set_note(path.clone(), &text).map(|()| {
+                        if text.trim().is_empty() {
+                            tr!(NoteRemoved, path = path.display())
+                        } else {
+                            tr!(Noted, path = path.display())
+                        }
+                    }),
+                };
+                match result {
+                    Ok(notice) => {
+                        self.ui.notice = Some(notice);
+                        return Ok(());
+                    }
+                    // keep the prompt for fixing the text
+                    Err(e) => self.ui.notice = Some(format!("{e:#}")),
+                }
+            }
+            // Complete the folder by Everything, or fill in the next candidate
+            KeyCode::Tab => {
+                if prompt.action.is_path() && !prompt.next_completion() {
+                    prompt.completing = Some(prompt.text().to_string());
+                    app.query_folder_completions(prompt.text())?;
+                }
+            }
+            _ => {
+                let old = prompt.text().to_string();
+                ui::key_map_for_textarea(key_event.into(), &mut prompt.textarea);
+                if prompt.text() != old {
+                    prompt.clear_completions();
+                }
+            }
+        }
+        self.ui.prompt = Some(prompt);
+        Ok(())
+    }
+
+    /// Search the text in the folder, in place of the folder searched in before if any.
+    fn search_in_folder(&mut self, folder: &Path, app: &mut App) -> Result<()> {
+        if app.is_offline() {
+            self.ui.notice = Some(tr!(Offline).to_string());
+            return Ok(());
+        }
+        let text = self.ui.search_text();
+        // the folder quoted with the trailing backslash, `"C:\Users\" text`
+        let words = text
+            .strip_prefix('"')
+            .and_then(|rest| rest.split_once("\\\""))
+            .map_or(text.as_str(), |(_, words)| words.trim_start());
+        let folder = folder.display().to_string();
+        let search = format!(r#""{}\" {words}"#, folder.trim_end_matches('\\'));
+        let search = search.trim_end();
+        self.ui.set_search_text(search);
+        app.send_query(search)?;
+        self.ui.unselect();
+        Ok(())
+    }
+
+    fn handle_batch_rename_key_events(&mut self, key_event: KeyEvent) -> Result<()> {
+        let Some(mut batch) = self.ui.batch_rename.take() else {
+            return Ok(());
+        };
+        match (key_event.modifiers, key_event.code) {
+            (_, KeyCode::Esc) => return Ok(()),
+            (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C')) => {
+                self.quit();
+                return Ok(());
+            }
+            // confirm over the popup, which is kept for fixing the rule
+            (_, KeyCode::Enter) => match &batch.plan {
+                Ok(renamings) => match crate::app::rename::check(renamings) {
+                    Ok(()) => {
+                        let action = ConfirmAction::BatchRename(renamings.clone());
+                        self.ui.confirm = Some(Confirm::new(action));
+                    }
+                    Err(e) => self.ui.notice = Some(format!("{e:#}")),
+                },
+                Err(e) => self.ui.notice = Some(e.clone()),
+            },
+            (_, KeyCode::Tab | KeyCode::BackTab) => {
+                batch.is_focus_replace = !batch.is_focus_replace
+            }
+            (KeyModifiers::ALT, KeyCode::Char('r')) => batch.toggle_regex(),
+            (KeyModifiers::ALT, KeyCode::Char('c')) => batch.next_case(),
+            (_, KeyCode::Up) => batch.scroll = batch.scroll.saturating_sub(1),
+            (_, KeyCode::Down) => batch.scroll += 1,
+            _ => batch.input(key_event),
+        }
+        self.ui.batch_rename = Some(batch);
+        Ok(())
+    }
+
+    /// Move or copy the file into the folder, or ask what to do if it exists there.
+    fn transfer_to(
+        &mut self,
+        kind: TransferKind,
+        source: PathBuf,
+        folder: &str,
+        app: &mut App,
+    ) -> Result<String> {
+        let folder = PathBuf::from(folder.trim());
+        anyhow::ensure!(folder.is_dir(), "{} is not a folder", folder.display());
+        let target = folder.join(source.file_name().unwrap_or_default());
+        anyhow::ensure!(
+            !transfer::is_same_file(&target, &source),
+            "{} is in the folder already",
+            source.display()
+        );
+        if target.exists() {
+            let notice = tr!(Exists, path = target.display());
+            self.ui.conflict = Some(Conflict {
+                kind,
+                source,
+                target,
+            });
+            return Ok(notice);
+        }
+        let action = ConfirmAction::Transfer(kind, source, target);
+        if kind == TransferKind::Move {
+            let notice = action.describe();
+            self.ui.confirm = Some(Confirm::new(action));
+            return Ok(notice);
+        }
+        self.run_confirmed(action, app)
+    }
+
+    fn handle_confirm_key_events(&mut self, key_event: KeyEvent, app: &mut App) -> Result<()> {
+        let Some(mut confirm) = self.ui.confirm.take() else {
+            return Ok(());
+        };
+        match (key_event.modifiers, key_event.code) {
+            (_, KeyCode::Esc) => {}
+            (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C')) => self.quit(),
+            // the bulk ones by typing the count
+            (_, code) if confirm.count.is_some() => {
+                match code {
+                    KeyCode::Enter if confirm.is_count_typed() => {
+                        let done = self.run_confirmed(confirm.action, app);
+                        self.ui.notice = Some(done.unwrap_or_else(|e| format!("{e:#}")));
+                        return Ok(());
+                    }
+                    KeyCode::Char(c) if c.is_ascii_digit() => confirm.typed.push(c),
+                    KeyCode::Backspace => {
+                        confirm.typed.pop();
+                    }
+                    _ => {}
+                }
+                self.ui.confirm = Some(confirm);
+            }
+            (_, KeyCode::Char('y') | KeyCode::Char('Y')) => {
+                let done = self.run_confirmed(confirm.action, app);
+                self.ui.notice = Some(done.unwrap_or_else(|e| format!("{e:#}")));
+            }
+            (_, KeyCode::Char('n') | KeyCode::Char('N')) => {}
+            _ => self.ui.confirm = Some(confirm),
+        }
+        Ok(())
+    }
+
+    /// Do the confirmed action, or only tell it in the dry run, the notice of it returned.
+    fn run_confirmed(&mut self, action: ConfirmAction, app: &mut App) -> Result<String> {
+        if app.dry_run {
+            return Ok(tr!(DryRun, what = action.describe()));
+        }
+        match action {
+            ConfirmAction::Recycle(paths) => app.recycle(&paths).map(|()| {
+                for path in &paths {
+                    self.ui.marked.remove(path);
+                }
+                match paths.as_slice() {
+                    [path] => tr!(Recycled, path = path.display()),
+                    _ => tr!(RecycledCount, count = paths.len()),
+                }
+            }),
+            ConfirmAction::Transfer(kind, source, target) => app
+                .start_transfer(kind, source, target)
+                .map(|()| tr!(InBackground, verb = kind.verb())),
+            ConfirmAction::BatchRename(renamings) => app.batch_rename(&renamings).map(|count| {
+                self.ui.batch_rename = None;
+                self.ui.marked.clear();
+                tr!(BatchRenamed, count = count)
+            }),
+            ConfirmAction::Extract(source, folder) => app
+                .start_extract(source, folder)
+                .map(|()| tr!(InBackground, verb = TransferKind::Extract.verb())),
+        }
+    }
+
+    fn handle_conflict_key_events(&mut self, key_event: KeyEvent, app: &mut App) -> Result<()> {
+        let Some(conflict) = self.ui.conflict.take() else {
+            return Ok(());
+        };
+        let target = match key_event.code {
+            KeyCode::Char('o') | KeyCode::Char('O') => conflict.target,
+            KeyCode::Char('r') | KeyCode::Char('R') => transfer::free_path(&conflict.target),
+            KeyCode::Char('s') | KeyCode::Char('S') | KeyCode::Esc => {
+                self.ui.notice = Some(tr!(Skipped, path = conflict.source.display()));
+                return Ok(());
+            }
+            KeyCode::Char('c') | KeyCode::Char('C')
+                if key_event.modifiers == KeyModifiers::CONTROL =>
+            {
+                self.quit();
+                return Ok(());
+            }
+            _ => {
+                self.ui.conflict = Some(conflict);
+                return Ok(());
+            }
+        };
+        // the choice is the confirmation
+        let action = ConfirmAction::Transfer(conflict.kind, conflict.source, target);
+        let done = self.run_confirmed(action, app);
+        self.ui.notice = Some(done.unwrap_or_else(|e| format!("{e:#}")));
+        Ok(())
+    }
+
+    fn handle_shortcut_key_events(&mut self, key_event: KeyEvent, app: &mut App) -> Result<()> {
+        let Some(path) = self.ui.shortcut.take() else {
+            return Ok(());
+        };
+        let target = match app.shortcuts.target(&path) {
+            Some(Some(Ok(shortcut))) => Some(shortcut.target),
+            _ => None,
+        };
+        let opened = match (key_event.modifiers, key_event.code, target) {
+            (_, KeyCode::Esc, _) => return Ok(()),
+            (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C'), _) => {
+                self.quit();
+                return Ok(());
+            }
+            // Ctrl+Enter opens the folder of the target and selects it, as in the results
+            (KeyModifiers::CONTROL, KeyCode::Enter, Some(target)) => {
+                shell::reveal(&target, &app.config.actions)
+            }
+            (_, KeyCode::Enter, Some(target)) if !app.allow_open(&target) => {
+                Err(anyhow::anyhow!(tr!(Vetoed)))
+            }
+            (_, KeyCode::Enter, Some(target)) => {
+                shell::open(&target, &app.config.actions).and_then(|()| app.add_opened(&target))
+            }
+            _ => {
+                self.ui.shortcut = Some(path);
+                return Ok(());
+            }
+        };
+        if let Err(e) = opened {
+            self.ui.notice = Some(format!("{e:#}"));
+        }
+        Ok(())
+    }
+
+    fn handle_volumes_key_events(&mut self, key_event: KeyEvent, app: &mut App) -> Result<()> {
+        let Some(selected) = self.ui.volumes else {
+            return Ok(());
+        };
+        let len = self.ui.volumes_len();
+        match (key_event.modifiers, key_event.code) {
+            (_, KeyCode::Esc) => self.ui.volumes = None,
+            (KeyModifiers::ALT, KeyCode::Char('v') | KeyCode::Char('V')) => self.ui.volumes = None,
+            (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C')) => self.quit(),
+            (_, KeyCode::Up) => self.ui.volumes = Some(selected.checked_sub(1).unwrap_or(len - 1)),
+            (_, KeyCode::Down) => self.ui.volumes = Some((selected + 1) % len),
+            (_, KeyCode::Enter) => {
+                let volume = self.ui.volume_at(selected);
+                self.search_in_volume(volume.as_deref(), app)?;
+            }
+            (_, KeyCode::Backspace) => self.search_in_volume(None, app)?,
+            // the drive letter searches in the drive at once, e.g. `d` for `D:\`
+            (KeyModifiers::NONE | KeyModifiers::SHIFT, KeyCode::Char(c))
+                if c.is_ascii_alphabetic() =>
+            {
+                let volume = format!("{}:", c.to_ascii_uppercase());
+                self.search_in_volume(Some(&volume), app)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_recycled_key_events(&mut self, key_event: KeyEvent, app: &mut App) -> Result<()> {
+        let Some(selected) = self.ui.recycled else {
+            return Ok(());
+        };
+        let len = app.recycled.len().max(1);
+        match (key_event.modifiers, key_event.code) {
+            (_, KeyCode::Esc) | (KeyModifiers::ALT, KeyCode::Delete) => self.ui.recycled = None,
+            (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C')) => self.quit(),
+            (_, KeyCode::Up) => {
+                self.ui.recycled = Some(selected.checked_sub(1).unwrap_or(len - 1));
+            }
+            (_, KeyCode::Down) => self.ui.recycled = Some((selected + 1) % len),
+            (_, KeyCode::Enter) if selected < app.recycled.len() => {
+                self.ui.notice = Some(match app.restore(selected) {
+                    Ok(path) => tr!(Restored, path = path.display()),
+                    Err(e) => format!("{e:#}"),
+                });
+                let len = app.recycled.len();
+                self.ui.recycled = Some(selected.min(len.saturating_sub(1)));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_opened_key_events(&mut self, key_event: KeyEvent, app: &mut App) -> Result<()> {
+        let Some(selected) = self.ui.opened else {
+            return Ok(());
+        };
+        let len = app.opened.files.len().max(1);
+        let path = app.opened.files.get(selected).map(|file| file.path.clone());
+        let done = match (key_event.modifiers, key_event.code, path) {
+            (_, KeyCode::Esc, _) => {
+                self.ui.opened = None;
+                Ok(())
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('h') | KeyCode::Char('H'), _) => {
+                self.ui.opened = None;
+                Ok(())
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C'), _) => {
+                self.quit();
+                Ok(())
+            }
+            (_, KeyCode::Up, _) => {
+                self.ui.opened = Some(selected.checked_sub(1).unwrap_or(len - 1));
+                Ok(())
+            }
+            (_, KeyCode::Down, _) => {
+                self.ui.opened = Some((selected + 1) % len);
+                Ok(())
+            }
+            // Ctrl+Enter opens the folder and selects it, as in the results
+            (KeyModifiers::CONTROL, KeyCode::Enter, Some(path)) => {
+                self.ui.opened = None;
+                shell::reveal(&path, &app.config.actions)
+            }
+            (_, KeyCode::Enter, Some(path)) if !app.allow_open(&path) => {
+                Err(anyhow::anyhow!(tr!(Vetoed)))
+            }
+            (_, KeyCode::Enter, Some(path)) => {
+                self.ui.opened = None;
+                shell::open(&path, &app.config.actions).and_then(|()| app.add_opened(&path))
+            }
+            // search it by the name, e.g. it is moved or renamed
+            (_, KeyCode::Tab, Some(_)) if app.is_offline() => {
+                self.ui.opened = None;
+                Err(anyhow::anyhow!(tr!(Offline)))
+            }
+            (_, KeyCode::Tab, Some(path)) => {
+                self.ui.opened = None;
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                self.ui.set_search_text(&name);
+                self.ui.unselect();
+                app.send_query(&name)
+            }
+            (_, KeyCode::Delete, Some(path)) => {
+                let forgot = app.opened.remove(&path);
+                let len = app.opened.files.len();
+                self.ui.opened = Some(selected.min(len.saturating_sub(1)));
+                forgot
+            }
+            _ => Ok(()),
+        };
+        if let Err(e) = done {
+            self.ui.notice = Some(format!("{e:#}"));
+        }
+        Ok(())
+    }
+
+    /// Put the volume into the search and query it, or take it out for all the volumes if none.
+    fn search_in_volume(&mut self, volume: Option<&str>, app: &mut App) -> Result<()> {
+        self.ui.volumes = None;
+        if app.is_offline() {
+            self.ui.notice = Some(tr!(Offline).to_string());
+            return Ok(());
+        }
+        let search = filter::set_volume(&self.ui.search_text(), volume);
+        self.ui.set_search_text(&search);
+        self.ui.unselect();
+        app.send_query(&search)
+    }
+
+    /// Sort by it and query again.
+    fn set_sort(&mut self, sort: (SortType, Option<SortKey>), app: &mut App) -> Result<()> {
+        (app.sort_type, app.secondary_sort) = sort;
+        app.is_sort_chosen = true;
+        let label = sort_label(app.sort_type, app.secondary_sort);
+        self.ui.notice = Some(tr!(SortBy, sort = label));
+        let search = app.last_search.clone();
+        app.send_query(&search)
+    }
+
+    /// Continue with the sort which is not fast sort, cancel it, or show the status of the
+    /// indexes and the fast sorts instead.
+    fn handle_slow_sort_key_events(&mut self, key_event: KeyEvent, app: &mut App) -> Result<()> {
+        let Some(sort) = self.ui.slow_sort.take() else {
+            return Ok(());
+        };
+        match key_event.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => self.set_sort(sort, app)?,
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {}
+            KeyCode::Char('s') | KeyCode::Char('S') => self.ui.is_popup_show = true,
+            KeyCode::Char('c') | KeyCode::Char('C')
+                if key_event.modifiers == KeyModifiers::CONTROL =>
+            {
+                self.quit();
+            }
+            _ => self.ui.slow_sort = Some(sort),
+        }
+        Ok(())
+    }
+
+    /// Tell the finished tasks, the last one in the notice.
+    fn handle_task_done(&mut self, app: &mut App) {
+        for (task, result) in app.finish_tasks() {
+            self.ui.notice =
+                Some(result.unwrap_or_else(|e| {
+                    tr!(TaskFailed, title = task.title, error = format!("{e:#}"))
+                }));
+        }
+        if let Some(selected) = self.ui.tasks {
+            let len = app.tasks.running().count();
+            self.ui.tasks = Some(selected.min(len.saturating_sub(1)));
+        }
+    }
+
+    fn handle_tasks_key_events(&mut self, key_event: KeyEvent, app: &mut App) -> Result<()> {
+        let Some(selected) = self.ui.tasks else {
+            return Ok(());
+        };
+        let ids: Vec<u64> = app.tasks.running().map(|task| task.id).collect();
+        let len = ids.len().max(1);
+        match (key_event.modifiers, key_event.code) {
+            (_, KeyCode::Esc | KeyCode::F(8)) => self.ui.tasks = None,
+            (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C')) => self.quit(),
+            (_, KeyCode::Up) => self.ui.tasks = Some(selected.checked_sub(1).unwrap_or(len - 1)),
+            (_, KeyCode::Down) => self.ui.tasks = Some((selected + 1) % len),
+            // it is taken out when its thread stops
+            (_, KeyCode::Delete) => {
+                if let Some(&id) = ids.get(selected) {
+                    app.tasks.cancel(id);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_report_key_events(&mut self, key_event: KeyEvent, app: &mut App) -> Result<()> {
+        match key_event.code {
+            KeyCode::Esc => self.ui.report = None,
+            KeyCode::Char('t') | KeyCode::Char('T')
+                if key_event.modifiers == KeyModifiers::CONTROL =>
+            {
+                self.ui.report = None;
+            }
+            KeyCode::Char('c') | KeyCode::Char('C')
+                if key_event.modifiers == KeyModifiers::CONTROL =>
+            {
+                self.quit();
+            }
+            // Fetch all the matches in the background, the report is updated when back
+            KeyCode::Char('f') | KeyCode::Char('F') => {
+                if app.is_offline() {
+                    self.ui.notice = Some(tr!(Offline).to_string());
+                } else if self
+                    .ui
+                    .report
+                    .as_ref()
+                    .is_some_and(|report| report.is_partial())
+                    && !app.is_full_report_pending()
+                {
+                    app.query_full_report()?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_history_key_events(&mut self, key_event: KeyEvent, app: &mut App) -> Result<()> {
+        match key_event.code {
+            KeyCode::Esc => {
+                self.ui.is_history_show = false;
+            }
+            KeyCode::Char('r') | KeyCode::Char('R')
+                if key_event.modifiers == KeyModifiers::CONTROL =>
+            {
+                self.ui.is_history_show = false;
+            }
+            KeyCode::Char('c') | KeyCode::Char('C')
+                if key_event.modifiers == KeyModifiers::CONTROL =>
+            {
+                self.quit();
+            }
+            // Switch between the most frequently and the most recently run
+            KeyCode::Tab => {
+                self.ui.is_history_by_recent = !self.ui.is_history_by_recent;
+                self.ui.history_state.select(Some(0));
+                app.query_run_history(self.ui.is_history_by_recent)?;
+            }
+            KeyCode::Up => self.ui.select_history_previous_n(1),
+            KeyCode::Down => self.ui.select_history_next_n(1, app),
+            KeyCode::Left => {
+                self.ui.history_scroll = self.ui.history_scroll.saturating_sub(ui::SCROLL_STEP);
+            }
+            KeyCode::Right => {
+                self.ui.history_scroll = self.ui.history_scroll.saturating_add(ui::SCROLL_STEP);
+            }
+            KeyCode::PageUp => {
+                let page_height = self.ui.last_page_height.unwrap_or(1) as usize;
+                self.ui.select_history_previous_n(page_height);
+            }
+            KeyCode::PageDown => {
+                let page_height = self.ui.last_page_height.unwrap_or(1) as usize;
+                self.ui.select_history_next_n(page_height, app);
+            }
+            // Launch the selected item
+            KeyCode::Enter => {
+                if let Some(path) = self.ui.get_selected_history_path(app) {
+                    // launch it by explorer like the launcher, not by the `open` command
+                    let reveal = key_event.modifiers == KeyModifiers::CONTROL;
+                    let opened = if !app.allow_open(&path) {
+                        Err(anyhow::anyhow!(tr!(Vetoed)))
+                    } else if reveal {
+                        shell::reveal(&path, &app.config.actions)
+                    } else {
+                        shell::explorer(&path, false).and_then(|()| app.add_opened(&path))
+                    };
+                    if let Err(e) = opened {
+                        self.ui.notice = Some(format!("{e:#}"));
+                    }
+                    self.ui.is_history_show = false;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn up(&mut self, app: &mut App) -> Result<()> {
+        if self.ui.is_compare_show() {
+            self.ui.select_compare_previous_n(1);
+        } else if !self.ui.is_focus_search_bar {
+            if self.ui.is_first_selected() {
+                self.ui.unselect();
+                self.ui.is_focus_search_bar = true;
+            } else {
+                self.ui.select_previous_n(1, app);
+            }
+        }
+        Ok(())
+    }
+
+    fn down(&mut self, app: &mut App) -> Result<()> {
+        if self.ui.is_compare_show() {
+            self.ui.select_compare_next_n(1);
+        } else if self.ui.is_focus_search_bar && app.query_results.load().number > 0 {
+            self.ui.select_first(app);
+            self.ui.is_focus_search_bar = false;
+        } else {
+            if !self.ui.is_selected() {
+                self.ui.select_first(app);
+            } else if app.config.keys.wrap && self.ui.is_last_selected(app) {
+                self.select_first(app)?;
+            } else {
+                self.ui.select_next_n(1, app);
+            }
+        }
+        Ok(())
+    }
+
+    /// Take the suggestion for no results, and query again.
+    fn take_suggestion(&mut self, index: usize, app: &mut App) -> Result<()> {
+        let Some(&suggestion) = app.suggestions().get(index) else {
+            return Ok(());
+        };
+        if let Some(feature) = suggestion
+            .requires()
+            .filter(|&feature| !app.status.supports(feature))
+        {
+            self.ui.notice = Some(feature.unsupported());
+            return Ok(());
+        }
+        let text = app.apply_suggestion(suggestion)?;
+        self.ui.set_search_text(&text);
+        Ok(())
+    }
+
+    /// Select the first result, querying the first ones again if the last ones are loaded.
+    fn select_first(&mut self, app: &mut App) -> Result<()> {
+        if app.query_first_results()? {
+            self.ui.jump = Some(Jump::First);
+        } else {
+            self.ui.select_first(app);
+        }
+        Ok(())
+    }
+
+    /// Select the last result, querying the last ones first if more match than loaded.
+    fn select_last(&mut self, app: &mut App) -> Result<()> {
+        if app.query_last_results()? {
+            self.ui.jump = Some(Jump::Last);
+        } else {
+            self.ui.select_last(app);
         }
         Ok(())
     }
 
     fn page_up(&mut self, app: &mut App) -> Result<()> {
-        if !self.ui.is_focus_search_bar {
+        if self.ui.is_compare_show() {
+            let page_height = self.ui.last_page_height.unwrap_or(1) as usize;
+            self.ui.select_compare_previous_n(page_height);
+        } else if !self.ui.is_focus_search_bar {
             self.ui.select_previous_page(app);
             // let old_offset = self.ui.list_state.offset();
             // let page_offset = self.ui.last_page_height.unwrap() as usize;
@@ -328,7 +2479,10 @@ impl<B: Backend> Tui<'_, B> {
     }
 
     fn page_down(&mut self, app: &mut App) -> Result<()> {
-        if !self.ui.is_focus_search_bar {
+        if self.ui.is_compare_show() {
+            let page_height = self.ui.last_page_height.unwrap_or(1) as usize;
+            self.ui.select_compare_next_n(page_height);
+        } else if !self.ui.is_focus_search_bar {
             self.ui.select_next_page(app);
             // let old_offset = self.ui.list_state.offset();
             // let page_offset = self.ui.last_page_height.unwrap() as usize;
@@ -0,0 +1,233 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// What the keys do in the main view, dispatched by [`super::Tui::run_action`].
+///
+/// The keys of them are all in [`BINDINGS`], for the help, the menus and the scripts to share.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// stop the transfer or the query, close the compare view, or quit.
+    Back,
+    Quit,
+    /// query the search text, or open the selected one.
+    Submit,
+    /// open the folder of the selected one and select it.
+    Reveal,
+    Properties,
+    ContextMenu,
+    FocusSearch,
+    /// focus the search bar with the text selected, for typing another search.
+    ReplaceSearch,
+    /// search the text of the clipboard, the name only of a path.
+    PasteSearch,
+    /// the search bar of several lines OR-combined, or back to one line.
+    ToggleMultiline,
+    /// the menu of the snippets of the search.
+    Templates,
+    /// the search bar, the results and the extension sidebar in turn.
+    NextFocus,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    SelectFirst,
+    SelectLast,
+    ScrollLeft,
+    ScrollRight,
+    Recycle,
+    /// the files recycled by ery in this run, to restore them.
+    RecycledFiles,
+    /// the tasks in the background with the progress, to cancel one.
+    Tasks,
+    Rename,
+    /// rename the marked ones at once, or the selected one if none.
+    BatchRename,
+    /// mark the selected one for renaming at once, or unmark it.
+    ToggleMark,
+    /// show the target of the selected shortcut, to open it or its folder.
+    ShortcutTarget,
+    /// the volumes of the results with their counts, to search in one.
+    Volumes,
+    /// the files opened through ery lately, to open or search them again.
+    OpenedFiles,
+    Move,
+    Copy,
+    Symlink,
+    Hardlink,
+    Extract,
+    /// keep the selected one at the top across the queries.
+    TogglePin,
+    /// write a note of the selected one, kept across the runs.
+    EditNote,
+    /// exclude the folder of the selected one from all the queries.
+    Exclude,
+    TogglePreview,
+    ToggleDebug,
+    ToggleStatus,
+    ToggleExtSidebar,
+    ToggleCompare,
+    Elevate,
+    CyclePathMode,
+    CycleSort,
+    /// the next named filter of the queries, e.g. `Audio`.
+    CycleFilter,
+    /// match the file names, the full paths, or the names with the folders containing them.
+    CycleScope,
+    /// the words of the search as globs, e.g. `src/**/*.rs`, or back to the syntax of Everything.
+    ToggleGlob,
+    /// the searches in the project of the launch folder, without its builds and dependencies.
+    ToggleProject,
+    /// the files changed today, the latest changed first.
+    RecentlyChanged,
+    DateFilter,
+    SizeFilter,
+    CommandsMenu,
+    DiskUsage,
+    Report,
+    RunHistory,
+    TakeBaseline,
+    OpenInEverything,
+    Requery,
+    /// go back to the query before, with its text, sort and scope.
+    UndoQuery,
+    RedoQuery,
+    SaveFileList,
+    SaveSnapshot,
+}
+
+/// Where the key works.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum When {
+    Always,
+    /// the results are focused, the key is for typing in the search bar otherwise.
+    Results,
+}
+
+/// A key of an action.
+#[derive(Debug, Clone, Copy)]
+pub struct Binding {
+    pub code: KeyCode,
+    /// none for any modifiers.
+    pub modifiers: Option<KeyModifiers>,
+    pub when: When,
+    pub action: Action,
+}
+
+const fn bind(
+    code: KeyCode,
+    modifiers: Option<KeyModifiers>,
+    when: When,
+    action: Action,
+) -> Binding {
+    Binding {
+        code,
+        modifiers,
+        when,
+        action,
+    }
+}
+
+const CTRL: Option<KeyModifiers> = Some(KeyModifiers::CONTROL);
+const ALT: Option<KeyModifiers> = Some(KeyModifiers::ALT);
+const SHIFT: Option<KeyModifiers> = Some(KeyModifiers::SHIFT);
+const CTRL_SHIFT: Option<KeyModifiers> = Some(KeyModifiers::CONTROL.union(KeyModifiers::SHIFT));
+const NONE: Option<KeyModifiers> = Some(KeyModifiers::NONE);
+const ANY: Option<KeyModifiers> = None;
+
+/// The built-in keys of the main view, the first one matching is taken.
+pub const BINDINGS: &[Binding] = {
+    use Action::*;
+    use KeyCode::{Char, F};
+    use When::*;
+    &[
+        bind(KeyCode::Esc, ANY, Always, Back),
+        bind(Char('c'), CTRL, Always, Quit),
+        bind(KeyCode::Enter, ALT, Results, Properties),
+        bind(KeyCode::Enter, ALT, Always, ToggleMultiline),
+        bind(KeyCode::Enter, CTRL, Results, Reveal),
+        bind(KeyCode::Enter, ANY, Always, Submit),
+        bind(F(10), SHIFT, Results, ContextMenu),
+        bind(KeyCode::Menu, ANY, Results, ContextMenu),
+        bind(KeyCode::Backspace, ANY, Results, FocusSearch),
+        bind(Char('/'), ANY, Results, ReplaceSearch),
+        bind(Char('v'), CTRL_SHIFT, Always, PasteSearch),
+        bind(KeyCode::Tab, ANY, Always, NextFocus),
+        bind(KeyCode::Up, ANY, Always, Up),
+        bind(KeyCode::Down, ANY, Always, Down),
+        bind(KeyCode::PageUp, ANY, Always, PageUp),
+        bind(KeyCode::PageDown, ANY, Always, PageDown),
+        bind(KeyCode::Home, ANY, Results, SelectFirst),
+        bind(KeyCode::End, ANY, Results, SelectLast),
+        bind(KeyCode::Left, ANY, Results, ScrollLeft),
+        bind(KeyCode::Right, ANY, Results, ScrollRight),
+        bind(KeyCode::Delete, ALT, Always, RecycledFiles),
+        bind(KeyCode::Delete, ANY, Results, Recycle),
+        bind(F(2), SHIFT, Always, BatchRename),
+        bind(F(2), ANY, Always, Rename),
+        bind(Char(' '), NONE, Results, ToggleMark),
+        bind(F(6), ANY, Always, Move),
+        bind(F(7), ANY, Always, Copy),
+        bind(Char('l'), ALT, Always, Symlink),
+        bind(Char('h'), ALT, Always, Hardlink),
+        bind(Char('x'), ALT, Always, Extract),
+        bind(Char('o'), ALT, Always, ShortcutTarget),
+        bind(Char('p'), NONE, Results, TogglePin),
+        bind(Char('n'), NONE, Results, EditNote),
+        bind(Char('x'), NONE, Results, Exclude),
+        bind(F(3), ANY, Always, TogglePreview),
+        bind(F(4), ANY, Always, CycleScope),
+        bind(Char('g'), ALT, Always, ToggleGlob),
+        bind(Char('p'), ALT, Always, ToggleProject),
+        bind(F(12), ANY, Always, ToggleDebug),
+        bind(Char('.'), CTRL, Always, ToggleStatus),
+        bind(Char('e'), ALT, Always, ToggleExtSidebar),
+        bind(Char('v'), ALT, Always, Volumes),
+        bind(Char('k'), CTRL, Always, ToggleCompare),
+        bind(Char('e'), CTRL, Always, Elevate),
+        bind(Char('p'), CTRL, Always, CyclePathMode),
+        bind(Char('o'), CTRL, Always, CycleSort),
+        bind(Char('l'), CTRL, Always, CycleFilter),
+        bind(Char('r'), ALT, Always, RecentlyChanged),
+        bind(Char('d'), CTRL, Always, DateFilter),
+        bind(Char('f'), CTRL, Always, SizeFilter),
+        bind(F(8), ANY, Always, Tasks),
+        bind(F(9), ANY, Always, CommandsMenu),
+        bind(Char('u'), CTRL, Always, DiskUsage),
+        bind(Char('t'), CTRL, Always, Report),
+        bind(Char('r'), CTRL, Always, RunHistory),
+        bind(Char('h'), CTRL, Always, OpenedFiles),
+        bind(Char('b'), CTRL, Always, TakeBaseline),
+        bind(Char('g'), CTRL, Always, OpenInEverything),
+        bind(F(5), ANY, Always, Requery),
+        bind(Char('z'), CTRL, Results, UndoQuery),
+        bind(Char('y'), CTRL, Results, RedoQuery),
+        bind(Char('z'), CTRL_SHIFT, Results, RedoQuery),
+        bind(Char('s'), ALT, Always, SaveFileList),
+        bind(Char('s'), CTRL, Always, SaveSnapshot),
+        bind(Char('t'), ALT, Always, Templates),
+    ]
+};
+
+impl Binding {
+    fn matches(&self, key_event: &KeyEvent, is_focus_search_bar: bool) -> bool {
+        // the chars come in uppercase with shift
+        let code = match key_event.code {
+            KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+            code => code,
+        };
+        code == self.code
+            && self
+                .modifiers
+                .is_none_or(|modifiers| modifiers == key_event.modifiers)
+            && (self.when == When::Always || !is_focus_search_bar)
+    }
+}
+
+impl Action {
+    /// The action of the key, none if it is for typing in the search bar.
+    pub fn of(key_event: &KeyEvent, is_focus_search_bar: bool) -> Option<Self> {
+        BINDINGS
+            .iter()
+            .find(|binding| binding.matches(key_event, is_focus_search_bar))
+            .map(|binding| binding.action)
+    }
+}
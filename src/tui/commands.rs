@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+
+use anyhow::Context;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::config::UserCommand;
+
+/// A user command in config, with its key parsed.
+#[derive(Debug, Clone)]
+pub struct Command {
+    pub name: String,
+    pub run: String,
+    pub key: Option<KeyBinding>,
+    pub console: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct KeyBinding {
+    modifiers: KeyModifiers,
+    code: KeyCode,
+    /// as written in config, for showing in the menu.
+    pub label: String,
+}
+
+impl KeyBinding {
+    /// Parse the key like `alt+v`, `ctrl+shift+k` or `f6`.
+    ///
+    /// The keys of printable chars need `ctrl` or `alt`, or they could not be typed in the
+    /// search bar any more.
+    pub fn parse(s: &str) -> anyhow::Result<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut parts: Vec<&str> = s.split('+').map(str::trim).collect();
+        let key = parts.pop().unwrap_or_default().to_lowercase();
+        for modifier in parts {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => anyhow::bail!("unknown modifier {modifier:?} in key {s:?}"),
+            };
+        }
+        let code = match key.as_str() {
+            "enter" => KeyCode::Enter,
+            "tab" => KeyCode::Tab,
+            "space" => KeyCode::Char(' '),
+            "backspace" => KeyCode::Backspace,
+            "delete" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            _ => match key.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                Some(n @ 1..=24) => KeyCode::F(n),
+                _ => {
+                    let mut chars = key.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(c), None) => KeyCode::Char(c),
+                        _ => anyhow::bail!("unknown key {s:?}"),
+                    }
+                }
+            },
+        };
+        let is_text = !matches!(code, KeyCode::F(_))
+            && !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+        anyhow::ensure!(!is_text, "key {s:?} needs ctrl or alt, it is for typing");
+        Ok(Self {
+            modifiers,
+            code,
+            label: s.to_string(),
+        })
+    }
+
+    pub fn matches(&self, key_event: &KeyEvent) -> bool {
+        // the chars come in uppercase with shift
+        let code = match key_event.code {
+            KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+            code => code,
+        };
+        code == self.code && key_event.modifiers == self.modifiers
+    }
+}
+
+/// Parse the user commands in config, ordered by name.
+pub fn load(commands: &BTreeMap<String, UserCommand>) -> anyhow::Result<Vec<Command>> {
+    commands
+        .iter()
+        .map(|(name, command)| {
+            let options = command.options();
+            let key = options
+                .key
+                .as_deref()
+                .map(KeyBinding::parse)
+                .transpose()
+                .with_context(|| format!("invalid key of command {name:?} in config"))?;
+            Ok(Command {
+                name: name.clone(),
+                run: options.run,
+                key,
+                console: options.console,
+            })
+        })
+        .collect()
+}
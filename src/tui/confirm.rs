@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use crate::app::rename::Renaming;
+use crate::app::transfer::TransferKind;
+use crate::tr;
+
+/// Files recycled at once from this count on are confirmed by typing the count.
+const BULK: usize = 2;
+
+/// A destructive action waiting for the confirmation.
+#[derive(Debug)]
+pub enum ConfirmAction {
+    /// move the files to the Recycle Bin.
+    Recycle(Vec<PathBuf>),
+    /// move or copy the file to the target path, replacing it if it exists.
+    Transfer(TransferKind, PathBuf, PathBuf),
+    /// rename the files as planned.
+    BatchRename(Vec<Renaming>),
+    /// extract the archive into the folder.
+    Extract(PathBuf, PathBuf),
+}
+
+impl ConfirmAction {
+    /// What it does, in a line.
+    pub fn describe(&self) -> String {
+        match self {
+            ConfirmAction::Recycle(paths) => match paths.as_slice() {
+                [path] => tr!(ConfirmRecycle, path = path.display()),
+                _ => tr!(ConfirmRecycleCount, count = paths.len()),
+            },
+            ConfirmAction::Transfer(kind, source, target) => tr!(
+                ConfirmTransfer,
+                verb = kind.verb(),
+                path = source.display(),
+                target = target.display()
+            ),
+            ConfirmAction::BatchRename(renamings) => tr!(
+                ConfirmBatchRename,
+                count = renamings.iter().filter(|r| r.is_changed()).count()
+            ),
+            ConfirmAction::Extract(source, folder) => tr!(
+                ConfirmExtract,
+                path = source.display(),
+                target = folder.display()
+            ),
+        }
+    }
+}
+
+/// A popup asking to go on with a destructive action, by `y`, or by typing the count of the
+/// files for the bulk ones, so that a stray key does not recycle all the marked.
+#[derive(Debug)]
+pub struct Confirm {
+    pub action: ConfirmAction,
+    /// the count to type, none for `y`.
+    pub count: Option<usize>,
+    pub typed: String,
+}
+
+impl Confirm {
+    pub fn new(action: ConfirmAction) -> Self {
+        let count = match &action {
+            ConfirmAction::Recycle(paths) if paths.len() >= BULK => Some(paths.len()),
+            _ => None,
+        };
+        Self {
+            action,
+            count,
+            typed: String::new(),
+        }
+    }
+
+    /// Whether the count typed is the right one, for the bulk ones.
+    pub fn is_count_typed(&self) -> bool {
+        self.count
+            .is_some_and(|count| self.typed.trim() == count.to_string())
+    }
+}
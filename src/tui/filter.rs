@@ -0,0 +1,345 @@
+use chrono::{Days, Local, NaiveDate};
+
+use crate::i18n::Msg;
+use crate::tr;
+
+/// Format of the dates typed in the pickers, which Everything accepts too.
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Popups building the filters of Everything search syntax, which are inserted into the search
+/// text by [`set_filter`].
+#[derive(Debug)]
+pub enum FilterPicker {
+    Date(DatePicker),
+    Size(SizePicker),
+}
+
+impl FilterPicker {
+    pub fn select_previous(&mut self) {
+        let (selected, rows) = self.selection();
+        *selected = selected.checked_sub(1).unwrap_or(rows - 1);
+    }
+
+    pub fn select_next(&mut self) {
+        let (selected, rows) = self.selection();
+        *selected = (*selected + 1) % rows;
+    }
+
+    fn selection(&mut self) -> (&mut usize, usize) {
+        match self {
+            FilterPicker::Date(picker) => (&mut picker.selected, DatePicker::TO_ROW + 1),
+            FilterPicker::Size(picker) => (&mut picker.selected, SizePicker::MAX_ROW + 1),
+        }
+    }
+
+    /// Switch the date field, or the unit of the size being typed.
+    pub fn tab(&mut self) {
+        match self {
+            FilterPicker::Date(picker) => picker.toggle_field(),
+            FilterPicker::Size(picker) => {
+                if let Some(bound) = picker.editing() {
+                    bound.unit = bound.unit.next();
+                }
+            }
+        }
+    }
+
+    /// Move the value being typed a step, a day for dates, one unit for sizes.
+    pub fn step(&mut self, delta: i64) {
+        match self {
+            FilterPicker::Date(picker) => picker.step_days(delta),
+            FilterPicker::Size(picker) => {
+                if let Some(bound) = picker.editing() {
+                    let value = bound.value.parse::<u64>().unwrap_or_default();
+                    bound.value = value.saturating_add_signed(delta).to_string();
+                }
+            }
+        }
+    }
+
+    pub fn push(&mut self, c: char) {
+        match self {
+            FilterPicker::Date(picker) if c.is_ascii_digit() || c == '-' => {
+                if let Some(date) = picker.editing() {
+                    date.push(c);
+                }
+            }
+            FilterPicker::Size(picker) if c.is_ascii_digit() => {
+                if let Some(bound) = picker.editing() {
+                    bound.value.push(c);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn pop(&mut self) {
+        match self {
+            FilterPicker::Date(picker) => picker.editing().and_then(String::pop),
+            FilterPicker::Size(picker) => picker.editing().and_then(|bound| bound.value.pop()),
+        };
+    }
+
+    pub fn filter(&self) -> Result<String, String> {
+        match self {
+            FilterPicker::Date(picker) => picker.filter(),
+            FilterPicker::Size(picker) => picker.filter(),
+        }
+    }
+}
+
+/// Popup building a `dm:`/`dc:` filter of Everything search syntax.
+///
+/// Ref: https://www.voidtools.com/support/everything/searching/#date_syntax
+#[derive(Debug, Default)]
+pub struct DatePicker {
+    pub field: DateField,
+    /// index in [`DatePicker::PRESETS`], then the `from` and the `to` rows.
+    pub selected: usize,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DateField {
+    #[default]
+    Modified,
+    Created,
+}
+
+impl DateField {
+    pub fn prefix(self) -> &'static str {
+        match self {
+            DateField::Modified => "dm:",
+            DateField::Created => "dc:",
+        }
+    }
+}
+
+impl DatePicker {
+    /// Labels and the Everything date constants of the presets.
+    pub const PRESETS: [(Msg, &'static str); 4] = [
+        (Msg::Today, "today"),
+        (Msg::Yesterday, "yesterday"),
+        (Msg::Last7Days, "last7days"),
+        (Msg::ThisMonth, "thismonth"),
+    ];
+    pub const FROM_ROW: usize = Self::PRESETS.len();
+    pub const TO_ROW: usize = Self::PRESETS.len() + 1;
+
+    pub fn toggle_field(&mut self) {
+        self.field = match self.field {
+            DateField::Modified => DateField::Created,
+            DateField::Created => DateField::Modified,
+        };
+    }
+
+    /// The date being typed, if a row of the range is selected.
+    pub fn editing(&mut self) -> Option<&mut String> {
+        match self.selected {
+            Self::FROM_ROW => Some(&mut self.from),
+            Self::TO_ROW => Some(&mut self.to),
+            _ => None,
+        }
+    }
+
+    /// Move the date being typed by days, from today if it is empty or invalid.
+    pub fn step_days(&mut self, days: i64) {
+        let Some(date) = self.editing() else {
+            return;
+        };
+        let today = Local::now().date_naive();
+        let day = NaiveDate::parse_from_str(date, DATE_FORMAT).unwrap_or(today);
+        let day = if days < 0 {
+            day.checked_sub_days(Days::new(days.unsigned_abs()))
+        } else {
+            day.checked_add_days(Days::new(days as u64))
+        };
+        if let Some(day) = day {
+            *date = day.format(DATE_FORMAT).to_string();
+        }
+    }
+
+    /// The filter of the selected preset or the range, e.g. `dm:today` or
+    /// `dc:2024-01-01..2024-01-31`.
+    pub fn filter(&self) -> Result<String, String> {
+        let value = match Self::PRESETS.get(self.selected) {
+            Some((_, constant)) => constant.to_string(),
+            None => {
+                let from = parse_date(&self.from)?;
+                let to = parse_date(&self.to)?;
+                match (from, to) {
+                    (Some(from), Some(to)) if from > to => {
+                        return Err(tr!(DateAfter, from = self.from, to = self.to));
+                    }
+                    (Some(_), Some(_)) => format!("{}..{}", self.from, self.to),
+                    (Some(_), None) => format!(">={}", self.from),
+                    (None, Some(_)) => format!("<={}", self.to),
+                    (None, None) => return Err(tr!(TypeDate).to_string()),
+                }
+            }
+        };
+        Ok(format!("{}{value}", self.field.prefix()))
+    }
+}
+
+/// Popup building a `size:` filter of Everything search syntax.
+///
+/// Ref: https://www.voidtools.com/support/everything/searching/#size_syntax
+#[derive(Debug, Default)]
+pub struct SizePicker {
+    /// index in [`SizePicker::PRESETS`], then the `min` and the `max` rows.
+    pub selected: usize,
+    pub min: SizeBound,
+    pub max: SizeBound,
+}
+
+#[derive(Debug, Default)]
+pub struct SizeBound {
+    /// digits typed, empty for no bound.
+    pub value: String,
+    pub unit: SizeUnit,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SizeUnit {
+    Bytes,
+    KB,
+    #[default]
+    MB,
+    GB,
+}
+
+impl SizeUnit {
+    pub fn label(self) -> &'static str {
+        match self {
+            SizeUnit::Bytes => "B",
+            SizeUnit::KB => "KB",
+            SizeUnit::MB => "MB",
+            SizeUnit::GB => "GB",
+        }
+    }
+
+    fn suffix(self) -> &'static str {
+        match self {
+            SizeUnit::Bytes => "",
+            SizeUnit::KB => "kb",
+            SizeUnit::MB => "mb",
+            SizeUnit::GB => "gb",
+        }
+    }
+
+    fn bytes(self) -> u64 {
+        match self {
+            SizeUnit::Bytes => 1,
+            SizeUnit::KB => 1 << 10,
+            SizeUnit::MB => 1 << 20,
+            SizeUnit::GB => 1 << 30,
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            SizeUnit::Bytes => SizeUnit::KB,
+            SizeUnit::KB => SizeUnit::MB,
+            SizeUnit::MB => SizeUnit::GB,
+            SizeUnit::GB => SizeUnit::Bytes,
+        }
+    }
+}
+
+impl SizeBound {
+    /// Value in Everything syntax and in bytes, none if nothing is typed.
+    fn parse(&self) -> Option<(String, u64)> {
+        let value: u64 = self.value.parse().ok()?;
+        Some((
+            format!("{value}{}", self.unit.suffix()),
+            value.saturating_mul(self.unit.bytes()),
+        ))
+    }
+}
+
+impl SizePicker {
+    /// Labels and the filters of the presets.
+    pub const PRESETS: [(Msg, &'static str); 2] =
+        [(Msg::EmptyFiles, "empty"), (Msg::LargerThan1Gb, ">1gb")];
+    pub const MIN_ROW: usize = Self::PRESETS.len();
+    pub const MAX_ROW: usize = Self::PRESETS.len() + 1;
+
+    /// The bound being typed, if a row of the range is selected.
+    pub fn editing(&mut self) -> Option<&mut SizeBound> {
+        match self.selected {
+            Self::MIN_ROW => Some(&mut self.min),
+            Self::MAX_ROW => Some(&mut self.max),
+            _ => None,
+        }
+    }
+
+    /// The filter of the selected preset or the range, e.g. `size:empty` or `size:1mb..2gb`.
+    pub fn filter(&self) -> Result<String, String> {
+        let value = match Self::PRESETS.get(self.selected) {
+            Some((_, preset)) => preset.to_string(),
+            None => match (self.min.parse(), self.max.parse()) {
+                (Some((min, a)), Some((max, b))) if a > b => {
+                    return Err(tr!(SizeLarger, min = min, max = max));
+                }
+                (Some((min, _)), Some((max, _))) => format!("{min}..{max}"),
+                (Some((min, _)), None) => format!(">={min}"),
+                (None, Some((max, _))) => format!("<={max}"),
+                (None, None) => return Err(tr!(TypeSize).to_string()),
+            },
+        };
+        Ok(format!("size:{value}"))
+    }
+}
+
+fn parse_date(s: &str) -> Result<Option<NaiveDate>, String> {
+    if s.is_empty() {
+        return Ok(None);
+    }
+    NaiveDate::parse_from_str(s, DATE_FORMAT)
+        .map(Some)
+        .map_err(|_| tr!(InvalidDate, date = s))
+}
+
+/// Put the filter into the search text, replacing the filters of the same function, e.g. an
+/// old `dm:` one, so picking again refines the search instead of contradicting it.
+pub fn set_filter(search: &str, filter: &str) -> String {
+    let function = filter
+        .split_once(':')
+        .map_or(filter, |(function, _)| function);
+    let mut terms: Vec<&str> = search
+        .split_whitespace()
+        .filter(|term| {
+            !term
+                .split_once(':')
+                .is_some_and(|(f, _)| f.eq_ignore_ascii_case(function))
+        })
+        .collect();
+    terms.push(filter);
+    terms.join(" ")
+}
+
+/// Put the volume into the search text as `C:\`, replacing the one put before, or take it out
+/// for all the volumes if none.
+pub fn set_volume(search: &str, volume: Option<&str>) -> String {
+    let volume = volume.map(|volume| format!("{volume}\\"));
+    let mut terms: Vec<&str> = search
+        .split_whitespace()
+        .filter(|term| !is_volume(term))
+        .collect();
+    terms.extend(volume.as_deref());
+    terms.join(" ")
+}
+
+/// Like `C:`, `C:\` or `\\server\share\`.
+fn is_volume(term: &str) -> bool {
+    let term = term.strip_suffix('\\').unwrap_or(term);
+    let bytes = term.as_bytes();
+    let is_drive = bytes.len() == 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':';
+    let is_share = term.strip_prefix(r"\\").is_some_and(|share| {
+        let parts: Vec<&str> = share.split('\\').collect();
+        parts.len() == 2 && parts.iter().all(|part| !part.is_empty())
+    });
+    is_drive || is_share
+}
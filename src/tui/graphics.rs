@@ -0,0 +1,282 @@
+use std::{
+    env,
+    fmt::Write as _,
+    fs,
+    io::{self, Cursor, Write},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use anyhow::Context;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use crossterm::{cursor::MoveTo, queue, terminal};
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use ratatui::layout::Rect;
+
+use super::loader::Loader;
+use crate::app::event::AppSender;
+
+/// Images can be decoded for the thumbnails, with the enabled features of `image`.
+const IMAGE_EXTENSIONS: [&str; 9] = [
+    "png", "jpg", "jpeg", "gif", "bmp", "webp", "ico", "tif", "tiff",
+];
+
+/// Assumed when the terminal doesn't report its size in pixels, e.g. on Windows.
+const DEFAULT_CELL_SIZE: (u32, u32) = (8, 16);
+
+/// Max length of one chunk of the kitty graphics protocol.
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+/// Protocols to show images in terminals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    /// Ref: https://sw.kovidgoyal.net/kitty/graphics-protocol/
+    Kitty,
+    /// Ref: https://iterm2.com/documentation-images.html
+    Iterm,
+    /// Ref: https://vt100.net/docs/vt3xx-gp/chapter14.html
+    Sixel,
+}
+
+impl Protocol {
+    /// Guess the protocol from the environment variables, querying the terminal would race with
+    /// the event thread for the responses.
+    pub fn detect() -> Option<Self> {
+        let var = |key| env::var(key).unwrap_or_default();
+        let (term, program) = (var("TERM"), var("TERM_PROGRAM"));
+        if term.contains("kitty")
+            || env::var_os("KITTY_WINDOW_ID").is_some()
+            || program == "WezTerm"
+            || program == "ghostty"
+        {
+            Some(Protocol::Kitty)
+        } else if program == "iTerm.app" {
+            Some(Protocol::Iterm)
+        } else if env::var_os("WT_SESSION").is_some()
+            || term.contains("sixel")
+            || term.starts_with("foot")
+            || term == "mlterm"
+        {
+            // Windows Terminal supports sixel since 1.22
+            Some(Protocol::Sixel)
+        } else {
+            None
+        }
+    }
+}
+
+/// An image by the path, the date modified and the size of the area in cells.
+type ImageKey = (PathBuf, Option<SystemTime>, (u16, u16));
+
+/// Thumbnails drawn on the terminal directly, out of the buffer of ratatui.
+#[derive(Debug)]
+pub struct Graphics {
+    protocol: Protocol,
+    /// pixels of one cell.
+    cell_size: (u32, u32),
+    /// the image on the screen now.
+    shown: Option<(PathBuf, Rect)>,
+    /// the images encoded in the background.
+    encoded: Loader<ImageKey, anyhow::Result<String>>,
+}
+
+impl Graphics {
+    pub fn new(protocol: Protocol) -> Self {
+        let cell_size = terminal::window_size()
+            .ok()
+            .filter(|size| size.width > 0 && size.columns > 0 && size.rows > 0)
+            .map_or(DEFAULT_CELL_SIZE, |size| {
+                (
+                    (size.width / size.columns) as u32,
+                    (size.height / size.rows) as u32,
+                )
+            });
+        Self {
+            protocol,
+            cell_size,
+            shown: None,
+            encoded: Loader::default(),
+        }
+    }
+
+    /// Show the image in the area, or remove the shown image if none.
+    ///
+    /// Return true if the screen needs a repaint to wipe the old image, then call it again after
+    /// the repaint to show the new one. The new one is shown when it is called after it is
+    /// decoded in the background, the frontend is refreshed for it.
+    pub fn update(
+        &mut self,
+        target: Option<(&Path, Rect)>,
+        sender: &AppSender,
+    ) -> io::Result<bool> {
+        if self
+            .shown
+            .as_ref()
+            .map(|(path, area)| (path.as_path(), *area))
+            == target
+        {
+            return Ok(false);
+        }
+        let mut stdout = io::stdout().lock();
+        if self.shown.take().is_some() {
+            match self.protocol {
+                Protocol::Kitty => stdout.write_all(b"\x1b_Ga=d,q=2\x1b\\")?,
+                // the image is in the cells, only overwriting the cells can wipe it
+                Protocol::Iterm | Protocol::Sixel => return Ok(true),
+            }
+        }
+        if let Some((path, area)) = target {
+            let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+            let (protocol, cell_size) = (self.protocol, self.cell_size);
+            let image = self.encoded.get(
+                &(path.to_path_buf(), modified, (area.width, area.height)),
+                sender,
+                move |(path, _, (width, height))| {
+                    encode(protocol, cell_size, path, (*width, *height))
+                },
+            );
+            if let Some(image) = image {
+                // it is not retried if failed, the preview shows the info only
+                if let Ok(image) = image.as_ref() {
+                    queue!(stdout, MoveTo(area.x, area.y))?;
+                    stdout.write_all(image.as_bytes())?;
+                }
+                self.shown = Some((path.to_path_buf(), area));
+            }
+        }
+        stdout.flush()?;
+        Ok(false)
+    }
+
+    /// Forget the shown image, e.g. the screen is cleared.
+    pub fn clear(&mut self) -> io::Result<()> {
+        if self.shown.take().is_some() && self.protocol == Protocol::Kitty {
+            io::stdout().write_all(b"\x1b_Ga=d,q=2\x1b\\")?;
+        }
+        Ok(())
+    }
+}
+
+/// Decode the image, downscale it to fit in the area of `(width, height)` cells, then encode it
+/// in the protocol.
+fn encode(
+    protocol: Protocol,
+    (cell_width, cell_height): (u32, u32),
+    path: &Path,
+    (width, height): (u16, u16),
+) -> anyhow::Result<String> {
+    let (max_width, max_height) = (width as u32 * cell_width, height as u32 * cell_height);
+    let mut image =
+        image::open(path).with_context(|| format!("failed to decode image {}", path.display()))?;
+    if image.width() > max_width || image.height() > max_height {
+        image = image.thumbnail(max_width, max_height);
+    }
+    Ok(match protocol {
+        Protocol::Kitty => kitty(&image.to_rgba8()),
+        Protocol::Iterm => iterm(&image)?,
+        Protocol::Sixel => sixel(&image.to_rgba8()),
+    })
+}
+
+pub fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            IMAGE_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+        })
+}
+
+/// Raw RGBA pixels in chunks, without any response from the terminal (`q=2`).
+fn kitty(image: &RgbaImage) -> String {
+    let data = BASE64.encode(image.as_raw());
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(KITTY_CHUNK_SIZE).collect();
+    let mut out = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = (i + 1 < chunks.len()) as u8;
+        let chunk = std::str::from_utf8(chunk).unwrap(); // base64 is ascii
+        if i == 0 {
+            let (width, height) = image.dimensions();
+            let _ = write!(
+                out,
+                "\x1b_Ga=T,f=32,s={width},v={height},q=2,m={more};{chunk}\x1b\\"
+            );
+        } else {
+            let _ = write!(out, "\x1b_Gm={more};{chunk}\x1b\\");
+        }
+    }
+    out
+}
+
+fn iterm(image: &DynamicImage) -> anyhow::Result<String> {
+    let mut png = Vec::new();
+    image.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+    Ok(format!(
+        "\x1b]1337;File=inline=1;size={};width={}px;height={}px:{}\x07",
+        png.len(),
+        image.width(),
+        image.height(),
+        BASE64.encode(&png)
+    ))
+}
+
+/// Sixel with the 6x6x6 color cube, the transparent pixels are left as the background.
+fn sixel(image: &RgbaImage) -> String {
+    let (width, height) = image.dimensions();
+    let mut out = format!("\x1bP0;1;q\"1;1;{width};{height}");
+    for i in 0..216 {
+        let (r, g, b) = (i / 36, i / 6 % 6, i % 6);
+        let _ = write!(out, "#{i};2;{};{};{}", r * 20, g * 20, b * 20);
+    }
+    for top in (0..height).step_by(6) {
+        let bottom = (top + 6).min(height);
+        let mut used = [false; 216];
+        for y in top..bottom {
+            for x in 0..width {
+                if let Some(color) = palette_index(image.get_pixel(x, y)) {
+                    used[color] = true;
+                }
+            }
+        }
+        for color in (0..216).filter(|&color| used[color]) {
+            let _ = write!(out, "#{color}");
+            let mut run: Option<(char, u32)> = None;
+            for x in 0..width {
+                let bits = (top..bottom)
+                    .filter(|&y| palette_index(image.get_pixel(x, y)) == Some(color))
+                    .fold(0, |bits, y| bits | 1 << (y - top));
+                let sixel = char::from(63 + bits as u8);
+                match &mut run {
+                    Some((c, count)) if *c == sixel => *count += 1,
+                    _ => {
+                        if let Some((c, count)) = run {
+                            push_run(&mut out, c, count);
+                        }
+                        run = Some((sixel, 1));
+                    }
+                }
+            }
+            if let Some((c, count)) = run {
+                push_run(&mut out, c, count);
+            }
+            // back to the start of the band for the next color
+            out.push('$');
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+fn palette_index(pixel: &Rgba<u8>) -> Option<usize> {
+    let [r, g, b, a] = pixel.0;
+    let level = |c: u8| (c as usize * 5 + 127) / 255;
+    (a >= 128).then(|| level(r) * 36 + level(g) * 6 + level(b))
+}
+
+fn push_run(out: &mut String, c: char, count: u32) {
+    if count > 3 {
+        let _ = write!(out, "!{count}{c}");
+    } else {
+        out.push_str(&c.to_string().repeat(count as usize));
+    }
+}
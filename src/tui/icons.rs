@@ -0,0 +1,160 @@
+use std::{collections::HashMap, path::Path};
+
+use crate::app::ery::QueryEntry;
+use crate::config::{IconMode, IconsConfig};
+
+/// Icon of the entries, keyed by the extension (or the kind of the entry).
+#[derive(Debug, Default)]
+pub struct Icons {
+    mode: IconMode,
+    /// user defined icons, keyed by the lowercase extension, or `folder`/`volume`/`file`.
+    overrides: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Volume,
+    Folder,
+    File,
+    Image,
+    Audio,
+    Video,
+    Archive,
+    Pdf,
+    Document,
+    Spreadsheet,
+    Presentation,
+    Text,
+    Code,
+    Executable,
+    Shortcut,
+}
+
+impl Icons {
+    pub fn new(config: &IconsConfig, no_icons: bool) -> Self {
+        Self {
+            mode: if no_icons {
+                IconMode::None
+            } else {
+                config.mode
+            },
+            overrides: config
+                .extensions
+                .iter()
+                .map(|(k, v)| (k.to_lowercase(), v.clone()))
+                .collect(),
+        }
+    }
+
+    /// The icon followed by a space, or empty if icons are disabled.
+    pub fn icon(&self, entry: &QueryEntry) -> String {
+        if self.mode == IconMode::None {
+            return String::new();
+        }
+        let extension = entry_extension(entry);
+        let kind = kind_of(entry, extension.as_deref());
+        let key = match kind {
+            Kind::Volume => Some("volume"),
+            Kind::Folder => Some("folder"),
+            _ => extension.as_deref(),
+        };
+        let icon = key
+            .and_then(|key| self.overrides.get(key))
+            .or_else(|| self.overrides.get("file").filter(|_| kind == Kind::File))
+            .map(String::as_str)
+            .unwrap_or(match self.mode {
+                IconMode::Nerd => nerd_glyph(kind),
+                _ => emoji(kind),
+            });
+        format!("{icon} ")
+    }
+
+    /// The mark of the pinned entries followed by a space, a plain one if icons are disabled.
+    pub fn pin(&self) -> &'static str {
+        match self.mode {
+            IconMode::Emoji => "📌 ",
+            IconMode::Nerd => "\u{f435} ",
+            IconMode::None => "* ",
+        }
+    }
+}
+
+fn entry_extension(entry: &QueryEntry) -> Option<String> {
+    let extension = match &entry.extension {
+        Some(extension) => extension.clone(),
+        None => Path::new(entry.filename.as_ref()?)
+            .extension()?
+            .to_os_string(),
+    };
+    Some(extension.to_string_lossy().to_lowercase())
+}
+
+fn kind_of(entry: &QueryEntry, extension: Option<&str>) -> Kind {
+    if entry.is_volume {
+        return Kind::Volume;
+    }
+    if entry.is_folder {
+        return Kind::Folder;
+    }
+    match extension.unwrap_or_default() {
+        "png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp" | "ico" | "svg" | "tif" | "tiff"
+        | "heic" | "psd" => Kind::Image,
+        "mp3" | "flac" | "wav" | "ogg" | "m4a" | "aac" | "wma" | "ape" => Kind::Audio,
+        "mp4" | "mkv" | "avi" | "mov" | "wmv" | "flv" | "webm" | "m4v" => Kind::Video,
+        "zip" | "7z" | "rar" | "tar" | "gz" | "xz" | "bz2" | "zst" | "cab" | "iso" => Kind::Archive,
+        "pdf" => Kind::Pdf,
+        "doc" | "docx" | "odt" | "rtf" => Kind::Document,
+        "xls" | "xlsx" | "ods" | "csv" => Kind::Spreadsheet,
+        "ppt" | "pptx" | "odp" => Kind::Presentation,
+        "txt" | "md" | "log" | "ini" | "toml" | "yaml" | "yml" | "json" | "xml" => Kind::Text,
+        "rs" | "c" | "h" | "cpp" | "hpp" | "cs" | "go" | "java" | "py" | "js" | "ts" | "tsx"
+        | "jsx" | "html" | "css" | "lua" | "sh" | "ps1" | "bat" | "cmd" => Kind::Code,
+        "exe" | "msi" | "com" | "dll" | "sys" => Kind::Executable,
+        "lnk" | "url" => Kind::Shortcut,
+        _ => Kind::File,
+    }
+}
+
+// Prefer the emoji with the default emoji presentation (no variation selector needed), which
+// are two cells wide in all the terminals.
+fn emoji(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Volume => "💽",
+        Kind::Folder => "📁",
+        Kind::File => "📄",
+        Kind::Image => "🎨",
+        Kind::Audio => "🎵",
+        Kind::Video => "🎬",
+        Kind::Archive => "📦",
+        Kind::Pdf => "📕",
+        Kind::Document => "📘",
+        Kind::Spreadsheet => "📗",
+        Kind::Presentation => "📙",
+        Kind::Text => "📝",
+        Kind::Code => "📜",
+        Kind::Executable => "🚀",
+        Kind::Shortcut => "🔗",
+    }
+}
+
+// Font Awesome glyphs in Nerd Fonts.
+// Ref: https://www.nerdfonts.com/cheat-sheet
+fn nerd_glyph(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Volume => "\u{f0a0}",
+        Kind::Folder => "\u{f07b}",
+        Kind::File => "\u{f15b}",
+        Kind::Image => "\u{f1c5}",
+        Kind::Audio => "\u{f1c7}",
+        Kind::Video => "\u{f1c8}",
+        Kind::Archive => "\u{f1c6}",
+        Kind::Pdf => "\u{f1c1}",
+        Kind::Document => "\u{f1c2}",
+        Kind::Spreadsheet => "\u{f1c3}",
+        Kind::Presentation => "\u{f1c4}",
+        Kind::Text => "\u{f15c}",
+        Kind::Code => "\u{f1c9}",
+        Kind::Executable => "\u{f013}",
+        Kind::Shortcut => "\u{f0c1}",
+    }
+}
@@ -0,0 +1,62 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use crate::app::event::{AppEvent, AppSender};
+
+/// The values kept for the recent keys, the older ones are loaded again when needed.
+const CACHED: usize = 8;
+
+/// The keys and their values, the recent first, none while loading.
+type Cache<K, V> = VecDeque<(K, Option<Arc<V>>)>;
+
+/// The values of the keys loaded in the background, e.g. the listing of the archive previewed,
+/// not to freeze the terminal in the render. The frontend is refreshed when one is loaded.
+#[derive(Debug)]
+pub struct Loader<K, V> {
+    cache: Arc<Mutex<Cache<K, V>>>,
+}
+
+impl<K, V> Default for Loader<K, V> {
+    fn default() -> Self {
+        Self {
+            cache: Default::default(),
+        }
+    }
+}
+
+impl<K, V> Loader<K, V>
+where
+    K: PartialEq + Clone + Send + 'static,
+    V: Send + Sync + 'static,
+{
+    /// The value of the key, or none while it is loaded by `load` in a worker thread.
+    pub fn get(
+        &self,
+        key: &K,
+        sender: &AppSender,
+        load: impl FnOnce(&K) -> V + Send + 'static,
+    ) -> Option<Arc<V>> {
+        let mut cache = self.cache.lock().unwrap();
+        if let Some(i) = cache.iter().position(|(k, _)| k == key) {
+            let entry = cache.remove(i).unwrap();
+            let value = entry.1.clone();
+            cache.push_front(entry);
+            return value;
+        }
+        cache.push_front((key.clone(), None));
+        cache.truncate(CACHED);
+        let (cache, key, sender) = (Arc::clone(&self.cache), key.clone(), sender.clone());
+        thread::spawn(move || {
+            let value = Arc::new(load(&key));
+            // dropped if it is pushed out meanwhile
+            if let Some((_, slot)) = cache.lock().unwrap().iter_mut().find(|(k, _)| *k == key) {
+                *slot = Some(value);
+                sender.send(AppEvent::Refresh);
+            }
+        });
+        None
+    }
+}
@@ -0,0 +1,134 @@
+use std::path::PathBuf;
+
+use tui_textarea::{CursorMove, TextArea};
+
+use crate::app::link::LinkKind;
+use crate::app::transfer::TransferKind;
+
+/// What the text of the prompt is for.
+#[derive(Debug)]
+pub enum PromptAction {
+    /// the new name of the file.
+    Rename(PathBuf),
+    /// the folder to move the file into.
+    Move(PathBuf),
+    /// the folder to copy the file into.
+    Copy(PathBuf),
+    /// the path of the link to the file, or the folder to put it in.
+    Link(LinkKind, PathBuf),
+    /// the folder to extract the archive into.
+    Extract(PathBuf),
+    /// the note of the file, empty to remove it.
+    Note(PathBuf),
+}
+
+impl PromptAction {
+    /// Whether the text is a path, completed by the folders in Everything.
+    pub fn is_path(&self) -> bool {
+        !matches!(self, PromptAction::Rename(_) | PromptAction::Note(_))
+    }
+}
+
+/// A move or copy waiting for the choice, as the target exists.
+#[derive(Debug)]
+pub struct Conflict {
+    pub kind: TransferKind,
+    pub source: PathBuf,
+    pub target: PathBuf,
+}
+
+/// A one-line text input in a popup, e.g. the new name of a file.
+#[derive(Debug)]
+pub struct Prompt<'a> {
+    pub title: String,
+    pub action: PromptAction,
+    pub textarea: TextArea<'a>,
+    /// the text being completed, until the candidates are back.
+    pub completing: Option<String>,
+    /// the candidates of the completion for the text, cycled by `Tab`.
+    pub completions: Vec<String>,
+    completion: Option<usize>,
+}
+
+impl Prompt<'_> {
+    /// The prompt with the text filled in and the cursor at the end of it.
+    pub fn new(title: String, action: PromptAction, text: &str) -> Self {
+        let mut prompt = Self {
+            title,
+            action,
+            textarea: TextArea::default(),
+            completing: None,
+            completions: vec![],
+            completion: None,
+        };
+        prompt.set_text(text);
+        prompt
+    }
+
+    pub fn text(&self) -> &str {
+        &self.textarea.lines()[0]
+    }
+
+    fn set_text(&mut self, text: &str) {
+        self.textarea = TextArea::new(vec![text.to_string()]);
+        self.textarea.move_cursor(CursorMove::End);
+    }
+
+    /// Forget the completion, the text is edited.
+    pub fn clear_completions(&mut self) {
+        self.completing = None;
+        self.completions.clear();
+        self.completion = None;
+    }
+
+    /// Complete the text by the candidates, to the only one, or to the common prefix of them
+    /// which are kept for cycling.
+    pub fn complete(&mut self, mut candidates: Vec<String>) {
+        self.completing = None;
+        candidates.sort_by_key(|candidate| candidate.to_lowercase());
+        match candidates.as_slice() {
+            [] => {}
+            [only] => self.set_text(&format!("{only}\\")),
+            [first, ..] => {
+                // the paths are case-insensitive, keep the case of the first one
+                let len = candidates
+                    .iter()
+                    .map(|candidate| common_prefix_len(first, candidate))
+                    .min()
+                    .unwrap_or_default();
+                if len > self.text().len() {
+                    self.set_text(&first[..len]);
+                }
+                self.completions = candidates;
+                self.completion = None;
+            }
+        }
+    }
+
+    /// Fill in the next candidate of the completion, false if there is none.
+    pub fn next_completion(&mut self) -> bool {
+        if self.completions.is_empty() {
+            return false;
+        }
+        let next = self
+            .completion
+            .map_or(0, |i| (i + 1) % self.completions.len());
+        self.completion = Some(next);
+        let text = format!("{}\\", self.completions[next]);
+        self.set_text(&text);
+        true
+    }
+
+    pub fn selected_completion(&self) -> Option<usize> {
+        self.completion
+    }
+}
+
+/// Bytes of the common prefix in `a`, ignoring the case.
+fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.chars()
+        .zip(b.chars())
+        .take_while(|(x, y)| x.to_lowercase().eq(y.to_lowercase()))
+        .map(|(x, _)| x.len_utf8())
+        .sum()
+}
@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+
+use crossterm::event::KeyEvent;
+use tui_textarea::TextArea;
+
+use crate::app::rename::{self, Case, Renaming, Rule};
+
+use super::ui;
+
+/// The popup renaming several files at once, with the new names previewed.
+#[derive(Debug)]
+pub struct BatchRename<'a> {
+    /// in the order of the results, numbered so.
+    paths: Vec<PathBuf>,
+    pub find: TextArea<'a>,
+    pub replace: TextArea<'a>,
+    /// typing in `replace`, or in `find` otherwise.
+    pub is_focus_replace: bool,
+    pub regex: bool,
+    pub case: Case,
+    /// the new names, or the error of the regex.
+    pub plan: Result<Vec<Renaming>, String>,
+    /// the first one shown of the new names.
+    pub scroll: usize,
+}
+
+impl BatchRename<'_> {
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        let mut batch = Self {
+            paths,
+            find: TextArea::default(),
+            replace: TextArea::default(),
+            is_focus_replace: false,
+            regex: false,
+            case: Case::Keep,
+            plan: Ok(vec![]),
+            scroll: 0,
+        };
+        batch.update();
+        batch
+    }
+
+    /// Type in the field focused, the new names are planned again.
+    pub fn input(&mut self, key_event: KeyEvent) {
+        let field = if self.is_focus_replace {
+            &mut self.replace
+        } else {
+            &mut self.find
+        };
+        let old = field.lines()[0].clone();
+        ui::key_map_for_textarea(key_event.into(), field);
+        if field.lines()[0] != old {
+            self.update();
+        }
+    }
+
+    pub fn toggle_regex(&mut self) {
+        self.regex = !self.regex;
+        self.update();
+    }
+
+    pub fn next_case(&mut self) {
+        self.case = self.case.next();
+        self.update();
+    }
+
+    /// The count of the files renamed, and of the ones which can't be.
+    pub fn counts(&self) -> (usize, usize) {
+        let Ok(plan) = &self.plan else {
+            return (0, 0);
+        };
+        let changed = plan.iter().filter(|renaming| renaming.is_changed()).count();
+        let problems = plan
+            .iter()
+            .filter(|renaming| renaming.problem.is_some())
+            .count();
+        (changed, problems)
+    }
+
+    /// Plan the new names again, for the rule changed.
+    pub fn update(&mut self) {
+        let rule = Rule {
+            find: self.find.lines()[0].clone(),
+            replace: self.replace.lines()[0].clone(),
+            regex: self.regex,
+            case: self.case,
+        };
+        self.plan = rename::plan(&self.paths, &rule).map_err(|e| format!("{e:#}"));
+    }
+}
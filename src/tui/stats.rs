@@ -0,0 +1,47 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// The events in it are counted for the rate.
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Numbers of the drawing and the events for profiling, shown in the debug overlay by `F12`.
+#[derive(Debug, Default)]
+pub struct Stats {
+    /// how long the last frame took to render and flush to the terminal.
+    pub render: Duration,
+    /// the slowest frame so far.
+    pub render_max: Duration,
+    pub frames: u64,
+    /// when the events in the last window came.
+    events: VecDeque<Instant>,
+}
+
+impl Stats {
+    pub fn record_frame(&mut self, took: Duration) {
+        self.render = took;
+        self.render_max = self.render_max.max(took);
+        self.frames += 1;
+    }
+
+    pub fn record_event(&mut self) {
+        let now = Instant::now();
+        self.events.push_back(now);
+        while self
+            .events
+            .front()
+            .is_some_and(|&time| now.duration_since(time) > RATE_WINDOW)
+        {
+            self.events.pop_front();
+        }
+    }
+
+    /// Events in the last second.
+    pub fn events_per_sec(&self) -> usize {
+        self.events
+            .iter()
+            .filter(|time| time.elapsed() <= RATE_WINDOW)
+            .count()
+    }
+}
@@ -0,0 +1,53 @@
+use std::collections::BTreeMap;
+
+use crate::app::capability::Feature;
+use crate::i18n::Msg;
+
+/// The snippets of the Everything syntax always in the templates, after the ones of config, and
+/// the feature of Everything they need.
+const BUILTIN_TEMPLATES: &[(Msg, &str, Option<Feature>)] = &[
+    (Msg::TemplateModifiedToday, "dm:today", None),
+    (Msg::TemplateModifiedThisWeek, "dm:thisweek", None),
+    (Msg::TemplateLargerThan100Mb, "size:>100mb", None),
+    (Msg::TemplateEmptyFolders, "folder: empty:", None),
+    (Msg::TemplateSameNames, "dupe:", None),
+    (Msg::TemplateExtensions, "ext:", None),
+    (Msg::TemplateInFolder, "parent:", None),
+    (Msg::TemplateRegex, "regex:", None),
+    (Msg::TemplateNotInWindows, r#"!path:"C:\Windows\""#, None),
+    // it reads every file without the content index of 1.5
+    (
+        Msg::TemplateContent,
+        "content:",
+        Some(Feature::ContentIndex),
+    ),
+];
+
+/// A snippet of the search, inserted from the templates menu (`Alt+T`).
+#[derive(Debug, Clone)]
+pub struct Template {
+    pub name: String,
+    pub snippet: String,
+    /// the feature of Everything the snippet needs, disabled on the older ones.
+    pub requires: Option<Feature>,
+}
+
+/// The templates of `[templates]` in config, then the built-in ones.
+pub fn load(templates: &BTreeMap<String, String>) -> Vec<Template> {
+    let builtin = BUILTIN_TEMPLATES
+        .iter()
+        .map(|&(name, snippet, requires)| Template {
+            name: name.text().to_string(),
+            snippet: snippet.to_string(),
+            requires,
+        });
+    templates
+        .iter()
+        .map(|(name, snippet)| Template {
+            name: name.clone(),
+            snippet: snippet.clone(),
+            requires: None,
+        })
+        .chain(builtin)
+        .collect()
+}
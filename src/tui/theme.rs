@@ -0,0 +1,115 @@
+use std::str::FromStr;
+
+use anyhow::Context;
+use ratatui::style::Color;
+
+use crate::config::{ThemeConfig, ThemePreset};
+
+// Prefer standard 8-bit RGB colors, therefore, more terminals can be supported.
+// Ref: https://en.wikipedia.org/wiki/ANSI_escape_code#8-bit
+
+// Everything (voidtools) icon color.
+const _MAIN_COLOR_24_BIT: Color = Color::Rgb(255, 128, 0);
+// Ref: https://stackoverflow.com/a/60392218
+// RGB ff8000 -> xterm color approx 208 (DarkOrange	#ff8700	rgb(255,135,0))
+const MAIN_COLOR_8_BIT: Color = Color::Indexed(208);
+const _FONT_COLOR_24_BIT: Color = Color::Rgb(229, 192, 123);
+// RGB e5c07b -> xterm color approx 180 (d7af87)
+const FONT_COLOR_8_BIT: Color = Color::Indexed(180);
+const _DARK_GRAY_COLOR: Color = Color::DarkGray;
+const TERM_GRAY_COLOR: Color = Color::Indexed(8);
+
+const _LIGHT_MAIN_COLOR_8_BIT: Color = Color::Indexed(220);
+const LIGHT_FONT_COLOR_8_BIT: Color = Color::Indexed(214);
+
+// Colors for the compare view, #87d787 / #d78787 / #87afff.
+const ADDED_COLOR: Color = Color::Indexed(114);
+const REMOVED_COLOR: Color = Color::Indexed(174);
+const CHANGED_COLOR: Color = Color::Indexed(111);
+
+/// Colors of the TUI, a preset with the overrides in config.
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    /// the borders and the titles.
+    pub main: Color,
+    pub font: Color,
+    /// the selected ones.
+    pub light_font: Color,
+    /// the paths and the hints.
+    pub gray: Color,
+    pub added: Color,
+    pub removed: Color,
+    pub changed: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::EVERYTHING
+    }
+}
+
+impl Theme {
+    /// The orange of the Everything icon.
+    pub const EVERYTHING: Self = Self {
+        main: MAIN_COLOR_8_BIT,
+        font: FONT_COLOR_8_BIT,
+        light_font: LIGHT_FONT_COLOR_8_BIT,
+        gray: TERM_GRAY_COLOR,
+        added: ADDED_COLOR,
+        removed: REMOVED_COLOR,
+        changed: CHANGED_COLOR,
+    };
+
+    /// Blue, #00afff / #afd7d7 / #00d7ff.
+    pub const OCEAN: Self = Self {
+        main: Color::Indexed(39),
+        font: Color::Indexed(152),
+        light_font: Color::Indexed(45),
+        ..Self::EVERYTHING
+    };
+
+    /// The colors of the terminal itself, for the light backgrounds too.
+    pub const MONO: Self = Self {
+        main: Color::Reset,
+        font: Color::Reset,
+        light_font: Color::Reset,
+        gray: Color::DarkGray,
+        added: Color::Green,
+        removed: Color::Red,
+        changed: Color::Blue,
+    };
+
+    /// The basic colors, which the terminals show at their strongest.
+    pub const HIGH_CONTRAST: Self = Self {
+        main: Color::White,
+        font: Color::White,
+        light_font: Color::Yellow,
+        gray: Color::Gray,
+        added: Color::LightGreen,
+        removed: Color::LightRed,
+        changed: Color::LightCyan,
+    };
+
+    pub fn new(config: &ThemeConfig) -> anyhow::Result<Self> {
+        let mut theme = match config.preset {
+            ThemePreset::Everything => Self::EVERYTHING,
+            ThemePreset::Ocean => Self::OCEAN,
+            ThemePreset::Mono => Self::MONO,
+            ThemePreset::HighContrast => Self::HIGH_CONTRAST,
+        };
+        let overrides = [
+            (&mut theme.main, &config.main, "main"),
+            (&mut theme.font, &config.font, "font"),
+            (&mut theme.light_font, &config.light_font, "light_font"),
+            (&mut theme.gray, &config.gray, "gray"),
+        ];
+        for (color, value, key) in overrides {
+            if let Some(value) = value {
+                *color = Color::from_str(value)
+                    .ok()
+                    .with_context(|| format!("invalid color {value:?} of theme.{key}"))?;
+            }
+        }
+        Ok(theme)
+    }
+}
@@ -1,5 +1,15 @@
-use std::{cmp::min, path::PathBuf};
+use std::{
+    cmp::min,
+    collections::HashSet,
+    ffi::OsStr,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
 
+use chrono::Local;
+use everything_sdk::{RequestFlags, SortType};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Style, Stylize},
@@ -9,29 +19,47 @@ use ratatui::{
 };
 use tui_textarea::{CursorMove, Input, Key, TextArea};
 
-use crate::app::App;
-
-// Prefer standard 8-bit RGB colors, therefore, more terminals can be supported.
-// Ref: https://en.wikipedia.org/wiki/ANSI_escape_code#8-bit
-
-// Everything (voidtools) icon color.
-const _MAIN_COLOR_24_BIT: Color = Color::Rgb(255, 128, 0);
-// Ref: https://stackoverflow.com/a/60392218
-// RGB ff8000 -> xterm color approx 208 (DarkOrange	#ff8700	rgb(255,135,0))
-const MAIN_COLOR_8_BIT: Color = Color::Indexed(208);
-const MAIN_COLOR: Color = MAIN_COLOR_8_BIT;
-const _FONT_COLOR_24_BIT: Color = Color::Rgb(229, 192, 123);
-// RGB e5c07b -> xterm color approx 180 (d7af87)
-const FONT_COLOR_8_BIT: Color = Color::Indexed(180);
-const FONT_COLOR: Color = FONT_COLOR_8_BIT;
-const _DARK_GRAY_COLOR: Color = Color::DarkGray;
-const TERM_GRAY_COLOR: Color = Color::Indexed(8);
-const GRAY_COLOR: Color = TERM_GRAY_COLOR;
-
-const _LIGHT_MAIN_COLOR_8_BIT: Color = Color::Indexed(220);
-const _LIGHT_MAIN_COLOR: Color = _LIGHT_MAIN_COLOR_8_BIT;
-const LIGHT_FONT_COLOR_8_BIT: Color = Color::Indexed(214);
-const LIGHT_FONT_COLOR: Color = LIGHT_FONT_COLOR_8_BIT;
+use super::commands::Command;
+use super::confirm::Confirm;
+use super::filter::{DateField, DatePicker, FilterPicker, SizePicker};
+use super::graphics::{self, Graphics};
+use super::icons::Icons;
+use super::loader::Loader;
+use super::prompt::{Conflict, Prompt};
+use super::rename::BatchRename;
+use super::stats::Stats;
+use super::templates::Template;
+use super::theme::Theme;
+use super::width;
+use super::wizard::{Wizard, STEPS};
+use crate::app::diff::{DiffKind, ResultsDiff};
+use crate::app::ery::QueryResults;
+use crate::app::extensions::ExtensionCounts;
+use crate::app::git::Badge;
+use crate::app::grep::{self, LineMatch};
+use crate::app::report::{Report, ReportItem};
+use crate::app::sort::{is_recently_changed, sort_label, SortKey};
+use crate::app::task::{Task, Unit};
+use crate::app::usage::DiskUsage;
+use crate::app::volumes::{self, VolumeCounts};
+use crate::app::{App, Status};
+use crate::archive::{self, ArchiveEntry};
+use crate::config::PathMode;
+use crate::format;
+use crate::shell::Shortcut;
+use crate::tr;
+
+/// Height of the search bar, with the borders.
+const SEARCH_BAR_HEIGHT: u16 = 3;
+
+/// Lines of the multi-line search bar shown at most, the others are scrolled.
+const MAX_SEARCH_LINES: u16 = 8;
+
+/// Cells scrolled horizontally by one `←`/`→`.
+pub const SCROLL_STEP: usize = 8;
+
+/// Width of the extension sidebar, with the borders.
+const EXT_SIDEBAR_WIDTH: u16 = 24;
 
 #[derive(Debug)]
 pub struct UI<'a> {
@@ -41,6 +69,113 @@ pub struct UI<'a> {
     pub list_state: ListState,
     pub last_page_height: Option<u16>,
     pub is_popup_show: bool,
+    /// show the diff with the baseline instead of the results when it is some.
+    pub compare: Option<ResultsDiff>,
+    pub compare_state: ListState,
+    /// one-shot message for the user, shown at the bottom of the results.
+    pub notice: Option<String>,
+    pub is_history_show: bool,
+    /// sort the run history by date run instead of run count.
+    pub is_history_by_recent: bool,
+    pub history_state: ListState,
+    pub icons: Icons,
+    pub path_mode: PathMode,
+    /// horizontal scroll of the lists, in cells.
+    pub results_scroll: usize,
+    pub compare_scroll: usize,
+    pub history_scroll: usize,
+    pub is_preview_show: bool,
+    /// draw the image thumbnails, none if disabled or unsupported by the terminal.
+    pub graphics: Option<Graphics>,
+    /// the image to preview and where, updated in every render.
+    preview_image: Option<(PathBuf, Rect)>,
+    /// the contents of the archives previewed, by the path and the date modified.
+    archive_listings: Loader<(PathBuf, Option<SystemTime>), Result<Vec<ArchiveEntry>, String>>,
+    /// the lines of the file previewed matching the search of them, none if not shown.
+    content_matches: Option<ContentMatches>,
+    /// the lines matched of the files previewed, by the path, the date modified and the terms.
+    content_greps: Loader<(PathBuf, Option<SystemTime>, Vec<String>), Grepped>,
+    /// the popup picking a filter, shown when it is some.
+    pub filter_picker: Option<FilterPicker>,
+    /// the user commands in config.
+    pub commands: Vec<Command>,
+    /// the menu of the user commands, shown when it is some.
+    pub commands_menu: Option<ListState>,
+    /// the snippets of the search in config and the built-in ones.
+    pub templates: Vec<Template>,
+    /// the menu of the templates, shown when it is some.
+    pub templates_menu: Option<ListState>,
+    /// the search bar of several lines, each one OR-combined into the query.
+    pub is_multiline: bool,
+    pub theme: Theme,
+    /// the error of reloading the config, shown in a popup when it is some.
+    pub config_error: Option<String>,
+    /// the setup on the first run, shown when it is some.
+    pub wizard: Option<Wizard>,
+    /// show the disk usage of `App::usage_folder` instead of the results, `usage` is none while
+    /// scanning.
+    pub is_usage_show: bool,
+    pub usage: Option<DiskUsage>,
+    pub usage_state: ListState,
+    /// the sidebar of the results counted by extension, beside the results.
+    pub is_ext_sidebar_show: bool,
+    pub is_focus_ext_sidebar: bool,
+    ext_counts: ExtensionCounts,
+    pub ext_state: ListState,
+    /// where the sidebar is drawn, for the mouse clicks.
+    ext_sidebar_area: Option<Rect>,
+    volume_counts: VolumeCounts,
+    /// the popup of the volumes of the results with the selected row, all of them first,
+    /// shown when it is some.
+    pub volumes: Option<usize>,
+    /// the popup of the files opened through ery with the selected row, shown when it is some.
+    pub opened: Option<usize>,
+    /// the popup of the files recycled by ery with the selected row, shown when it is some.
+    pub recycled: Option<usize>,
+    /// the popup of the tasks in the background with the selected row, shown when it is some.
+    pub tasks: Option<usize>,
+    /// the column of the volume of each result.
+    pub is_volume_show: bool,
+    /// the text input for a file operation, shown in a popup when it is some.
+    pub prompt: Option<Prompt<'a>>,
+    /// the move or copy waiting for the choice of the existing target, shown when it is some.
+    pub conflict: Option<Conflict>,
+    /// the destructive action waiting for the confirmation.
+    pub confirm: Option<Confirm>,
+    /// the results marked by `Space`, renamed at once by `Shift+F2`.
+    pub marked: HashSet<PathBuf>,
+    /// the popup renaming the marked ones, shown when it is some.
+    pub batch_rename: Option<BatchRename<'a>>,
+    /// the shortcut of which the target is shown in a popup when it is some.
+    pub shortcut: Option<PathBuf>,
+    /// the sort waiting for the confirmation as it is not fast sort, shown when it is some.
+    pub slow_sort: Option<(SortType, Option<SortKey>)>,
+    /// the report of the results, shown in a popup when it is some.
+    pub report: Option<Report>,
+    /// the end of the results to select when the query loading it is back.
+    pub jump: Option<Jump>,
+    /// full path of the selected result, selected again when the results are refreshed.
+    sticky_path: Option<PathBuf>,
+    /// accessibility mode, with a plain text status line where the terminal cursor is, for the
+    /// screen readers following it.
+    pub accessible: bool,
+    /// the folders of the selected one in a footer, numbered and clicked to search in them.
+    pub is_breadcrumbs_show: bool,
+    /// the folders in the footer from the drive and where, none for the ones dropped, updated in
+    /// every render.
+    breadcrumbs: Vec<(PathBuf, Rect)>,
+    /// something changed since the last draw, it is drawn in the next frame.
+    pub dirty: bool,
+    /// the overlay of the statistics for profiling, toggled by `F12`.
+    pub is_debug_show: bool,
+    pub stats: Stats,
+}
+
+/// An end of the results, maybe beyond the ones loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Jump {
+    First,
+    Last,
 }
 
 impl UI<'_> {
@@ -57,13 +192,75 @@ impl UI<'_> {
             list_state,
             last_page_height: None,
             is_popup_show: false,
+            compare: None,
+            compare_state: ListState::default(),
+            notice: None,
+            is_history_show: false,
+            is_history_by_recent: false,
+            history_state: ListState::default(),
+            icons: Icons::default(),
+            path_mode: PathMode::default(),
+            results_scroll: 0,
+            compare_scroll: 0,
+            history_scroll: 0,
+            is_preview_show: false,
+            graphics: None,
+            preview_image: None,
+            archive_listings: Loader::default(),
+            content_matches: None,
+            content_greps: Loader::default(),
+            filter_picker: None,
+            commands: vec![],
+            commands_menu: None,
+            templates: vec![],
+            templates_menu: None,
+            is_multiline: false,
+            theme: Theme::default(),
+            config_error: None,
+            wizard: None,
+            is_usage_show: false,
+            usage: None,
+            usage_state: ListState::default(),
+            is_ext_sidebar_show: false,
+            is_focus_ext_sidebar: false,
+            ext_counts: ExtensionCounts::default(),
+            ext_state: ListState::default(),
+            ext_sidebar_area: None,
+            volume_counts: VolumeCounts::default(),
+            volumes: None,
+            opened: None,
+            recycled: None,
+            tasks: None,
+            is_volume_show: false,
+            prompt: None,
+            conflict: None,
+            confirm: None,
+            marked: HashSet::new(),
+            batch_rename: None,
+            shortcut: None,
+            slow_sort: None,
+            report: None,
+            jump: None,
+            sticky_path: None,
+            accessible: false,
+            is_breadcrumbs_show: true,
+            breadcrumbs: vec![],
+            dirty: true,
+            is_debug_show: false,
+            stats: Stats::default(),
         }
     }
 
     pub fn render(&mut self, app: &mut App, frame: &mut Frame) {
+        let theme = self.theme;
         let chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Length(3), Constraint::Min(1)])
+            .constraints([
+                Constraint::Length(self.search_bar_height()),
+                Constraint::Min(1),
+                Constraint::Length(self.breadcrumbs_height()),
+                Constraint::Length(self.status_height()),
+            ])
             .split(frame.area());
 
         self.last_page_height = Some(
@@ -75,7 +272,10 @@ impl UI<'_> {
                 .height,
         );
 
-        self.textarea.set_style(Style::default().fg(FONT_COLOR));
+        self.textarea.set_style(Style::default().fg(theme.font));
+        self.textarea.set_placeholder_text(tr!(SearchPlaceholder));
+        self.textarea
+            .set_placeholder_style(Style::default().fg(theme.gray));
         self.textarea.set_cursor_line_style(Style::default());
         if self.is_focus_search_bar {
             self.textarea.set_cursor_style(self.cursor_style);
@@ -83,273 +283,2818 @@ impl UI<'_> {
             self.textarea
                 .set_cursor_style(self.textarea.cursor_line_style());
         }
-        self.textarea.set_block(
-            Block::default()
-                .style(Style::default().fg(MAIN_COLOR))
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
-                .title("Everything"),
-        );
+        let block = Block::default()
+            .style(Style::default().fg(theme.main))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(match &app.offline_snapshot {
+                Some(path) if is_file_list(path) => tr!(TitleFileList, path = path.display()),
+                Some(path) => tr!(TitleSnapshot, path = path.display()),
+                None => match (app.launcher, &app.filter) {
+                    (true, Some(filter)) => tr!(TitleLauncherFilter, filter = filter.name),
+                    (true, None) => tr!(TitleLauncher).to_string(),
+                    (false, Some(filter)) => tr!(TitleFilter, filter = filter.name),
+                    (false, None) => "Everything".to_string(),
+                },
+            })
+            .title(self.admin_title(app));
+        // the scope of the matching, the globs and the project as a chip before the text, none
+        // for the defaults
+        let labels: Vec<String> = [
+            app.scope.label().map(str::to_string),
+            app.glob.then(|| "glob".to_string()),
+            app.project
+                .as_ref()
+                .filter(|_| app.in_project)
+                .map(|project| project.name()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        let chip = (!labels.is_empty()).then(|| format!(" {} ", labels.join(" · ")));
+        let chip_width = chip
+            .as_deref()
+            .map_or(0, |chip| width::width(chip) as u16 + 1);
+        let [chip_area, search_area] =
+            Layout::horizontal([Constraint::Length(chip_width), Constraint::Min(1)])
+                .areas(block.inner(chunks[0]));
+        frame.render_widget(block, chunks[0]);
+        if let Some(chip) = chip {
+            let chip = Span::styled(chip, Style::default().fg(theme.main).reversed());
+            frame.render_widget(chip, chip_area);
+        }
+        self.textarea.set_block(Block::default());
+        frame.render_widget(&self.textarea, search_area);
+
+        self.preview_image = None;
+        self.ext_sidebar_area = None;
+        if self.compare.is_some() {
+            self.render_compare(frame, chunks[1]);
+        } else if self.is_usage_show {
+            self.render_usage(frame, chunks[1]);
+        } else {
+            let area = if self.is_ext_sidebar_show {
+                let panes = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Min(1), Constraint::Length(EXT_SIDEBAR_WIDTH)])
+                    .split(chunks[1]);
+                self.render_ext_sidebar(app, frame, panes[1]);
+                panes[0]
+            } else {
+                chunks[1]
+            };
+            if self.is_preview_show {
+                let panes = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+                    .split(area);
+                self.render_results(app, frame, panes[0]);
+                self.render_preview(app, frame, panes[1]);
+            } else {
+                self.render_results(app, frame, area);
+            }
+        }
+
+        if self.is_popup_show {
+            let popup_block = Block::new()
+                .title(vec![Span::styled(
+                    tr!(StatusTitle),
+                    Style::default().fg(theme.main),
+                )])
+                .style(Style::default().fg(theme.main))
+                .borders(Borders::ALL);
+
+            let (major, minor, revision, build) = app.status.version;
+
+            let text: Vec<Line<'_>> = [
+                format!(" {}: {major}.{minor}.{revision}.{build}", tr!(Version)),
+                format!(" {}: {}", tr!(IsAdmin), yes_or_no(app.status.is_admin)),
+                format!(" {}: {}", tr!(AppData), yes_or_no(app.status.is_appdata)),
+                format!(" {}: ", tr!(Indexed)),
+                format!(
+                    " - {}: {} {}",
+                    tr!(FileSize),
+                    yes_or_no(app.status.is_file_size_indexed),
+                    is_fast_sort(app.status.is_size_fast_sort),
+                ),
+                format!(
+                    " - {}: {} {}",
+                    tr!(FolderSize),
+                    yes_or_no(app.status.is_folder_size_indexed),
+                    is_fast_sort(app.status.is_size_fast_sort),
+                ),
+                format!(
+                    " - {}: {} {}",
+                    tr!(DateModified),
+                    yes_or_no(app.status.is_date_modified_indexed),
+                    is_fast_sort(app.status.is_date_modified_fast_sort),
+                ),
+                format!(
+                    " - {}: {} {}",
+                    tr!(DateCreated),
+                    yes_or_no(app.status.is_date_created_indexed),
+                    is_fast_sort(app.status.is_date_created_fast_sort),
+                ),
+                format!(
+                    " - {}: {} {}",
+                    tr!(DateAccessed),
+                    yes_or_no(app.status.is_date_accessed_indexed),
+                    is_fast_sort(app.status.is_date_accessed_fast_sort),
+                ),
+                format!(
+                    " - {}: {} {}",
+                    tr!(Attributes),
+                    yes_or_no(app.status.is_attributes_indexed),
+                    is_fast_sort(app.status.is_attributes_fast_sort),
+                ),
+            ]
+            .map(|s| Line::from(s))
+            .into();
+
+            let paragraph = Paragraph::new(text)
+                .style(Style::default().fg(theme.font))
+                .block(popup_block);
+
+            let popup_area = centered_rect(frame.area(), 80, 60);
+            frame.render_widget(Clear, popup_area);
+            frame.render_widget(paragraph, popup_area);
+        }
+
+        if self.is_history_show {
+            self.render_history(app, frame);
+        }
+
+        if let Some(picker) = &self.filter_picker {
+            render_filter_picker(picker, self.theme, frame);
+        }
+
+        if let Some(menu) = self.commands_menu.as_mut() {
+            render_commands_menu(&self.commands, menu, self.theme, frame);
+        }
+
+        if let Some(menu) = self.templates_menu.as_mut() {
+            render_templates_menu(&self.templates, menu, &app.status, self.theme, frame);
+        }
+
+        if let Some(report) = &self.report {
+            render_report(report, app.is_full_report_pending(), self.theme, frame);
+        }
+
+        // the terminal cursor at the text being typed, where the IME shows the composition
+        let mut cursor = None;
+        if self.is_focus_search_bar {
+            cursor = Some(textarea_cursor(&self.textarea, search_area));
+        }
+        if let Some(prompt) = self.prompt.as_mut() {
+            cursor = Some(render_prompt(prompt, self.theme, frame));
+        }
+
+        if let Some(conflict) = &self.conflict {
+            render_conflict(conflict, self.theme, frame);
+        }
+
+        self.render_breadcrumbs(app, frame, chunks[2]);
+
+        if let Some(batch) = self.batch_rename.as_mut() {
+            cursor = Some(render_batch_rename(batch, self.theme, frame));
+        }
+
+        // over the batch renaming it confirms
+        if let Some(confirm) = &self.confirm {
+            render_confirm(confirm, app.dry_run, self.theme, frame);
+            cursor = None;
+        }
+
+        if let Some(path) = &self.shortcut {
+            render_shortcut(
+                path,
+                app.shortcuts.target(path).flatten(),
+                self.theme,
+                frame,
+            );
+        }
+
+        if let Some(selected) = self.volumes {
+            self.render_volumes(app, selected, frame);
+        }
+
+        if let Some(selected) = self.opened {
+            render_opened(app, selected, self.theme, frame);
+        }
+
+        if let Some(selected) = self.recycled {
+            render_recycled(app, selected, self.theme, frame);
+        }
+
+        if let Some(selected) = self.tasks {
+            render_tasks(app, selected, self.theme, frame);
+        }
+
+        if let Some((sort_type, secondary_sort)) = self.slow_sort {
+            render_slow_sort(&sort_label(sort_type, secondary_sort), self.theme, frame);
+        }
+
+        if let Some(e) = &self.config_error {
+            render_config_error(e, self.theme, frame);
+        }
+
+        if let Some(wizard) = &self.wizard {
+            render_wizard(wizard, self.theme, frame);
+        }
+
+        if self.accessible {
+            self.render_status_line(app, frame, chunks[3]);
+        }
+        let is_popup = self.wizard.is_some() || self.config_error.is_some();
+        if let Some(position) = cursor.filter(|_| !is_popup) {
+            frame.set_cursor_position(position);
+        }
+
+        if self.is_debug_show {
+            render_debug(&self.stats, app, self.theme, frame);
+        }
+
+        // the image is drawn over everything, hide it under the popups
+        if self.is_popup_show
+            || self.is_history_show
+            || self.filter_picker.is_some()
+            || self.commands_menu.is_some()
+            || self.templates_menu.is_some()
+            || self.report.is_some()
+            || self.prompt.is_some()
+            || self.conflict.is_some()
+            || self.confirm.is_some()
+            || self.batch_rename.is_some()
+            || self.shortcut.is_some()
+            || self.volumes.is_some()
+            || self.opened.is_some()
+            || self.recycled.is_some()
+            || self.tasks.is_some()
+            || self.slow_sort.is_some()
+            || self.config_error.is_some()
+            || self.wizard.is_some()
+            || self.is_debug_show
+        {
+            self.preview_image = None;
+        }
+    }
+
+    fn render_results(&mut self, app: &App, frame: &mut Frame, area: Rect) {
+        let theme = self.theme;
+        let results = app.query_results.load();
+
+        let (num, total) = (results.number, results.total);
+        assert!(num <= total);
+
+        // ////
+        // let show_path = self
+        //     .list_state
+        //     .selected()
+        //     .and_then(|index| results.entrys.get(index))
+        //     .and_then(|entry| entry.filepath.clone());
+        // ////
+
+        let offset = self.list_state.offset();
+        let selected = self.list_state.selected();
+        let scope = self.scope_of(results.paths.iter());
+        let block = Block::new()
+            .title(vec![
+                Span::styled(
+                    tr!(
+                        TotalResults,
+                        total = total,
+                        offset = offset,
+                        selected = format!("{selected:?}")
+                    ),
+                    Style::default().fg(if num > 0 { theme.main } else { theme.gray }),
+                ),
+                Span::styled(
+                    format!("『{}』", format::os_str(&results.search)),
+                    // format!("『{:?}』", show_path),
+                    Style::default().fg(theme.gray),
+                ),
+                Span::styled(
+                    self.live_count(app, &results.search),
+                    Style::default().fg(theme.font),
+                ),
+                Span::styled(
+                    tr!(SortIs, sort = sort_label(app.sort_type, app.secondary_sort)),
+                    Style::default().fg(theme.gray),
+                ),
+                Span::styled(
+                    if app.is_querying() {
+                        tr!(SearchingCancel)
+                    } else {
+                        ""
+                    },
+                    Style::default().fg(theme.font),
+                ),
+                Span::styled(
+                    app.merge
+                        .as_ref()
+                        .map_or(String::new(), |merge| tr!(Merged, label = merge.label())),
+                    Style::default().fg(theme.gray),
+                ),
+                Span::styled(
+                    app.baseline.as_ref().map_or(String::new(), |baseline| {
+                        tr!(Baseline, search = format::os_str(&baseline.search))
+                    }),
+                    Style::default().fg(theme.gray),
+                ),
+                Span::styled(
+                    scope.as_ref().map_or(String::new(), |scope| {
+                        tr!(ScopeIs, folder = format::os_str(scope.as_os_str()))
+                    }),
+                    Style::default().fg(theme.gray),
+                ),
+            ])
+            .title_bottom(self.notice_line())
+            .title_bottom(self.dates_line(&results))
+            .title_bottom(query_error_line(app, theme))
+            .title_bottom(fallback_line(app, theme))
+            .title_bottom(task_line(app, theme).right_aligned())
+            .style(Style::default().fg(theme.main))
+            .borders(Borders::ALL);
+
+        // nothing queried yet, the examples instead of a blank list
+        if results.entrys.is_empty() && results.search.is_empty() && !app.is_querying() {
+            let paragraph = Paragraph::new(self.empty_state_lines(app)).block(block);
+            frame.render_widget(paragraph, area);
+            return;
+        }
+
+        if self.is_no_results(app) {
+            let paragraph = Paragraph::new(self.suggestion_lines(app)).block(block);
+            frame.render_widget(paragraph, area);
+            return;
+        }
+
+        let row_width = inner_width(area);
+        let now = Local::now();
+        self.volume_counts.update(&results);
+        let volume_width = self
+            .volume_counts
+            .sorted()
+            .iter()
+            .map(|(volume, _)| width::width(volume))
+            .max()
+            .filter(|_| self.is_volume_show);
+        // the date recently changed instead when sorted by it
+        let recently_changed = is_recently_changed(app.sort_type);
+        let has_date = results.request_flags.contains(if recently_changed {
+            RequestFlags::EVERYTHING_REQUEST_DATE_RECENTLY_CHANGED
+        } else {
+            RequestFlags::EVERYTHING_REQUEST_DATE_MODIFIED
+        });
+        let lines: Vec<Line> = results
+            .entrys
+            .iter()
+            .map(|entry| {
+                let pin = results
+                    .filepath(entry)
+                    .filter(|path| app.pins.is_pinned(path))
+                    .map_or("", |_| self.icons.pin());
+                let icon = format!("{pin}{}", self.icons.icon(entry));
+                // the volume of it in a column, padded to the widest one
+                let volume = volume_width.map_or(String::new(), |volume_width| {
+                    let volume = results
+                        .path(entry)
+                        .and_then(volumes::volume)
+                        .unwrap_or_default();
+                    format!("{} ", width::fit(&volume, volume_width))
+                });
+                // the git status of the files in the repositories
+                let badge = results
+                    .filepath(entry)
+                    .and_then(|path| app.git.badge(&path));
+                let (badge, badge_color) = match badge {
+                    Some(badge) => (format!("{} ", badge.symbol()), badge_color(badge, theme)),
+                    None => (String::new(), theme.gray),
+                };
+                // the note of the scripts goes after the name
+                let note = app
+                    .scripts
+                    .as_ref()
+                    .and_then(|scripts| scripts.on_result(&results, entry))
+                    .map_or(String::new(), |note| format!(" {note}"));
+                // which file list of the database it is from, none for the indexed ones
+                let list = entry
+                    .file_list_filename
+                    .as_deref()
+                    .filter(|list| !list.is_empty() && !app.is_offline())
+                    .map_or(String::new(), |list| {
+                        format!(" [{}]", format::os_str(list_name(list)))
+                    });
+                // which searches of the merge it is from
+                let tag = app
+                    .merge
+                    .as_ref()
+                    .and_then(|merge| merge.tag(&results.filepath(entry)?))
+                    .map_or(String::new(), |tag| format!(" [{tag}]"));
+                let list = format!("{list}{tag}");
+                // the note of the user, dimmed after the name
+                let remark = results
+                    .filepath(entry)
+                    .and_then(|path| app.notes.get(&path).map(|note| format!(" ({note})")))
+                    .unwrap_or_default();
+                // the date modified, colored by the age of it
+                let date = if recently_changed {
+                    entry.date_recently_changed
+                } else {
+                    entry.date_modified
+                };
+                let (date, age) = match date.filter(|_| has_date) {
+                    Some(date) => (format!(" {}", format::date(date)), format::age(date, now)),
+                    None => (String::new(), None),
+                };
+                let filename_style = match results.filepath(entry) {
+                    Some(path) if self.marked.contains(&path) => {
+                        Style::default().fg(theme.added).bold()
+                    }
+                    _ => Style::default().fg(theme.font),
+                };
+                let (filename, path) = fit_name_and_path(
+                    &format::os_str(entry.filename.as_ref().unwrap()),
+                    results.path(entry).unwrap(),
+                    row_width.saturating_sub(
+                        width::width(&volume)
+                            + width::width(&icon)
+                            + width::width(&badge)
+                            + width::width(&note)
+                            + width::width(&remark)
+                            + width::width(&list)
+                            + width::width(&date),
+                    ),
+                    self.path_mode,
+                    scope.as_deref(),
+                );
+                Line::from(vec![
+                    Span::styled(volume, Style::default().fg(theme.gray)),
+                    Span::styled(icon, Style::default().fg(theme.gray)),
+                    Span::styled(badge, Style::default().fg(badge_color)),
+                    Span::styled(filename, filename_style),
+                    Span::styled(note, Style::default().fg(theme.main)),
+                    Span::styled(remark, Style::default().dim().fg(theme.gray)),
+                    Span::styled(list, Style::default().fg(theme.gray)),
+                    Span::styled(date, Style::default().fg(age_color(age, theme))),
+                    Span::styled(" ", Style::default()),
+                    Span::styled(path, Style::default().italic().fg(theme.gray)),
+                ])
+            })
+            .collect();
+        self.results_scroll = clamp_scroll(self.results_scroll, &lines, row_width);
+        let items: Vec<ListItem> = lines
+            .into_iter()
+            .map(|line| ListItem::new(scroll_line(line, self.results_scroll)))
+            .collect();
+
+        let list = if self.is_focus_search_bar {
+            List::new(items).block(block)
+        } else {
+            List::new(items)
+                .block(block)
+                .highlight_style(self.highlight_style())
+        };
+
+        // let list = list;
+        // .highlight_style(Style::default().underlined());
+        // .highlight_style(Style::default().fg(Color::Rgb(255, 169, 0)));
+
+        frame.render_stateful_widget(list, area, &mut self.list_state);
+    }
+
+    /// Whether the query is back with no results, the suggestions shown instead.
+    pub fn is_no_results(&self, app: &App) -> bool {
+        let results = app.query_results.load();
+        results.entrys.is_empty() && !results.search.is_empty() && !app.is_querying()
+    }
+
+    /// The suggestions for no results with their keys, the ones for Everything 1.5 grayed on
+    /// the older one.
+    fn suggestion_lines(&self, app: &App) -> Vec<Line<'static>> {
+        let theme = self.theme;
+        let suggestions = app.suggestions();
+        if suggestions.is_empty() {
+            return vec![];
+        }
+        let mut lines = vec![Line::styled(
+            tr!(NoResults),
+            Style::default().fg(theme.main),
+        )];
+        for (i, suggestion) in suggestions.into_iter().enumerate() {
+            let supported = suggestion
+                .requires()
+                .is_none_or(|feature| app.status.supports(feature));
+            let (style, marker) = if supported {
+                (Style::default().fg(theme.font), "")
+            } else {
+                (Style::default().fg(theme.gray), " (1.5)")
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  alt+{}  ", i + 1),
+                    Style::default().fg(theme.main),
+                ),
+                Span::styled(format!("{}{marker}", suggestion.label()), style),
+            ]));
+        }
+        lines
+    }
+
+    /// The examples of the searches and the recent ones, shown before any query.
+    fn empty_state_lines(&self, app: &App) -> Vec<Line<'static>> {
+        let theme = self.theme;
+        let examples = [
+            ("*.rs dm:today", tr!(ExampleToday)),
+            ("ext:pdf invoice", tr!(ExampleInvoice)),
+            ("size:>1gb", tr!(ExampleLarge)),
+            ("downloads\\ *.zip", tr!(ExampleFolder)),
+        ];
+        let width = examples
+            .iter()
+            .map(|(search, _)| width::width(search))
+            .max()
+            .unwrap_or_default()
+            + 2;
+        let mut lines = vec![Line::styled(tr!(Examples), Style::default().fg(theme.main))];
+        for (search, text) in examples {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {search:<width$}"),
+                    Style::default().fg(theme.font),
+                ),
+                Span::styled(text, Style::default().fg(theme.gray)),
+            ]));
+        }
+        if !app.recent.searches.is_empty() {
+            lines.push(Line::default());
+            lines.push(Line::styled(
+                tr!(RecentSearches),
+                Style::default().fg(theme.main),
+            ));
+            for search in &app.recent.searches {
+                lines.push(Line::styled(
+                    format!("  {search}"),
+                    Style::default().fg(theme.font),
+                ));
+            }
+        }
+        lines
+    }
+
+    fn render_preview(&mut self, app: &App, frame: &mut Frame, area: Rect) {
+        let theme = self.theme;
+        let block = Block::new()
+            .title(Span::styled(
+                tr!(PreviewTitle),
+                Style::default().fg(theme.main),
+            ))
+            .style(Style::default().fg(theme.main))
+            .borders(Borders::ALL);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let results = app.query_results.load();
+        let Some(entry) = self
+            .list_state
+            .selected()
+            .and_then(|i| results.entrys.get(i))
+        else {
+            self.content_matches = None;
+            return;
+        };
+        let row_width = inner.width as usize;
+        let filename = entry.filename.as_deref().unwrap_or_default();
+        let path = results.path(entry).unwrap_or(Path::new(""));
+        let mut lines = vec![
+            Line::styled(
+                width::truncate_end(&format::os_str(filename), row_width),
+                Style::default().fg(theme.font),
+            ),
+            Line::styled(
+                width::truncate_middle(&format::os_str(path.as_os_str()), row_width),
+                Style::default().italic().fg(theme.gray),
+            ),
+        ];
+        // only the fields requested by the flag preset
+        let fields = [
+            (
+                RequestFlags::EVERYTHING_REQUEST_SIZE,
+                tr!(Size),
+                entry.size.map(format::size),
+            ),
+            (
+                RequestFlags::EVERYTHING_REQUEST_DATE_MODIFIED,
+                tr!(Modified),
+                entry.date_modified.map(format::date),
+            ),
+            (
+                RequestFlags::EVERYTHING_REQUEST_DATE_CREATED,
+                tr!(Created),
+                entry.date_created.map(format::date),
+            ),
+            (
+                RequestFlags::EVERYTHING_REQUEST_DATE_ACCESSED,
+                tr!(Accessed),
+                entry.date_accessed.map(format::date),
+            ),
+            (
+                RequestFlags::EVERYTHING_REQUEST_ATTRIBUTES,
+                tr!(Attributes),
+                entry.attributes.map(format::attributes),
+            ),
+            (
+                RequestFlags::EVERYTHING_REQUEST_DATE_RECENTLY_CHANGED,
+                tr!(RecentlyChanged),
+                entry.date_recently_changed.map(format::date),
+            ),
+            (
+                RequestFlags::EVERYTHING_REQUEST_RUN_COUNT,
+                tr!(RunCount),
+                entry.run_count.map(|count| count.to_string()),
+            ),
+            (
+                RequestFlags::EVERYTHING_REQUEST_DATE_RUN,
+                tr!(LastRun),
+                entry.date_run.map(format::date),
+            ),
+        ];
+        for (flag, label, value) in fields {
+            if results.request_flags.contains(flag) {
+                lines.push(Line::styled(
+                    format!("{label}: {}", value.unwrap_or_default()),
+                    Style::default().fg(theme.gray),
+                ));
+            }
+        }
+        // only for the entries from a file list
+        if let Some(list) = entry
+            .file_list_filename
+            .as_deref()
+            .filter(|list| !list.is_empty())
+        {
+            lines.push(Line::styled(
+                format!("{}: {}", tr!(FileList), format::os_str(list)),
+                Style::default().fg(theme.gray),
+            ));
+        }
+        // and an empty line before the image
+        let info_height = lines.len() as u16 + 1;
+        frame.render_widget(Paragraph::new(lines), inner);
+
+        if let Some(filepath) = results.filepath(entry) {
+            let image_area = Rect {
+                y: inner.y + info_height,
+                height: inner.height.saturating_sub(info_height),
+                ..inner
+            };
+            if self.graphics.is_some() && graphics::is_image(&filepath) && !image_area.is_empty() {
+                self.preview_image = Some((filepath, image_area));
+            } else if let Some(backend) =
+                archive::backend(&filepath, app.config.archive.seven_zip.as_deref())
+            {
+                let modified = fs::metadata(&filepath).and_then(|m| m.modified()).ok();
+                let listing = self.archive_listings.get(
+                    &(filepath, modified),
+                    &app.sender,
+                    move |(path, _)| backend.list(path).map_err(|e| format!("{e:#}")),
+                );
+                let lines = match listing {
+                    Some(listing) => archive_lines(&listing, row_width, theme),
+                    None => vec![Line::styled(tr!(Loading), Style::default().fg(theme.gray))],
+                };
+                frame.render_widget(Paragraph::new(lines), image_area);
+            } else if app.config.preview.grep && !entry.is_folder && !image_area.is_empty() {
+                let terms = grep::terms(&app.last_search);
+                if terms.is_empty() {
+                    self.content_matches = None;
+                    return;
+                }
+                let modified = fs::metadata(&filepath).and_then(|m| m.modified()).ok();
+                let found = self.content_greps.get(
+                    &(filepath.clone(), modified, terms.clone()),
+                    &app.sender,
+                    |(path, _, terms)| grep::grep(path, terms).map_err(|e| format!("{e:#}")),
+                );
+                let mut matches = match self.content_matches.take() {
+                    Some(matches) if matches.path == filepath && matches.terms == terms => matches,
+                    _ => ContentMatches {
+                        path: filepath,
+                        terms,
+                        matches: None,
+                        current: 0,
+                    },
+                };
+                matches.matches = found;
+                let lines = matches.lines(image_area.height as usize, theme);
+                frame.render_widget(Paragraph::new(lines), image_area);
+                self.content_matches = Some(matches);
+                return;
+            }
+        }
+        self.content_matches = None;
+    }
+
+    /// Pick the next line matched in the preview, or the previous one, false if none is shown.
+    pub fn next_content_match(&mut self, forward: bool) -> bool {
+        let Some(matches) = self.content_matches.as_mut() else {
+            return false;
+        };
+        let count = matches
+            .matches
+            .as_deref()
+            .and_then(|matches| matches.as_ref().ok())
+            .map_or(0, Vec::len);
+        if !self.is_preview_show || count == 0 {
+            return false;
+        }
+        matches.current = if forward {
+            (matches.current + 1) % count
+        } else {
+            (matches.current + count - 1) % count
+        };
+        true
+    }
+
+    /// Index of the selected entry in the preview, if it is not converted fully yet.
+    pub fn partial_selected(&self, app: &App) -> Option<usize> {
+        if !self.is_preview_show || self.compare.is_some() {
+            return None;
+        }
+        let index = self.list_state.selected()?;
+        let results = app.query_results.load();
+        results
+            .entrys
+            .get(index)
+            .is_some_and(|entry| entry.is_partial)
+            .then_some(index)
+    }
+
+    /// Draw the image of the preview after the render, return true if a repaint is needed.
+    pub fn update_image(&mut self, app: &App) -> io::Result<bool> {
+        let Some(graphics) = self.graphics.as_mut() else {
+            return Ok(false);
+        };
+        let target = self
+            .preview_image
+            .as_ref()
+            .map(|(path, area)| (path.as_path(), *area));
+        graphics.update(target, &app.sender)
+    }
+
+    fn render_compare(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = self.theme;
+        let Some(diff) = self.compare.as_ref() else {
+            return;
+        };
+
+        let block = Block::new()
+            .title(vec![
+                Span::styled(
+                    tr!(
+                        Compare,
+                        added = diff.count(DiffKind::Added),
+                        removed = diff.count(DiffKind::Removed),
+                        changed = diff.count(DiffKind::Changed),
+                    ),
+                    Style::default().fg(theme.main),
+                ),
+                Span::styled(
+                    format!(
+                        "『{}』 -> 『{}』",
+                        format::os_str(&diff.baseline_search),
+                        format::os_str(&diff.current_search)
+                    ),
+                    Style::default().fg(theme.gray),
+                ),
+            ])
+            .title_bottom(self.notice_line())
+            .style(Style::default().fg(theme.main))
+            .borders(Borders::ALL);
+
+        let row_width = inner_width(area);
+        let scope = self.scope_of(diff.entrys.iter().filter_map(|x| x.path.as_deref()));
+        let lines: Vec<Line> = diff
+            .entrys
+            .iter()
+            .map(|x| {
+                let (mark, color) = match x.kind {
+                    DiffKind::Added => ("+ ", theme.added),
+                    DiffKind::Removed => ("- ", theme.removed),
+                    DiffKind::Changed => ("~ ", theme.changed),
+                };
+                let filename = x.entry.filename.as_deref().unwrap_or_default();
+                let path = x.path.as_deref().unwrap_or(Path::new(""));
+                let (filename, path) = fit_name_and_path(
+                    &format::os_str(filename),
+                    path,
+                    row_width.saturating_sub(width::width(mark)),
+                    self.path_mode,
+                    scope.as_deref(),
+                );
+                Line::from(vec![
+                    Span::styled(mark, Style::default().fg(color)),
+                    Span::styled(filename, Style::default().fg(color)),
+                    Span::styled(" ", Style::default()),
+                    Span::styled(path, Style::default().italic().fg(theme.gray)),
+                ])
+            })
+            .collect();
+        self.compare_scroll = clamp_scroll(self.compare_scroll, &lines, row_width);
+        let items: Vec<ListItem> = lines
+            .into_iter()
+            .map(|line| ListItem::new(scroll_line(line, self.compare_scroll)))
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(self.highlight_style());
+
+        frame.render_stateful_widget(list, area, &mut self.compare_state);
+    }
+
+    fn render_ext_sidebar(&mut self, app: &App, frame: &mut Frame, area: Rect) {
+        let theme = self.theme;
+        let results = app.query_results.load();
+        self.ext_counts.update(&results);
+        let counts = self.ext_counts.sorted();
+        let width = area.width.saturating_sub(2) as usize;
+        let items: Vec<ListItem> = counts
+            .iter()
+            .map(|(ext, count)| {
+                let count = count.to_string();
+                let name = if ext.is_empty() { "(none)" } else { ext };
+                let name_width = width.saturating_sub(count.len() + 2);
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!(" {} ", width::fit(name, name_width)),
+                        Style::default().fg(theme.font),
+                    ),
+                    Span::styled(count, Style::default().fg(theme.gray)),
+                ]))
+            })
+            .collect();
+        let selected = self.ext_state.selected().unwrap_or_default();
+        self.ext_state
+            .select(counts.len().checked_sub(1).map(|last| min(selected, last)));
+
+        let block = Block::new()
+            .title(Span::styled(
+                tr!(ExtensionsTitle),
+                Style::default().fg(theme.main),
+            ))
+            .style(Style::default().fg(theme.main))
+            .borders(Borders::ALL);
+        let mut list = List::new(items).block(block);
+        if self.is_focus_ext_sidebar {
+            list = list.highlight_style(self.highlight_style());
+        }
+        frame.render_stateful_widget(list, area, &mut self.ext_state);
+        self.ext_sidebar_area = Some(area);
+    }
+
+    /// Select the extension at the position of the mouse click, if it is in the sidebar.
+    pub fn click_ext_sidebar(&mut self, column: u16, row: u16) -> bool {
+        let Some(area) = self.ext_sidebar_area else {
+            return false;
+        };
+        let inner = area.inner(Margin {
+            vertical: 1,
+            horizontal: 1,
+        });
+        if !inner.contains((column, row).into()) {
+            return false;
+        }
+        let index = self.ext_state.offset() + (row - inner.y) as usize;
+        if index >= self.ext_counts.sorted().len() {
+            return false;
+        }
+        self.ext_state.select(Some(index));
+        true
+    }
+
+    /// The selected extension in the sidebar, empty for the files without one.
+    pub fn get_selected_extension(&self) -> Option<String> {
+        let counts = self.ext_counts.sorted();
+        let (ext, _) = counts.get(self.ext_state.selected()?)?;
+        Some(ext.to_string())
+    }
+
+    pub fn ext_sidebar_len(&self) -> usize {
+        self.ext_counts.sorted().len()
+    }
+
+    fn render_volumes(&mut self, app: &App, selected: usize, frame: &mut Frame) {
+        let theme = self.theme;
+        let results = app.query_results.load();
+        self.volume_counts.update(&results);
+        let counts = self.volume_counts.sorted();
+        let total: usize = counts.iter().map(|(_, count)| count).sum();
+        let rows = [(tr!(AllVolumes), total)].into_iter().chain(counts);
+        let name_width = rows
+            .clone()
+            .map(|(volume, _)| width::width(volume))
+            .max()
+            .unwrap_or_default();
+        let lines: Vec<Line> = rows
+            .enumerate()
+            .map(|(row, (volume, count))| {
+                let style = if row == selected {
+                    Style::default().fg(theme.light_font).reversed()
+                } else {
+                    Style::default().fg(theme.font)
+                };
+                Line::from(vec![
+                    Span::styled(format!(" {} ", width::fit(volume, name_width)), style),
+                    Span::styled(format!(" {count}"), Style::default().fg(theme.gray)),
+                ])
+            })
+            .collect();
+
+        let block = Block::new()
+            .title(Span::styled(tr!(Volumes), Style::default().fg(theme.main)))
+            .title_bottom(Line::styled(
+                tr!(VolumesHint),
+                Style::default().fg(theme.gray),
+            ))
+            .style(Style::default().fg(theme.main))
+            .borders(Borders::ALL);
+        let height = lines.len() as u16 + 2;
+        let area = centered_rect(frame.area(), 60, 100);
+        let popup_area = Rect {
+            y: area.y + area.height.saturating_sub(height) / 2,
+            height: height.min(area.height),
+            ..area
+        };
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+    }
+
+    /// The rows of the volumes popup, all of them and each one.
+    pub fn volumes_len(&self) -> usize {
+        self.volume_counts.len() + 1
+    }
+
+    /// The volume of the row in the volumes popup, none for all of them.
+    pub fn volume_at(&self, row: usize) -> Option<String> {
+        let counts = self.volume_counts.sorted();
+        let (volume, _) = counts.get(row.checked_sub(1)?)?;
+        Some(volume.to_string())
+    }
+
+    fn render_usage(&mut self, frame: &mut Frame, area: Rect) {
+        const BAR_WIDTH: usize = 20;
+        let theme = self.theme;
+        let title = match &self.usage {
+            Some(usage) => vec![
+                Span::styled(
+                    tr!(DiskUsage, total = format::size(usage.total)),
+                    Style::default().fg(theme.main),
+                ),
+                Span::styled(
+                    format!("『{}』", format::os_str(usage.folder.as_os_str())),
+                    Style::default().fg(theme.gray),
+                ),
+                Span::styled(
+                    if usage.is_aggregated {
+                        tr!(DiskUsageSummed)
+                    } else {
+                        ""
+                    },
+                    Style::default().fg(theme.gray),
+                ),
+            ],
+            None => vec![Span::styled(
+                tr!(DiskUsageScanning),
+                Style::default().fg(theme.main),
+            )],
+        };
+        let block = Block::new()
+            .title(title)
+            .title_bottom(self.notice_line())
+            .style(Style::default().fg(theme.main))
+            .borders(Borders::ALL);
+
+        let Some(usage) = &self.usage else {
+            frame.render_widget(block, area);
+            return;
+        };
+        let items: Vec<ListItem> = usage
+            .entrys
+            .iter()
+            .map(|entry| {
+                let ratio = if usage.total == 0 {
+                    0.0
+                } else {
+                    entry.size as f64 / usage.total as f64
+                };
+                let filled = (ratio * BAR_WIDTH as f64).round() as usize;
+                let bar = format!("[{}{}]", "#".repeat(filled), " ".repeat(BAR_WIDTH - filled));
+                let mut name = format::os_str(&entry.name).into_owned();
+                if entry.is_folder {
+                    name.push('\\');
+                }
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{:>10} ", format::size(entry.size)),
+                        Style::default().fg(theme.font),
+                    ),
+                    Span::styled(
+                        format!("{:>5.1}% ", ratio * 100.0),
+                        Style::default().fg(theme.gray),
+                    ),
+                    Span::styled(bar, Style::default().fg(theme.main)),
+                    Span::styled(format!(" {name}"), Style::default().fg(theme.font)),
+                ]))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(self.highlight_style());
+        frame.render_stateful_widget(list, area, &mut self.usage_state);
+    }
+
+    fn render_history(&mut self, app: &App, frame: &mut Frame) {
+        let theme = self.theme;
+        let history = app.run_history.load();
+
+        let tab_style = |active: bool| {
+            if active {
+                Style::default().fg(theme.light_font).underlined()
+            } else {
+                Style::default().fg(theme.gray)
+            }
+        };
+        let block = Block::new()
+            .title(vec![
+                Span::styled(tr!(RunHistory), Style::default().fg(theme.main)),
+                Span::styled(tr!(Frequent), tab_style(!self.is_history_by_recent)),
+                Span::styled(" | ", Style::default().fg(theme.gray)),
+                Span::styled(tr!(Recent), tab_style(self.is_history_by_recent)),
+                Span::styled(" (tab)", Style::default().fg(theme.gray)),
+            ])
+            .style(Style::default().fg(theme.main))
+            .borders(Borders::ALL);
+
+        let popup_area = centered_rect(frame.area(), 80, 70);
+        // run count, then the filename column aligned up to half of the rest
+        let row_width = inner_width(popup_area).saturating_sub(5);
+        let filename_width = history
+            .entrys
+            .iter()
+            .filter_map(|entry| entry.filename.as_deref())
+            .map(|filename| width::width(&format::os_str(filename)))
+            .max()
+            .unwrap_or_default()
+            .min(row_width / 2);
+        let lines: Vec<Line> = history
+            .entrys
+            .iter()
+            .map(|entry| {
+                let filename = entry.filename.as_deref().unwrap_or_default();
+                let path = history.path(entry).unwrap_or(Path::new(""));
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:>4} ", entry.run_count.unwrap_or_default()),
+                        Style::default().fg(theme.gray),
+                    ),
+                    Span::styled(
+                        width::fit(&format::os_str(filename), filename_width),
+                        Style::default().fg(theme.font),
+                    ),
+                    Span::styled(" ", Style::default()),
+                    Span::styled(
+                        width::truncate_middle(
+                            &format::os_str(path.as_os_str()),
+                            row_width.saturating_sub(filename_width + 1),
+                        ),
+                        Style::default().italic().fg(theme.gray),
+                    ),
+                ])
+            })
+            .collect();
+        self.history_scroll = clamp_scroll(self.history_scroll, &lines, row_width + 5);
+        let items: Vec<ListItem> = lines
+            .into_iter()
+            .map(|line| ListItem::new(scroll_line(line, self.history_scroll)))
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(self.highlight_style());
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_stateful_widget(list, popup_area, &mut self.history_state);
+    }
+
+    /// Number of the matches of the text being typed, if it is not queried yet.
+    fn live_count(&self, app: &App, queried: &OsStr) -> String {
+        let count = app.live_count.load();
+        let typing = app.search_for(&self.search_text());
+        if count.search.is_empty() || count.search != *typing || queried == typing.as_str() {
+            return String::new();
+        }
+        tr!(LiveCount, count = count.total)
+    }
+
+    /// The folder which the paths are shown relative to, only in the relative path mode.
+    fn scope_of<'p>(&self, paths: impl Iterator<Item = &'p Path>) -> Option<PathBuf> {
+        if self.path_mode == PathMode::Relative {
+            common_ancestor(paths)
+        } else {
+            None
+        }
+    }
+
+    pub fn scroll_left(&mut self) {
+        let scroll = if self.is_compare_show() {
+            &mut self.compare_scroll
+        } else {
+            &mut self.results_scroll
+        };
+        *scroll = scroll.saturating_sub(SCROLL_STEP);
+    }
+
+    /// Scroll to the right, it is clamped to the longest line when rendering.
+    pub fn scroll_right(&mut self) {
+        let scroll = if self.is_compare_show() {
+            &mut self.compare_scroll
+        } else {
+            &mut self.results_scroll
+        };
+        *scroll = scroll.saturating_add(SCROLL_STEP);
+    }
+
+    /// Whether Everything runs as administrator, some operations fail without it.
+    fn admin_title(&self, app: &App) -> Line<'static> {
+        let theme = self.theme;
+        if app.is_offline() {
+            Line::default()
+        } else if app.status.is_admin {
+            Line::styled(tr!(Admin), Style::default().fg(theme.light_font).bold()).right_aligned()
+        } else {
+            Line::styled(tr!(NotAdmin), Style::default().fg(theme.gray)).right_aligned()
+        }
+    }
+
+    /// The selected one of the lists, reversed too in accessibility mode, not by color only.
+    fn highlight_style(&self) -> Style {
+        let style = Style::default().fg(self.theme.light_font);
+        if self.accessible {
+            style.reversed()
+        } else {
+            style
+        }
+    }
+
+    fn breadcrumbs_height(&self) -> u16 {
+        if self.is_breadcrumbs_show {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// The folders of the selected one from the drive, numbered by the first nine for the keys.
+    /// The nearest ones are kept if they are too long for the footer.
+    fn render_breadcrumbs(&mut self, app: &App, frame: &mut Frame, area: Rect) {
+        const SEPARATOR: &str = " › ";
+        const ELLIPSIS: &str = "… ";
+        let theme = self.theme;
+        self.breadcrumbs.clear();
+        if area.is_empty() {
+            return;
+        }
+        let Some(path) = self.get_selected_full_path(app) else {
+            return;
+        };
+        let mut folders: Vec<&Path> = path.ancestors().skip(1).collect();
+        folders.reverse();
+        let segments: Vec<(String, String)> = folders
+            .iter()
+            .enumerate()
+            .map(|(i, folder)| {
+                let number = if i < 9 {
+                    format!("{} ", i + 1)
+                } else {
+                    String::new()
+                };
+                let name = folder
+                    .file_name()
+                    .unwrap_or(folder.as_os_str())
+                    .to_string_lossy()
+                    .trim_end_matches('\\')
+                    .to_string();
+                (number, name)
+            })
+            .collect();
+        let segment_width =
+            |(number, name): &(String, String)| width::width(number) + width::width(name);
+        // drop the farthest ones until the rest fit
+        let mut first = 0;
+        while first + 1 < segments.len() {
+            let shown = &segments[first..];
+            let total: usize = shown.iter().map(segment_width).sum::<usize>()
+                + width::width(SEPARATOR) * (shown.len() - 1)
+                + if first > 0 { width::width(ELLIPSIS) } else { 0 };
+            if total <= area.width as usize {
+                break;
+            }
+            first += 1;
+        }
+
+        // the ones dropped are still taken by the numbers
+        self.breadcrumbs = folders[..first]
+            .iter()
+            .map(|folder| (folder.to_path_buf(), Rect::default()))
+            .collect();
+        let mut spans = vec![];
+        let mut x = area.x;
+        if first > 0 {
+            spans.push(Span::styled(ELLIPSIS, Style::default().fg(theme.gray)));
+            x += width::width(ELLIPSIS) as u16;
+        }
+        for (i, segment) in segments.iter().enumerate().skip(first) {
+            if i > first {
+                spans.push(Span::styled(SEPARATOR, Style::default().fg(theme.gray)));
+                x += width::width(SEPARATOR) as u16;
+            }
+            let (number, name) = segment;
+            spans.push(Span::styled(
+                number.clone(),
+                Style::default().fg(theme.main),
+            ));
+            spans.push(Span::styled(name.clone(), Style::default().fg(theme.font)));
+            let width = (segment_width(segment) as u16).min(area.right().saturating_sub(x));
+            let segment_area = Rect { x, width, ..area };
+            self.breadcrumbs
+                .push((folders[i].to_path_buf(), segment_area.intersection(area)));
+            x = x.saturating_add(width);
+        }
+        frame.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+
+    /// The folder in the footer of the number, from 1 for the drive.
+    pub fn breadcrumb(&self, number: usize) -> Option<PathBuf> {
+        let index = number.checked_sub(1)?;
+        self.breadcrumbs
+            .get(index)
+            .map(|(folder, _)| folder.clone())
+    }
+
+    /// The folder in the footer clicked, if any.
+    pub fn click_breadcrumb(&self, column: u16, row: u16) -> Option<PathBuf> {
+        self.breadcrumbs
+            .iter()
+            .find(|(_, area)| area.contains((column, row).into()))
+            .map(|(folder, _)| folder.clone())
+    }
+
+    fn status_height(&self) -> u16 {
+        if self.accessible {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// The state in plain text, and the terminal cursor at the text being typed or the status,
+    /// so the screen readers read out the changes.
+    fn render_status_line(&self, app: &App, frame: &mut Frame, area: Rect) {
+        let results = app.query_results.load();
+        let mut status = match self.list_state.selected() {
+            _ if app.is_querying() => tr!(Searching).to_string(),
+            Some(index) if !self.is_focus_search_bar => match results.entrys.get(index) {
+                Some(entry) => {
+                    let kind = if entry.is_folder {
+                        tr!(Folder)
+                    } else {
+                        tr!(File)
+                    };
+                    let name = entry.filename.as_deref().unwrap_or_default();
+                    let path = results.path(entry).unwrap_or(Path::new(""));
+                    tr!(
+                        SelectedStatus,
+                        index = index + 1,
+                        number = results.number,
+                        kind = kind,
+                        name = format::os_str(name),
+                        path = format::os_str(path.as_os_str()),
+                    )
+                }
+                None => String::new(),
+            },
+            _ => tr!(
+                ResultsStatus,
+                number = results.number,
+                total = results.total,
+                search = format::os_str(&results.search)
+            ),
+        };
+        if let Some(notice) = &self.notice {
+            status = format!("{notice}. {status}");
+        }
+        frame.render_widget(
+            Paragraph::new(status).style(Style::default().fg(self.theme.font)),
+            area,
+        );
+
+        // at the text being typed otherwise, see `render`
+        if !self.is_focus_search_bar {
+            frame.set_cursor_position((area.x, area.y));
+        }
+    }
+
+    /// The exact dates of the selected one, to the second.
+    fn dates_line(&self, results: &QueryResults) -> Line<'static> {
+        let entry = match self.list_state.selected() {
+            Some(index) if !self.is_focus_search_bar => results.entrys.get(index),
+            _ => None,
+        };
+        let Some(entry) = entry else {
+            return Line::default();
+        };
+        let dates: Vec<String> = [
+            (tr!(DateModifiedShort), entry.date_modified),
+            (tr!(DateCreatedShort), entry.date_created),
+            (tr!(DateAccessedShort), entry.date_accessed),
+            (tr!(DateChangedShort), entry.date_recently_changed),
+        ]
+        .into_iter()
+        .filter_map(|(label, date)| Some(format!("{label} {}", format::date_exact(date?))))
+        .collect();
+        if dates.is_empty() {
+            return Line::default();
+        }
+        Line::styled(
+            format!(" {} ", dates.join(", ")),
+            Style::default().fg(self.theme.gray),
+        )
+    }
+
+    fn notice_line(&self) -> Line<'static> {
+        let theme = self.theme;
+        match &self.notice {
+            Some(notice) => Line::styled(format!(" {notice} "), Style::default().fg(theme.font)),
+            None => Line::default(),
+        }
+    }
+
+    /// Update the layout-dependent state for the new terminal height, so that the paging works
+    /// before the next render, and keep the selections visible.
+    pub fn resize(&mut self, height: u16, app: &App) {
+        // the search bar, the borders of the list, the breadcrumbs and the status line
+        let page_height = height
+            .saturating_sub(
+                self.search_bar_height() + 2 + self.breadcrumbs_height() + self.status_height(),
+            )
+            .max(1);
+        self.last_page_height = Some(page_height);
+        self.ensure_visible(app);
+        let compare_len = self.compare.as_ref().map_or(0, |diff| diff.entrys.len());
+        keep_visible(&mut self.compare_state, compare_len, page_height as usize);
+    }
+
+    /// Keep the invariant of the results list: the selection and the offset within the results,
+    /// and the selected one in the page. Kept whenever the results or the page height change,
+    /// e.g. re-queried or sorted, so the paging never counts from past the end.
+    pub fn ensure_visible(&mut self, app: &App) {
+        let results = app.query_results.load();
+        self.keep_results_visible(results.entrys.len());
+    }
+
+    fn keep_results_visible(&mut self, len: usize) {
+        let page_height = self.last_page_height.unwrap_or(1) as usize;
+        keep_visible(&mut self.list_state, len, page_height);
+    }
+
+    /// The query text of the search bar. The non-empty lines of the multi-line one are
+    /// OR-combined, each in a group.
+    pub fn search_text(&self) -> String {
+        if !self.is_multiline {
+            return self.textarea.lines()[0].clone();
+        }
+        let lines: Vec<&str> = self
+            .textarea
+            .lines()
+            .iter()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+        match lines.as_slice() {
+            [line] => line.to_string(),
+            lines => {
+                let groups: Vec<String> = lines.iter().map(|line| format!("<{line}>")).collect();
+                groups.join(" | ")
+            }
+        }
+    }
+
+    /// Switch the search bar to several lines, or back to one line of the query of them.
+    pub fn toggle_multiline(&mut self) {
+        if self.is_multiline {
+            let text = self.search_text();
+            self.is_multiline = false;
+            self.set_search_text(&text);
+        } else {
+            self.is_multiline = true;
+        }
+        self.is_focus_search_bar = true;
+    }
+
+    fn search_bar_height(&self) -> u16 {
+        if self.is_multiline {
+            let lines = (self.textarea.lines().len() as u16).clamp(2, MAX_SEARCH_LINES);
+            lines + 2
+        } else {
+            SEARCH_BAR_HEIGHT
+        }
+    }
+
+    /// Insert the text at the cursor over the selected, in several lines only if the search bar
+    /// is in several lines.
+    pub fn insert_search_text(&mut self, text: &str) {
+        let text = if self.is_multiline {
+            text.lines().collect::<Vec<_>>().join("\n")
+        } else {
+            one_line(text)
+        };
+        let old_yank = self.textarea.yank_text();
+        self.textarea.set_yank_text(text);
+        self.textarea.paste();
+        self.textarea.set_yank_text(old_yank);
+    }
+
+    pub fn set_search_text(&mut self, text: &str) {
+        let old_yank = self.textarea.yank_text();
+        self.textarea.set_yank_text(text);
+        self.textarea.select_all();
+        self.textarea.paste();
+        self.textarea.set_yank_text(old_yank);
+    }
+
+    pub fn is_selected(&self) -> bool {
+        self.list_state.selected().is_some()
+    }
+
+    pub fn is_first_selected(&self) -> bool {
+        self.list_state.selected().is_some_and(|i| i == 0)
+    }
+
+    pub fn is_last_selected(&self, app: &App) -> bool {
+        let results = app.query_results.load();
+        self.list_state
+            .selected()
+            .is_some_and(|i| i + 1 >= results.number as usize)
+    }
+
+    pub fn select_first(&mut self, app: &mut App) {
+        let results = app.query_results.load();
+        if results.number > 0 {
+            self.list_state.select(Some(0));
+        }
+    }
+
+    pub fn select_last(&mut self, app: &mut App) {
+        let results = app.query_results.load();
+        if results.number > 0 {
+            self.list_state.select(Some(results.number as usize - 1));
+        }
+    }
+
+    pub fn select_previous_n(&mut self, n: usize, app: &mut App) {
+        let results = app.query_results.load();
+        if results.number > 0 {
+            let last = (results.number - 1) as usize;
+            self.list_state.select(
+                self.list_state
+                    .selected()
+                    .and_then(|i| Some(min(last, i.saturating_sub(n)))),
+            );
+        }
+    }
+
+    pub fn select_next_n(&mut self, n: usize, app: &mut App) {
+        let results = app.query_results.load();
+        if results.number > 0 {
+            let last = (results.number - 1) as usize;
+            self.list_state.select(
+                self.list_state
+                    .selected()
+                    .and_then(|i| Some(min(last, i.saturating_add(n)))),
+            );
+        }
+    }
+
+    /// Whether the selected one is within a page of the end of the results loaded.
+    pub fn is_near_end(&self, app: &App) -> bool {
+        let Some(selected) = self.list_state.selected() else {
+            return false;
+        };
+        let page_height = self.last_page_height.unwrap_or(1) as usize;
+        selected + page_height >= app.query_results.load().entrys.len()
+    }
+
+    pub fn is_first_page(&self) -> bool {
+        self.list_state.offset() == 0
+    }
+
+    pub fn is_last_page(&self, results_number: u32) -> bool {
+        let page_height = self.last_page_height.unwrap_or(1) as u32;
+        let offset = self.list_state.offset() as u32;
+        results_number.saturating_sub(offset) <= page_height
+    }
+
+    pub fn select_next_page(&mut self, app: &mut App) {
+        let results = app.query_results.load();
+        self.keep_results_visible(results.entrys.len());
+        if results.number > 0 {
+            if self.is_last_page(results.number) {
+                self.list_state.select(Some(results.number as usize - 1));
+            } else {
+                let old_offset = self.list_state.offset();
+                let page_height = self.last_page_height.unwrap() as usize;
+                let new_offset = old_offset.saturating_add(page_height);
+                *self.list_state.offset_mut() = new_offset;
+
+                let n = new_offset - old_offset;
+                let last = (results.number - 1) as usize;
+                self.list_state.select(
+                    self.list_state
+                        .selected()
+                        .and_then(|i| Some(min(last, i.saturating_add(n)))),
+                );
+                self.keep_results_visible(results.entrys.len());
+            }
+        }
+    }
+
+    pub fn select_previous_page(&mut self, app: &mut App) {
+        let results = app.query_results.load();
+        self.keep_results_visible(results.entrys.len());
+        if results.number > 0 {
+            if self.is_first_page() {
+                self.list_state.select(Some(0));
+            } else {
+                let old_offset = self.list_state.offset();
+                let page_height = self.last_page_height.unwrap() as usize;
+                let new_offset = old_offset.saturating_sub(page_height);
+                *self.list_state.offset_mut() = new_offset;
+
+                let n = old_offset - new_offset;
+                let last = (results.number - 1) as usize;
+                self.list_state.select(
+                    self.list_state
+                        .selected()
+                        .and_then(|i| Some(min(last, i.saturating_sub(n)))),
+                );
+                self.keep_results_visible(results.entrys.len());
+            }
+        }
+    }
+
+    pub fn is_compare_show(&self) -> bool {
+        self.compare.is_some()
+    }
+
+    /// Show (or refresh) the compare view, do nothing if there is no baseline yet.
+    pub fn show_compare(&mut self, app: &App) {
+        if let Some(diff) = app.diff_with_baseline() {
+            let last = diff.entrys.len().checked_sub(1);
+            let selected = self.compare_state.selected().or(Some(0));
+            self.compare_state
+                .select(last.and_then(|last| selected.map(|i| min(i, last))));
+            self.compare = Some(diff);
+        }
+    }
+
+    /// Show the disk usage view, and update it with the results back.
+    pub fn show_usage(&mut self, app: &App) {
+        self.is_usage_show = true;
+        self.usage = app.disk_usage();
+        let len = self.usage.as_ref().map_or(0, |usage| usage.entrys.len());
+        let selected = self.usage_state.selected().unwrap_or_default();
+        self.usage_state
+            .select(len.checked_sub(1).map(|last| min(selected, last)));
+    }
+
+    pub fn hide_usage(&mut self) {
+        self.is_usage_show = false;
+        self.usage = None;
+        self.usage_state = ListState::default();
+    }
+
+    /// Full path of the selected one in the disk usage view, and whether it is a folder.
+    pub fn get_selected_usage_path(&self) -> Option<(PathBuf, bool)> {
+        let usage = self.usage.as_ref()?;
+        let entry = usage.entrys.get(self.usage_state.selected()?)?;
+        Some((usage.folder.join(&entry.name), entry.is_folder))
+    }
+
+    pub fn hide_compare(&mut self) {
+        self.compare = None;
+        self.compare_state = ListState::default();
+    }
+
+    pub fn select_compare_previous_n(&mut self, n: usize) {
+        if let Some(i) = self.compare_state.selected() {
+            self.compare_state.select(Some(i.saturating_sub(n)));
+        }
+    }
+
+    pub fn select_compare_next_n(&mut self, n: usize) {
+        let len = self.compare.as_ref().map_or(0, |diff| diff.entrys.len());
+        if let Some(i) = self.compare_state.selected() {
+            self.compare_state
+                .select(Some(min(len.saturating_sub(1), i.saturating_add(n))));
+        }
+    }
+
+    pub fn select_history_previous_n(&mut self, n: usize) {
+        let selected = self.history_state.selected().map(|i| i.saturating_sub(n));
+        self.history_state.select(selected.or(Some(0)));
+    }
+
+    pub fn select_history_next_n(&mut self, n: usize, app: &App) {
+        let len = app.run_history.load().entrys.len();
+        if len > 0 {
+            let selected = self
+                .history_state
+                .selected()
+                .map_or(0, |i| i.saturating_add(n));
+            self.history_state.select(Some(min(len - 1, selected)));
+        }
+    }
+
+    pub fn get_selected_history_path(&self, app: &App) -> Option<PathBuf> {
+        let index = self.history_state.selected()?;
+        let history = app.run_history.load();
+        history.filepath(history.entrys.get(index)?)
+    }
+
+    pub fn unselect(&mut self) {
+        self.list_state.select(None);
+    }
+
+    /// Remember the path of the selected result, the one to keep across the refreshes.
+    pub fn remember_selected(&mut self, app: &App) {
+        let results = app.query_results.load();
+        self.sticky_path = self
+            .list_state
+            .selected()
+            .and_then(|i| results.entrys.get(i))
+            .and_then(|entry| results.filepath(entry));
+    }
+
+    /// Select the remembered path again in the new results, e.g. refreshed in watch mode, or
+    /// keep the index if it is gone, rather than selecting another file at the same index.
+    pub fn keep_selected(&mut self, app: &App) {
+        let (Some(path), Some(index)) = (&self.sticky_path, self.list_state.selected()) else {
+            return;
+        };
+        let results = app.query_results.load();
+        let moved = results
+            .entrys
+            .get(index)
+            .and_then(|entry| results.filepath(entry))
+            .is_none_or(|selected| selected != *path);
+        if moved {
+            if let Some(position) = results.position(path) {
+                self.list_state.select(Some(position));
+            }
+        }
+    }
+
+    /// The marked ones in the order of the results, the ones not in them are left out.
+    pub fn marked_paths(&self, app: &App) -> Vec<PathBuf> {
+        let results = app.query_results.load();
+        results
+            .entrys
+            .iter()
+            .filter_map(|entry| results.filepath(entry))
+            .filter(|path| self.marked.contains(path))
+            .collect()
+    }
+
+    pub fn get_selected_full_path(&self, app: &App) -> Option<PathBuf> {
+        let index = self.list_state.selected()?;
+        let results = app.query_results.load();
+        let entry = results.entrys.get(index)?;
+        results.filepath(entry)
+    }
+}
+
+fn render_filter_picker(picker: &FilterPicker, theme: Theme, frame: &mut Frame) {
+    let tab_style = |active: bool| {
+        if active {
+            Style::default().fg(theme.light_font).underlined()
+        } else {
+            Style::default().fg(theme.gray)
+        }
+    };
+    let (title, hint, selected, presets, bounds) = match picker {
+        FilterPicker::Date(picker) => (
+            vec![
+                Span::styled(tr!(DateFilter), Style::default().fg(theme.main)),
+                Span::styled(
+                    tr!(Modified),
+                    tab_style(picker.field == DateField::Modified),
+                ),
+                Span::styled(" | ", Style::default().fg(theme.gray)),
+                Span::styled(tr!(Created), tab_style(picker.field == DateField::Created)),
+                Span::styled(" (tab)", Style::default().fg(theme.gray)),
+            ],
+            tr!(DateFilterHint),
+            picker.selected,
+            DatePicker::PRESETS.map(|(label, _)| label.text()).to_vec(),
+            [(tr!(From), &picker.from), (tr!(To), &picker.to)].map(|(label, date)| {
+                let date = if date.is_empty() { "YYYY-MM-DD" } else { date };
+                (label, format!(" {date} "))
+            }),
+        ),
+        FilterPicker::Size(picker) => (
+            vec![Span::styled(
+                tr!(SizeFilter),
+                Style::default().fg(theme.main),
+            )],
+            tr!(SizeFilterHint),
+            picker.selected,
+            SizePicker::PRESETS.map(|(label, _)| label.text()).to_vec(),
+            [(tr!(Min), &picker.min), (tr!(Max), &picker.max)].map(|(label, bound)| {
+                let value = if bound.value.is_empty() {
+                    "-"
+                } else {
+                    &bound.value
+                };
+                (label, format!(" {value:>6} {:<2} ", bound.unit.label()))
+            }),
+        ),
+    };
+    let block = Block::new()
+        .title(title)
+        .title_bottom(Line::styled(hint, Style::default().fg(theme.gray)))
+        .style(Style::default().fg(theme.main))
+        .borders(Borders::ALL);
+
+    let row_style = |row: usize| {
+        if row == selected {
+            Style::default().fg(theme.light_font).reversed()
+        } else {
+            Style::default().fg(theme.font)
+        }
+    };
+    // the presets, then the rows of the range
+    let mut lines: Vec<Line> = presets
+        .iter()
+        .enumerate()
+        .map(|(row, label)| Line::styled(format!(" {label} "), row_style(row)))
+        .collect();
+    lines.push(Line::default());
+    for (row, (label, value)) in bounds.into_iter().enumerate() {
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {label:<5}"), Style::default().fg(theme.gray)),
+            Span::styled(value, row_style(presets.len() + row)),
+        ]));
+    }
+
+    let height = lines.len() as u16 + 2;
+    let area = centered_rect(frame.area(), 60, 100);
+    let popup_area = Rect {
+        y: area.y + area.height.saturating_sub(height) / 2,
+        height: height.min(area.height),
+        ..area
+    };
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
+
+fn render_commands_menu(
+    commands: &[Command],
+    state: &mut ListState,
+    theme: Theme,
+    frame: &mut Frame,
+) {
+    let block = Block::new()
+        .title(Span::styled(
+            tr!(CommandsTitle),
+            Style::default().fg(theme.main),
+        ))
+        .title_bottom(Line::styled(
+            tr!(CommandsHint),
+            Style::default().fg(theme.gray),
+        ))
+        .style(Style::default().fg(theme.main))
+        .borders(Borders::ALL);
+
+    let name_width = commands
+        .iter()
+        .map(|command| width::width(&command.name))
+        .max()
+        .unwrap_or_default();
+    let items: Vec<ListItem> = commands
+        .iter()
+        .map(|command| {
+            let key = command.key.as_ref().map_or("", |key| key.label.as_str());
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!(" {} ", width::fit(&command.name, name_width)),
+                    Style::default().fg(theme.font),
+                ),
+                Span::styled(format!("{key} "), Style::default().fg(theme.gray)),
+            ]))
+        })
+        .collect();
+
+    let height = items.len() as u16 + 2;
+    let area = centered_rect(frame.area(), 60, 100);
+    let popup_area = Rect {
+        y: area.y + area.height.saturating_sub(height) / 2,
+        height: height.min(area.height),
+        ..area
+    };
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().fg(theme.light_font).reversed());
+    frame.render_widget(Clear, popup_area);
+    frame.render_stateful_widget(list, popup_area, state);
+}
+
+fn render_templates_menu(
+    templates: &[Template],
+    state: &mut ListState,
+    status: &Status,
+    theme: Theme,
+    frame: &mut Frame,
+) {
+    let block = Block::new()
+        .title(Span::styled(
+            tr!(TemplatesTitle),
+            Style::default().fg(theme.main),
+        ))
+        .title_bottom(Line::styled(
+            tr!(TemplatesHint),
+            Style::default().fg(theme.gray),
+        ))
+        .style(Style::default().fg(theme.main))
+        .borders(Borders::ALL);
+
+    let name_width = templates
+        .iter()
+        .map(|template| width::width(&template.name))
+        .max()
+        .unwrap_or_default();
+    let items: Vec<ListItem> = templates
+        .iter()
+        .map(|template| {
+            // disabled on the older Everything, with the version needed
+            let unsupported = template.requires.filter(|&f| !status.supports(f));
+            let name_color = if unsupported.is_some() {
+                theme.gray
+            } else {
+                theme.font
+            };
+            let mut spans = vec![
+                Span::styled(
+                    format!(" {} ", width::fit(&template.name, name_width)),
+                    Style::default().fg(name_color),
+                ),
+                Span::styled(
+                    format!("{} ", template.snippet),
+                    Style::default().fg(theme.gray),
+                ),
+            ];
+            if let Some(feature) = unsupported {
+                let (major, minor) = feature.since();
+                spans.push(Span::styled(
+                    format!("({major}.{minor}) "),
+                    Style::default().fg(theme.gray),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let height = items.len() as u16 + 2;
+    let area = centered_rect(frame.area(), 60, 100);
+    let popup_area = Rect {
+        y: area.y + area.height.saturating_sub(height) / 2,
+        height: height.min(area.height),
+        ..area
+    };
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().fg(theme.light_font).reversed());
+    frame.render_widget(Clear, popup_area);
+    frame.render_stateful_widget(list, popup_area, state);
+}
+
+fn render_report(report: &Report, is_pending: bool, theme: Theme, frame: &mut Frame) {
+    let scope = if is_pending {
+        tr!(ReportPending)
+    } else if report.is_partial() {
+        tr!(ReportPartial)
+    } else {
+        tr!(ReportAll)
+    };
+    let block = Block::new()
+        .title(vec![
+            Span::styled(tr!(ReportTitle), Style::default().fg(theme.main)),
+            Span::styled(
+                format!("『{}』", format::os_str(&report.search)),
+                Style::default().fg(theme.light_font),
+            ),
+            Span::styled(
+                format!(" {} of {} ", report.count, report.total),
+                Style::default().fg(theme.gray),
+            ),
+        ])
+        .title_bottom(Line::styled(
+            format!(" {scope}, esc to close "),
+            Style::default().fg(theme.gray),
+        ))
+        .style(Style::default().fg(theme.main))
+        .borders(Borders::ALL);
+
+    let popup_area = centered_rect(frame.area(), 90, 80);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(block.inner(popup_area));
+
+    let heading = |title: &str| Line::styled(format!(" {title}"), Style::default().fg(theme.main));
+    let not_requested = || Line::styled(tr!(NotRequested), Style::default().fg(theme.gray));
+    let width = columns[0].width as usize;
+    let items = |lines: &mut Vec<Line>, items: &[ReportItem], value: fn(u64) -> String| {
+        for item in items {
+            let value = value(item.value);
+            let path = format::os_str(item.path.as_os_str());
+            let path_width = width.saturating_sub(width::width(&value) + 5);
+            lines.push(Line::from(vec![
+                Span::styled(format!("   {value}  "), Style::default().fg(theme.gray)),
+                Span::styled(
+                    width::truncate_middle(&path, path_width),
+                    Style::default().fg(theme.font),
+                ),
+            ]));
+        }
+    };
+    let mut left = vec![];
+    for (title, list, has) in [
+        (tr!(Newest), &report.newest, report.has_dates),
+        (tr!(Oldest), &report.oldest, report.has_dates),
+    ] {
+        left.push(heading(title));
+        if has {
+            items(&mut left, list, format::date);
+        } else {
+            left.push(not_requested());
+        }
+    }
+    for (title, list) in [
+        (tr!(Largest), &report.largest),
+        (tr!(Smallest), &report.smallest),
+    ] {
+        left.push(heading(title));
+        if report.has_sizes {
+            items(&mut left, list, |size| {
+                format!("{:>10}", format::size(size))
+            });
+        } else {
+            left.push(not_requested());
+        }
+    }
+
+    let width = columns[1].width as usize;
+    let count = |name: &str, count: usize| {
+        let count = count.to_string();
+        let name_width = width.saturating_sub(count.len() + 5);
+        Line::from(vec![
+            Span::styled(
+                format!(
+                    "   {} ",
+                    width::fit(&width::truncate_start(name, name_width), name_width)
+                ),
+                Style::default().fg(theme.font),
+            ),
+            Span::styled(count, Style::default().fg(theme.gray)),
+        ])
+    };
+    let mut right = vec![heading(tr!(Extensions))];
+    right.extend(report.extensions.iter().map(|(ext, n)| count(ext, *n)));
+    right.push(heading(tr!(Folders)));
+    right.extend(
+        report
+            .folders
+            .iter()
+            .map(|(folder, n)| count(&format::os_str(folder.as_os_str()), *n)),
+    );
+
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(block, popup_area);
+    frame.render_widget(Paragraph::new(left), columns[0]);
+    frame.render_widget(Paragraph::new(right), columns[1]);
+}
+
+/// Whether the file browsed offline is an Everything file list, or a snapshot.
+fn is_file_list(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("efu"))
+}
+
+/// The name of the file list without the folder, as Everything gives the full path.
+fn list_name(list: &OsStr) -> &OsStr {
+    Path::new(list).file_name().unwrap_or(list)
+}
+
+/// The lines of a file matching the terms, or why it is not searched.
+type Grepped = Result<Vec<LineMatch>, String>;
+
+/// The lines of the file previewed matching the terms of the search.
+#[derive(Debug)]
+struct ContentMatches {
+    path: PathBuf,
+    terms: Vec<String>,
+    /// none while the file is searched in the background.
+    matches: Option<Arc<Grepped>>,
+    /// the one picked by `n` and `N`.
+    current: usize,
+}
+
+impl ContentMatches {
+    /// The count first, then the lines matched with the numbers, scrolled to the current one.
+    fn lines(&self, height: usize, theme: Theme) -> Vec<Line<'static>> {
+        let matches = match self.matches.as_deref() {
+            Some(Ok(matches)) => matches,
+            Some(Err(e)) => return vec![Line::styled(e.clone(), Style::default().fg(theme.gray))],
+            None => return vec![Line::styled(tr!(Loading), Style::default().fg(theme.gray))],
+        };
+        let mut lines = vec![Line::styled(
+            tr!(ContentMatches, count = matches.len()),
+            Style::default().fg(theme.main),
+        )];
+        let rows = height.saturating_sub(1).max(1);
+        let skip = (self.current + 1).saturating_sub(rows);
+        lines.extend(
+            matches
+                .iter()
+                .enumerate()
+                .skip(skip)
+                .take(rows)
+                .map(|(i, m)| {
+                    let number_style = if i == self.current {
+                        Style::default().fg(theme.light_font).reversed()
+                    } else {
+                        Style::default().fg(theme.gray)
+                    };
+                    // tabs are drawn as one space, so the ranges are still of the text
+                    let text = m.text.replace('\t', " ");
+                    let mut spans = vec![
+                        Span::styled(format!("{:>5}", m.line), number_style),
+                        " ".into(),
+                    ];
+                    let mut end = 0;
+                    for range in &m.ranges {
+                        spans.push(Span::styled(
+                            text[end..range.start].to_string(),
+                            Style::default().fg(theme.font),
+                        ));
+                        spans.push(Span::styled(
+                            text[range.clone()].to_string(),
+                            Style::default().fg(theme.changed).bold(),
+                        ));
+                        end = range.end;
+                    }
+                    spans.push(Span::styled(
+                        text[end..].to_string(),
+                        Style::default().fg(theme.font),
+                    ));
+                    Line::from(spans)
+                }),
+        );
+        lines
+    }
+}
+
+/// The contents of the archive in the preview, the count and the total size first.
+fn archive_lines(
+    listing: &Result<Vec<ArchiveEntry>, String>,
+    row_width: usize,
+    theme: Theme,
+) -> Vec<Line<'static>> {
+    let entries = match listing {
+        Ok(entries) => entries,
+        Err(e) => return vec![Line::styled(e.clone(), Style::default().fg(theme.gray))],
+    };
+    let total: u64 = entries.iter().map(|entry| entry.size).sum();
+    let mut lines = vec![Line::styled(
+        tr!(Archive, count = entries.len(), size = format::size(total)),
+        Style::default().fg(theme.main),
+    )];
+    lines.extend(entries.iter().map(|entry| {
+        let size = if entry.is_folder {
+            String::new()
+        } else {
+            format::size(entry.size)
+        };
+        let name = width::truncate_middle(&entry.name, row_width.saturating_sub(11));
+        Line::from(vec![
+            Span::styled(format!("{size:>10} "), Style::default().fg(theme.gray)),
+            Span::styled(name, Style::default().fg(theme.font)),
+        ])
+    }));
+    lines
+}
 
-        frame.render_widget(&self.textarea, chunks[0]);
+/// The color of the date by its age, the recent ones stand out.
+fn age_color(age: Option<format::Age>, theme: Theme) -> Color {
+    match age {
+        Some(format::Age::Today) => theme.added,
+        Some(format::Age::ThisWeek) => theme.changed,
+        Some(format::Age::ThisMonth) => theme.font,
+        Some(format::Age::Older) | None => theme.gray,
+    }
+}
 
-        let results = app.query_results.read().unwrap();
+fn badge_color(badge: Badge, theme: Theme) -> Color {
+    match badge {
+        Badge::Modified => theme.changed,
+        Badge::Untracked => theme.added,
+        Badge::Ignored => theme.gray,
+    }
+}
 
-        let (num, total) = (results.number, results.total);
-        assert!(num <= total);
+/// The timeout of the query, empty if none. It can not be retried, as Everything is still
+/// busy with the query.
+fn query_error_line(app: &App, theme: Theme) -> Line<'static> {
+    match app.query_error() {
+        Some(e) => Line::styled(
+            tr!(QueryError, error = e),
+            Style::default().fg(theme.light_font),
+        ),
+        None => Line::default(),
+    }
+}
 
-        // ////
-        // let show_path = self
-        //     .list_state
-        //     .selected()
-        //     .and_then(|index| results.entrys.get(index))
-        //     .and_then(|entry| entry.filepath.clone());
-        // ////
+/// The relaxed search queried for the one of no results, empty if none.
+fn fallback_line(app: &App, theme: Theme) -> Line<'static> {
+    match &app.fallback {
+        Some(original) => Line::styled(
+            tr!(DidYouMean, original = original, search = app.last_search),
+            Style::default().fg(theme.light_font),
+        ),
+        None => Line::default(),
+    }
+}
 
-        let offset = self.list_state.offset();
-        let selected = self.list_state.selected();
-        let block = Block::new()
-            .title(vec![
-                Span::styled(
-                    format!("Total Results: {total} (Offset: {offset} Selected: {selected:?})"),
-                    Style::default().fg(if num > 0 { MAIN_COLOR } else { GRAY_COLOR }),
-                ),
-                Span::styled(
-                    format!("『{}』", results.search.to_string_lossy()),
-                    // format!("『{:?}』", show_path),
-                    Style::default().fg(GRAY_COLOR),
-                ),
-            ])
-            .style(Style::default().fg(MAIN_COLOR))
-            .borders(Borders::ALL);
+/// The progress of the earliest task in the background with the count of the others, empty
+/// if none.
+fn task_line(app: &App, theme: Theme) -> Line<'static> {
+    let mut running = app.tasks.running();
+    let Some(task) = running.next() else {
+        return Line::default();
+    };
+    let others = running.count();
+    let mut spans = vec![
+        Span::styled(format!(" {} ", task.title), Style::default().fg(theme.font)),
+        Span::styled(progress_bar(task), Style::default().fg(theme.main)),
+        Span::styled(
+            tr!(TransferCancel, amount = task_amount(task)),
+            Style::default().fg(theme.gray),
+        ),
+    ];
+    if others > 0 {
+        spans.push(Span::styled(
+            tr!(MoreTasks, count = others),
+            Style::default().fg(theme.gray),
+        ));
+    }
+    Line::from(spans)
+}
 
-        let items: Vec<ListItem> = results
-            .entrys
+fn progress_bar(task: &Task) -> String {
+    const BAR_WIDTH: usize = 10;
+    let ratio = task.progress.ratio();
+    let filled = ((ratio * BAR_WIDTH as f64).round() as usize).min(BAR_WIDTH);
+    format!("[{}{}]", "#".repeat(filled), " ".repeat(BAR_WIDTH - filled))
+}
+
+/// How much of the task is done, in the unit of it.
+fn task_amount(task: &Task) -> String {
+    let (done, total) = task.progress.get();
+    match task.unit {
+        Unit::Bytes => tr!(
+            TransferAmount,
+            done = format::size(done),
+            total = format::size(total)
+        ),
+        Unit::Items => tr!(TransferAmount, done = done, total = total),
+        Unit::Percent => format!("{:.0}%", task.progress.ratio() * 100.0),
+    }
+}
+
+fn render_tasks(app: &App, selected: usize, theme: Theme, frame: &mut Frame) {
+    let tasks: Vec<&Task> = app.tasks.running().collect();
+    let lines: Vec<Line> = if tasks.is_empty() {
+        vec![Line::styled(
+            format!(" {}", tr!(NoTasks)),
+            Style::default().fg(theme.gray),
+        )]
+    } else {
+        tasks
             .iter()
-            .map(|entry| {
-                ListItem::new(vec![Line::from(vec![
-                    Span::styled(
-                        if entry.is_folder { "📁 " } else { "📄 " },
-                        Style::default().fg(GRAY_COLOR),
-                    ),
-                    Span::styled(
-                        format!("{}", entry.filename.as_ref().unwrap().to_string_lossy()),
-                        Style::default().fg(FONT_COLOR),
-                    ),
-                    Span::styled(" ", Style::default()),
+            .enumerate()
+            .map(|(row, task)| {
+                let style = if row == selected {
+                    Style::default().fg(theme.light_font).reversed()
+                } else {
+                    Style::default().fg(theme.font)
+                };
+                Line::from(vec![
+                    Span::styled(format!(" {} ", task.title), style),
+                    Span::styled(progress_bar(task), Style::default().fg(theme.main)),
                     Span::styled(
-                        format!("{}", entry.path.as_ref().unwrap().display()),
-                        Style::default().italic().fg(GRAY_COLOR),
+                        format!(" {}", task_amount(task)),
+                        Style::default().fg(theme.gray),
                     ),
-                ])])
+                ])
             })
-            .collect();
+            .collect()
+    };
 
-        let list = if self.is_focus_search_bar {
-            List::new(items).block(block)
-        } else {
-            List::new(items)
-                .block(block)
-                .highlight_style(Style::default().fg(LIGHT_FONT_COLOR))
-        };
+    let block = Block::new()
+        .title(Span::styled(tr!(Tasks), Style::default().fg(theme.main)))
+        .title_bottom(Line::styled(
+            tr!(TasksHint),
+            Style::default().fg(theme.gray),
+        ))
+        .style(Style::default().fg(theme.main))
+        .borders(Borders::ALL);
+    let height = lines.len() as u16 + 2;
+    let area = centered_rect(frame.area(), 80, 100);
+    let popup_area = Rect {
+        y: area.y + area.height.saturating_sub(height) / 2,
+        height: height.min(area.height),
+        ..area
+    };
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
 
-        // let list = list;
-        // .highlight_style(Style::default().underlined());
-        // .highlight_style(Style::default().fg(Color::Rgb(255, 169, 0)));
+/// The statistics in the top right corner, over everything.
+fn render_debug(stats: &Stats, app: &App, theme: Theme, frame: &mut Frame) {
+    const WIDTH: u16 = 44;
+    let (count, memory, timings) = {
+        let results = app.query_results.load();
+        (results.entrys.len(), results.memory_size(), results.timings)
+    };
+    let lines: Vec<Line> = [
+        format!(
+            " Render: {:.2?} (max {:.2?})",
+            stats.render, stats.render_max
+        ),
+        format!(" Frames: {}", stats.frames),
+        format!(" Events: {}/s", stats.events_per_sec()),
+        format!(" Queries in flight: {}", app.queries_in_flight()),
+        format!(" Results: {count}, {}", format::size(memory as u64)),
+        format!(
+            " Last query: IPC {:.2?}, conversion {:.2?}",
+            timings.ipc, timings.conversion
+        ),
+    ]
+    .into_iter()
+    .map(|line| Line::styled(line, Style::default().fg(theme.font)))
+    .collect();
 
-        frame.render_stateful_widget(list, chunks[1], &mut self.list_state);
+    let block = Block::new()
+        .title(Span::styled("Debug (F12)", Style::default().fg(theme.main)))
+        .style(Style::default().fg(theme.main))
+        .borders(Borders::ALL);
+    let area = frame.area();
+    let width = WIDTH.min(area.width);
+    let popup_area = Rect {
+        x: area.x + area.width - width,
+        y: area.y,
+        width,
+        height: (lines.len() as u16 + 2).min(area.height),
+    };
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
 
-        if self.is_popup_show {
-            let popup_block = Block::new()
-                .title(vec![Span::styled(
-                    format!("Everything Status (ctrl+.)"),
-                    Style::default().fg(MAIN_COLOR),
-                )])
-                .style(Style::default().fg(MAIN_COLOR))
-                .borders(Borders::ALL);
+fn render_confirm(confirm: &Confirm, is_dry_run: bool, theme: Theme, frame: &mut Frame) {
+    let title = if is_dry_run {
+        tr!(ConfirmDryRun)
+    } else {
+        tr!(Confirm)
+    };
+    let hint = if confirm.count.is_some() {
+        tr!(ConfirmCountHint)
+    } else {
+        tr!(ConfirmHint)
+    };
+    let block = Block::new()
+        .title(Span::styled(title, Style::default().fg(theme.main)))
+        .title_bottom(Line::styled(hint, Style::default().fg(theme.gray)))
+        .style(Style::default().fg(theme.main))
+        .borders(Borders::ALL);
+    let mut lines = vec![Line::styled(
+        format!(" {}", confirm.action.describe()),
+        Style::default().fg(theme.font),
+    )];
+    if let Some(count) = confirm.count {
+        lines.push(Line::styled(
+            tr!(TypeCount, count = count, typed = confirm.typed),
+            Style::default().fg(theme.light_font),
+        ));
+    }
 
-            let (major, minor, revision, build) = app.status.version;
+    let height = lines.len() as u16 + 2;
+    let area = centered_rect(frame.area(), 80, 100);
+    let popup_area = Rect {
+        y: area.y + area.height.saturating_sub(height) / 2,
+        height: height.min(area.height),
+        ..area
+    };
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
 
-            let text: Vec<Line<'_>> = [
-                format!(" Version: {major}.{minor}.{revision}.{build}"),
-                format!(" Admin: {}", yes_or_no(app.status.is_admin)),
-                format!(" AppData: {}", yes_or_no(app.status.is_appdata)),
-                format!(" Indexed: "),
-                format!(
-                    " - File Size: {} {}",
-                    yes_or_no(app.status.is_file_size_indexed),
-                    is_fast_sort(app.status.is_size_fast_sort),
-                ),
-                format!(
-                    " - Folder Size: {} {}",
-                    yes_or_no(app.status.is_folder_size_indexed),
-                    is_fast_sort(app.status.is_size_fast_sort),
-                ),
-                format!(
-                    " - Date Modified: {} {}",
-                    yes_or_no(app.status.is_date_modified_indexed),
-                    is_fast_sort(app.status.is_date_modified_fast_sort),
-                ),
-                format!(
-                    " - Date Created: {} {}",
-                    yes_or_no(app.status.is_date_created_indexed),
-                    is_fast_sort(app.status.is_date_created_fast_sort),
-                ),
-                format!(
-                    " - Date Accessed: {} {}",
-                    yes_or_no(app.status.is_date_accessed_indexed),
-                    is_fast_sort(app.status.is_date_accessed_fast_sort),
-                ),
-                format!(
-                    " - Attritubes: {} {}",
-                    yes_or_no(app.status.is_attributes_indexed),
-                    is_fast_sort(app.status.is_attributes_fast_sort),
-                ),
-            ]
-            .map(|s| Line::from(s))
-            .into();
+fn render_conflict(conflict: &Conflict, theme: Theme, frame: &mut Frame) {
+    let block = Block::new()
+        .title(Span::styled(
+            tr!(FileExists),
+            Style::default().fg(theme.main),
+        ))
+        .title_bottom(Line::styled(
+            tr!(ConflictHint),
+            Style::default().fg(theme.gray),
+        ))
+        .style(Style::default().fg(theme.main))
+        .borders(Borders::ALL);
+    let what = if conflict.target.is_dir() {
+        tr!(ExistingFolder)
+    } else {
+        tr!(ExistingFile)
+    };
+    let lines = vec![
+        Line::styled(
+            format!(" {} {}", conflict.kind.verb(), conflict.source.display()),
+            Style::default().fg(theme.font),
+        ),
+        Line::styled(
+            tr!(ConflictTo, path = conflict.target.display()),
+            Style::default().fg(theme.font),
+        ),
+        Line::styled(
+            tr!(ConflictExisting, what = what),
+            Style::default().fg(theme.gray),
+        ),
+    ];
 
-            let paragraph = Paragraph::new(text)
-                .style(Style::default().fg(FONT_COLOR))
-                .block(popup_block);
+    let height = lines.len() as u16 + 2;
+    let area = centered_rect(frame.area(), 80, 100);
+    let popup_area = Rect {
+        y: area.y + area.height.saturating_sub(height) / 2,
+        height: height.min(area.height),
+        ..area
+    };
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
 
-            let popup_area = centered_rect(frame.area(), 80, 60);
-            frame.render_widget(Clear, popup_area);
-            frame.render_widget(paragraph, popup_area);
+fn render_shortcut(
+    path: &Path,
+    target: Option<Result<Shortcut, String>>,
+    theme: Theme,
+    frame: &mut Frame,
+) {
+    let block = Block::new()
+        .title(Span::styled(tr!(Shortcut), Style::default().fg(theme.main)))
+        .title_bottom(Line::styled(
+            tr!(ShortcutHint),
+            Style::default().fg(theme.gray),
+        ))
+        .style(Style::default().fg(theme.main))
+        .borders(Borders::ALL);
+    let mut lines = vec![Line::styled(
+        format!(" {}", path.display()),
+        Style::default().fg(theme.gray),
+    )];
+    match target {
+        None => lines.push(Line::styled(
+            tr!(ShortcutReading),
+            Style::default().fg(theme.gray),
+        )),
+        Some(Ok(shortcut)) => {
+            lines.push(Line::styled(
+                tr!(ShortcutTarget, path = shortcut.target.display()),
+                Style::default().fg(theme.font),
+            ));
+            if !shortcut.arguments.is_empty() {
+                lines.push(Line::styled(
+                    tr!(ShortcutArguments, arguments = shortcut.arguments),
+                    Style::default().fg(theme.font),
+                ));
+            }
+            if let Some(dir) = &shortcut.working_dir {
+                lines.push(Line::styled(
+                    tr!(ShortcutStartIn, path = dir.display()),
+                    Style::default().fg(theme.font),
+                ));
+            }
         }
+        Some(Err(e)) => lines.push(Line::styled(
+            format!(" {e}"),
+            Style::default().fg(theme.removed),
+        )),
     }
 
-    pub fn set_search_text(&mut self, text: &str) {
-        let old_yank = self.textarea.yank_text();
-        self.textarea.set_yank_text(text);
-        self.textarea.select_all();
-        self.textarea.paste();
-        self.textarea.set_yank_text(old_yank);
-    }
+    let height = lines.len() as u16 + 2;
+    let area = centered_rect(frame.area(), 80, 100);
+    let popup_area = Rect {
+        y: area.y + area.height.saturating_sub(height) / 2,
+        height: height.min(area.height),
+        ..area
+    };
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
 
-    pub fn is_selected(&self) -> bool {
-        self.list_state.selected().is_some()
-    }
+fn render_slow_sort(label: &str, theme: Theme, frame: &mut Frame) {
+    let block = Block::new()
+        .title(Span::styled(tr!(SlowSort), Style::default().fg(theme.main)))
+        .title_bottom(Line::styled(
+            tr!(SlowSortHint),
+            Style::default().fg(theme.gray),
+        ))
+        .style(Style::default().fg(theme.main))
+        .borders(Borders::ALL);
+    let lines = vec![
+        Line::styled(
+            tr!(SlowSortWarning, sort = label),
+            Style::default().fg(theme.font),
+        ),
+        Line::styled(tr!(SlowSortTip), Style::default().fg(theme.gray)),
+    ];
 
-    pub fn is_first_selected(&self) -> bool {
-        self.list_state.selected().is_some_and(|i| i == 0)
-    }
+    let height = lines.len() as u16 + 2;
+    let area = centered_rect(frame.area(), 80, 100);
+    let popup_area = Rect {
+        y: area.y + area.height.saturating_sub(height) / 2,
+        height: height.min(area.height),
+        ..area
+    };
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
 
-    pub fn select_first(&mut self, app: &mut App) {
-        if let Ok(results) = app.query_results.try_read() {
-            if results.number > 0 {
-                self.list_state.select(Some(0));
-            }
-        }
+/// Render the prompt, and where the cursor of it is.
+fn render_prompt(prompt: &mut Prompt, theme: Theme, frame: &mut Frame) -> (u16, u16) {
+    let block = Block::new()
+        .title(Span::styled(
+            width::truncate_middle(&prompt.title, frame.area().width as usize * 7 / 10),
+            Style::default().fg(theme.main),
+        ))
+        .title_bottom(Line::styled(
+            tr!(PromptHint),
+            Style::default().fg(theme.gray),
+        ))
+        .style(Style::default().fg(theme.main))
+        .borders(Borders::ALL);
+    prompt.textarea.set_block(block);
+    prompt.textarea.set_style(Style::default().fg(theme.font));
+    prompt.textarea.set_cursor_line_style(Style::default());
+
+    // the candidates of the completion are listed under the input
+    const MAX_COMPLETIONS: usize = 8;
+    let rows = prompt.completions.len().min(MAX_COMPLETIONS) as u16;
+    let height = 3 + rows;
+    let area = centered_rect(frame.area(), 80, 100);
+    let popup_area = Rect {
+        y: area.y + area.height.saturating_sub(height) / 2,
+        height: height.min(area.height),
+        ..area
+    };
+    let [input_area, list_area] =
+        Layout::vertical([Constraint::Length(3), Constraint::Length(rows)]).areas(popup_area);
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(&prompt.textarea, input_area);
+    let text_area = input_area.inner(Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    let cursor = textarea_cursor(&prompt.textarea, text_area);
+    if rows > 0 {
+        let items: Vec<ListItem> = prompt
+            .completions
+            .iter()
+            .map(|candidate| ListItem::new(format!(" {candidate}")))
+            .collect();
+        let list = List::new(items)
+            .style(Style::default().fg(theme.gray))
+            .highlight_style(Style::default().fg(theme.light_font).reversed());
+        let mut state = ListState::default().with_selected(prompt.selected_completion());
+        frame.render_stateful_widget(list, list_area, &mut state);
     }
+    cursor
+}
 
-    pub fn _select_last(&mut self, app: &mut App) {
-        if let Ok(results) = app.query_results.try_read() {
-            if results.number > 0 {
-                self.list_state.select(Some(results.number as usize - 1));
-            }
-        }
+/// The inputs of the find and the replace, and the new names under them, the problems in red.
+fn render_batch_rename(batch: &mut BatchRename, theme: Theme, frame: &mut Frame) -> (u16, u16) {
+    let (changed, problems) = batch.counts();
+    let count = batch.plan.as_ref().map_or(0, Vec::len);
+    let on_off = |on: bool| if on { tr!(On) } else { tr!(Off) };
+    let block = Block::new()
+        .title(Span::styled(
+            tr!(
+                BatchRename,
+                count = count,
+                changed = changed,
+                problems = problems
+            ),
+            Style::default().fg(theme.main),
+        ))
+        .title_bottom(Line::styled(
+            tr!(
+                BatchRenameHint,
+                regex = on_off(batch.regex),
+                case = batch.case.label()
+            ),
+            Style::default().fg(theme.gray),
+        ))
+        .style(Style::default().fg(theme.main))
+        .borders(Borders::ALL);
+    let area = centered_rect(frame.area(), 80, 80);
+    frame.render_widget(Clear, area);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let [find_area, replace_area, list_area] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Length(3),
+        Constraint::Min(0),
+    ])
+    .areas(inner);
+
+    let field_block = |title: &'static str, focused: bool| {
+        let color = if focused { theme.main } else { theme.gray };
+        Block::new()
+            .title(Span::styled(title, Style::default().fg(color)))
+            .style(Style::default().fg(color))
+            .borders(Borders::ALL)
+    };
+    batch
+        .find
+        .set_block(field_block(tr!(RenameFind), !batch.is_focus_replace));
+    batch
+        .replace
+        .set_block(field_block(tr!(RenameReplace), batch.is_focus_replace));
+    for field in [&mut batch.find, &mut batch.replace] {
+        field.set_style(Style::default().fg(theme.font));
+        field.set_cursor_line_style(Style::default());
     }
+    frame.render_widget(&batch.find, find_area);
+    frame.render_widget(&batch.replace, replace_area);
+    let (field, field_area) = if batch.is_focus_replace {
+        (&batch.replace, replace_area)
+    } else {
+        (&batch.find, find_area)
+    };
+    let cursor = textarea_cursor(
+        field,
+        field_area.inner(Margin {
+            vertical: 1,
+            horizontal: 1,
+        }),
+    );
 
-    pub fn select_previous_n(&mut self, n: usize, app: &mut App) {
-        if let Ok(results) = app.query_results.try_read() {
-            if results.number > 0 {
-                let last = (results.number - 1) as usize;
-                self.list_state.select(
-                    self.list_state
-                        .selected()
-                        .and_then(|i| Some(min(last, i.saturating_sub(n)))),
-                );
-            }
+    let renamings = match &batch.plan {
+        Ok(renamings) => renamings,
+        Err(e) => {
+            let line = Line::styled(format!(" {e}"), Style::default().fg(theme.removed));
+            frame.render_widget(Paragraph::new(line), list_area);
+            return cursor;
         }
-    }
+    };
+    let rows = list_area.height as usize;
+    batch.scroll = batch.scroll.min(renamings.len().saturating_sub(rows));
+    let lines: Vec<Line> = renamings
+        .iter()
+        .skip(batch.scroll)
+        .take(rows)
+        .map(|renaming| {
+            let to_style = match (renaming.problem, renaming.is_changed()) {
+                (Some(_), _) => Style::default().fg(theme.removed),
+                (None, true) => Style::default().fg(theme.added),
+                (None, false) => Style::default().fg(theme.gray),
+            };
+            let problem = renaming
+                .problem
+                .map_or(String::new(), |problem| format!(" ({})", problem.label()));
+            Line::from(vec![
+                Span::styled(
+                    format!(" {} → ", renaming.old_name()),
+                    Style::default().fg(theme.font),
+                ),
+                Span::styled(renaming.to.clone(), to_style),
+                Span::styled(problem, Style::default().fg(theme.removed)),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), list_area);
+    cursor
+}
 
-    pub fn select_next_n(&mut self, n: usize, app: &mut App) {
-        if let Ok(results) = app.query_results.try_read() {
-            if results.number > 0 {
-                let last = (results.number - 1) as usize;
-                self.list_state.select(
-                    self.list_state
-                        .selected()
-                        .and_then(|i| Some(min(last, i.saturating_add(n)))),
-                );
-            }
-        };
-    }
+/// Where the cursor of the text area is on the screen, after the cells of the text before it,
+/// two for a CJK char. Kept in the area when the text is scrolled.
+fn textarea_cursor(textarea: &TextArea, area: Rect) -> (u16, u16) {
+    let (row, col) = textarea.cursor();
+    let typed: String = textarea.lines()[row].chars().take(col).collect();
+    let x = area.x + width::width(&typed) as u16;
+    let y = area.y + row as u16;
+    (
+        x.min(area.right().saturating_sub(1)),
+        y.min(area.bottom().saturating_sub(1)),
+    )
+}
 
-    pub fn is_first_page(&self) -> bool {
-        self.list_state.offset() == 0
-    }
+/// The text in one line, the line breaks of the pasted text as spaces.
+pub fn one_line(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
 
-    pub fn is_last_page(&self, results_number: u32) -> bool {
-        let page_height = self.last_page_height.unwrap() as u32;
-        if results_number <= page_height {
-            true
-        } else {
-            let offset = self.list_state.offset();
-            (results_number - offset as u32) <= page_height
-        }
-    }
+fn render_config_error(error: &str, theme: Theme, frame: &mut Frame) {
+    let block = Block::new()
+        .title(Span::styled(
+            tr!(ConfigError),
+            Style::default().fg(theme.main),
+        ))
+        .title_bottom(Line::styled(
+            tr!(ConfigErrorHint),
+            Style::default().fg(theme.gray),
+        ))
+        .style(Style::default().fg(theme.main))
+        .borders(Borders::ALL);
+    // the error of toml shows the line and points at the column
+    let lines: Vec<Line> = error
+        .lines()
+        .map(|line| Line::styled(format!(" {line}"), Style::default().fg(theme.font)))
+        .collect();
 
-    pub fn select_next_page(&mut self, app: &mut App) {
-        if let Ok(results) = app.query_results.try_read() {
-            if results.number > 0 {
-                if self.is_last_page(results.number) {
-                    self.list_state.select(Some(results.number as usize - 1));
-                } else {
-                    let old_offset = self.list_state.offset();
-                    let page_height = self.last_page_height.unwrap() as usize;
-                    let new_offset = old_offset.saturating_add(page_height);
-                    *self.list_state.offset_mut() = new_offset;
-
-                    let n = new_offset - old_offset;
-                    let last = (results.number - 1) as usize;
-                    self.list_state.select(
-                        self.list_state
-                            .selected()
-                            .and_then(|i| Some(min(last, i.saturating_add(n)))),
-                    );
-                }
-            }
+    let height = lines.len() as u16 + 2;
+    let area = centered_rect(frame.area(), 80, 100);
+    let popup_area = Rect {
+        y: area.y + area.height.saturating_sub(height) / 2,
+        height: height.min(area.height),
+        ..area
+    };
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
+
+fn render_wizard(wizard: &Wizard, theme: Theme, frame: &mut Frame) {
+    let (step, choice) = wizard.current();
+    let block = Block::new()
+        .title(vec![
+            Span::styled(tr!(Welcome), Style::default().fg(theme.main)),
+            Span::styled(
+                format!("({}/{}) ", wizard.step + 1, STEPS.len()),
+                Style::default().fg(theme.gray),
+            ),
+            Span::styled(step.title.text(), Style::default().fg(theme.light_font)),
+        ])
+        .title_bottom(Line::styled(
+            tr!(WizardHint),
+            Style::default().fg(theme.gray),
+        ))
+        .style(Style::default().fg(theme.main))
+        .borders(Borders::ALL);
+
+    let mut lines = vec![
+        Line::styled(tr!(WizardIntro), Style::default().fg(theme.gray)),
+        Line::default(),
+    ];
+    lines.extend(step.options.iter().enumerate().map(|(row, (label, _))| {
+        let style = if row == choice {
+            Style::default().fg(theme.light_font).reversed()
+        } else {
+            Style::default().fg(theme.font)
         };
-    }
+        Line::styled(format!(" {} ", label.text()), style)
+    }));
 
-    pub fn select_previous_page(&mut self, app: &mut App) {
-        if let Ok(results) = app.query_results.try_read() {
-            if results.number > 0 {
-                if self.is_first_page() {
-                    self.list_state.select(Some(0));
+    let height = lines.len() as u16 + 2;
+    let area = centered_rect(frame.area(), 70, 100);
+    let popup_area = Rect {
+        y: area.y + area.height.saturating_sub(height) / 2,
+        height: height.min(area.height),
+        ..area
+    };
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
+
+fn render_opened(app: &App, selected: usize, theme: Theme, frame: &mut Frame) {
+    let files: Vec<_> = app
+        .opened
+        .files
+        .iter()
+        .map(|file| (file.path.as_path(), file.time))
+        .collect();
+    let texts = (tr!(OpenedFiles), tr!(OpenedHint), tr!(NoOpened));
+    render_files(&files, texts, selected, theme, frame);
+}
+
+fn render_recycled(app: &App, selected: usize, theme: Theme, frame: &mut Frame) {
+    let files: Vec<_> = app
+        .recycled
+        .iter()
+        .map(|(path, time)| (path.as_path(), *time))
+        .collect();
+    let texts = (tr!(RecycledFiles), tr!(RecycledHint), tr!(NoRecycled));
+    render_files(&files, texts, selected, theme, frame);
+}
+
+/// A popup of the files with the times, e.g. when they are opened, with the texts of the
+/// title, the hint and the line shown if there are no files.
+fn render_files(
+    files: &[(&Path, u64)],
+    (title, hint, empty): (&str, &str, &str),
+    selected: usize,
+    theme: Theme,
+    frame: &mut Frame,
+) {
+    let name_width = files
+        .iter()
+        .map(|(path, _)| width::width(&file_name(path)))
+        .max()
+        .unwrap_or_default()
+        .min(40);
+    let lines: Vec<Line> = if files.is_empty() {
+        vec![Line::styled(
+            format!(" {empty}"),
+            Style::default().fg(theme.gray),
+        )]
+    } else {
+        files
+            .iter()
+            .enumerate()
+            .map(|(row, (path, time))| {
+                let style = if row == selected {
+                    Style::default().fg(theme.light_font).reversed()
                 } else {
-                    let old_offset = self.list_state.offset();
-                    let page_height = self.last_page_height.unwrap() as usize;
-                    let new_offset = old_offset.saturating_sub(page_height);
-                    *self.list_state.offset_mut() = new_offset;
-
-                    let n = old_offset - new_offset;
-                    let last = (results.number - 1) as usize;
-                    self.list_state.select(
-                        self.list_state
-                            .selected()
-                            .and_then(|i| Some(min(last, i.saturating_sub(n)))),
-                    );
-                }
-            }
-        };
-    }
+                    Style::default().fg(theme.font)
+                };
+                let folder = path.parent().unwrap_or(Path::new(""));
+                Line::from(vec![
+                    Span::styled(
+                        format!(" {} ", format::date(*time)),
+                        Style::default().fg(theme.gray),
+                    ),
+                    Span::styled(
+                        format!(" {} ", width::fit(&file_name(path), name_width)),
+                        style,
+                    ),
+                    Span::styled(
+                        format!(" {}", folder.display()),
+                        Style::default().fg(theme.gray),
+                    ),
+                ])
+            })
+            .collect()
+    };
 
-    pub fn unselect(&mut self) {
-        self.list_state.select(None);
-    }
+    let block = Block::new()
+        .title(Span::styled(title, Style::default().fg(theme.main)))
+        .title_bottom(Line::styled(hint, Style::default().fg(theme.gray)))
+        .style(Style::default().fg(theme.main))
+        .borders(Borders::ALL);
+    let area = centered_rect(frame.area(), 80, 80);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let popup_area = Rect {
+        y: area.y + (area.height - height) / 2,
+        height,
+        ..area
+    };
+    // keep the selected one in view
+    let rows = height.saturating_sub(2) as usize;
+    let scroll = (selected + 1).saturating_sub(rows) as u16;
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(
+        Paragraph::new(lines).block(block).scroll((scroll, 0)),
+        popup_area,
+    );
+}
 
-    pub fn get_selected_full_path(&self, app: &App) -> Option<PathBuf> {
-        let index = self.list_state.selected()?;
-        if let Ok(results) = app.query_results.read() {
-            let entry = results.entrys.get(index)?;
-            entry.filepath.clone()
-        } else {
-            None
-        }
-    }
+fn file_name(path: &Path) -> String {
+    path.file_name().map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    )
 }
 
 fn centered_rect(r: Rect, percent_x: u16, percent_y: u16) -> Rect {
@@ -372,6 +3117,98 @@ fn centered_rect(r: Rect, percent_x: u16, percent_y: u16) -> Rect {
         .split(popup_layout[1])[1]
 }
 
+/// Clamp the selection and the offset to the list length, and scroll the list to make the
+/// selected one in the page.
+fn keep_visible(state: &mut ListState, len: usize, page_height: usize) {
+    let Some(last) = len.checked_sub(1) else {
+        *state = ListState::default();
+        return;
+    };
+    let mut offset = min(state.offset(), len.saturating_sub(page_height));
+    if let Some(selected) = state.selected().map(|i| min(i, last)) {
+        if selected < offset {
+            offset = selected;
+        } else if selected >= offset + page_height {
+            offset = selected + 1 - page_height;
+        }
+        state.select(Some(selected));
+    }
+    *state.offset_mut() = offset;
+}
+
+/// Width of the area inside the borders.
+fn inner_width(area: Rect) -> usize {
+    area.width.saturating_sub(2) as usize
+}
+
+/// Fit the filename and its path in one row of `width` cells, separated by a space. The path
+/// is shortened by the path mode first, then the filename is truncated at the end. Nothing is
+/// shortened in the full path mode.
+fn fit_name_and_path(
+    filename: &str,
+    path: &Path,
+    width: usize,
+    mode: PathMode,
+    scope: Option<&Path>,
+) -> (String, String) {
+    let path = match scope.and_then(|scope| path.strip_prefix(scope).ok()) {
+        Some(relative) => format::os_str(Path::new(".").join(relative).as_os_str()).into_owned(),
+        None => format::os_str(path.as_os_str()).into_owned(),
+    };
+    if mode == PathMode::Full {
+        return (filename.to_string(), path);
+    }
+    let filename = width::truncate_end(filename, width);
+    let rest = width.saturating_sub(width::width(&filename) + 1);
+    let path = match mode {
+        PathMode::Tail => width::truncate_start(&path, rest),
+        _ => width::truncate_middle(&path, rest),
+    };
+    (filename, path)
+}
+
+/// The deepest folder containing all the paths, none if they are on different volumes.
+fn common_ancestor<'p>(mut paths: impl Iterator<Item = &'p Path>) -> Option<PathBuf> {
+    let mut ancestor = paths.next()?.to_path_buf();
+    for path in paths {
+        while !path.starts_with(&ancestor) {
+            if !ancestor.pop() {
+                return None;
+            }
+        }
+    }
+    Some(ancestor)
+}
+
+/// Clamp the horizontal scroll so that the longest line ends at the right border at most.
+fn clamp_scroll(scroll: usize, lines: &[Line], width: usize) -> usize {
+    let longest = lines.iter().map(Line::width).max().unwrap_or_default();
+    min(scroll, longest.saturating_sub(width))
+}
+
+/// Scroll the line to the left by `n` cells.
+fn scroll_line(line: Line<'_>, n: usize) -> Line<'_> {
+    let mut n = n;
+    let spans: Vec<Span> = line
+        .spans
+        .into_iter()
+        .filter_map(|span| {
+            if n == 0 {
+                return Some(span);
+            }
+            let span_width = span.width();
+            if n >= span_width {
+                n -= span_width;
+                return None;
+            }
+            let content = width::skip(&span.content, n);
+            n = 0;
+            Some(Span::styled(content, span.style))
+        })
+        .collect();
+    Line::from(spans)
+}
+
 fn yes_or_no(b: bool) -> char {
     if b {
         // '🆗'
@@ -392,12 +3229,30 @@ fn yes_or_no(b: bool) -> char {
 
 fn is_fast_sort(b: bool) -> &'static str {
     if b {
-        "(fast sort)"
+        tr!(FastSort)
     } else {
         ""
     }
 }
 
+/// Move the cursor, extending the selection from where it started with shift as the edit
+/// controls of Windows do, or dropping it without.
+fn move_cursor_with_shift(textarea: &mut TextArea, cursor_move: CursorMove, shift: bool) {
+    if !shift {
+        textarea.cancel_selection();
+    } else if textarea.selection_range().is_none() {
+        textarea.start_selection();
+    }
+    textarea.move_cursor(cursor_move);
+}
+
+/// Undo or redo until the text changes, as the history may hold the edits of the same text,
+/// e.g. the search set to what it is already, which would take the key for nothing.
+fn step_history(textarea: &mut TextArea, mut step: impl FnMut(&mut TextArea) -> bool) {
+    let text = textarea.lines().to_vec();
+    while step(textarea) && textarea.lines() == text {}
+}
+
 /// Custom key mappings for [`tui_textarea::TextArea`], enjoy an good typing for input.
 ///
 /// Ref: https://docs.rs/tui-textarea/0.4.0/tui_textarea/#define-your-own-key-mappings
@@ -435,21 +3290,41 @@ pub fn key_map_for_textarea(input: Input, textarea: &mut TextArea) {
         } => {
             textarea.paste();
         }
-        // Move cursor forward by word
+        // Select all the text
         Input {
-            key: Key::Right,
+            key: Key::Char('a'),
             ctrl: true,
             shift: false,
             alt: false,
-        } => textarea.move_cursor(CursorMove::WordForward),
-        // Move cursor backward by word
+        } => textarea.select_all(),
+        // Move cursor forward by word, or select by word with shift
+        Input {
+            key: Key::Right,
+            ctrl: true,
+            shift,
+            alt: false,
+        } => move_cursor_with_shift(textarea, CursorMove::WordForward, shift),
+        // Move cursor backward by word, or select by word with shift
         Input {
             key: Key::Left,
             ctrl: true,
-            shift: false,
+            shift,
+            alt: false,
+        } => move_cursor_with_shift(textarea, CursorMove::WordBack, shift),
+        // Move cursor to the start or the end of the line, or select to there with shift
+        Input {
+            key: Key::Home,
+            ctrl: false,
+            shift,
+            alt: false,
+        } => move_cursor_with_shift(textarea, CursorMove::Head, shift),
+        Input {
+            key: Key::End,
+            ctrl: false,
+            shift,
             alt: false,
-        } => textarea.move_cursor(CursorMove::WordBack),
-        // Delete one character next to cursor
+        } => move_cursor_with_shift(textarea, CursorMove::End, shift),
+        // Delete the word before cursor, or the selected text
         Input {
             key: Key::Backspace,
             ctrl: true,
@@ -458,35 +3333,35 @@ pub fn key_map_for_textarea(input: Input, textarea: &mut TextArea) {
         } => {
             textarea.delete_word();
         }
-        // Select forward by word
+        // Delete the word after cursor, or the selected text
         Input {
-            key: Key::Right,
+            key: Key::Delete,
             ctrl: true,
-            shift: true,
+            shift: false,
             alt: false,
         } => {
-            textarea.start_selection();
-            textarea.move_cursor(CursorMove::WordForward);
+            textarea.delete_next_word();
         }
-        // Select backward by word
+        // Undo
         Input {
-            key: Key::Left,
+            key: Key::Char('z'),
             ctrl: true,
-            shift: true,
+            shift: false,
             alt: false,
-        } => {
-            textarea.start_selection();
-            textarea.move_cursor(CursorMove::WordBack);
-        }
-        // Undo
+        } => step_history(textarea, |textarea| textarea.undo()),
+        // Redo
         Input {
-            key: Key::Char('z'),
+            key: Key::Char('y'),
             ctrl: true,
             shift: false,
             alt: false,
-        } => {
-            textarea.undo();
         }
+        | Input {
+            key: Key::Char('z' | 'Z'),
+            ctrl: true,
+            shift: true,
+            alt: false,
+        } => step_history(textarea, |textarea| textarea.redo()),
         // ignore it, do nothing
         Input { ctrl: true, .. } => {}
         // will not capture in here
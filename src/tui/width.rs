@@ -0,0 +1,148 @@
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+const ELLIPSIS: char = '…';
+
+/// Width of the text in terminal cells, CJK and emoji take two cells.
+pub fn width(s: &str) -> usize {
+    s.width()
+}
+
+/// Truncate the text to fit in `max` cells, ended with an ellipsis if truncated.
+pub fn truncate_end(s: &str, max: usize) -> String {
+    if s.width() <= max {
+        return s.to_string();
+    }
+    if max == 0 {
+        return String::new();
+    }
+    let mut truncated = take_head(s, max - 1);
+    truncated.push(ELLIPSIS);
+    truncated
+}
+
+/// Truncate the text to fit in `max` cells with an ellipsis in the middle, keeping more of the
+/// tail, which is the most specific part of a path.
+pub fn truncate_middle(s: &str, max: usize) -> String {
+    if s.width() <= max {
+        return s.to_string();
+    }
+    if max == 0 {
+        return String::new();
+    }
+    let budget = max - 1;
+    let tail = take_tail(s, budget - budget / 3);
+    // a wide char may not fit in the tail, give the cell to the head
+    let head = take_head(s, budget - tail.width());
+    format!("{head}{ELLIPSIS}{tail}")
+}
+
+/// Truncate the text to fit in `max` cells, started with an ellipsis if truncated.
+pub fn truncate_start(s: &str, max: usize) -> String {
+    if s.width() <= max {
+        return s.to_string();
+    }
+    if max == 0 {
+        return String::new();
+    }
+    format!("{ELLIPSIS}{}", take_tail(s, max - 1))
+}
+
+/// Skip the first `n` cells of the text.
+pub fn skip(s: &str, n: usize) -> String {
+    let mut width = 0;
+    let mut chars = s.chars();
+    while width < n {
+        match chars.next() {
+            Some(c) => width += c.width().unwrap_or(0),
+            None => break,
+        }
+    }
+    // the right half of a wide char is left
+    format!("{}{}", " ".repeat(width.saturating_sub(n)), chars.as_str())
+}
+
+/// Truncate or pad the text with spaces to exactly `width` cells.
+pub fn fit(s: &str, width: usize) -> String {
+    let fitted = truncate_end(s, width);
+    // a wide char cut off may leave one cell
+    let padding = width.saturating_sub(fitted.width());
+    format!("{fitted}{}", " ".repeat(padding))
+}
+
+fn take_head(s: &str, max: usize) -> String {
+    let mut width = 0;
+    s.chars()
+        .take_while(|c| {
+            width += c.width().unwrap_or(0);
+            width <= max
+        })
+        .collect()
+}
+
+fn take_tail(s: &str, max: usize) -> String {
+    let mut width = 0;
+    let mut chars: Vec<char> = s
+        .chars()
+        .rev()
+        .take_while(|c| {
+            width += c.width().unwrap_or(0);
+            width <= max
+        })
+        .collect();
+    chars.reverse();
+    chars.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_chars_take_two_cells() {
+        assert_eq!(width("a中文"), 5);
+        assert_eq!(width(""), 0);
+    }
+
+    #[test]
+    fn text_of_the_width_exactly_is_kept() {
+        assert_eq!(truncate_end("hello", 5), "hello");
+        assert_eq!(truncate_middle("中文字", 6), "中文字");
+        assert_eq!(truncate_start("中文字", 6), "中文字");
+    }
+
+    #[test]
+    fn text_wider_by_a_cell_is_truncated() {
+        assert_eq!(truncate_end("hello", 4), "hel…");
+        assert_eq!(truncate_start("hello", 4), "…llo");
+        assert_eq!(truncate_middle("abcdefghij", 7), "ab…ghij");
+        assert_eq!(truncate_end("中文字", 5), "中文…");
+    }
+
+    #[test]
+    fn wide_chars_are_not_cut_in_half() {
+        // one cell short, rather than half of `文`
+        assert_eq!(truncate_end("中文字", 4), "中…");
+        assert_eq!(truncate_start("中文字", 4), "…字");
+        assert_eq!(truncate_middle("中文字符串", 7), "中…符串");
+        assert_eq!(truncate_middle("中文字符串", 6), "…符串");
+        assert_eq!(fit("中文字", 4), "中… ");
+        assert_eq!(skip("中文", 1), " 文");
+    }
+
+    #[test]
+    fn no_room_leaves_nothing_or_the_ellipsis() {
+        assert_eq!(truncate_end("hello", 0), "");
+        assert_eq!(truncate_middle("hello", 0), "");
+        assert_eq!(truncate_start("hello", 0), "");
+        assert_eq!(truncate_end("hello", 1), "…");
+        assert_eq!(truncate_end("中文", 1), "…");
+    }
+
+    #[test]
+    fn text_is_padded_or_skipped_by_cells() {
+        assert_eq!(fit("ab", 4), "ab  ");
+        assert_eq!(fit("abcdef", 4), "abc…");
+        assert_eq!(skip("abc", 1), "bc");
+        assert_eq!(skip("ab", 5), "");
+    }
+}
@@ -0,0 +1,141 @@
+use std::fs;
+
+use anyhow::Context;
+
+use crate::config::{Config, ThemeConfig, ThemePreset};
+use crate::i18n::Msg;
+
+/// A question of the setup, the title and the options of `(label, value in config)`.
+pub struct Step {
+    pub title: Msg,
+    pub options: &'static [(Msg, &'static str)],
+}
+
+pub const STEPS: [Step; 5] = [
+    Step {
+        title: Msg::StepTheme,
+        options: &[
+            (Msg::ThemeEverything, "everything"),
+            (Msg::ThemeOcean, "ocean"),
+            (Msg::ThemeMono, "mono"),
+        ],
+    },
+    Step {
+        title: Msg::StepKeys,
+        options: &[(Msg::KeysDefault, "default"), (Msg::KeysVim, "vim")],
+    },
+    Step {
+        title: Msg::StepIcons,
+        options: &[
+            (Msg::IconsEmoji, "emoji"),
+            (Msg::IconsNerd, "nerd"),
+            (Msg::IconsNone, "none"),
+        ],
+    },
+    Step {
+        title: Msg::StepSort,
+        options: &[
+            (Msg::SortByName, "name"),
+            (Msg::SortByPath, "path"),
+            (Msg::SortBySize, "size"),
+            (Msg::SortByDateModified, "date-modified"),
+            (Msg::SortByExtension, "extension"),
+            (Msg::SortByRelevance, "relevance"),
+        ],
+    },
+    Step {
+        title: Msg::StepAsYouType,
+        options: &[(Msg::AsYouTypeNo, "false"), (Msg::AsYouTypeYes, "true")],
+    },
+];
+
+/// The setup popup on the first run, writing the config file at the end.
+#[derive(Debug, Default)]
+pub struct Wizard {
+    /// index in [`STEPS`].
+    pub step: usize,
+    /// the chosen option of every step.
+    pub choices: [usize; STEPS.len()],
+}
+
+impl Wizard {
+    pub fn current(&self) -> (&'static Step, usize) {
+        (&STEPS[self.step], self.choices[self.step])
+    }
+
+    pub fn select_previous(&mut self) {
+        let rows = STEPS[self.step].options.len();
+        let choice = &mut self.choices[self.step];
+        *choice = choice.checked_sub(1).unwrap_or(rows - 1);
+    }
+
+    pub fn select_next(&mut self) {
+        let rows = STEPS[self.step].options.len();
+        let choice = &mut self.choices[self.step];
+        *choice = (*choice + 1) % rows;
+    }
+
+    /// Go to the next step, false if it is the last one already.
+    pub fn next(&mut self) -> bool {
+        if self.step + 1 < STEPS.len() {
+            self.step += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn back(&mut self) {
+        self.step = self.step.saturating_sub(1);
+    }
+
+    /// The theme chosen, for showing it while choosing.
+    pub fn theme(&self) -> ThemeConfig {
+        let preset = match self.value(0) {
+            "ocean" => ThemePreset::Ocean,
+            "mono" => ThemePreset::Mono,
+            _ => ThemePreset::Everything,
+        };
+        ThemeConfig {
+            preset,
+            ..Default::default()
+        }
+    }
+
+    fn value(&self, step: usize) -> &'static str {
+        STEPS[step].options[self.choices[step]].1
+    }
+
+    /// The config file of the choices, with the comments pointing to the other options.
+    pub fn config_text(&self) -> String {
+        let [theme, keys, icons, sort, as_you_type] = [0, 1, 2, 3, 4].map(|step| self.value(step));
+        format!(
+            r#"# Written by the setup of ery, see https://github.com/owtotwo/ery-rs#config for more.
+
+[icons]
+mode = "{icons}" # or "emoji", "nerd", "none"
+
+[keys]
+profile = "{keys}" # or "default", "vim"
+
+[query]
+sort = "{sort}" # or "name", "path", "size", "date-modified", "recently-changed", "extension", "relevance"
+as_you_type = {as_you_type}
+
+[theme]
+preset = "{theme}" # or "everything", "ocean", "mono"
+"#
+        )
+    }
+
+    /// Write the config file, then it is reloaded by the TUI.
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = Config::path().context("no config directory")?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        fs::write(&path, self.config_text())
+            .with_context(|| format!("failed to write config {}", path.display()))
+    }
+}
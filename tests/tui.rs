@@ -0,0 +1,522 @@
+//! The TUI driven headless, drawn on ratatui's `TestBackend` with the mock client instead of
+//! Everything.
+
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+use unicode_width::UnicodeWidthStr;
+
+use ery::app::client::mock::MockClient;
+use ery::app::App;
+use ery::config::Config;
+use ery::i18n::Lang;
+use ery::tui::{Event, Overrides, Tui};
+
+struct Harness {
+    tui: Tui<'static, TestBackend>,
+    app: App,
+    events: mpsc::Receiver<Event>,
+}
+
+impl Harness {
+    /// The TUI of the size, searching the files of the paths.
+    fn new(width: u16, height: u16, files: &[&str]) -> Self {
+        let files: Vec<_> = files.iter().map(|&path| (path, 0)).collect();
+        Self::with_client(width, height, MockClient::with_files(&files))
+    }
+
+    fn with_client(width: u16, height: u16, client: MockClient) -> Self {
+        let mut config = Config::default();
+        // no probing the terminal for the graphics
+        config.preview.images = false;
+        let terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        let mut tui = Tui::new(terminal);
+        // the texts asserted are the English ones, whatever the language of the machine
+        let overrides = Overrides {
+            no_icons: true,
+            lang: Some(Lang::En),
+            ..Default::default()
+        };
+        tui.set_config(&config, overrides).unwrap();
+        let (sender, events) = mpsc::channel();
+        let mut app = App::with_client(sender, config, client).unwrap();
+        tui.handle_resize_event(height, &mut app).unwrap();
+        Self { tui, app, events }
+    }
+
+    fn key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        let key_event = KeyEvent::new(code, modifiers);
+        self.tui
+            .handle_key_events(key_event, &mut self.app)
+            .unwrap();
+    }
+
+    fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.key(KeyCode::Char(c), KeyModifiers::NONE);
+        }
+    }
+
+    /// Type the text, query it on `Enter` and wait for the results.
+    fn search(&mut self, text: &str) {
+        self.type_text(text);
+        self.key(KeyCode::Enter, KeyModifiers::NONE);
+        self.wait_results();
+    }
+
+    fn wait_results(&mut self) {
+        while self.app.is_querying() {
+            let event = self.events.recv_timeout(Duration::from_secs(5)).unwrap();
+            if let Event::Refresh = event {
+                self.tui.handle_refresh_event(&mut self.app).unwrap();
+            }
+        }
+    }
+
+    /// Draw a frame, and the lines of the screen. The cells covered by a wide char are left
+    /// out, they may keep what was drawn there before.
+    fn screen(&mut self) -> Vec<String> {
+        self.tui.draw(&mut self.app).unwrap();
+        let buffer = self.tui.backend().buffer();
+        let width = buffer.area.width as usize;
+        buffer
+            .content
+            .chunks(width)
+            .map(|row| {
+                let mut covered = 0;
+                let mut line = String::new();
+                for cell in row {
+                    if covered > 0 {
+                        covered -= 1;
+                        continue;
+                    }
+                    line.push_str(cell.symbol());
+                    covered = cell.symbol().width().saturating_sub(1);
+                }
+                line
+            })
+            .collect()
+    }
+
+    /// Draw a frame, and assert the whole screen is the lines, e.g. nothing left over of the
+    /// popup closed.
+    #[track_caller]
+    fn assert_screen(&mut self, expected: &[&str]) {
+        // the counts of the search typed too
+        self.wait_results();
+        assert_eq!(self.screen(), expected);
+    }
+}
+
+const FILES: &[&str] = &[
+    r"C:\Users\me\Documents\report.docx",
+    r"C:\Users\me\Documents\notes.txt",
+    r"D:\Archive\Report.pdf",
+];
+
+#[test]
+fn list_shows_the_results() {
+    let mut harness = Harness::new(80, 8, FILES);
+    harness.search("report");
+    harness.assert_screen(&[
+        "╭Everything──────────────────────────────────────────────── Not Admin (ctrl+e) ╮",
+        "│report                                                                        │",
+        "╰──────────────────────────────────────────────────────────────────────────────╯",
+        "┌Total Results: 2 (Offset: 0 Selected: None)『report』(Sort: name) ────────────┐",
+        r"│report.docx C:\Users\me\Documents                                             │",
+        r"│Report.pdf D:\Archive                                                         │",
+        "└──────────────────────────────────────────────────────────────────────────────┘",
+        "                                                                                ",
+    ]);
+}
+
+#[test]
+fn list_is_empty_before_searching() {
+    let mut harness = Harness::new(80, 20, FILES);
+    harness.assert_screen(&[
+        "╭Everything──────────────────────────────────────────────── Not Admin (ctrl+e) ╮",
+        "│ Type to search Everything…                                                   │",
+        "╰──────────────────────────────────────────────────────────────────────────────╯",
+        "┌Total Results: 0 (Offset: 0 Selected: None)『』(Sort: name) ──────────────────┐",
+        "│Examples                                                                      │",
+        "│  *.rs dm:today     Rust files modified today                                 │",
+        "│  ext:pdf invoice   PDFs with invoice in the name                             │",
+        "│  size:>1gb         files over 1 GB                                           │",
+        r"│  downloads\ *.zip  zips in the folders named downloads                       │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "└──────────────────────────────────────────────────────────────────────────────┘",
+        "                                                                                ",
+    ]);
+}
+
+#[test]
+fn down_selects_the_first_result() {
+    let mut harness = Harness::new(80, 8, FILES);
+    harness.search("report");
+    harness.key(KeyCode::Down, KeyModifiers::NONE);
+    harness.assert_screen(&[
+        "╭Everything──────────────────────────────────────────────── Not Admin (ctrl+e) ╮",
+        "│report                                                                        │",
+        "╰──────────────────────────────────────────────────────────────────────────────╯",
+        "┌Total Results: 2 (Offset: 0 Selected: Some(0))『report』(Sort: name) ─────────┐",
+        r"│report.docx C:\Users\me\Documents                                             │",
+        r"│Report.pdf D:\Archive                                                         │",
+        "└──────────────────────────────────────────────────────────────────────────────┘",
+        r"1  › 2 C:\Users\me\Documents                                                    ",
+    ]);
+}
+
+#[test]
+fn status_popup_toggles() {
+    let mut harness = Harness::new(80, 30, FILES);
+    harness.key(KeyCode::Char('.'), KeyModifiers::CONTROL);
+    harness.assert_screen(&[
+        "╭Everything──────────────────────────────────────────────── Not Admin (ctrl+e) ╮",
+        "│ Type to search Everything…                                                   │",
+        "╰──────────────────────────────────────────────────────────────────────────────╯",
+        "┌Total Results: 0 (Offset: 0 Selected: None)『』(Sort: name) ──────────────────┐",
+        "│Examples                                                                      │",
+        "│  *.rs dm:today     Rust files modified today                                 │",
+        "│  ext:p┌Everything Status (ctrl+.)────────────────────────────────────┐       │",
+        "│  size:│ Version: 1.4.1.1024                                          │       │",
+        "│  downl│ Admin: 🟤                                                    │       │",
+        "│       │ AppData: 🟢                                                  │       │",
+        "│       │ Indexed:                                                     │       │",
+        "│       │ - File Size: 🟢                                              │       │",
+        "│       │ - Folder Size: 🟤                                            │       │",
+        "│       │ - Date Modified: 🟤                                          │       │",
+        "│       │ - Date Created: 🟤                                           │       │",
+        "│       │ - Date Accessed: 🟤                                          │       │",
+        "│       │ - Attributes: 🟤                                             │       │",
+        "│       │                                                              │       │",
+        "│       │                                                              │       │",
+        "│       │                                                              │       │",
+        "│       │                                                              │       │",
+        "│       │                                                              │       │",
+        "│       │                                                              │       │",
+        "│       └──────────────────────────────────────────────────────────────┘       │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "└──────────────────────────────────────────────────────────────────────────────┘",
+        "                                                                                ",
+    ]);
+    harness.key(KeyCode::Char('.'), KeyModifiers::CONTROL);
+    harness.assert_screen(&[
+        "╭Everything──────────────────────────────────────────────── Not Admin (ctrl+e) ╮",
+        "│ Type to search Everything…                                                   │",
+        "╰──────────────────────────────────────────────────────────────────────────────╯",
+        "┌Total Results: 0 (Offset: 0 Selected: None)『』(Sort: name) ──────────────────┐",
+        "│Examples                                                                      │",
+        "│  *.rs dm:today     Rust files modified today                                 │",
+        "│  ext:pdf invoice   PDFs with invoice in the name                             │",
+        "│  size:>1gb         files over 1 GB                                           │",
+        r"│  downloads\ *.zip  zips in the folders named downloads                       │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "└──────────────────────────────────────────────────────────────────────────────┘",
+        "                                                                                ",
+    ]);
+}
+
+#[test]
+fn filter_picker_closes_on_esc() {
+    let mut harness = Harness::new(80, 30, FILES);
+    harness.key(KeyCode::Char('d'), KeyModifiers::CONTROL);
+    harness.assert_screen(&[
+        "╭Everything──────────────────────────────────────────────── Not Admin (ctrl+e) ╮",
+        "│ Type to search Everything…                                                   │",
+        "╰──────────────────────────────────────────────────────────────────────────────╯",
+        "┌Total Results: 0 (Offset: 0 Selected: None)『』(Sort: name) ──────────────────┐",
+        "│Examples                                                                      │",
+        "│  *.rs dm:today     Rust files modified today                                 │",
+        "│  ext:pdf invoice   PDFs with invoice in the name                             │",
+        "│  size:>1gb         files over 1 GB                                           │",
+        r"│  downloads\ *.zip  zips in the folders named downloads                       │",
+        "│                                                                              │",
+        "│               ┌Date Filter (ctrl+d) Modified | Created (tab)─┐               │",
+        "│               │ Today                                        │               │",
+        "│               │ Yesterday                                    │               │",
+        "│               │ Last 7 days                                  │               │",
+        "│               │ This month                                   │               │",
+        "│               │                                              │               │",
+        "│               │ From  YYYY-MM-DD                             │               │",
+        "│               │ To    YYYY-MM-DD                             │               │",
+        "│               └ enter to insert, ←/→ to change the date by a ┘               │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "└──────────────────────────────────────────────────────────────────────────────┘",
+        "                                                                                ",
+    ]);
+    harness.key(KeyCode::Esc, KeyModifiers::NONE);
+    // the picker takes the esc, ery is still running
+    harness.key(KeyCode::Char('f'), KeyModifiers::CONTROL);
+    harness.assert_screen(&[
+        "╭Everything──────────────────────────────────────────────── Not Admin (ctrl+e) ╮",
+        "│ Type to search Everything…                                                   │",
+        "╰──────────────────────────────────────────────────────────────────────────────╯",
+        "┌Total Results: 0 (Offset: 0 Selected: None)『』(Sort: name) ──────────────────┐",
+        "│Examples                                                                      │",
+        "│  *.rs dm:today     Rust files modified today                                 │",
+        "│  ext:pdf invoice   PDFs with invoice in the name                             │",
+        "│  size:>1gb         files over 1 GB                                           │",
+        r"│  downloads\ *.zip  zips in the folders named downloads                       │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│               ┌Size Filter (ctrl+f) ─────────────────────────┐               │",
+        "│               │ Empty files                                  │               │",
+        "│               │ Larger than 1 GB                             │               │",
+        "│               │                                              │               │",
+        "│               │ Min        - MB                              │               │",
+        "│               │ Max        - MB                              │               │",
+        "│               └ enter to insert, ←/→ to change the size, tab ┘               │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "│                                                                              │",
+        "└──────────────────────────────────────────────────────────────────────────────┘",
+        "                                                                                ",
+    ]);
+}
+
+#[test]
+fn commands_menu_tells_no_commands() {
+    let mut harness = Harness::new(80, 8, FILES);
+    harness.key(KeyCode::F(9), KeyModifiers::NONE);
+    harness.assert_screen(&[
+        "╭Everything──────────────────────────────────────────────── Not Admin (ctrl+e) ╮",
+        "│ Type to search Everything…                                                   │",
+        "╰──────────────────────────────────────────────────────────────────────────────╯",
+        "┌Total Results: 0 (Offset: 0 Selected: None)『』(Sort: name) ──────────────────┐",
+        "│Examples                                                                      │",
+        "│  *.rs dm:today     Rust files modified today                                 │",
+        "└ No commands, add them to [commands] in config ───────────────────────────────┘",
+        "                                                                                ",
+    ]);
+    // until the next key
+    harness.key(KeyCode::Right, KeyModifiers::NONE);
+    harness.assert_screen(&[
+        "╭Everything──────────────────────────────────────────────── Not Admin (ctrl+e) ╮",
+        "│ Type to search Everything…                                                   │",
+        "╰──────────────────────────────────────────────────────────────────────────────╯",
+        "┌Total Results: 0 (Offset: 0 Selected: None)『』(Sort: name) ──────────────────┐",
+        "│Examples                                                                      │",
+        "│  *.rs dm:today     Rust files modified today                                 │",
+        "└──────────────────────────────────────────────────────────────────────────────┘",
+        "                                                                                ",
+    ]);
+}
+
+#[test]
+fn page_down_moves_by_pages_to_the_last() {
+    let paths: Vec<String> = (0..100)
+        .map(|i| format!(r"C:\data\file_{i:03}.txt"))
+        .collect();
+    let files: Vec<&str> = paths.iter().map(String::as_str).collect();
+    let mut harness = Harness::new(80, 8, &files);
+    harness.search("file_");
+    harness.key(KeyCode::Down, KeyModifiers::NONE);
+    harness.assert_screen(&[
+        "╭Everything──────────────────────────────────────────────── Not Admin (ctrl+e) ╮",
+        "│file_                                                                         │",
+        "╰──────────────────────────────────────────────────────────────────────────────╯",
+        "┌Total Results: 100 (Offset: 0 Selected: Some(0))『file_』(Sort: name) ────────┐",
+        r"│file_000.txt C:\data                                                          │",
+        r"│file_001.txt C:\data                                                          │",
+        "└──────────────────────────────────────────────────────────────────────────────┘",
+        r"1  › 2 C:\data                                                                  ",
+    ]);
+
+    // a page down, the selected one is at the top
+    harness.key(KeyCode::PageDown, KeyModifiers::NONE);
+    harness.assert_screen(&[
+        "╭Everything──────────────────────────────────────────────── Not Admin (ctrl+e) ╮",
+        "│file_                                                                         │",
+        "╰──────────────────────────────────────────────────────────────────────────────╯",
+        "┌Total Results: 100 (Offset: 2 Selected: Some(2))『file_』(Sort: name) ────────┐",
+        r"│file_002.txt C:\data                                                          │",
+        r"│file_003.txt C:\data                                                          │",
+        "└──────────────────────────────────────────────────────────────────────────────┘",
+        r"1  › 2 C:\data                                                                  ",
+    ]);
+
+    for _ in 0..100 {
+        harness.key(KeyCode::PageDown, KeyModifiers::NONE);
+    }
+    harness.assert_screen(&[
+        "╭Everything──────────────────────────────────────────────── Not Admin (ctrl+e) ╮",
+        "│file_                                                                         │",
+        "╰──────────────────────────────────────────────────────────────────────────────╯",
+        "┌Total Results: 100 (Offset: 98 Selected: Some(99))『file_』(Sort: name) ──────┐",
+        r"│file_098.txt C:\data                                                          │",
+        r"│file_099.txt C:\data                                                          │",
+        "└──────────────────────────────────────────────────────────────────────────────┘",
+        r"1  › 2 C:\data                                                                  ",
+    ]);
+
+    for _ in 0..100 {
+        harness.key(KeyCode::PageUp, KeyModifiers::NONE);
+    }
+    harness.assert_screen(&[
+        "╭Everything──────────────────────────────────────────────── Not Admin (ctrl+e) ╮",
+        "│file_                                                                         │",
+        "╰──────────────────────────────────────────────────────────────────────────────╯",
+        "┌Total Results: 100 (Offset: 0 Selected: Some(0))『file_』(Sort: name) ────────┐",
+        r"│file_000.txt C:\data                                                          │",
+        r"│file_001.txt C:\data                                                          │",
+        "└──────────────────────────────────────────────────────────────────────────────┘",
+        r"1  › 2 C:\data                                                                  ",
+    ]);
+}
+
+#[test]
+fn ctrl_backspace_and_ctrl_delete_delete_words() {
+    let mut harness = Harness::new(80, 8, FILES);
+    harness.type_text("alpha beta gamma");
+    harness.key(KeyCode::Backspace, KeyModifiers::CONTROL);
+    harness.assert_screen(&[
+        "╭Everything──────────────────────────────────────────────── Not Admin (ctrl+e) ╮",
+        "│alpha beta                                                                    │",
+        "╰──────────────────────────────────────────────────────────────────────────────╯",
+        "┌Total Results: 0 (Offset: 0 Selected: None)『』(0 matches, enter to show) (Sor┐",
+        "│Examples                                                                      │",
+        "│  *.rs dm:today     Rust files modified today                                 │",
+        "└──────────────────────────────────────────────────────────────────────────────┘",
+        "                                                                                ",
+    ]);
+    harness.key(KeyCode::Home, KeyModifiers::NONE);
+    harness.key(KeyCode::Delete, KeyModifiers::CONTROL);
+    harness.assert_screen(&[
+        "╭Everything──────────────────────────────────────────────── Not Admin (ctrl+e) ╮",
+        "│ beta                                                                         │",
+        "╰──────────────────────────────────────────────────────────────────────────────╯",
+        "┌Total Results: 0 (Offset: 0 Selected: None)『』(0 matches, enter to show) (Sor┐",
+        "│Examples                                                                      │",
+        "│  *.rs dm:today     Rust files modified today                                 │",
+        "└──────────────────────────────────────────────────────────────────────────────┘",
+        "                                                                                ",
+    ]);
+}
+
+#[test]
+fn ctrl_a_selects_all_the_search() {
+    let mut harness = Harness::new(80, 8, FILES);
+    harness.type_text("alpha beta");
+    harness.key(KeyCode::Char('a'), KeyModifiers::CONTROL);
+    harness.type_text("notes");
+    harness.assert_screen(&[
+        "╭Everything──────────────────────────────────────────────── Not Admin (ctrl+e) ╮",
+        "│notes                                                                         │",
+        "╰──────────────────────────────────────────────────────────────────────────────╯",
+        "┌Total Results: 0 (Offset: 0 Selected: None)『』(1 matches, enter to show) (Sor┐",
+        "│Examples                                                                      │",
+        "│  *.rs dm:today     Rust files modified today                                 │",
+        "└──────────────────────────────────────────────────────────────────────────────┘",
+        "                                                                                ",
+    ]);
+}
+
+#[test]
+fn shift_home_selects_to_the_start() {
+    let mut harness = Harness::new(80, 8, FILES);
+    harness.type_text("alpha beta");
+    harness.key(KeyCode::Left, KeyModifiers::CONTROL);
+    harness.key(KeyCode::Home, KeyModifiers::SHIFT);
+    harness.key(KeyCode::Backspace, KeyModifiers::NONE);
+    harness.assert_screen(&[
+        "╭Everything──────────────────────────────────────────────── Not Admin (ctrl+e) ╮",
+        "│beta                                                                          │",
+        "╰──────────────────────────────────────────────────────────────────────────────╯",
+        "┌Total Results: 0 (Offset: 0 Selected: None)『』(0 matches, enter to show) (Sor┐",
+        "│Examples                                                                      │",
+        "│  *.rs dm:today     Rust files modified today                                 │",
+        "└──────────────────────────────────────────────────────────────────────────────┘",
+        "                                                                                ",
+    ]);
+}
+
+#[test]
+fn ctrl_y_redoes_the_search_undone() {
+    let mut harness = Harness::new(80, 8, FILES);
+    harness.type_text("alpha");
+    harness.key(KeyCode::Char('z'), KeyModifiers::CONTROL);
+    harness.assert_screen(&[
+        "╭Everything──────────────────────────────────────────────── Not Admin (ctrl+e) ╮",
+        "│alph                                                                          │",
+        "╰──────────────────────────────────────────────────────────────────────────────╯",
+        "┌Total Results: 0 (Offset: 0 Selected: None)『』(0 matches, enter to show) (Sor┐",
+        "│Examples                                                                      │",
+        "│  *.rs dm:today     Rust files modified today                                 │",
+        "└──────────────────────────────────────────────────────────────────────────────┘",
+        "                                                                                ",
+    ]);
+    harness.key(KeyCode::Char('y'), KeyModifiers::CONTROL);
+    harness.assert_screen(&[
+        "╭Everything──────────────────────────────────────────────── Not Admin (ctrl+e) ╮",
+        "│alpha                                                                         │",
+        "╰──────────────────────────────────────────────────────────────────────────────╯",
+        "┌Total Results: 0 (Offset: 0 Selected: None)『』(0 matches, enter to show) (Sor┐",
+        "│Examples                                                                      │",
+        "│  *.rs dm:today     Rust files modified today                                 │",
+        "└──────────────────────────────────────────────────────────────────────────────┘",
+        "                                                                                ",
+    ]);
+}
+
+#[test]
+fn esc_stops_waiting_for_the_slow_query() {
+    let client = MockClient {
+        delay: Duration::from_secs(2),
+        ..MockClient::with_files(&[(FILES[0], 0)])
+    };
+    let mut harness = Harness::with_client(80, 8, client);
+    harness.type_text("report");
+    harness.key(KeyCode::Enter, KeyModifiers::NONE);
+    assert!(harness.app.is_querying());
+    harness.key(KeyCode::Esc, KeyModifiers::NONE);
+    // still in Everything, but not waited for
+    assert!(!harness.app.is_querying());
+    assert_eq!(harness.app.queries_in_flight(), 0);
+}